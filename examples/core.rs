@@ -1,6 +1,6 @@
 use std::{thread, time::Duration};
 
-use raw_input::Core;
+use raw_input::{Core, CoreError};
 
 fn main() {
     println!("=== Core Lifecycle Management ===");
@@ -77,8 +77,8 @@ fn test_core_reentrancy_protection() {
 
     let second_start = Core::start();
     assert!(
-        second_start.is_ok(),
-        "Subsequent Core::start() should not fail or re-register"
+        matches!(second_start, Err(CoreError::AlreadyRunning)),
+        "Subsequent Core::start() should report the running core instead of re-registering"
     );
 
     Core::stop();
@@ -0,0 +1,92 @@
+/*
+ * C bindings for the raw-input crate.
+ *
+ * Build the shared library with:
+ *     cargo rustc --release --features ffi --crate-type cdylib
+ */
+#ifndef RAW_INPUT_H
+#define RAW_INPUT_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* raw_input_event.kind */
+#define RAW_INPUT_MOUSE_MOVE 0
+#define RAW_INPUT_MOUSE_WHEEL 1
+#define RAW_INPUT_MOUSE_DOWN 2
+#define RAW_INPUT_MOUSE_UP 3
+#define RAW_INPUT_KEY_DOWN 4
+#define RAW_INPUT_KEY_UP 5
+#define RAW_INPUT_FOCUS_CHANGED 6
+#define RAW_INPUT_MOUSE_MOVE_ABSOLUTE 7
+#define RAW_INPUT_MODIFIERS_CHANGED 8
+
+/* raw_input_event.button */
+#define RAW_INPUT_BUTTON_LEFT 0
+#define RAW_INPUT_BUTTON_RIGHT 1
+#define RAW_INPUT_BUTTON_MIDDLE 2
+#define RAW_INPUT_BUTTON_BACK 3
+#define RAW_INPUT_BUTTON_FORWARD 4
+
+/* An input event. Fields not used by `kind` are zero. */
+typedef struct raw_input_event {
+    uint32_t kind;
+    /* Mouse button id, for button events. */
+    uint32_t button;
+    /* Deltas, for move and wheel events. */
+    double x;
+    double y;
+    /* Stable key id (see raw_input::wire::key_id), for keyboard events. */
+    uint64_t key;
+    /* Raw platform key code, valid when has_code is set. The process id for focus
+     * changes, the modifier bits for modifier changes. */
+    uint32_t code;
+    bool has_code;
+} raw_input_event;
+
+typedef struct raw_input_monitor {
+    double x;
+    double y;
+    double width;
+    double height;
+    double scale_factor;
+    bool is_primary;
+} raw_input_monitor;
+
+/* Invoked for every event, on the thread that dispatches events. */
+typedef void (*raw_input_callback)(const raw_input_event *event, void *user_data);
+
+/* Runs the event loop on the calling thread until raw_input_core_stop().
+ * Returns 0 on success, -1 if the platform hooks could not be installed and
+ * -2 if the core is already running. */
+int32_t raw_input_core_start(void);
+void raw_input_core_stop(void);
+
+void raw_input_listen_start(void);
+void raw_input_listen_stop(void);
+
+/* Returns a non-zero subscription id. */
+uint64_t raw_input_subscribe(raw_input_callback callback, void *user_data);
+/* Returns false if the id is unknown. */
+bool raw_input_unsubscribe(uint64_t id);
+
+/* Returns false if the event is null or invalid. */
+bool raw_input_simulate(const raw_input_event *event);
+
+/* Returns false if the cursor position is unavailable. */
+bool raw_input_cursor_position(double *x, double *y);
+
+/* Writes up to `capacity` monitors to `out` (which may be null) and returns the
+ * total number of monitors. */
+size_t raw_input_monitors(raw_input_monitor *out, size_t capacity);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* RAW_INPUT_H */
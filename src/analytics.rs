@@ -0,0 +1,293 @@
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Event, Key, Listen, Modifiers, SubscriptionHandle};
+
+/// How much history [`Analytics::last`] can look back by default.
+const DEFAULT_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+/// Average word length used for words per minute, by convention.
+const CHARS_PER_WORD: f64 = 5.0;
+
+/// Aggregated input statistics over a period, see [`Analytics`].
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct InputStats {
+    /// The period the statistics cover.
+    pub duration: Duration,
+    /// Key presses, not counting auto-repeat.
+    pub keystrokes: u64,
+    /// Key presses of keys other than modifiers, the typed characters.
+    pub characters: u64,
+    /// Mouse button presses.
+    pub clicks: u64,
+    /// Scrolled distance on both axes, in lines.
+    pub scroll: f64,
+    /// Distance the mouse moved, in the units of [`Event::MouseMove`] deltas.
+    pub mouse_travel: f64,
+}
+
+impl InputStats {
+    /// Actions per minute: key presses and clicks.
+    pub fn apm(&self) -> f64 {
+        self.per_minute((self.keystrokes + self.clicks) as f64)
+    }
+
+    /// Words per minute, counting five typed characters as a word.
+    pub fn wpm(&self) -> f64 {
+        self.per_minute(self.characters as f64 / CHARS_PER_WORD)
+    }
+
+    fn per_minute(&self, count: f64) -> f64 {
+        let minutes = self.duration.as_secs_f64() / 60.0;
+        if minutes > 0.0 { count / minutes } else { 0.0 }
+    }
+
+    fn add(&mut self, other: &InputStats) {
+        self.keystrokes += other.keystrokes;
+        self.characters += other.characters;
+        self.clicks += other.clicks;
+        self.scroll += other.scroll;
+        self.mouse_travel += other.mouse_travel;
+    }
+}
+
+/// Aggregates keystrokes, clicks, scrolling and mouse travel from [`Listen`], so
+/// productivity trackers need not process every event themselves.
+///
+/// Statistics are kept in one-second buckets for the retention period, which bounds
+/// how far [`Analytics::last`] can look back.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use raw_input::Analytics;
+///
+/// let analytics = Analytics::start();
+/// // Later
+/// let recent = analytics.last(Duration::from_secs(60));
+/// println!("{:.0} APM, {:.0} WPM", recent.apm(), recent.wpm());
+/// ```
+pub struct Analytics {
+    handle: SubscriptionHandle,
+    tracker: Arc<Mutex<Tracker>>,
+    start: Instant,
+}
+
+impl Analytics {
+    /// Starts aggregating with the default retention of one hour.
+    ///
+    /// Requires `Core` and `Listen` to be running.
+    pub fn start() -> Self {
+        Self::with_retention(DEFAULT_RETENTION)
+    }
+
+    /// Starts aggregating, keeping the per-second history for `retention`.
+    pub fn with_retention(retention: Duration) -> Self {
+        let start = Instant::now();
+        let tracker = Arc::new(Mutex::new(Tracker::new(retention)));
+        let sink = tracker.clone();
+
+        let handle = Listen::subscribe(move |event| {
+            let time = start.elapsed();
+            lock(&sink).record(time, event);
+        });
+
+        Analytics {
+            handle,
+            tracker,
+            start,
+        }
+    }
+
+    /// Returns the statistics since the start or the last [`Analytics::reset`].
+    pub fn totals(&self) -> InputStats {
+        lock(&self.tracker).totals(self.start.elapsed())
+    }
+
+    /// Returns the statistics of the trailing `window`, limited by the retention.
+    pub fn last(&self, window: Duration) -> InputStats {
+        lock(&self.tracker).last(self.start.elapsed(), window)
+    }
+
+    /// Clears all statistics.
+    pub fn reset(&self) {
+        lock(&self.tracker).reset(self.start.elapsed());
+    }
+
+    /// Stops aggregating and returns the totals.
+    pub fn stop(self) -> InputStats {
+        let totals = self.totals();
+        self.handle.unsubscribe();
+        totals
+    }
+}
+
+fn lock(tracker: &Mutex<Tracker>) -> std::sync::MutexGuard<'_, Tracker> {
+    tracker.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// The aggregation state, driven by offsets from the start of [`Analytics`].
+struct Tracker {
+    retention: Duration,
+    /// Where the totals start, moved by `reset`.
+    since: Duration,
+    totals: InputStats,
+    /// Statistics per second since the start, oldest first.
+    buckets: VecDeque<(u64, InputStats)>,
+    /// Keys held down, so auto-repeat is not counted.
+    pressed: HashSet<Key>,
+}
+
+impl Tracker {
+    fn new(retention: Duration) -> Self {
+        Tracker {
+            retention,
+            since: Duration::ZERO,
+            totals: InputStats::default(),
+            buckets: VecDeque::new(),
+            pressed: HashSet::new(),
+        }
+    }
+
+    fn record(&mut self, time: Duration, event: Event) {
+        let mut stats = InputStats::default();
+        match event {
+            Event::KeyDown { key, .. } => {
+                if !self.pressed.insert(key) {
+                    return;
+                }
+                stats.keystrokes = 1;
+                stats.characters = u64::from(Modifiers::from_key(key).is_none());
+            }
+            Event::KeyUp { key, .. } => {
+                self.pressed.remove(&key);
+                return;
+            }
+            Event::MouseDown { .. } => stats.clicks = 1,
+            Event::MouseWheel { delta, .. } => stats.scroll = delta.x.abs() + delta.y.abs(),
+            Event::MouseMove { delta } => stats.mouse_travel = delta.x.hypot(delta.y),
+            _ => return,
+        }
+
+        self.totals.add(&stats);
+        let second = time.as_secs();
+        match self.buckets.back_mut() {
+            Some((last, bucket)) if *last == second => bucket.add(&stats),
+            _ => self.buckets.push_back((second, stats)),
+        }
+
+        let oldest = time.saturating_sub(self.retention).as_secs();
+        while self
+            .buckets
+            .front()
+            .is_some_and(|(second, _)| *second < oldest)
+        {
+            self.buckets.pop_front();
+        }
+    }
+
+    fn totals(&self, now: Duration) -> InputStats {
+        InputStats {
+            duration: now.saturating_sub(self.since),
+            ..self.totals
+        }
+    }
+
+    fn last(&self, now: Duration, window: Duration) -> InputStats {
+        let window = window
+            .min(self.retention)
+            .min(now.saturating_sub(self.since));
+        let first = now.saturating_sub(window).as_secs();
+        let mut stats = InputStats {
+            duration: window,
+            ..InputStats::default()
+        };
+        for (_, bucket) in self.buckets.iter().filter(|(second, _)| *second >= first) {
+            stats.add(bucket);
+        }
+        stats
+    }
+
+    fn reset(&mut self, now: Duration) {
+        self.since = now;
+        self.totals = InputStats::default();
+        self.buckets.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MouseButton, Point, fixtures::key_down};
+
+    fn key_up(key: Key) -> Event {
+        Event::KeyUp { key, code: None }
+    }
+
+    #[test]
+    fn test_counts_presses_without_auto_repeat() {
+        let mut tracker = Tracker::new(DEFAULT_RETENTION);
+        let at = Duration::from_millis(100);
+        tracker.record(at, key_down(Key::ShiftLeft));
+        tracker.record(at, key_down(Key::KeyA));
+        tracker.record(at, key_down(Key::KeyA));
+        tracker.record(at, key_up(Key::KeyA));
+        tracker.record(at, key_down(Key::KeyA));
+        tracker.record(
+            at,
+            Event::MouseDown {
+                button: MouseButton::Left,
+            },
+        );
+        tracker.record(
+            at,
+            Event::MouseMove {
+                delta: Point { x: 3.0, y: 4.0 },
+            },
+        );
+        tracker.record(
+            at,
+            Event::MouseWheel {
+                delta: Point { x: 0.0, y: -2.0 },
+                raw: None,
+            },
+        );
+
+        let stats = tracker.totals(Duration::from_secs(60));
+        assert_eq!(stats.keystrokes, 3);
+        assert_eq!(stats.characters, 2);
+        assert_eq!(stats.clicks, 1);
+        assert_eq!(stats.mouse_travel, 5.0);
+        assert_eq!(stats.scroll, 2.0);
+        assert_eq!(stats.apm(), 4.0);
+        assert_eq!(stats.wpm(), 0.4);
+    }
+
+    #[test]
+    fn test_window_and_retention() {
+        let mut tracker = Tracker::new(Duration::from_secs(10));
+        for second in 0..20 {
+            tracker.record(Duration::from_secs(second), key_down(Key::KeyA));
+            tracker.record(Duration::from_secs(second), key_up(Key::KeyA));
+        }
+
+        let now = Duration::from_secs(20);
+        assert_eq!(tracker.last(now, Duration::from_secs(5)).keystrokes, 5);
+        // Limited to the retention
+        let stats = tracker.last(now, Duration::from_secs(60));
+        assert_eq!(stats.duration, Duration::from_secs(10));
+        assert_eq!(stats.keystrokes, 10);
+        assert_eq!(tracker.totals(now).keystrokes, 20);
+
+        tracker.reset(now);
+        assert_eq!(tracker.totals(now).keystrokes, 0);
+        assert_eq!(tracker.last(now, Duration::from_secs(5)).keystrokes, 0);
+    }
+}
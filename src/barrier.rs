@@ -0,0 +1,1038 @@
+//! Client side of the Barrier protocol, also spoken by InputLeap.
+//!
+//! A [`Client`] connects to a Barrier server as one of its screens and replays the
+//! input the server sends while the pointer is on that screen with [`Simulate`]. This
+//! turns a machine into a KVM node of an existing Barrier setup. [`Message::from_event`]
+//! translates locally captured events into protocol messages for the other direction.
+//!
+//! # Protocol
+//!
+//! Messages travel over TCP, each prefixed by its `u32` length ([`BarrierTransport`]).
+//! Apart from the greeting, a message starts with a four character code such as `DKDN`
+//! followed by its fields. All integers are big-endian, strings are prefixed by their
+//! `u32` length.
+//!
+//! Barrier servers enable TLS by default. Either disable it in the server settings, or
+//! wrap the stream in a TLS implementation of your choice before handing it to
+//! [`BarrierTransport`].
+//!
+//! # Example
+//! ```no_run
+//! use std::net::TcpStream;
+//! use raw_input::barrier::{BarrierTransport, Client};
+//!
+//! let stream = TcpStream::connect("192.168.1.10:24800")?;
+//! let client = Client::connect(BarrierTransport::new(stream)?, "laptop")?;
+//! let err = client.run();
+//! eprintln!("Disconnected: {}", err);
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+    net::TcpStream,
+};
+
+use crate::{
+    Display, Keyboard, Simulate,
+    event::{Event, MouseButton, Point},
+    key::{Key, Modifiers},
+    remote::Transport,
+    wire,
+};
+
+/// The protocol version announced to servers.
+pub const PROTOCOL_VERSION: (u16, u16) = (1, 6);
+
+/// Greeting that opens every connection, followed by the protocol version.
+const MAGIC: &[u8; 7] = b"Barrier";
+
+/// Largest message accepted by [`BarrierTransport`]. Clipboard transfers are chunked
+/// well below this.
+const MAX_MESSAGE_LEN: usize = 4 * 1024 * 1024;
+
+/// One step of the mouse wheel in the protocol's wheel deltas.
+const WHEEL_STEP: f64 = 120.0;
+
+/// A [`Transport`] over a stream, with messages prefixed by their big-endian `u32`
+/// length as Barrier expects.
+pub struct BarrierTransport<S> {
+    stream: S,
+}
+
+impl BarrierTransport<TcpStream> {
+    /// Wraps a connected stream. Disables Nagle's algorithm to keep input latency low.
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self { stream })
+    }
+}
+
+impl<S: Read + Write + Send> BarrierTransport<S> {
+    /// Wraps any stream, such as a TLS session over a TCP connection.
+    pub fn from_stream(stream: S) -> Self {
+        Self { stream }
+    }
+}
+
+impl<S: Read + Write + Send> Transport for BarrierTransport<S> {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(4 + frame.len());
+        buf.extend_from_slice(&(frame.len() as u32).to_be_bytes());
+        buf.extend_from_slice(frame);
+        self.stream.write_all(&buf)
+    }
+
+    fn recv(&mut self) -> io::Result<Vec<u8>> {
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len)?;
+        let len = u32::from_be_bytes(len) as usize;
+        if len > MAX_MESSAGE_LEN {
+            return Err(invalid_data("message too large"));
+        }
+        let mut frame = vec![0u8; len];
+        self.stream.read_exact(&mut frame)?;
+        Ok(frame)
+    }
+}
+
+/// The geometry a client reports for its screen, see [`Message::Info`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct ScreenInfo {
+    pub x: i16,
+    pub y: i16,
+    pub width: i16,
+    pub height: i16,
+    /// The cursor position.
+    pub mouse_x: i16,
+    pub mouse_y: i16,
+}
+
+impl ScreenInfo {
+    /// Describes the virtual screen of this machine, in logical units.
+    pub fn current() -> Self {
+        let (x, y, width, height) = Display::get_virtual_screen_bounds();
+        let (mouse_x, mouse_y) = Display::get_cursor_position().unwrap_or((x, y));
+        Self {
+            x: x as i16,
+            y: y as i16,
+            width: width as i16,
+            height: height as i16,
+            mouse_x: mouse_x as i16,
+            mouse_y: mouse_y as i16,
+        }
+    }
+}
+
+/// A protocol message.
+///
+/// Key messages carry a key id, the held modifiers and the server's own code for the
+/// physical key. Key ids are Unicode code points for keys that type a character, and
+/// values in the `0xE000`–`0xEFFF` range for the others (`0xEF0D` is Enter). Mouse
+/// buttons are numbered 1 (left), 2 (middle), 3 (right), 4 (back) and 5 (forward).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Message {
+    /// The server's greeting, with its protocol version.
+    Hello { major: u16, minor: u16 },
+    /// The client's answer to the greeting, naming its screen.
+    HelloBack {
+        major: u16,
+        minor: u16,
+        name: String,
+    },
+    /// `QINF`: the server asks for the screen geometry.
+    QueryInfo,
+    /// `DINF`: the client's screen geometry.
+    Info(ScreenInfo),
+    /// `CIAK`: the server received the screen geometry.
+    InfoAck,
+    /// `CALV`: sent by the server every few seconds, and echoed by the client.
+    KeepAlive,
+    /// `CINN`: the pointer entered the client's screen at the given position.
+    Enter {
+        x: i16,
+        y: i16,
+        sequence: u32,
+        modifiers: u16,
+    },
+    /// `COUT`: the pointer left the client's screen.
+    Leave,
+    /// `DKDN`
+    KeyDown {
+        id: u16,
+        modifiers: u16,
+        button: u16,
+    },
+    /// `DKRP`: the key auto-repeated `count` times.
+    KeyRepeat {
+        id: u16,
+        modifiers: u16,
+        count: u16,
+        button: u16,
+    },
+    /// `DKUP`
+    KeyUp {
+        id: u16,
+        modifiers: u16,
+        button: u16,
+    },
+    /// `DMDN`
+    MouseDown { button: u8 },
+    /// `DMUP`
+    MouseUp { button: u8 },
+    /// `DMMV`: absolute pointer position on the client's screen.
+    MouseMove { x: i16, y: i16 },
+    /// `DMRM`: relative pointer movement.
+    MouseRelativeMove { dx: i16, dy: i16 },
+    /// `DMWM`: wheel movement, 120 per step. Positive `dy` scrolls up.
+    MouseWheel { dx: i16, dy: i16 },
+    /// `CBYE`: the server closes the connection.
+    Close,
+    /// `EICV`: the server does not speak the client's protocol version.
+    Incompatible { major: u16, minor: u16 },
+    /// `EBSY`: a client with the same name is already connected.
+    Busy,
+    /// `EUNK`: the server has no screen with the client's name.
+    UnknownClient,
+    /// `EBAD`: the server received a malformed message.
+    BadProtocol,
+    /// Any other message, such as clipboard transfers and option changes.
+    Other { code: [u8; 4], payload: Vec<u8> },
+}
+
+impl Message {
+    /// Decodes a message without its length prefix.
+    pub fn decode(frame: &[u8]) -> io::Result<Message> {
+        let mut input = frame;
+        if let Some(rest) = input.strip_prefix(MAGIC.as_slice()) {
+            input = rest;
+            let major = take_u16(&mut input)?;
+            let minor = take_u16(&mut input)?;
+            if input.is_empty() {
+                return Ok(Message::Hello { major, minor });
+            }
+            let name = take_string(&mut input)?;
+            return Ok(Message::HelloBack { major, minor, name });
+        }
+
+        let code = wire::take::<4>(&mut input).ok_or_else(|| invalid_data("truncated message"))?;
+        let input = &mut input;
+        Ok(match &code {
+            b"QINF" => Message::QueryInfo,
+            b"DINF" => {
+                let (x, y, width, height) = (
+                    take_i16(input)?,
+                    take_i16(input)?,
+                    take_i16(input)?,
+                    take_i16(input)?,
+                );
+                // The obsolete jump zone size
+                take_i16(input)?;
+                Message::Info(ScreenInfo {
+                    x,
+                    y,
+                    width,
+                    height,
+                    mouse_x: take_i16(input)?,
+                    mouse_y: take_i16(input)?,
+                })
+            }
+            b"CIAK" => Message::InfoAck,
+            b"CALV" => Message::KeepAlive,
+            b"CINN" => Message::Enter {
+                x: take_i16(input)?,
+                y: take_i16(input)?,
+                sequence: take_u32(input)?,
+                modifiers: take_u16(input)?,
+            },
+            b"COUT" => Message::Leave,
+            b"DKDN" => Message::KeyDown {
+                id: take_u16(input)?,
+                modifiers: take_u16(input)?,
+                button: take_u16(input)?,
+            },
+            b"DKRP" => Message::KeyRepeat {
+                id: take_u16(input)?,
+                modifiers: take_u16(input)?,
+                count: take_u16(input)?,
+                button: take_u16(input)?,
+            },
+            b"DKUP" => Message::KeyUp {
+                id: take_u16(input)?,
+                modifiers: take_u16(input)?,
+                button: take_u16(input)?,
+            },
+            b"DMDN" => Message::MouseDown {
+                button: take_u8(input)?,
+            },
+            b"DMUP" => Message::MouseUp {
+                button: take_u8(input)?,
+            },
+            b"DMMV" => Message::MouseMove {
+                x: take_i16(input)?,
+                y: take_i16(input)?,
+            },
+            b"DMRM" => Message::MouseRelativeMove {
+                dx: take_i16(input)?,
+                dy: take_i16(input)?,
+            },
+            b"DMWM" => Message::MouseWheel {
+                dx: take_i16(input)?,
+                dy: take_i16(input)?,
+            },
+            b"CBYE" => Message::Close,
+            b"EICV" => Message::Incompatible {
+                major: take_u16(input)?,
+                minor: take_u16(input)?,
+            },
+            b"EBSY" => Message::Busy,
+            b"EUNK" => Message::UnknownClient,
+            b"EBAD" => Message::BadProtocol,
+            _ => Message::Other {
+                code,
+                payload: input.to_vec(),
+            },
+        })
+    }
+
+    /// Encodes the message without its length prefix.
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        let mut code = |code: &[u8; 4]| out.extend_from_slice(code);
+        match self {
+            Message::Hello { .. } | Message::HelloBack { .. } => {}
+            Message::QueryInfo => code(b"QINF"),
+            Message::Info(_) => code(b"DINF"),
+            Message::InfoAck => code(b"CIAK"),
+            Message::KeepAlive => code(b"CALV"),
+            Message::Enter { .. } => code(b"CINN"),
+            Message::Leave => code(b"COUT"),
+            Message::KeyDown { .. } => code(b"DKDN"),
+            Message::KeyRepeat { .. } => code(b"DKRP"),
+            Message::KeyUp { .. } => code(b"DKUP"),
+            Message::MouseDown { .. } => code(b"DMDN"),
+            Message::MouseUp { .. } => code(b"DMUP"),
+            Message::MouseMove { .. } => code(b"DMMV"),
+            Message::MouseRelativeMove { .. } => code(b"DMRM"),
+            Message::MouseWheel { .. } => code(b"DMWM"),
+            Message::Close => code(b"CBYE"),
+            Message::Incompatible { .. } => code(b"EICV"),
+            Message::Busy => code(b"EBSY"),
+            Message::UnknownClient => code(b"EUNK"),
+            Message::BadProtocol => code(b"EBAD"),
+            Message::Other { code: other, .. } => code(other),
+        }
+
+        let mut put = |value: &[u8]| out.extend_from_slice(value);
+        match self {
+            Message::Hello { major, minor } => {
+                put(MAGIC);
+                put(&major.to_be_bytes());
+                put(&minor.to_be_bytes());
+            }
+            Message::HelloBack { major, minor, name } => {
+                put(MAGIC);
+                put(&major.to_be_bytes());
+                put(&minor.to_be_bytes());
+                put(&(name.len() as u32).to_be_bytes());
+                put(name.as_bytes());
+            }
+            Message::Info(info) => {
+                for value in [
+                    info.x,
+                    info.y,
+                    info.width,
+                    info.height,
+                    0,
+                    info.mouse_x,
+                    info.mouse_y,
+                ] {
+                    put(&value.to_be_bytes());
+                }
+            }
+            Message::Enter {
+                x,
+                y,
+                sequence,
+                modifiers,
+            } => {
+                put(&x.to_be_bytes());
+                put(&y.to_be_bytes());
+                put(&sequence.to_be_bytes());
+                put(&modifiers.to_be_bytes());
+            }
+            Message::KeyDown {
+                id,
+                modifiers,
+                button,
+            }
+            | Message::KeyUp {
+                id,
+                modifiers,
+                button,
+            } => {
+                put(&id.to_be_bytes());
+                put(&modifiers.to_be_bytes());
+                put(&button.to_be_bytes());
+            }
+            Message::KeyRepeat {
+                id,
+                modifiers,
+                count,
+                button,
+            } => {
+                for value in [id, modifiers, count, button] {
+                    put(&value.to_be_bytes());
+                }
+            }
+            Message::MouseDown { button } | Message::MouseUp { button } => put(&[*button]),
+            Message::MouseMove { x, y } => {
+                put(&x.to_be_bytes());
+                put(&y.to_be_bytes());
+            }
+            Message::MouseRelativeMove { dx, dy } | Message::MouseWheel { dx, dy } => {
+                put(&dx.to_be_bytes());
+                put(&dy.to_be_bytes());
+            }
+            Message::Incompatible { major, minor } => {
+                put(&major.to_be_bytes());
+                put(&minor.to_be_bytes());
+            }
+            Message::Other { payload, .. } => put(payload),
+            _ => {}
+        }
+    }
+
+    /// Translates a locally captured event into the message a Barrier server sends for
+    /// it, so captures from [`Listen`](crate::Listen) can drive a Barrier client.
+    ///
+    /// Mouse moves become relative moves, absolute ones absolute moves. Keys are
+    /// identified by the character they type on the active layout without modifiers, so
+    /// modifier state is left to the modifier keys' own messages. Returns `None` for keys
+    /// with neither a character nor a protocol id, and for events without a protocol
+    /// equivalent.
+    pub fn from_event(event: &Event) -> Option<Message> {
+        Some(match *event {
+            Event::MouseMove { delta } => Message::MouseRelativeMove {
+                dx: delta.x.round() as i16,
+                dy: delta.y.round() as i16,
+            },
+            Event::MouseMoveAbsolute { position } => Message::MouseMove {
+                x: position.x.round() as i16,
+                y: position.y.round() as i16,
+            },
+            Event::MouseWheel { delta, .. } => Message::MouseWheel {
+                dx: (delta.x * WHEEL_STEP).round() as i16,
+                dy: (delta.y * WHEEL_STEP).round() as i16,
+            },
+            Event::MouseDown { button } => Message::MouseDown {
+                button: button_id(button),
+            },
+            Event::MouseUp { button } => Message::MouseUp {
+                button: button_id(button),
+            },
+            Event::KeyDown { key, code } => Message::KeyDown {
+                id: key_id(key)?,
+                modifiers: 0,
+                button: code.unwrap_or(0) as u16,
+            },
+            Event::KeyUp { key, code } => Message::KeyUp {
+                id: key_id(key)?,
+                modifiers: 0,
+                button: code.unwrap_or(0) as u16,
+            },
+            Event::FocusChanged { .. } | Event::ModifiersChanged { .. } => return None,
+        })
+    }
+}
+
+/// Key ids of keys that type no character.
+const SPECIAL_KEYS: &[(u16, Key)] = &[
+    (0xEF08, Key::Backspace),
+    (0xEF09, Key::Tab),
+    (0xEF0D, Key::Enter),
+    (0xEF13, Key::Pause),
+    (0xEF14, Key::ScrollLock),
+    (0xEF1B, Key::Escape),
+    (0xEF50, Key::Home),
+    (0xEF51, Key::ArrowLeft),
+    (0xEF52, Key::ArrowUp),
+    (0xEF53, Key::ArrowRight),
+    (0xEF54, Key::ArrowDown),
+    (0xEF55, Key::PageUp),
+    (0xEF56, Key::PageDown),
+    (0xEF57, Key::End),
+    (0xEF61, Key::PrintScreen),
+    (0xEF63, Key::Insert),
+    (0xEF67, Key::ContextMenu),
+    (0xEF6A, Key::Help),
+    (0xEF7E, Key::AltRight),
+    (0xEF7F, Key::NumLock),
+    (0xEF8D, Key::NumpadEnter),
+    // Keypad keys with NumLock off
+    (0xEF95, Key::Numpad7),
+    (0xEF96, Key::Numpad4),
+    (0xEF97, Key::Numpad8),
+    (0xEF98, Key::Numpad6),
+    (0xEF99, Key::Numpad2),
+    (0xEF9A, Key::Numpad9),
+    (0xEF9B, Key::Numpad3),
+    (0xEF9C, Key::Numpad1),
+    (0xEF9D, Key::Numpad5),
+    (0xEF9E, Key::Numpad0),
+    (0xEF9F, Key::NumpadDecimal),
+    (0xEFAA, Key::NumpadMultiply),
+    (0xEFAB, Key::NumpadAdd),
+    (0xEFAD, Key::NumpadSubtract),
+    (0xEFAE, Key::NumpadDecimal),
+    (0xEFAF, Key::NumpadDivide),
+    (0xEFB0, Key::Numpad0),
+    (0xEFB1, Key::Numpad1),
+    (0xEFB2, Key::Numpad2),
+    (0xEFB3, Key::Numpad3),
+    (0xEFB4, Key::Numpad4),
+    (0xEFB5, Key::Numpad5),
+    (0xEFB6, Key::Numpad6),
+    (0xEFB7, Key::Numpad7),
+    (0xEFB8, Key::Numpad8),
+    (0xEFB9, Key::Numpad9),
+    (0xEFBE, Key::F1),
+    (0xEFBF, Key::F2),
+    (0xEFC0, Key::F3),
+    (0xEFC1, Key::F4),
+    (0xEFC2, Key::F5),
+    (0xEFC3, Key::F6),
+    (0xEFC4, Key::F7),
+    (0xEFC5, Key::F8),
+    (0xEFC6, Key::F9),
+    (0xEFC7, Key::F10),
+    (0xEFC8, Key::F11),
+    (0xEFC9, Key::F12),
+    (0xEFCA, Key::F13),
+    (0xEFCB, Key::F14),
+    (0xEFCC, Key::F15),
+    (0xEFCD, Key::F16),
+    (0xEFCE, Key::F17),
+    (0xEFCF, Key::F18),
+    (0xEFD0, Key::F19),
+    (0xEFD1, Key::F20),
+    (0xEFD2, Key::F21),
+    (0xEFD3, Key::F22),
+    (0xEFD4, Key::F23),
+    (0xEFD5, Key::F24),
+    (0xEFE1, Key::ShiftLeft),
+    (0xEFE2, Key::ShiftRight),
+    (0xEFE3, Key::ControlLeft),
+    (0xEFE4, Key::ControlRight),
+    (0xEFE5, Key::CapsLock),
+    (0xEFE7, Key::MetaLeft),
+    (0xEFE8, Key::MetaRight),
+    (0xEFE9, Key::AltLeft),
+    (0xEFEA, Key::AltRight),
+    // Servers send the Windows and Command keys as Super
+    (0xEFEB, Key::MetaLeft),
+    (0xEFEC, Key::MetaRight),
+    (0xEFFF, Key::Delete),
+];
+
+/// Returns the local key for a key id, looking characters up on the active layout.
+fn key_for_id(id: u16) -> Option<Key> {
+    if let Some(&(_, key)) = SPECIAL_KEYS.iter().find(|(special, _)| *special == id) {
+        return Some(key);
+    }
+    if (0xE000..=0xEFFF).contains(&id) {
+        return None;
+    }
+    match char::from_u32(u32::from(id))? {
+        ' ' => Some(Key::Space),
+        ch => Keyboard::key_for_char(ch).map(|(key, _)| key),
+    }
+}
+
+/// Returns the key id for a local key.
+fn key_id(key: Key) -> Option<u16> {
+    if let Some(&(id, _)) = SPECIAL_KEYS.iter().find(|(_, special)| *special == key) {
+        return Some(id);
+    }
+    match key {
+        Key::Space => Some(0x20),
+        key => u16::try_from(u32::from(Keyboard::char_for_key(key, Modifiers::NONE)?)).ok(),
+    }
+}
+
+fn button_id(button: MouseButton) -> u8 {
+    match button {
+        MouseButton::Left => 1,
+        MouseButton::Middle => 2,
+        MouseButton::Right => 3,
+        MouseButton::Back => 4,
+        MouseButton::Forward => 5,
+    }
+}
+
+fn button_for_id(id: u8) -> Option<MouseButton> {
+    Some(match id {
+        1 => MouseButton::Left,
+        2 => MouseButton::Middle,
+        3 => MouseButton::Right,
+        4 => MouseButton::Back,
+        5 => MouseButton::Forward,
+        _ => return None,
+    })
+}
+
+/// What the client does in response to a server message.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Action {
+    Reply(Message),
+    /// Answer with the current screen geometry.
+    ReportInfo,
+    Simulate(Event),
+    MoveTo(f64, f64),
+    /// Type a character no key produces on the active layout.
+    Type(char),
+}
+
+/// Translates server messages into actions, tracking what is held down so nothing is
+/// left pressed when the pointer leaves the screen.
+#[derive(Debug, Default)]
+pub(crate) struct Replayer {
+    /// Held keys by the server's key code, with the local key pressed for them.
+    keys: HashMap<u16, Key>,
+    buttons: Vec<MouseButton>,
+}
+
+impl Replayer {
+    /// Handles a message. Returns an error for messages that end the connection.
+    pub(crate) fn handle(&mut self, message: Message, actions: &mut Vec<Action>) -> io::Result<()> {
+        match message {
+            Message::KeepAlive => actions.push(Action::Reply(Message::KeepAlive)),
+            Message::QueryInfo => actions.push(Action::ReportInfo),
+            Message::Enter { x, y, .. } => actions.push(Action::MoveTo(x.into(), y.into())),
+            Message::Leave => self.release_all(actions),
+            Message::KeyDown { id, button, .. } => match key_for_id(id) {
+                Some(key) => {
+                    self.keys.insert(slot(id, button), key);
+                    actions.push(Action::Simulate(Event::KeyDown { key, code: None }));
+                }
+                None => {
+                    if let Some(ch) = char::from_u32(u32::from(id)).filter(|ch| !ch.is_control()) {
+                        actions.push(Action::Type(ch));
+                    }
+                }
+            },
+            Message::KeyRepeat {
+                id, count, button, ..
+            } => {
+                if let Some(&key) = self.keys.get(&slot(id, button)) {
+                    for _ in 0..count {
+                        actions.push(Action::Simulate(Event::KeyDown { key, code: None }));
+                    }
+                }
+            }
+            Message::KeyUp { id, button, .. } => {
+                // The key pressed for the id, which may have changed with the modifiers
+                let key = self
+                    .keys
+                    .remove(&slot(id, button))
+                    .or_else(|| key_for_id(id));
+                if let Some(key) = key {
+                    actions.push(Action::Simulate(Event::KeyUp { key, code: None }));
+                }
+            }
+            Message::MouseDown { button } => {
+                if let Some(button) = button_for_id(button) {
+                    self.buttons.push(button);
+                    actions.push(Action::Simulate(Event::MouseDown { button }));
+                }
+            }
+            Message::MouseUp { button } => {
+                if let Some(button) = button_for_id(button) {
+                    self.buttons.retain(|&held| held != button);
+                    actions.push(Action::Simulate(Event::MouseUp { button }));
+                }
+            }
+            Message::MouseMove { x, y } => actions.push(Action::MoveTo(x.into(), y.into())),
+            Message::MouseRelativeMove { dx, dy } => {
+                actions.push(Action::Simulate(Event::MouseMove {
+                    delta: Point {
+                        x: dx.into(),
+                        y: dy.into(),
+                    },
+                }));
+            }
+            Message::MouseWheel { dx, dy } => {
+                actions.push(Action::Simulate(Event::MouseWheel {
+                    delta: Point {
+                        x: f64::from(dx) / WHEEL_STEP,
+                        y: f64::from(dy) / WHEEL_STEP,
+                    },
+                    raw: None,
+                }));
+            }
+            Message::Close => {
+                return Err(io::Error::new(
+                    io::ErrorKind::ConnectionAborted,
+                    "server closed the connection",
+                ));
+            }
+            Message::Incompatible { major, minor } => {
+                return Err(invalid_data(&format!(
+                    "server requires protocol version {}.{}",
+                    major, minor
+                )));
+            }
+            Message::Busy => {
+                return Err(invalid_data("a client with this name is already connected"));
+            }
+            Message::UnknownClient => {
+                return Err(invalid_data("the server has no screen with this name"));
+            }
+            Message::BadProtocol => return Err(invalid_data("the server rejected a message")),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Releases every key and button the server pressed.
+    pub(crate) fn release_all(&mut self, actions: &mut Vec<Action>) {
+        for (_, key) in self.keys.drain() {
+            actions.push(Action::Simulate(Event::KeyUp { key, code: None }));
+        }
+        for button in self.buttons.drain(..) {
+            actions.push(Action::Simulate(Event::MouseUp { button }));
+        }
+    }
+}
+
+/// Identifies a held key by the server's key code, or by its id for servers that do not
+/// send one.
+fn slot(id: u16, button: u16) -> u16 {
+    if button != 0 { button } else { id }
+}
+
+/// A connection to a Barrier server, as one of its screens.
+pub struct Client<T: Transport> {
+    transport: T,
+    server_version: (u16, u16),
+}
+
+impl<T: Transport> Client<T> {
+    /// Waits for the server's greeting and answers it with the screen `name`, which must
+    /// match a screen in the server's configuration.
+    pub fn connect(mut transport: T, name: &str) -> io::Result<Self> {
+        let Message::Hello { major, minor } = Message::decode(&transport.recv()?)? else {
+            return Err(invalid_data("expected hello"));
+        };
+        if major != PROTOCOL_VERSION.0 {
+            return Err(invalid_data(&format!(
+                "unsupported protocol version {}.{}",
+                major, minor
+            )));
+        }
+
+        let mut client = Self {
+            transport,
+            server_version: (major, minor),
+        };
+        client.send(&Message::HelloBack {
+            major: PROTOCOL_VERSION.0,
+            minor: PROTOCOL_VERSION.1.min(minor),
+            name: name.to_string(),
+        })?;
+        Ok(client)
+    }
+
+    /// Returns the protocol version the server announced.
+    pub fn server_version(&self) -> (u16, u16) {
+        self.server_version
+    }
+
+    /// Sends a message to the server.
+    pub fn send(&mut self, message: &Message) -> io::Result<()> {
+        let mut frame = Vec::new();
+        message.encode(&mut frame);
+        self.transport.send(&frame)
+    }
+
+    /// Blocks until the next message from the server arrives.
+    pub fn recv(&mut self) -> io::Result<Message> {
+        Message::decode(&self.transport.recv()?)
+    }
+
+    /// Replays the server's input with [`Simulate`] until the connection ends.
+    ///
+    /// Answers keep-alives and screen queries, moves the pointer to where it entered
+    /// the screen, and releases everything still held when it leaves. Characters with
+    /// no key on the active layout are typed with [`Simulate::unicode`]. Clipboard and
+    /// option messages are ignored.
+    ///
+    /// Blocks the current thread; returns the error that ended the connection.
+    pub fn run(mut self) -> io::Error {
+        let mut replayer = Replayer::default();
+        let mut actions = Vec::new();
+        let err = loop {
+            let handled = self
+                .recv()
+                .and_then(|message| replayer.handle(message, &mut actions));
+            let result = handled.and_then(|()| self.perform(&mut actions));
+            if let Err(err) = result {
+                break err;
+            }
+        };
+
+        actions.clear();
+        replayer.release_all(&mut actions);
+        let _ = self.perform(&mut actions);
+        err
+    }
+
+    fn perform(&mut self, actions: &mut Vec<Action>) -> io::Result<()> {
+        for action in actions.drain(..) {
+            match action {
+                Action::Reply(message) => self.send(&message)?,
+                Action::ReportInfo => self.send(&Message::Info(ScreenInfo::current()))?,
+                Action::Simulate(event) => Simulate::simulate(event),
+                Action::MoveTo(x, y) => Simulate::mouse_move_to(x, y),
+                Action::Type(ch) => Simulate::unicode(ch.encode_utf8(&mut [0u8; 4])),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn take_u8(input: &mut &[u8]) -> io::Result<u8> {
+    wire::take::<1>(input)
+        .map(|[b]| b)
+        .ok_or_else(|| invalid_data("truncated message"))
+}
+
+fn take_u16(input: &mut &[u8]) -> io::Result<u16> {
+    wire::take(input)
+        .map(u16::from_be_bytes)
+        .ok_or_else(|| invalid_data("truncated message"))
+}
+
+fn take_i16(input: &mut &[u8]) -> io::Result<i16> {
+    take_u16(input).map(|value| value as i16)
+}
+
+fn take_u32(input: &mut &[u8]) -> io::Result<u32> {
+    wire::take(input)
+        .map(u32::from_be_bytes)
+        .ok_or_else(|| invalid_data("truncated message"))
+}
+
+fn take_string(input: &mut &[u8]) -> io::Result<String> {
+    let len = take_u32(input)? as usize;
+    if input.len() < len {
+        return Err(invalid_data("truncated message"));
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    String::from_utf8(bytes.to_vec()).map_err(|_| invalid_data("invalid string"))
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use super::*;
+
+    fn roundtrip(message: Message) {
+        let mut frame = Vec::new();
+        message.encode(&mut frame);
+        assert_eq!(Message::decode(&frame).unwrap(), message);
+    }
+
+    #[test]
+    fn test_message_layout() {
+        let mut frame = Vec::new();
+        Message::MouseMove { x: 300, y: -2 }.encode(&mut frame);
+        assert_eq!(frame, b"DMMV\x01\x2c\xff\xfe");
+
+        let mut frame = Vec::new();
+        Message::Hello { major: 1, minor: 6 }.encode(&mut frame);
+        assert_eq!(frame, b"Barrier\x00\x01\x00\x06");
+
+        roundtrip(Message::HelloBack {
+            major: 1,
+            minor: 6,
+            name: "laptop".to_string(),
+        });
+        roundtrip(Message::Info(ScreenInfo {
+            x: -1920,
+            y: 0,
+            width: 3840,
+            height: 1080,
+            mouse_x: 10,
+            mouse_y: 20,
+        }));
+        roundtrip(Message::Enter {
+            x: 1,
+            y: 2,
+            sequence: 7,
+            modifiers: 1,
+        });
+        roundtrip(Message::KeyRepeat {
+            id: 0x61,
+            modifiers: 0,
+            count: 3,
+            button: 38,
+        });
+        roundtrip(Message::MouseWheel { dx: 0, dy: -120 });
+        roundtrip(Message::Other {
+            code: *b"DCLP",
+            payload: vec![1, 2, 3],
+        });
+
+        assert_eq!(
+            Message::decode(b"DKDN\x00").unwrap_err().kind(),
+            io::ErrorKind::InvalidData
+        );
+    }
+
+    #[test]
+    fn test_replayer_translates_input() {
+        let mut replayer = Replayer::default();
+        let mut actions = Vec::new();
+        for message in [
+            Message::KeepAlive,
+            Message::QueryInfo,
+            Message::Enter {
+                x: 5,
+                y: 6,
+                sequence: 1,
+                modifiers: 0,
+            },
+            Message::KeyDown {
+                id: 0xEFE1,
+                modifiers: 0,
+                button: 50,
+            },
+            Message::MouseDown { button: 3 },
+            Message::MouseWheel { dx: 0, dy: 240 },
+            Message::KeyDown {
+                id: 0xD83D,
+                modifiers: 0,
+                button: 0,
+            },
+            // No layout has a key for it
+            Message::KeyDown {
+                id: 0x2603,
+                modifiers: 0,
+                button: 0,
+            },
+        ] {
+            replayer.handle(message, &mut actions).unwrap();
+        }
+
+        assert_eq!(actions[0], Action::Reply(Message::KeepAlive));
+        assert_eq!(actions[1], Action::ReportInfo);
+        assert_eq!(actions[2], Action::MoveTo(5.0, 6.0));
+        assert_eq!(
+            actions[3],
+            Action::Simulate(Event::KeyDown {
+                key: Key::ShiftLeft,
+                code: None
+            })
+        );
+        assert_eq!(
+            actions[4],
+            Action::Simulate(Event::MouseDown {
+                button: MouseButton::Right
+            })
+        );
+        assert_eq!(
+            actions[5],
+            Action::Simulate(Event::MouseWheel {
+                delta: Point { x: 0.0, y: 2.0 },
+                raw: None,
+            })
+        );
+        // Surrogates are no characters and are dropped
+        assert_eq!(actions[6..], [Action::Type('☃')]);
+    }
+
+    #[test]
+    fn test_leave_releases_held_input() {
+        let mut replayer = Replayer::default();
+        let mut actions = Vec::new();
+        let shift = |down| match down {
+            true => Message::KeyDown {
+                id: 0xEFE1,
+                modifiers: 0,
+                button: 50,
+            },
+            false => Message::KeyUp {
+                id: 0xEFE1,
+                modifiers: 1,
+                button: 50,
+            },
+        };
+        replayer.handle(shift(true), &mut actions).unwrap();
+        replayer.handle(shift(false), &mut actions).unwrap();
+        replayer
+            .handle(
+                Message::KeyDown {
+                    id: 0xEF1B,
+                    modifiers: 0,
+                    button: 9,
+                },
+                &mut actions,
+            )
+            .unwrap();
+        replayer
+            .handle(Message::MouseDown { button: 1 }, &mut actions)
+            .unwrap();
+
+        actions.clear();
+        replayer.handle(Message::Leave, &mut actions).unwrap();
+        assert_eq!(
+            actions,
+            [
+                Action::Simulate(Event::KeyUp {
+                    key: Key::Escape,
+                    code: None
+                }),
+                Action::Simulate(Event::MouseUp {
+                    button: MouseButton::Left
+                }),
+            ]
+        );
+
+        let err = replayer.handle(Message::Busy, &mut actions).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_client_handshake() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut transport = BarrierTransport::new(stream).unwrap();
+            let mut hello = Vec::new();
+            Message::Hello { major: 1, minor: 8 }.encode(&mut hello);
+            transport.send(&hello).unwrap();
+            Message::decode(&transport.recv().unwrap()).unwrap()
+        });
+
+        let stream = TcpStream::connect(addr).unwrap();
+        let client = Client::connect(BarrierTransport::new(stream).unwrap(), "laptop").unwrap();
+        assert_eq!(client.server_version(), (1, 8));
+        assert_eq!(
+            server.join().unwrap(),
+            Message::HelloBack {
+                major: 1,
+                minor: 6,
+                name: "laptop".to_string(),
+            }
+        );
+    }
+}
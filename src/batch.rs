@@ -0,0 +1,41 @@
+use std::{
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Listen, event::Event, subscription::SubscriptionHandle};
+
+/// Subscribes `callback` to batches of events, see
+/// [`Listen::subscribe_batch`](crate::Listen::subscribe_batch).
+pub(crate) fn subscribe<F>(interval: Duration, mut callback: F) -> SubscriptionHandle
+where
+    F: FnMut(&[Event]) + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<Event>();
+
+    // The worker ends once the subscription, and with it the sender, is dropped
+    thread::Builder::new()
+        .name("raw-input-batch".to_string())
+        .spawn(move || {
+            let mut batch = Vec::new();
+            while let Ok(first) = receiver.recv() {
+                batch.push(first);
+                let deadline = Instant::now() + interval;
+                loop {
+                    let timeout = deadline.saturating_duration_since(Instant::now());
+                    match receiver.recv_timeout(timeout) {
+                        Ok(event) => batch.push(event),
+                        Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+                callback(&batch);
+                batch.clear();
+            }
+        })
+        .expect("failed to spawn thread");
+
+    Listen::subscribe(move |event| {
+        let _ = sender.send(event);
+    })
+}
@@ -0,0 +1,86 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use crate::{
+    StateError,
+    platform::{GrabImpl, PlatformGrab},
+};
+
+/// Set while an [`InputBlock`] exists, there is only one.
+static IS_BLOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Blocks all physical input until dropped or its timeout expires, see
+/// [`Grab::block_input`](crate::Grab::block_input).
+#[must_use = "input is unblocked when the block is dropped"]
+pub struct InputBlock {
+    release: Sender<()>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl InputBlock {
+    /// Unblocks input, the same as dropping the block.
+    pub fn release(self) {}
+
+    /// Returns `true` until the block is released or its timeout expired.
+    pub fn is_active(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+}
+
+impl Drop for InputBlock {
+    fn drop(&mut self) {
+        let _ = self.release.send(());
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Blocks input for at most `timeout`.
+///
+/// The block is held by a thread of its own, since on Windows only the thread that
+/// blocked input may unblock it.
+pub(crate) fn start(timeout: Duration) -> Result<InputBlock, StateError> {
+    if IS_BLOCKED.swap(true, Ordering::SeqCst) {
+        return Err(StateError::AlreadyRunning);
+    }
+
+    let (started_tx, started_rx) = mpsc::channel();
+    let (release, release_rx) = mpsc::channel::<()>();
+    let thread = thread::Builder::new()
+        .name("raw-input-block".to_string())
+        .spawn(move || {
+            let result = PlatformGrab::block_input(true);
+            let blocked = result.is_ok();
+            let _ = started_tx.send(result);
+            if blocked {
+                // Released, dropped, or timed out
+                let _ = release_rx.recv_timeout(timeout);
+                let _ = PlatformGrab::block_input(false);
+            }
+            IS_BLOCKED.store(false, Ordering::SeqCst);
+        })
+        .expect("failed to spawn thread");
+
+    match started_rx.recv() {
+        Ok(Ok(())) => Ok(InputBlock {
+            release,
+            thread: Some(thread),
+        }),
+        result => {
+            let _ = thread.join();
+            Err(result
+                .ok()
+                .and_then(Result::err)
+                .unwrap_or(StateError::NotStarted))
+        }
+    }
+}
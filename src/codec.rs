@@ -0,0 +1,117 @@
+//! The [serde representation](crate::SCHEMA_VERSION) in compact binary formats.
+//!
+//! The `postcard` feature adds [`to_postcard`] and [`from_postcard`], which neither
+//! allocate nor need `std` on the encoding side, so events can be passed to firmware or
+//! over fixed-size buffers. The `bincode` feature adds [`to_bincode`] and
+//! [`from_bincode`]. Both work with any type of the crate that implements `Serialize`,
+//! and pin the format options so data written by one crate version reads in another.
+//!
+//! Unlike [`wire`](crate::wire), neither format can skip unknown variants: a reader that
+//! predates a variant fails on it.
+//!
+//! # Example
+//! ```
+//! # #[cfg(feature = "postcard")] {
+//! use raw_input::{Event, Key, codec};
+//!
+//! let event = Event::KeyDown { key: Key::KeyA, code: Some(65) };
+//! let mut buffer = [0; codec::MAX_EVENT_LEN];
+//! let bytes = codec::to_postcard(&event, &mut buffer).unwrap();
+//! assert_eq!(codec::from_postcard::<Event>(bytes).unwrap(), event);
+//! # }
+//! ```
+
+use serde::{Serialize, de::DeserializeOwned};
+
+/// Size of a buffer that holds any [`Event`](crate::Event) encoded with
+/// [`to_postcard`] or [`to_bincode`].
+pub const MAX_EVENT_LEN: usize = 64;
+
+/// Encodes `value` with postcard into `buffer` and returns the part written. Fails if
+/// the buffer is too small.
+#[cfg(feature = "postcard")]
+pub fn to_postcard<'a, T>(value: &T, buffer: &'a mut [u8]) -> Result<&'a mut [u8], postcard::Error>
+where
+    T: Serialize + ?Sized,
+{
+    postcard::to_slice(value, buffer)
+}
+
+/// Decodes a value encoded with [`to_postcard`]. Bytes after the value are ignored.
+#[cfg(feature = "postcard")]
+pub fn from_postcard<T>(bytes: &[u8]) -> Result<T, postcard::Error>
+where
+    T: DeserializeOwned,
+{
+    postcard::from_bytes(bytes)
+}
+
+/// Options of the bincode encoding: little-endian varints, as bincode's `standard()`.
+#[cfg(feature = "bincode")]
+const BINCODE_CONFIG: bincode::config::Configuration = bincode::config::standard();
+
+/// Encodes `value` with bincode.
+#[cfg(feature = "bincode")]
+pub fn to_bincode<T>(value: &T) -> Result<Vec<u8>, bincode::error::EncodeError>
+where
+    T: Serialize + ?Sized,
+{
+    bincode::serde::encode_to_vec(value, BINCODE_CONFIG)
+}
+
+/// Decodes a value encoded with [`to_bincode`]. Bytes after the value are ignored.
+#[cfg(feature = "bincode")]
+pub fn from_bincode<T>(bytes: &[u8]) -> Result<T, bincode::error::DecodeError>
+where
+    T: DeserializeOwned,
+{
+    bincode::serde::decode_from_slice(bytes, BINCODE_CONFIG).map(|(value, _)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Event, Key, RawKey, wire::tests::every_event};
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn test_postcard_roundtrip_every_variant() {
+        let mut buffer = [0; MAX_EVENT_LEN];
+        for event in every_event() {
+            let bytes = to_postcard(&event, &mut buffer).unwrap();
+            assert_eq!(from_postcard::<Event>(bytes).unwrap(), event);
+        }
+        for key in Key::ALL.iter().copied().chain([Key::Raw(RawKey(u32::MAX))]) {
+            let bytes = to_postcard(&key, &mut buffer).unwrap();
+            assert_eq!(from_postcard::<Key>(bytes).unwrap(), key);
+        }
+    }
+
+    #[cfg(feature = "bincode")]
+    #[test]
+    fn test_bincode_roundtrip_every_variant() {
+        for event in every_event() {
+            let bytes = to_bincode(&event).unwrap();
+            assert!(bytes.len() <= MAX_EVENT_LEN);
+            assert_eq!(from_bincode::<Event>(&bytes).unwrap(), event);
+        }
+        for key in Key::ALL.iter().copied().chain([Key::Raw(RawKey(u32::MAX))]) {
+            assert_eq!(
+                from_bincode::<Key>(&to_bincode(&key).unwrap()).unwrap(),
+                key
+            );
+        }
+    }
+
+    #[cfg(feature = "postcard")]
+    #[test]
+    fn test_postcard_is_compact() {
+        let mut buffer = [0; MAX_EVENT_LEN];
+        let key = Event::KeyUp {
+            key: Key::Escape,
+            code: None,
+        };
+        assert!(to_postcard(&key, &mut buffer).unwrap().len() <= 4);
+        assert!(to_postcard(&key, &mut [0; 1]).is_err());
+    }
+}
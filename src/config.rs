@@ -0,0 +1,597 @@
+//! Declarative setup from a TOML file.
+//!
+//! [`from_toml`] reads the `Core` options, the `Listen` and `Grab` flags, hotkeys and
+//! key remaps from a file, and [`Config::apply`] puts them into effect. Settings missing
+//! from the file are left as they are. Starting `Core`, `Listen` and `Grab` is left to
+//! the application.
+//!
+//! ```toml
+//! [core]
+//! managed = true
+//! listen_only = false
+//! tap_location = "Session"      # Hid, Session or AnnotatedSession
+//! thread_priority = "High"      # Normal, High or TimeCritical
+//!
+//! [listen]
+//! mouse_move = false
+//! keyboard = true
+//! ignore_injected = true
+//! only_when_exe = "code.exe"
+//!
+//! [grab]
+//! keyboard = true
+//! pass_injected = true
+//! only_when_bundle_id = "com.example.Game"
+//!
+//! [[hotkey]]
+//! name = "toggle"
+//! keys = "Ctrl+Alt+T"           # or a sequence, e.g. "Ctrl+K D"
+//! suppress = true
+//! timeout_ms = 800
+//!
+//! [remap]
+//! CapsLock = "Escape"
+//! ```
+//!
+//! `[listen]` accepts `mouse_move`, `mouse_wheel`, `mouse_button`, `keyboard`,
+//! `scancode_mode`, `ignore_injected` and `scroll_lines`; `[grab]` accepts `mouse_move`,
+//! `mouse_wheel`, `mouse_button`, `keyboard`, `pass_injected` and `exclude_own_windows`.
+//! Both accept `only_when_exe` or `only_when_bundle_id`. Each takes the argument of the
+//! method of the same name. Keys and shortcuts use the names [`Key`] and
+//! [`Shortcut`](crate::Shortcut) parse.
+//!
+//! Unknown sections and keys are errors, so typos do not go unnoticed.
+//!
+//! # Example
+//! ```no_run
+//! use raw_input::{Core, Grab, Listen, config};
+//!
+//! let config = config::from_toml("raw-input.toml").expect("invalid configuration");
+//! let handle = config.apply(|name| println!("Hotkey {} pressed", name));
+//!
+//! std::thread::spawn(|| Core::start());
+//! Listen::start().ok();
+//! Grab::start().ok();
+//!
+//! // Later
+//! handle.remove();
+//! ```
+
+use std::{fmt, fs, io, path::Path, sync::Arc, time::Duration};
+
+use serde::{Deserialize, Deserializer, de};
+use toml::Spanned;
+
+use crate::{
+    AppMatcher, Core, Grab, HotkeyHandle, HotkeySequence, Key, Listen, Remap, RemapHandle,
+    TapLocation, ThreadPriority,
+};
+
+/// An error produced by [`from_toml`] or [`parse`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// A line is not valid: bad syntax, an unknown section or key, or a value of the
+    /// wrong type. Lines are counted from 1.
+    Invalid { line: usize, message: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read the configuration: {err}"),
+            Self::Invalid { line, message } => write!(f, "line {line}: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Invalid { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for ConfigError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+/// The `[core]` section. `None` leaves a setting unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct CoreConfig {
+    pub managed: Option<bool>,
+    pub listen_only: Option<bool>,
+    pub tap_location: Option<TapLocation>,
+    pub thread_priority: Option<ThreadPriority>,
+}
+
+/// The `[listen]` section. `None` leaves a setting unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListenConfig {
+    pub mouse_move: Option<bool>,
+    pub mouse_wheel: Option<bool>,
+    pub mouse_button: Option<bool>,
+    pub keyboard: Option<bool>,
+    pub scancode_mode: Option<bool>,
+    pub ignore_injected: Option<bool>,
+    pub scroll_lines: Option<bool>,
+    pub only_when_app: Option<AppMatcher>,
+}
+
+/// The `[grab]` section. `None` leaves a setting unchanged.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct GrabConfig {
+    pub mouse_move: Option<bool>,
+    pub mouse_wheel: Option<bool>,
+    pub mouse_button: Option<bool>,
+    pub keyboard: Option<bool>,
+    pub pass_injected: Option<bool>,
+    pub exclude_own_windows: Option<bool>,
+    pub only_when_app: Option<AppMatcher>,
+}
+
+/// A `[[hotkey]]` entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HotkeyConfig {
+    /// Passed to the callback of [`Config::apply`] when the hotkey fires.
+    pub name: String,
+    pub sequence: HotkeySequence,
+}
+
+/// A parsed configuration file.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Config {
+    pub core: CoreConfig,
+    pub listen: ListenConfig,
+    pub grab: GrabConfig,
+    pub hotkeys: Vec<HotkeyConfig>,
+    /// `(from, to)` pairs of the `[remap]` section, in file order.
+    pub remaps: Vec<(Key, Key)>,
+}
+
+impl Config {
+    /// Applies the settings and registers the hotkeys and remaps.
+    ///
+    /// `on_hotkey` is called with the name of a hotkey when it fires, on the core thread.
+    /// The hotkeys and remaps stay registered until [`ConfigHandle::remove`] is called.
+    pub fn apply<F>(&self, on_hotkey: F) -> ConfigHandle
+    where
+        F: Fn(&str) + Send + Sync + 'static,
+    {
+        let core = &self.core;
+        if let Some(enable) = core.managed {
+            Core::managed(enable);
+        }
+        if let Some(enable) = core.listen_only {
+            Core::listen_only(enable);
+        }
+        if let Some(location) = core.tap_location {
+            Core::tap_location(location);
+        }
+        if let Some(priority) = core.thread_priority {
+            Core::thread_priority(priority);
+        }
+
+        let listen = &self.listen;
+        set_flag(listen.mouse_move, Listen::mouse_move);
+        set_flag(listen.mouse_wheel, Listen::mouse_wheel);
+        set_flag(listen.mouse_button, Listen::mouse_button);
+        set_flag(listen.keyboard, Listen::keyboard);
+        set_flag(listen.scancode_mode, Listen::scancode_mode);
+        set_flag(listen.ignore_injected, Listen::ignore_injected);
+        set_flag(listen.scroll_lines, Listen::scroll_lines);
+        if let Some(matcher) = &listen.only_when_app {
+            Listen::only_when_app(Some(matcher.clone()));
+        }
+
+        let grab = &self.grab;
+        set_flag(grab.mouse_move, Grab::mouse_move);
+        set_flag(grab.mouse_wheel, Grab::mouse_wheel);
+        set_flag(grab.mouse_button, Grab::mouse_button);
+        set_flag(grab.keyboard, Grab::keyboard);
+        set_flag(grab.pass_injected, Grab::pass_injected);
+        set_flag(grab.exclude_own_windows, Grab::exclude_own_windows);
+        if let Some(matcher) = &grab.only_when_app {
+            Grab::only_when_app(Some(matcher.clone()));
+        }
+
+        let on_hotkey = Arc::new(on_hotkey);
+        let hotkeys = self
+            .hotkeys
+            .iter()
+            .map(|hotkey| {
+                let name = hotkey.name.clone();
+                let on_hotkey = on_hotkey.clone();
+                hotkey.sequence.clone().register(move || on_hotkey(&name))
+            })
+            .collect();
+        let remaps = self
+            .remaps
+            .iter()
+            .map(|&(from, to)| Remap::add(from, to))
+            .collect();
+
+        ConfigHandle { hotkeys, remaps }
+    }
+}
+
+/// Calls `set` with a flag the file gives a value.
+fn set_flag(value: Option<bool>, set: fn(bool)) {
+    if let Some(enable) = value {
+        set(enable);
+    }
+}
+
+/// The hotkeys and remaps registered by [`Config::apply`].
+pub struct ConfigHandle {
+    hotkeys: Vec<HotkeyHandle>,
+    remaps: Vec<RemapHandle>,
+}
+
+impl ConfigHandle {
+    /// Unregisters the hotkeys and removes the remaps. Other settings are kept.
+    pub fn remove(self) {
+        self.hotkeys.into_iter().for_each(HotkeyHandle::unregister);
+        self.remaps.into_iter().for_each(RemapHandle::remove);
+    }
+}
+
+/// Reads a configuration file.
+pub fn from_toml(path: impl AsRef<Path>) -> Result<Config, ConfigError> {
+    parse(&fs::read_to_string(path)?)
+}
+
+/// Parses a configuration, as [`from_toml`] does for the contents of a file.
+pub fn parse(text: &str) -> Result<Config, ConfigError> {
+    let file: File = toml::from_str(text).map_err(|err| ConfigError::Invalid {
+        line: err.span().map_or(1, |span| line_at(text, span.start)),
+        message: err.message().to_string(),
+    })?;
+    let invalid = |value: &Spanned<String>, message: String| ConfigError::Invalid {
+        line: line_at(text, value.span().start),
+        message,
+    };
+    let parse_key = |name: &Spanned<String>| {
+        name.get_ref()
+            .parse::<Key>()
+            .map_err(|_| invalid(name, format!("unknown key {:?}", name.get_ref())))
+    };
+    let app_matcher =
+        |exe: Option<Spanned<String>>, bundle_id: Option<Spanned<String>>| match (exe, bundle_id) {
+            (Some(exe), Some(_)) => Err(invalid(
+                &exe,
+                "only one of only_when_exe and only_when_bundle_id may be set".to_string(),
+            )),
+            (Some(exe), None) => Ok(Some(AppMatcher::exe(exe.into_inner()))),
+            (None, Some(id)) => Ok(Some(AppMatcher::bundle_id(id.into_inner()))),
+            (None, None) => Ok(None),
+        };
+
+    let core = CoreConfig {
+        managed: file.core.managed,
+        listen_only: file.core.listen_only,
+        tap_location: file
+            .core
+            .tap_location
+            .map(|location| match location.get_ref().as_str() {
+                "Hid" => Ok(TapLocation::Hid),
+                "Session" => Ok(TapLocation::Session),
+                "AnnotatedSession" => Ok(TapLocation::AnnotatedSession),
+                _ => Err(invalid(&location, "unknown tap location".to_string())),
+            })
+            .transpose()?,
+        thread_priority: file
+            .core
+            .thread_priority
+            .map(|priority| match priority.get_ref().as_str() {
+                "Normal" => Ok(ThreadPriority::Normal),
+                "High" => Ok(ThreadPriority::High),
+                "TimeCritical" => Ok(ThreadPriority::TimeCritical),
+                _ => Err(invalid(&priority, "unknown thread priority".to_string())),
+            })
+            .transpose()?,
+    };
+
+    let listen = file.listen;
+    let listen = ListenConfig {
+        mouse_move: listen.mouse_move,
+        mouse_wheel: listen.mouse_wheel,
+        mouse_button: listen.mouse_button,
+        keyboard: listen.keyboard,
+        scancode_mode: listen.scancode_mode,
+        ignore_injected: listen.ignore_injected,
+        scroll_lines: listen.scroll_lines,
+        only_when_app: app_matcher(listen.only_when_exe, listen.only_when_bundle_id)?,
+    };
+
+    let grab = file.grab;
+    let grab = GrabConfig {
+        mouse_move: grab.mouse_move,
+        mouse_wheel: grab.mouse_wheel,
+        mouse_button: grab.mouse_button,
+        keyboard: grab.keyboard,
+        pass_injected: grab.pass_injected,
+        exclude_own_windows: grab.exclude_own_windows,
+        only_when_app: app_matcher(grab.only_when_exe, grab.only_when_bundle_id)?,
+    };
+
+    let hotkeys = file
+        .hotkey
+        .into_iter()
+        .map(|hotkey| {
+            let keys = &hotkey.keys;
+            let sequence = keys.get_ref().parse::<HotkeySequence>();
+            let mut sequence = sequence
+                .map_err(|err| invalid(keys, err.to_string()))?
+                .suppress(hotkey.suppress);
+            if let Some(ms) = hotkey.timeout_ms {
+                sequence = sequence.timeout(Duration::from_millis(ms));
+            }
+            Ok(HotkeyConfig {
+                name: hotkey.name,
+                sequence,
+            })
+        })
+        .collect::<Result<_, ConfigError>>()?;
+
+    let remaps = file
+        .remap
+        .0
+        .iter()
+        .map(|(from, to)| Ok((parse_key(from)?, parse_key(to)?)))
+        .collect::<Result<_, ConfigError>>()?;
+
+    Ok(Config {
+        core,
+        listen,
+        grab,
+        hotkeys,
+        remaps,
+    })
+}
+
+/// Returns the line, counted from 1, of a byte offset into `text`.
+fn line_at(text: &str, offset: usize) -> usize {
+    let before = &text.as_bytes()[..offset.min(text.len())];
+    before.iter().filter(|&&byte| byte == b'\n').count() + 1
+}
+
+/// The file as written, before keys, shortcuts and names are resolved. Strings keep
+/// their position for the errors of that step.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct File {
+    #[serde(default)]
+    core: CoreFile,
+    #[serde(default)]
+    listen: ListenFile,
+    #[serde(default)]
+    grab: GrabFile,
+    #[serde(default)]
+    hotkey: Vec<HotkeyFile>,
+    #[serde(default)]
+    remap: RemapFile,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CoreFile {
+    managed: Option<bool>,
+    listen_only: Option<bool>,
+    tap_location: Option<Spanned<String>>,
+    thread_priority: Option<Spanned<String>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ListenFile {
+    mouse_move: Option<bool>,
+    mouse_wheel: Option<bool>,
+    mouse_button: Option<bool>,
+    keyboard: Option<bool>,
+    scancode_mode: Option<bool>,
+    ignore_injected: Option<bool>,
+    scroll_lines: Option<bool>,
+    only_when_exe: Option<Spanned<String>>,
+    only_when_bundle_id: Option<Spanned<String>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct GrabFile {
+    mouse_move: Option<bool>,
+    mouse_wheel: Option<bool>,
+    mouse_button: Option<bool>,
+    keyboard: Option<bool>,
+    pass_injected: Option<bool>,
+    exclude_own_windows: Option<bool>,
+    only_when_exe: Option<Spanned<String>>,
+    only_when_bundle_id: Option<Spanned<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HotkeyFile {
+    name: String,
+    keys: Spanned<String>,
+    #[serde(default)]
+    suppress: bool,
+    timeout_ms: Option<u64>,
+}
+
+/// The `[remap]` table, in file order.
+#[derive(Default)]
+struct RemapFile(Vec<(Spanned<String>, Spanned<String>)>);
+
+impl<'de> Deserialize<'de> for RemapFile {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = RemapFile;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a table of key names")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<RemapFile, A::Error> {
+                let mut remaps = Vec::new();
+                while let Some(entry) = map.next_entry()? {
+                    remaps.push(entry);
+                }
+                Ok(RemapFile(remaps))
+            }
+        }
+
+        deserializer.deserialize_map(Visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Modifiers, Shortcut};
+
+    fn line_of(err: ConfigError) -> usize {
+        match err {
+            ConfigError::Invalid { line, .. } => line,
+            err => panic!("unexpected error {err}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_config() {
+        let config = parse(
+            r#"
+            # Settings
+            [core]
+            tap_location = "Session"
+            managed = true
+
+            [listen]
+            mouse_move = false # too noisy
+            only_when_exe = "code.exe"
+
+            [grab]
+            keyboard = true
+
+            [[hotkey]]
+            name = "say #1"
+            keys = "Ctrl+K D"
+            suppress = true
+            timeout_ms = 1_500
+
+            [[hotkey]]
+            name = 'quit'
+            keys = "Ctrl+Shift+Q"
+
+            [remap]
+            CapsLock = "Escape"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.core,
+            CoreConfig {
+                managed: Some(true),
+                tap_location: Some(TapLocation::Session),
+                ..Default::default()
+            }
+        );
+        assert_eq!(config.listen.mouse_move, Some(false));
+        assert_eq!(config.listen.keyboard, None);
+        assert_eq!(
+            config.listen.only_when_app,
+            Some(AppMatcher::exe("code.exe"))
+        );
+        assert_eq!(config.grab.keyboard, Some(true));
+        assert_eq!(
+            config.hotkeys,
+            [
+                HotkeyConfig {
+                    name: "say #1".to_string(),
+                    sequence: "Ctrl+K D"
+                        .parse::<HotkeySequence>()
+                        .unwrap()
+                        .suppress(true)
+                        .timeout(Duration::from_millis(1500)),
+                },
+                HotkeyConfig {
+                    name: "quit".to_string(),
+                    sequence: HotkeySequence::new([Shortcut::new(
+                        Modifiers::CONTROL | Modifiers::SHIFT,
+                        Key::KeyQ,
+                    )]),
+                },
+            ]
+        );
+        assert_eq!(config.remaps, [(Key::CapsLock, Key::Escape)]);
+    }
+
+    #[test]
+    fn test_parse_full_toml() {
+        let config = parse(
+            r#"
+            listen = { keyboard = true, mouse_move = false }
+            grab.exclude_own_windows = true
+            hotkey = [
+                { name = "one", keys = "Ctrl+1" },
+            ]
+
+            [remap]
+            KeyB = """KeyA"""
+            KeyA = "KeyB"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.listen.keyboard, Some(true));
+        assert_eq!(config.listen.mouse_move, Some(false));
+        assert_eq!(config.grab.exclude_own_windows, Some(true));
+        assert_eq!(config.hotkeys.len(), 1);
+        assert_eq!(
+            config.remaps,
+            [(Key::KeyB, Key::KeyA), (Key::KeyA, Key::KeyB)]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(line_of(parse("managed = true").unwrap_err()), 1);
+        assert_eq!(line_of(parse("[core]\nmanaged = 1").unwrap_err()), 2);
+        assert_eq!(line_of(parse("[core]\nmanged = true").unwrap_err()), 2);
+        assert_eq!(line_of(parse("\n\n[mouse]").unwrap_err()), 3);
+        assert_eq!(line_of(parse("[grab]\n[grab]").unwrap_err()), 2);
+        assert_eq!(line_of(parse("[listen]\nkeyboard = \"yes").unwrap_err()), 2);
+        assert_eq!(
+            line_of(parse("[remap]\nCapsLock = \"Nope\"").unwrap_err()),
+            2
+        );
+        assert_eq!(line_of(parse("[[hotkey]]\nname = \"x\"").unwrap_err()), 1);
+        assert_eq!(
+            line_of(parse("[[hotkey]]\nname = \"x\"\nkeys = \"Ctrl+\"").unwrap_err()),
+            3
+        );
+        assert_eq!(
+            line_of(parse("[core]\n\nthread_priority = \"Low\"").unwrap_err()),
+            3
+        );
+        assert_eq!(
+            line_of(
+                parse("[grab]\nonly_when_exe = \"a\"\nonly_when_bundle_id = \"b\"").unwrap_err()
+            ),
+            2
+        );
+        assert!(matches!(
+            from_toml("/nonexistent/raw-input.toml"),
+            Err(ConfigError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,118 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU8, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{CursorPosition, Display};
+
+const RUNNING: u8 = 0;
+const PAUSED: u8 = 1;
+const STOPPED: u8 = 2;
+
+/// Reports a sampled cursor position only when it differs from the previous one.
+#[derive(Debug, Default)]
+pub(crate) struct Sampler {
+    last: Option<CursorPosition>,
+}
+
+impl Sampler {
+    pub(crate) fn sample(&mut self, position: Option<CursorPosition>) -> Option<CursorPosition> {
+        let position = position?;
+        if self
+            .last
+            .as_ref()
+            .is_some_and(|last| last.physical == position.physical)
+        {
+            return None;
+        }
+        self.last = Some(position.clone());
+        Some(position)
+    }
+}
+
+/// Starts sampling the cursor every `interval` on a background thread.
+pub(crate) fn start<F>(interval: Duration, callback: F) -> CursorPollHandle
+where
+    F: Fn(CursorPosition) + Send + 'static,
+{
+    let state = Arc::new(AtomicU8::new(RUNNING));
+    let flag = state.clone();
+
+    thread::Builder::new()
+        .name("raw-input-cursor-poll".to_string())
+        .spawn(move || {
+            let mut sampler = Sampler::default();
+            loop {
+                match flag.load(Ordering::Relaxed) {
+                    STOPPED => break,
+                    // A paused poll reports the position it finds on resumption
+                    PAUSED => sampler = Sampler::default(),
+                    _ => {
+                        if let Some(position) = sampler.sample(Display::get_cursor()) {
+                            callback(position);
+                        }
+                    }
+                }
+                thread::sleep(interval);
+            }
+        })
+        .expect("failed to spawn thread");
+
+    CursorPollHandle { state }
+}
+
+/// A handle that controls a cursor poll started with
+/// [`Listen::cursor_positions`](crate::Listen::cursor_positions).
+pub struct CursorPollHandle {
+    state: Arc<AtomicU8>,
+}
+
+impl CursorPollHandle {
+    /// Pauses sampling. The callback will not be called until `resume` is called.
+    pub fn pause(&self) {
+        let _ = self
+            .state
+            .compare_exchange(RUNNING, PAUSED, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    /// Resumes a paused poll, reporting the current position with the next sample.
+    pub fn resume(&self) {
+        let _ = self
+            .state
+            .compare_exchange(PAUSED, RUNNING, Ordering::SeqCst, Ordering::SeqCst);
+    }
+
+    /// Stops sampling. The callback will not be called again.
+    pub fn stop(self) {
+        self.state.store(STOPPED, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn position(x: f64, y: f64) -> Option<CursorPosition> {
+        Some(CursorPosition {
+            physical: Point { x, y },
+            logical: Point { x, y },
+            monitor: None,
+        })
+    }
+
+    #[test]
+    fn test_sampler_reports_changes() {
+        let mut sampler = Sampler::default();
+        assert_eq!(sampler.sample(position(1.0, 2.0)), position(1.0, 2.0));
+        assert_eq!(sampler.sample(position(1.0, 2.0)), None);
+        // An unknown position is skipped without forgetting the last one
+        assert_eq!(sampler.sample(None), None);
+        assert_eq!(sampler.sample(position(1.0, 2.0)), None);
+        assert_eq!(sampler.sample(position(3.0, 2.0)), position(3.0, 2.0));
+    }
+}
@@ -0,0 +1,101 @@
+use crate::{
+    hid::HidUsage,
+    platform::{DevicesImpl, PlatformDevices},
+};
+
+/// An opaque identifier of an input device, valid while the device stays connected.
+///
+/// The same device has the same id in [`Devices::list`] and in
+/// [`HidReport`](crate::HidReport)s.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DeviceId(pub(crate) u64);
+
+/// What kind of input device a [`DeviceInfo`] describes.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum DeviceKind {
+    Mouse,
+    Keyboard,
+    /// Any other HID device, with its top-level collection usage.
+    Hid(HidUsage),
+}
+
+/// Capabilities of a mouse. Values the platform does not report are `None`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MouseInfo {
+    /// Resolution in counts per inch. Reported on macOS only.
+    pub dpi: Option<u32>,
+    /// Reports per second. Windows only reports it for PS/2 mice; macOS for most USB mice.
+    pub polling_rate: Option<u32>,
+    /// Number of buttons. Reported on Windows only.
+    pub buttons: Option<u32>,
+    /// Whether the mouse has a horizontal wheel. Reported on Windows only.
+    pub has_horizontal_wheel: Option<bool>,
+}
+
+/// Properties of a keyboard. Values the platform does not report are `None`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct KeyboardInfo {
+    /// The keyboard type, e.g. 4 for enhanced 101/102-key keyboards. Reported on
+    /// Windows only.
+    pub keyboard_type: Option<u32>,
+    /// The OEM-specific keyboard subtype. Reported on Windows only.
+    pub subtype: Option<u32>,
+    /// Number of function keys. Reported on Windows only.
+    pub function_keys: Option<u32>,
+    /// Number of LED indicators. Reported on Windows only.
+    pub indicators: Option<u32>,
+    /// Total number of keys. Reported on Windows only.
+    pub keys: Option<u32>,
+}
+
+/// A connected input device.
+///
+/// The vendor and product fields come from the device's USB or Bluetooth descriptors,
+/// so built-in PS/2 devices usually leave them `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct DeviceInfo {
+    pub id: DeviceId,
+    pub kind: DeviceKind,
+    /// USB vendor ID.
+    pub vendor_id: Option<u16>,
+    /// USB product ID.
+    pub product_id: Option<u16>,
+    /// Manufacturer name.
+    pub manufacturer: Option<String>,
+    /// Product name.
+    pub product: Option<String>,
+    /// Stable path of the device interface: the Raw Input device name on Windows, the
+    /// IORegistry path on macOS.
+    pub path: Option<String>,
+    /// Mouse capabilities, for [`DeviceKind::Mouse`].
+    pub mouse: Option<MouseInfo>,
+    /// Keyboard properties, for [`DeviceKind::Keyboard`].
+    pub keyboard: Option<KeyboardInfo>,
+}
+
+/// Input device enumeration.
+///
+/// Delta-based consumers can use [`MouseInfo::dpi`] to normalize movement across mice,
+/// and remappers can pick out a specific keyboard by its vendor and product.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{DeviceKind, Devices};
+///
+/// for device in Devices::list() {
+///     match device.kind {
+///         DeviceKind::Mouse => println!("{:?}: {:?}", device.product, device.mouse),
+///         DeviceKind::Keyboard => println!("{:?}: {:?}", device.product, device.keyboard),
+///         DeviceKind::Hid(_) => {}
+///     }
+/// }
+/// ```
+pub struct Devices;
+
+impl Devices {
+    /// Returns the connected mice, keyboards and other HID devices.
+    pub fn list() -> Vec<DeviceInfo> {
+        PlatformDevices::list()
+    }
+}
@@ -1,7 +1,14 @@
-use crate::event::Event;
+use crate::event::{Event, Point};
+use crate::foreground;
+use crate::sequence;
+use crate::state;
+use arc_swap::ArcSwap;
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant};
 
 /// Represents the current lifecycle state of a subscriber.
 #[derive(Debug, PartialEq)]
@@ -10,12 +17,18 @@ pub enum Status {
     Active,
     /// Subscriber is temporarily ignored.
     Paused,
+    /// Subscriber is ignored while its group is paused, and active again once the group
+    /// resumes.
+    GroupPaused,
 }
 
+/// A subscription callback, shared between `CALLBACKS` and the delivery snapshot.
+pub(crate) type Callback = Arc<dyn Fn(Event) + Send + Sync + 'static>;
+
 /// Internal container for a subscription callback.
 pub(crate) struct Subscriber {
     pub(crate) status: Status,
-    pub(crate) callback: Box<dyn Fn(Event) + Send + Sync + 'static>,
+    pub(crate) callback: Callback,
 }
 
 /// Global counter to generate unique subscription IDs.
@@ -24,22 +37,242 @@ pub(crate) static NEXT_ID: AtomicU64 = AtomicU64::new(0);
 /// Thread-safe global map storing all active event subscribers.
 pub(crate) static CALLBACKS: Lazy<DashMap<u64, Subscriber>> = Lazy::new(DashMap::new);
 
-/// Dispatches an event to all active subscribers.
+/// The group of each subscriber that was put in one, by subscription ID.
+pub(crate) static GROUPS: Lazy<DashMap<u64, String>> = Lazy::new(DashMap::new);
+
+/// The groups paused with [`pause_group`].
+static PAUSED_GROUPS: Lazy<Mutex<HashSet<String>>> = Lazy::new(Mutex::default);
+
+/// The callbacks of the `Active` subscribers in subscription order. Rebuilt by
+/// [`refresh`] and replaced wholesale, so delivery loads a snapshot without taking a
+/// lock or iterating the shards of `CALLBACKS` inside the hook.
+static ACTIVE: Lazy<ArcSwap<Vec<Callback>>> = Lazy::new(ArcSwap::default);
+
+/// Serializes [`refresh`], so concurrent refreshes cannot store a stale snapshot.
+static REFRESH: Mutex<()> = Mutex::new(());
+
+/// Set while at least one subscriber is `Active`, so the hooks can bail out before
+/// reading any event data when nobody is listening.
+static HAS_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` if at least one subscriber would receive events.
+#[inline]
+pub(crate) fn has_active() -> bool {
+    HAS_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Rebuilds the delivery snapshot and [`has_active`]. Must be called after every change
+/// to `CALLBACKS`, and never while holding a reference into it.
+pub(crate) fn refresh() {
+    let _refresh = REFRESH.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut active: Vec<(u64, Callback)> = CALLBACKS
+        .iter()
+        .filter(|subscriber| subscriber.status == Status::Active)
+        .map(|subscriber| (*subscriber.key(), subscriber.callback.clone()))
+        .collect();
+    active.sort_unstable_by_key(|&(id, _)| id);
+    HAS_ACTIVE.store(!active.is_empty(), Ordering::SeqCst);
+    ACTIVE.store(Arc::new(
+        active.into_iter().map(|(_, callback)| callback).collect(),
+    ));
+}
+
+fn lock_paused_groups() -> MutexGuard<'static, HashSet<String>> {
+    PAUSED_GROUPS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns `true` if subscriber `id` belongs to a paused group.
+pub(crate) fn is_group_paused(id: u64) -> bool {
+    GROUPS.get(&id).is_some_and(|group| lock_paused_groups().contains(group.as_str()))
+}
+
+/// Pauses or resumes subscriber `id` along with its group.
+fn apply_group_pause(id: u64, paused: bool) {
+    if let Some(mut subscriber) = CALLBACKS.get_mut(&id) {
+        match (paused, &subscriber.status) {
+            (true, Status::Active) => subscriber.status = Status::GroupPaused,
+            (false, Status::GroupPaused) => subscriber.status = Status::Active,
+            _ => {}
+        }
+    }
+}
+
+/// Puts subscriber `id` in `group`, pausing it if the group is paused.
+pub(crate) fn set_group(id: u64, group: String) {
+    let paused = lock_paused_groups().contains(&group);
+    GROUPS.insert(id, group);
+    apply_group_pause(id, paused);
+    refresh();
+}
+
+/// Pauses or resumes every subscriber in `group`, including those added to it later.
+///
+/// Subscribers paused on their own stay paused when the group resumes.
+pub(crate) fn set_group_paused(group: &str, paused: bool) {
+    {
+        let mut groups = lock_paused_groups();
+        match paused {
+            true => groups.insert(group.to_string()),
+            false => groups.remove(group),
+        };
+    }
+    let members: Vec<u64> = GROUPS
+        .iter()
+        .filter(|member| member.value() == group)
+        .map(|member| *member.key())
+        .collect();
+    for id in members {
+        apply_group_pause(id, paused);
+    }
+    refresh();
+}
+
+/// Tuning for high-polling-rate mice, see
+/// [`Listen::set_performance_mode`](crate::Listen::set_performance_mode).
 ///
-/// This function iterates through all registered callbacks and executes them
-/// if their status is set to `Active`.
+/// Gaming mice poll at up to 8 kHz, i.e. one mouse move every 125 µs. The dispatch path
+/// only performs relaxed atomic loads and clones a snapshot of the subscribers under an
+/// uncontended read lock, without allocating, and it is expected to deliver
+/// a mouse move to a subscriber in well under that budget even in debug builds; the
+/// stress test in this module checks 10 seconds worth of 8 kHz input. Slow subscribers
+/// can still fall behind, which is what move coalescing is for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct PerformanceMode {
+    /// Accumulates mouse move deltas and delivers at most one `MouseMove` per interval.
+    ///
+    /// No motion is lost: the accumulated delta is delivered with the next move after the
+    /// interval, or right before any other event. `None`, the default, delivers every move.
+    pub coalesce_moves: Option<Duration>,
+}
+
+/// Mouse move accumulation state for [`PerformanceMode::coalesce_moves`].
+struct Coalescing {
+    interval: Duration,
+    pending: Option<Point>,
+    last_delivery: Option<Instant>,
+}
+
+static COALESCING: Mutex<Coalescing> = Mutex::new(Coalescing {
+    interval: Duration::ZERO,
+    pending: None,
+    last_delivery: None,
+});
+
+/// Set while moves are coalesced, so the default mode never touches the lock.
+static IS_COALESCING: AtomicBool = AtomicBool::new(false);
+
+fn lock_coalescing() -> MutexGuard<'static, Coalescing> {
+    COALESCING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Applies a performance mode. A pending accumulated move is delivered first.
+pub(crate) fn set_performance_mode(mode: PerformanceMode) {
+    let pending = {
+        let mut state = lock_coalescing();
+        state.interval = mode.coalesce_moves.unwrap_or_default();
+        state.last_delivery = None;
+        IS_COALESCING.store(mode.coalesce_moves.is_some(), Ordering::SeqCst);
+        state.pending.take()
+    };
+    if let Some(delta) = pending {
+        deliver(Event::MouseMove { delta });
+    }
+}
+
+/// Returns the current performance mode.
+pub(crate) fn performance_mode() -> PerformanceMode {
+    let state = lock_coalescing();
+    PerformanceMode {
+        coalesce_moves: IS_COALESCING
+            .load(Ordering::SeqCst)
+            .then_some(state.interval),
+    }
+}
+
+/// Characters and lines per wheel notch applied by
+/// [`Listen::scroll_lines`](crate::Listen::scroll_lines).
+static SCROLL_LINES: Mutex<Point> = Mutex::new(Point { x: 1.0, y: 1.0 });
+
+/// Set while wheel deltas are scaled, so the default never touches the lock.
+static IS_SCROLL_LINES: AtomicBool = AtomicBool::new(false);
+
+/// Scales the wheel deltas delivered to subscribers by `lines`, or stops with `None`.
+pub(crate) fn set_scroll_lines(lines: Option<Point>) {
+    let mut state = SCROLL_LINES.lock().unwrap_or_else(PoisonError::into_inner);
+    *state = lines.unwrap_or(Point { x: 1.0, y: 1.0 });
+    IS_SCROLL_LINES.store(lines.is_some(), Ordering::SeqCst);
+}
+
+/// Multiplies a wheel delta by the scroll lines setting. The raw value is left as is.
+fn scale_wheel(event: Event) -> Event {
+    match event {
+        Event::MouseWheel { delta, raw } if IS_SCROLL_LINES.load(Ordering::Relaxed) => {
+            let lines = *SCROLL_LINES.lock().unwrap_or_else(PoisonError::into_inner);
+            Event::MouseWheel { delta: Point { x: delta.x * lines.x, y: delta.y * lines.y }, raw }
+        }
+        _ => event,
+    }
+}
+
+/// Dispatches an event to all active subscribers, coalescing mouse moves if enabled.
 pub(crate) fn dispatch(event: Event) {
-    for guard in CALLBACKS.iter() {
-        if guard.status == Status::Active {
-            (guard.callback)(event);
+    let is_key = matches!(event, Event::KeyDown { .. } | Event::KeyUp { .. });
+    if is_key && foreground::keyboard_withheld() {
+        return;
+    }
+    let event = scale_wheel(event);
+    if !IS_COALESCING.load(Ordering::Relaxed) {
+        deliver(event);
+        return;
+    }
+
+    let mut state = lock_coalescing();
+    let pending = state.pending.take();
+    if let Event::MouseMove { delta } = event {
+        let mut total = pending.unwrap_or(Point { x: 0.0, y: 0.0 });
+        total.x += delta.x;
+        total.y += delta.y;
+        let now = Instant::now();
+        if state
+            .last_delivery
+            .is_some_and(|last| now.duration_since(last) < state.interval)
+        {
+            state.pending = Some(total);
+            return;
         }
+        state.last_delivery = Some(now);
+        drop(state);
+        deliver(Event::MouseMove { delta: total });
+        return;
     }
+    drop(state);
+
+    if let Some(delta) = pending {
+        deliver(Event::MouseMove { delta });
+    }
+    deliver(event);
+}
+
+/// Executes every callback whose status is `Active`.
+///
+/// Callbacks run on a snapshot, so they may subscribe and unsubscribe themselves.
+fn deliver(event: Event) {
+    let start = Instant::now();
+    let callbacks = ACTIVE.load_full();
+    sequence::delivering(event, || {
+        for callback in callbacks.iter() {
+            callback(event);
+        }
+    });
+    state::dispatched(start.elapsed());
 }
 
 /// Clears all subscribers and resets the ID counter.
 pub(crate) fn remove_all() {
     CALLBACKS.clear();
+    GROUPS.clear();
     NEXT_ID.store(0, Ordering::SeqCst);
+    refresh();
 }
 
 #[cfg(test)]
@@ -63,8 +296,9 @@ mod tests {
         let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
         CALLBACKS.insert(id, Subscriber {
             status: Status::Active,
-            callback: Box::new(move |_| { called_clone.store(true, Ordering::SeqCst); }),
+            callback: Arc::new(move |_| { called_clone.store(true, Ordering::SeqCst); }),
         });
+        refresh();
         dispatch(dummy_event());
         assert!(called.load(Ordering::SeqCst), "active callback should be called");
         remove_all();
@@ -79,8 +313,9 @@ mod tests {
         let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
         CALLBACKS.insert(id, Subscriber {
             status: Status::Paused,
-            callback: Box::new(move |_| { called_clone.store(true, Ordering::SeqCst); }),
+            callback: Arc::new(move |_| { called_clone.store(true, Ordering::SeqCst); }),
         });
+        refresh();
         dispatch(dummy_event());
         assert!(!called.load(Ordering::SeqCst), "paused callback should not be called");
         remove_all();
@@ -98,18 +333,44 @@ mod tests {
         let id2 = NEXT_ID.fetch_add(1, Ordering::SeqCst);
         CALLBACKS.insert(id1, Subscriber {
             status: Status::Active,
-            callback: Box::new(move |_| { a.store(true, Ordering::SeqCst); }),
+            callback: Arc::new(move |_| { a.store(true, Ordering::SeqCst); }),
         });
         CALLBACKS.insert(id2, Subscriber {
             status: Status::Paused,
-            callback: Box::new(move |_| { p.store(true, Ordering::SeqCst); }),
+            callback: Arc::new(move |_| { p.store(true, Ordering::SeqCst); }),
         });
+        refresh();
         dispatch(dummy_event());
         assert!(active_called.load(Ordering::SeqCst), "active callback should be called");
         assert!(!paused_called.load(Ordering::SeqCst), "paused callback should not be called");
         remove_all();
     }
 
+    #[serial]
+    #[test]
+    fn test_dispatch_runs_snapshot_in_subscription_order() {
+        remove_all();
+        let order = std::sync::Arc::new(Mutex::new(Vec::new()));
+        for _ in 0..8 {
+            let sink = order.clone();
+            let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+            CALLBACKS.insert(id, Subscriber {
+                status: Status::Active,
+                callback: Arc::new(move |_| {
+                    sink.lock().unwrap().push(id);
+                    // Removing itself mid-delivery must not deadlock or skip the others
+                    CALLBACKS.remove(&id);
+                    refresh();
+                }),
+            });
+        }
+        refresh();
+        dispatch(dummy_event());
+        assert_eq!(*order.lock().unwrap(), (0..8).collect::<Vec<u64>>());
+        assert!(!has_active());
+        remove_all();
+    }
+
     #[serial]
     #[test]
     fn test_remove_all_clears_callbacks() {
@@ -117,11 +378,95 @@ mod tests {
         let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
         CALLBACKS.insert(id, Subscriber {
             status: Status::Active,
-            callback: Box::new(|_| {}),
+            callback: Arc::new(|_| {}),
         });
+        refresh();
         assert_eq!(CALLBACKS.len(), 1, "callback should exist before remove_all");
         remove_all();
         assert!(CALLBACKS.is_empty(), "callbacks should be empty after remove_all");
         assert_eq!(NEXT_ID.load(Ordering::SeqCst), 0, "NEXT_ID should reset to 0");
     }
+
+    #[serial]
+    #[test]
+    fn test_coalescing_accumulates_moves() {
+        remove_all();
+        let received = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        CALLBACKS.insert(id, Subscriber {
+            status: Status::Active,
+            callback: Arc::new(move |event| sink.lock().unwrap().push(event)),
+        });
+        refresh();
+        let mode = PerformanceMode { coalesce_moves: Some(Duration::from_secs(3600)) };
+        set_performance_mode(mode);
+        assert_eq!(performance_mode(), mode);
+
+        let mouse_move = |x, y| Event::MouseMove { delta: Point { x, y } };
+        dispatch(mouse_move(1.0, 1.0));
+        dispatch(mouse_move(2.0, 0.0));
+        dispatch(mouse_move(3.0, -1.0));
+        dispatch(dummy_event());
+        assert_eq!(
+            *received.lock().unwrap(),
+            vec![mouse_move(1.0, 1.0), mouse_move(5.0, -1.0), dummy_event()],
+            "moves within the interval should be merged and flushed before other events"
+        );
+
+        dispatch(mouse_move(4.0, 4.0));
+        set_performance_mode(PerformanceMode::default());
+        assert_eq!(received.lock().unwrap().last(), Some(&mouse_move(4.0, 4.0)));
+        remove_all();
+    }
+
+    #[serial]
+    #[test]
+    fn test_scroll_lines_scale_wheel_deltas() {
+        remove_all();
+        let received = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        CALLBACKS.insert(id, Subscriber {
+            status: Status::Active,
+            callback: Arc::new(move |event| sink.lock().unwrap().push(event)),
+        });
+        refresh();
+        let wheel = |y, raw| Event::MouseWheel {
+            delta: Point { x: 0.0, y },
+            raw: Some(Point { x: 0.0, y: raw }),
+        };
+
+        set_scroll_lines(Some(Point { x: 2.0, y: 3.0 }));
+        dispatch(wheel(-1.0, -120.0));
+        set_scroll_lines(None);
+        dispatch(wheel(-1.0, -120.0));
+        assert_eq!(*received.lock().unwrap(), vec![wheel(-3.0, -120.0), wheel(-1.0, -120.0)]);
+        remove_all();
+    }
+
+    #[serial]
+    #[test]
+    fn test_dispatch_keeps_up_with_8khz_mouse() {
+        remove_all();
+        let count = std::sync::Arc::new(AtomicU64::new(0));
+        let counter = count.clone();
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        CALLBACKS.insert(id, Subscriber {
+            status: Status::Active,
+            callback: Arc::new(move |_| { counter.fetch_add(1, Ordering::Relaxed); }),
+        });
+        refresh();
+
+        // 10 seconds of input at 8 kHz must be dispatched in less than 10 seconds
+        const EVENTS: u64 = 80_000;
+        let start = Instant::now();
+        for _ in 0..EVENTS {
+            dispatch(Event::MouseMove { delta: Point { x: 1.0, y: -1.0 } });
+        }
+        let elapsed = start.elapsed();
+        assert_eq!(count.load(Ordering::SeqCst), EVENTS);
+        assert!(elapsed < Duration::from_secs(10), "dispatch took {elapsed:?}");
+        remove_all();
+    }
 }
@@ -0,0 +1,119 @@
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{MonitorInfo, event::Point};
+
+/// A side of a monitor or of the virtual desktop.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Edge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Emitted when the cursor is pushed against an outer edge of the virtual desktop.
+///
+/// An outer edge is a monitor edge with no other monitor on the far side of the
+/// cursor, so moving across it would leave the desktop entirely. This is the
+/// primitive used by software KVM tools to hand the pointer over to another machine.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct EdgeEvent {
+    /// The edge being pushed against.
+    pub edge: Edge,
+    /// The monitor that owns the edge.
+    pub monitor: MonitorInfo,
+    /// The cursor position at the time of the event.
+    pub position: Point,
+}
+
+/// Checks whether a movement of `delta` at `position` pushes against an outer edge.
+///
+/// The cursor is clamped by the OS, so it sits on the last pixel of the edge while the
+/// raw delta keeps pointing outward; both conditions are required to fire.
+pub(crate) fn detect(monitors: &[MonitorInfo], position: Point, delta: Point) -> Option<EdgeEvent> {
+    let Point { x, y } = position;
+    let monitor = monitors.iter().find(|m| m.contains(position))?;
+
+    let bounds = monitor.bounds();
+    let (left, top, right, bottom) = (bounds.x, bounds.y, bounds.right(), bounds.bottom());
+
+    // (edge, cursor is on the edge, movement points outward, probe point beyond the edge)
+    let candidates = [
+        (Edge::Left, x < left + 1.0, delta.x < 0.0, (left - 1.0, y)),
+        (Edge::Right, x >= right - 1.0, delta.x > 0.0, (right, y)),
+        (Edge::Top, y < top + 1.0, delta.y < 0.0, (x, top - 1.0)),
+        (Edge::Bottom, y >= bottom - 1.0, delta.y > 0.0, (x, bottom)),
+    ];
+
+    candidates
+        .into_iter()
+        .find(|&(_, on_edge, outward, (px, py))| {
+            on_edge && outward && !monitors.iter().any(|m| m.contains(Point { x: px, y: py }))
+        })
+        .map(|(edge, ..)| EdgeEvent {
+            edge,
+            monitor: monitor.clone(),
+            position,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{monitor, point};
+
+    #[test]
+    fn test_detect_outer_edges() {
+        let monitors = vec![monitor("main", 0.0, 0.0, 1920.0, 1080.0)];
+
+        let event = detect(&monitors, point(0.0, 500.0), point(-3.0, 0.0)).unwrap();
+        assert_eq!(event.edge, Edge::Left);
+        assert_eq!(event.monitor.name, "main");
+
+        let event = detect(&monitors, point(1919.0, 500.0), point(5.0, 0.0)).unwrap();
+        assert_eq!(event.edge, Edge::Right);
+
+        let event = detect(&monitors, point(800.0, 0.0), point(0.0, -1.0)).unwrap();
+        assert_eq!(event.edge, Edge::Top);
+
+        let event = detect(&monitors, point(800.0, 1079.0), point(0.0, 2.0)).unwrap();
+        assert_eq!(event.edge, Edge::Bottom);
+    }
+
+    #[test]
+    fn test_detect_requires_outward_movement() {
+        let monitors = vec![monitor("main", 0.0, 0.0, 1920.0, 1080.0)];
+        assert!(detect(&monitors, point(0.0, 500.0), point(3.0, 0.0)).is_none());
+        assert!(detect(&monitors, point(0.0, 500.0), point(0.0, 3.0)).is_none());
+    }
+
+    #[test]
+    fn test_detect_ignores_interior_positions() {
+        let monitors = vec![monitor("main", 0.0, 0.0, 1920.0, 1080.0)];
+        assert!(detect(&monitors, point(960.0, 540.0), point(-10.0, -10.0)).is_none());
+    }
+
+    #[test]
+    fn test_detect_ignores_edges_shared_with_neighbors() {
+        let monitors = vec![
+            monitor("main", 0.0, 0.0, 1920.0, 1080.0),
+            monitor("right", 1920.0, 0.0, 1920.0, 540.0),
+        ];
+
+        // Covered by the right-hand monitor
+        assert!(detect(&monitors, point(1919.0, 100.0), point(5.0, 0.0)).is_none());
+
+        // Below the right-hand monitor, the edge is still an outer edge
+        let event = detect(&monitors, point(1919.0, 900.0), point(5.0, 0.0)).unwrap();
+        assert_eq!(event.edge, Edge::Right);
+    }
+
+    #[test]
+    fn test_detect_outside_all_monitors() {
+        let monitors = vec![monitor("main", 0.0, 0.0, 1920.0, 1080.0)];
+        assert!(detect(&monitors, point(-50.0, -50.0), point(-1.0, 0.0)).is_none());
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fmt, io};
+
+/// Errors that occur when trying to capture OS events.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CoreError {
+    /// The process may not observe input. On macOS, grant Accessibility (or Input
+    /// Monitoring, for a listen-only tap) in System Settings > Privacy & Security.
+    PermissionDenied,
+    /// The core already runs, on another thread or event loop.
+    AlreadyRunning,
+    /// macOS: Failed to create an event tap, although the permissions are granted.
+    MacEventTapError,
+    /// macOS: Failed to add the event tap to the run loop.
+    MacLoopSourceError,
+    /// Linux: Could not open the X11 display.
+    LinuxMissingDisplayError,
+    /// Linux: Keyboard-related error in the X11 or Wayland backend.
+    LinuxKeyboardError,
+    /// Linux: Failed to enable the XRecord context.
+    LinuxRecordContextEnablingError,
+    /// Linux: General XRecord context error.
+    LinuxRecordContextError,
+    /// Linux: The XRecord extension is missing or incompatible.
+    LinuxXRecordExtensionError,
+    /// Windows: Failed to set a low-level keyboard hook.
+    WindowsKeyHookError(OsError),
+    /// Windows: Failed to set a low-level mouse hook.
+    WindowsMouseHookError(OsError),
+    /// Windows: Failed to create the raw input window.
+    WindowsRawInputWindowError(OsError),
+    /// Windows: Failed to register for Raw Input devices.
+    WindowsRegisterRawInputError(OsError),
+}
+
+impl fmt::Display for CoreError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CoreError::PermissionDenied => write!(f, "permission to observe input denied"),
+            CoreError::AlreadyRunning => write!(f, "the core is already running"),
+            CoreError::MacEventTapError => write!(f, "failed to create the event tap"),
+            CoreError::MacLoopSourceError => {
+                write!(f, "failed to add the event tap to the run loop")
+            }
+            CoreError::LinuxMissingDisplayError => write!(f, "could not open the X11 display"),
+            CoreError::LinuxKeyboardError => write!(f, "keyboard error"),
+            CoreError::LinuxRecordContextEnablingError => {
+                write!(f, "failed to enable the XRecord context")
+            }
+            CoreError::LinuxRecordContextError => write!(f, "XRecord context error"),
+            CoreError::LinuxXRecordExtensionError => {
+                write!(f, "the XRecord extension is missing or incompatible")
+            }
+            CoreError::WindowsKeyHookError(err) => {
+                write!(f, "failed to set the keyboard hook: {err}")
+            }
+            CoreError::WindowsMouseHookError(err) => {
+                write!(f, "failed to set the mouse hook: {err}")
+            }
+            CoreError::WindowsRawInputWindowError(err) => {
+                write!(f, "failed to create the raw input window: {err}")
+            }
+            CoreError::WindowsRegisterRawInputError(err) => {
+                write!(f, "failed to register for raw input: {err}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CoreError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CoreError::WindowsKeyHookError(err)
+            | CoreError::WindowsMouseHookError(err)
+            | CoreError::WindowsRawInputWindowError(err)
+            | CoreError::WindowsRegisterRawInputError(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Misuse reported by [`Listen::start`](crate::Listen::start) and
+/// [`Grab::start`](crate::Grab::start).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum StateError {
+    /// The core is not running, so no events arrive until it is started.
+    NotStarted,
+    /// The process lacks the permission to observe (or, for `Grab`, block) input.
+    PermissionMissing,
+    /// Already started, nothing changed.
+    AlreadyRunning,
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::NotStarted => write!(f, "the core is not running"),
+            StateError::PermissionMissing => write!(f, "permission to access input missing"),
+            StateError::AlreadyRunning => write!(f, "already running"),
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+/// An error code reported by the operating system, e.g. from `GetLastError` on Windows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct OsError {
+    /// The raw error code.
+    pub code: i32,
+}
+
+impl OsError {
+    /// Converts to the equivalent [`io::Error`], whose message describes the code.
+    pub fn to_io_error(self) -> io::Error {
+        io::Error::from_raw_os_error(self.code)
+    }
+}
+
+impl fmt::Display for OsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The standard library looks up the system message, and appends the code
+        write!(f, "{}", self.to_io_error())
+    }
+}
+
+impl std::error::Error for OsError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_includes_os_code() {
+        let err = CoreError::WindowsKeyHookError(OsError { code: 5 });
+        assert!(
+            err.to_string()
+                .starts_with("failed to set the keyboard hook: ")
+        );
+        assert!(err.to_string().contains("os error 5"));
+        assert!(std::error::Error::source(&err).is_some());
+        assert!(std::error::Error::source(&CoreError::PermissionDenied).is_none());
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
-use crate::key::{Key, KeyCode};
+use crate::key::{Key, KeyCode, Modifiers};
 
 /// Represents the standard buttons on a mouse.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
@@ -30,7 +30,7 @@ pub struct Point {
 ///
 /// fn handle_event(event: Event) {
 ///     match event {
-///         Event::KeyDown { key: Key::Escape } => println!("Escape pressed!"),
+///         Event::KeyDown { key: Key::Escape, .. } => println!("Escape pressed!"),
 ///         _ => {}
 ///     }
 /// }
@@ -39,17 +39,51 @@ pub struct Point {
 #[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
 pub enum Event {
     MouseMove { delta: Point },
-    MouseWheel { delta: Point },
+    /// Pointer movement reported as a position rather than a delta, in the coordinates of
+    /// [`Display::get_cursor_position`](crate::Display::get_cursor_position).
+    ///
+    /// Pen tablets, touch screens and the virtual mice of VMs and remote desktop sessions
+    /// report where the pointer is instead of how far it moved. Delivered with mouse move
+    /// listening on Windows, from Raw Input; macOS reports all pointer input as relative
+    /// moves.
+    MouseMoveAbsolute { position: Point },
+    /// Wheel scroll, in lines (notches). Positive `y` scrolls up, positive `x` right.
+    ///
+    /// Precision touchpads and trackpads report fractional deltas as the fingers move
+    /// instead of whole notches: Windows forwards their high-resolution wheel deltas
+    /// unrounded, and on macOS continuous scroll events use the fixed-point delta.
+    ///
+    /// `raw` is the unnormalized value `delta` was derived from: the wheel delta in
+    /// `WHEEL_DELTA` units (120 per notch) on Windows and the pixel delta on macOS. It is
+    /// always set for captured events and may be `None` for synthetic events. `Simulate`
+    /// injects a set `raw` as is and ignores `delta`, so a recorded scroll replays without
+    /// rounding.
+    MouseWheel { delta: Point, raw: Option<Point> },
     MouseDown { button: MouseButton },
     MouseUp { button: MouseButton },
     /// Keyboard key press.
     ///
-    /// `code` is a platform-specific raw key identifier (e.g. scancode or virtual key).
-    /// It is optional and may be unavailable on some platforms or synthetic events.
+    /// `key` is the physical key position and does not depend on the keyboard layout.
+    /// `code` is the raw platform key identifier the key was derived from: the virtual-key
+    /// code on Windows (the UTF-16 unit for `VK_PACKET` input) and the `CGKeyCode` on macOS.
+    /// It is always set for captured events and may be `None` for synthetic events.
     KeyDown { key: Key, code: Option<KeyCode> },
     /// Keyboard key release.
     ///
-    /// `code` is a platform-specific raw key identifier (e.g. scancode or virtual key).
-    /// It is optional and may be unavailable on some platforms or synthetic events.
+    /// See [`Event::KeyDown`] for the meaning of `key` and `code`.
     KeyUp { key: Key, code: Option<KeyCode> },
+    /// The foreground window changed and another window now receives keyboard input.
+    ///
+    /// `pid` is the process id of its application; the executable and window title are
+    /// available from [`Listen::foreground`](crate::Listen::foreground). On Windows every
+    /// foreground window change is reported, on macOS only application activations.
+    /// Delivered while `Core` runs regardless of the listened input categories, and
+    /// ignored by `Simulate`.
+    FocusChanged { pid: u32 },
+    /// The modifiers held changed, reported right after the key event that changed them.
+    ///
+    /// Left and right keys count as one modifier, so pressing the second Shift while the
+    /// first is held changes nothing. Delivered with keyboard listening on every platform
+    /// and ignored by `Simulate`.
+    ModifiersChanged { modifiers: Modifiers },
 }
@@ -0,0 +1,367 @@
+//! C bindings, for consuming the crate from C, C++, C# or Node.js native addons.
+//!
+//! The declarations are mirrored in `include/raw_input.h`. Build a shared library with
+//!
+//! ```text
+//! cargo rustc --release --features ffi --crate-type cdylib
+//! ```
+//!
+//! Keys are exchanged as their stable [`wire`](crate::wire) ids and mouse buttons as
+//! `0` (left) to `4` (forward), so the numbering never changes between releases.
+
+use std::{
+    collections::HashMap,
+    ffi::c_void,
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    Core, CoreError, Display, Listen, Simulate,
+    event::{Event, Point},
+    key::Modifiers,
+    subscription::SubscriptionHandle,
+    wire,
+};
+
+/// [`RawInputEvent::kind`] of a mouse move.
+pub const RAW_INPUT_MOUSE_MOVE: u32 = 0;
+/// [`RawInputEvent::kind`] of a mouse wheel event.
+pub const RAW_INPUT_MOUSE_WHEEL: u32 = 1;
+/// [`RawInputEvent::kind`] of a mouse button press.
+pub const RAW_INPUT_MOUSE_DOWN: u32 = 2;
+/// [`RawInputEvent::kind`] of a mouse button release.
+pub const RAW_INPUT_MOUSE_UP: u32 = 3;
+/// [`RawInputEvent::kind`] of a key press.
+pub const RAW_INPUT_KEY_DOWN: u32 = 4;
+/// [`RawInputEvent::kind`] of a key release.
+pub const RAW_INPUT_KEY_UP: u32 = 5;
+/// [`RawInputEvent::kind`] of a foreground application change.
+pub const RAW_INPUT_FOCUS_CHANGED: u32 = 6;
+/// [`RawInputEvent::kind`] of a mouse move to an absolute position.
+pub const RAW_INPUT_MOUSE_MOVE_ABSOLUTE: u32 = 7;
+/// [`RawInputEvent::kind`] of a change of the modifiers held.
+pub const RAW_INPUT_MODIFIERS_CHANGED: u32 = 8;
+
+/// An [`Event`] in a C-compatible layout. Fields not used by `kind` are zero.
+///
+/// Wheel events carry the normalized delta only, their `raw` value is dropped.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct RawInputEvent {
+    /// One of the `RAW_INPUT_*` kind constants.
+    pub kind: u32,
+    /// Mouse button id, for button events.
+    pub button: u32,
+    /// Horizontal delta, for move and wheel events.
+    pub x: f64,
+    /// Vertical delta, for move and wheel events.
+    pub y: f64,
+    /// Stable key id, for keyboard events.
+    pub key: u64,
+    /// Raw platform key code, valid when `has_code` is set. The process id for focus
+    /// changes, the [`Modifiers::bits`](crate::Modifiers::bits) for modifier changes.
+    pub code: u32,
+    /// Whether `code` is set.
+    pub has_code: bool,
+}
+
+impl From<Event> for RawInputEvent {
+    fn from(event: Event) -> Self {
+        let point = |kind, delta: Point| RawInputEvent {
+            kind,
+            x: delta.x,
+            y: delta.y,
+            ..Default::default()
+        };
+        let button = |kind, button| RawInputEvent {
+            kind,
+            button: wire::button_to_id(button) as u32,
+            ..Default::default()
+        };
+        let key = |kind, key, code: Option<u32>| RawInputEvent {
+            kind,
+            key: wire::key_id(key),
+            code: code.unwrap_or(0),
+            has_code: code.is_some(),
+            ..Default::default()
+        };
+
+        match event {
+            Event::MouseMove { delta } => point(RAW_INPUT_MOUSE_MOVE, delta),
+            Event::MouseMoveAbsolute { position } => point(RAW_INPUT_MOUSE_MOVE_ABSOLUTE, position),
+            Event::MouseWheel { delta, .. } => point(RAW_INPUT_MOUSE_WHEEL, delta),
+            Event::MouseDown { button: b } => button(RAW_INPUT_MOUSE_DOWN, b),
+            Event::MouseUp { button: b } => button(RAW_INPUT_MOUSE_UP, b),
+            Event::KeyDown { key: k, code } => key(RAW_INPUT_KEY_DOWN, k, code),
+            Event::KeyUp { key: k, code } => key(RAW_INPUT_KEY_UP, k, code),
+            Event::FocusChanged { pid } => RawInputEvent {
+                kind: RAW_INPUT_FOCUS_CHANGED,
+                code: pid,
+                has_code: true,
+                ..Default::default()
+            },
+            Event::ModifiersChanged { modifiers } => RawInputEvent {
+                kind: RAW_INPUT_MODIFIERS_CHANGED,
+                code: u32::from(modifiers.bits()),
+                has_code: true,
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl RawInputEvent {
+    /// Converts back to an [`Event`], or `None` for an unknown kind or button.
+    pub fn to_event(&self) -> Option<Event> {
+        let delta = Point {
+            x: self.x,
+            y: self.y,
+        };
+        let button = || u8::try_from(self.button).ok().and_then(wire::id_to_button);
+        let key = wire::key_from_id(self.key);
+        let code = self.has_code.then_some(self.code);
+
+        let event = match self.kind {
+            RAW_INPUT_MOUSE_MOVE => Event::MouseMove { delta },
+            RAW_INPUT_MOUSE_MOVE_ABSOLUTE => Event::MouseMoveAbsolute { position: delta },
+            RAW_INPUT_MOUSE_WHEEL => Event::MouseWheel { delta, raw: None },
+            RAW_INPUT_MOUSE_DOWN => Event::MouseDown { button: button()? },
+            RAW_INPUT_MOUSE_UP => Event::MouseUp { button: button()? },
+            RAW_INPUT_KEY_DOWN => Event::KeyDown { key, code },
+            RAW_INPUT_KEY_UP => Event::KeyUp { key, code },
+            RAW_INPUT_FOCUS_CHANGED => Event::FocusChanged { pid: self.code },
+            RAW_INPUT_MODIFIERS_CHANGED => Event::ModifiersChanged {
+                modifiers: Modifiers::from_bits_truncate(self.code as u8),
+            },
+            _ => return None,
+        };
+        Some(event)
+    }
+}
+
+/// A monitor in a C-compatible layout, see [`MonitorInfo`](crate::MonitorInfo).
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct RawInputMonitor {
+    /// Left edge in the global physical coordinate system.
+    pub x: f64,
+    /// Top edge in the global physical coordinate system.
+    pub y: f64,
+    /// Width in physical pixels.
+    pub width: f64,
+    /// Height in physical pixels.
+    pub height: f64,
+    /// The UI scale factor.
+    pub scale_factor: f64,
+    /// Whether this is the primary monitor.
+    pub is_primary: bool,
+}
+
+/// Callback invoked for every event, on the thread that dispatches events.
+pub type RawInputCallback = extern "C" fn(event: *const RawInputEvent, user_data: *mut c_void);
+
+/// The caller's context pointer, handed back to the callback untouched.
+struct UserData(*mut c_void);
+
+// SAFETY: the pointer is never dereferenced by this crate; by passing it to
+// `raw_input_subscribe` the caller promises it may be used from the dispatch thread.
+unsafe impl Send for UserData {}
+unsafe impl Sync for UserData {}
+
+impl UserData {
+    // A method rather than a field access, so closures capture the whole `UserData`
+    fn get(&self) -> *mut c_void {
+        self.0
+    }
+}
+
+static SUBSCRIPTIONS: Lazy<Mutex<HashMap<u64, SubscriptionHandle>>> = Lazy::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Runs the core event loop on the calling thread until [`raw_input_core_stop`] is
+/// called. Returns `0` on success, `-1` if the platform hooks could not be installed and
+/// `-2` if the core is already running.
+#[unsafe(no_mangle)]
+pub extern "C" fn raw_input_core_start() -> i32 {
+    match Core::start() {
+        Ok(()) => 0,
+        Err(CoreError::AlreadyRunning) => -2,
+        Err(_) => -1,
+    }
+}
+
+/// Stops the core event loop.
+#[unsafe(no_mangle)]
+pub extern "C" fn raw_input_core_stop() {
+    Core::stop();
+}
+
+/// Starts delivering events to subscribers.
+#[unsafe(no_mangle)]
+pub extern "C" fn raw_input_listen_start() {
+    let _ = Listen::start();
+}
+
+/// Stops delivering events to subscribers.
+#[unsafe(no_mangle)]
+pub extern "C" fn raw_input_listen_stop() {
+    Listen::stop();
+}
+
+/// Subscribes `callback` to every input event and returns a non-zero subscription id.
+///
+/// `user_data` is passed to every invocation of the callback.
+#[unsafe(no_mangle)]
+pub extern "C" fn raw_input_subscribe(callback: RawInputCallback, user_data: *mut c_void) -> u64 {
+    let user_data = UserData(user_data);
+    let handle = Listen::subscribe(move |event| {
+        let event = RawInputEvent::from(event);
+        callback(&event, user_data.get());
+    });
+
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    lock().insert(id, handle);
+    id
+}
+
+/// Removes a subscription. Returns `false` if the id is unknown.
+#[unsafe(no_mangle)]
+pub extern "C" fn raw_input_unsubscribe(id: u64) -> bool {
+    let handle = lock().remove(&id);
+    match handle {
+        Some(handle) => {
+            handle.unsubscribe();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Simulates an event. Returns `false` if the event is null or invalid.
+///
+/// # Safety
+/// `event` must be null or point to a valid `RawInputEvent`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn raw_input_simulate(event: *const RawInputEvent) -> bool {
+    // SAFETY: guaranteed by the caller
+    let Some(event) = (unsafe { event.as_ref() }).and_then(RawInputEvent::to_event) else {
+        return false;
+    };
+    Simulate::simulate(event);
+    true
+}
+
+/// Writes the cursor position to `x` and `y`. Returns `false` if it is unavailable.
+///
+/// # Safety
+/// `x` and `y` must be valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn raw_input_cursor_position(x: *mut f64, y: *mut f64) -> bool {
+    let Some((cx, cy)) = Display::get_cursor_position() else {
+        return false;
+    };
+    // SAFETY: guaranteed by the caller
+    unsafe {
+        *x = cx;
+        *y = cy;
+    }
+    true
+}
+
+/// Writes up to `capacity` monitors to `out` and returns the total number of monitors.
+///
+/// Call with a null `out` to query the count first.
+///
+/// # Safety
+/// `out` must be null or valid for writes of `capacity` elements.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn raw_input_monitors(out: *mut RawInputMonitor, capacity: usize) -> usize {
+    let monitors = Display::get_available_monitors();
+    if !out.is_null() {
+        for (i, monitor) in monitors.iter().take(capacity).enumerate() {
+            let monitor = RawInputMonitor {
+                x: monitor.offset.0,
+                y: monitor.offset.1,
+                width: monitor.size.0,
+                height: monitor.size.1,
+                scale_factor: monitor.scale_factor,
+                is_primary: monitor.is_primary,
+            };
+            // SAFETY: `i < capacity`, guaranteed valid by the caller
+            unsafe { out.add(i).write(monitor) };
+        }
+    }
+    monitors.len()
+}
+
+fn lock() -> std::sync::MutexGuard<'static, HashMap<u64, SubscriptionHandle>> {
+    SUBSCRIPTIONS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        event::MouseButton,
+        key::{Key, RawKey},
+    };
+
+    #[test]
+    fn test_event_conversion_roundtrip() {
+        let events = [
+            Event::MouseMove {
+                delta: Point { x: 1.5, y: -2.0 },
+            },
+            Event::MouseMoveAbsolute {
+                position: Point { x: 100.0, y: 200.0 },
+            },
+            Event::MouseWheel {
+                delta: Point { x: 0.0, y: 1.0 },
+                raw: None,
+            },
+            Event::MouseDown {
+                button: MouseButton::Middle,
+            },
+            Event::MouseUp {
+                button: MouseButton::Forward,
+            },
+            Event::KeyDown {
+                key: Key::KeyA,
+                code: Some(65),
+            },
+            Event::KeyUp {
+                key: Key::Raw(RawKey(0xff)),
+                code: None,
+            },
+            Event::FocusChanged { pid: 4242 },
+            Event::ModifiersChanged {
+                modifiers: Modifiers::CONTROL | Modifiers::META,
+            },
+        ];
+        for event in events {
+            assert_eq!(RawInputEvent::from(event).to_event(), Some(event));
+        }
+    }
+
+    #[test]
+    fn test_invalid_events_are_rejected() {
+        let unknown_kind = RawInputEvent {
+            kind: 99,
+            ..Default::default()
+        };
+        assert_eq!(unknown_kind.to_event(), None);
+
+        let unknown_button = RawInputEvent {
+            kind: RAW_INPUT_MOUSE_DOWN,
+            button: 7,
+            ..Default::default()
+        };
+        assert_eq!(unknown_button.to_event(), None);
+        assert!(!unsafe { raw_input_simulate(std::ptr::null()) });
+    }
+}
@@ -0,0 +1,231 @@
+use std::{
+    collections::HashSet,
+    sync::{Mutex, PoisonError},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Listen,
+    event::Event,
+    foreground::AppMatcher,
+    key::{Key, Modifiers},
+    pipeline::{MiddlewareHandle, Pipeline, PipelineContext, Verdict},
+    platform::{GrabImpl, PlatformGrab},
+    subscription::SubscriptionHandle,
+};
+
+/// A category of [`Event`], as selected by [`EventFilter::categories`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum EventCategory {
+    /// `MouseMove` and `MouseMoveAbsolute`.
+    MouseMove,
+    MouseWheel,
+    /// `MouseDown` and `MouseUp`.
+    MouseButton,
+    /// `KeyDown`, `KeyUp` and `ModifiersChanged`.
+    Keyboard,
+    /// `FocusChanged`.
+    Focus,
+}
+
+impl EventCategory {
+    /// Returns the category `event` belongs to.
+    pub fn of(event: &Event) -> Self {
+        match event {
+            Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. } => Self::MouseMove,
+            Event::MouseWheel { .. } => Self::MouseWheel,
+            Event::MouseDown { .. } | Event::MouseUp { .. } => Self::MouseButton,
+            Event::KeyDown { .. } | Event::KeyUp { .. } | Event::ModifiersChanged { .. } => {
+                Self::Keyboard
+            }
+            Event::FocusChanged { .. } => Self::Focus,
+        }
+    }
+}
+
+/// A description of the events to capture, kept as data so applications can store and
+/// edit capture rules instead of compiling them in.
+///
+/// An event matches when every condition holds; empty fields impose no restriction.
+/// Attach a filter to a subscriber with [`Listen::subscribe_filtered`] or block the
+/// events it matches with [`Grab::add_filter`](crate::Grab::add_filter). With the
+/// `serialize` feature it serializes as a plain struct, see
+/// [`SCHEMA_VERSION`](crate::SCHEMA_VERSION), and missing fields read as empty.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{AppMatcher, EventCategory, EventFilter, Listen, Modifiers};
+///
+/// // Ctrl+wheel inside the editor
+/// let filter = EventFilter {
+///     categories: vec![EventCategory::MouseWheel],
+///     modifiers: Modifiers::CONTROL,
+///     app: Some(AppMatcher::exe("editor.exe")),
+///     ..Default::default()
+/// };
+/// let handle = Listen::subscribe_filtered(filter, |event| println!("Zoom: {:?}", event));
+///
+/// // Later
+/// handle.unsubscribe();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize), serde(default))]
+pub struct EventFilter {
+    /// The categories that match, all of them when empty.
+    pub categories: Vec<EventCategory>,
+    /// The keys whose events match, all of them when empty. Other categories are not
+    /// restricted by it.
+    pub keys: Vec<Key>,
+    /// Modifiers that must be held. Others may be held too.
+    pub modifiers: Modifiers,
+    /// The application that must have the focus, any when `None`.
+    pub app: Option<AppMatcher>,
+}
+
+impl EventFilter {
+    /// Creates a filter that matches every event.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if `event` matches while `modifiers` are held.
+    ///
+    /// The application is checked against the one that has the focus right now.
+    pub fn matches(&self, event: &Event, modifiers: Modifiers) -> bool {
+        let key_matches = match event {
+            Event::KeyDown { key, .. } | Event::KeyUp { key, .. } => {
+                self.keys.is_empty() || self.keys.contains(key)
+            }
+            _ => true,
+        };
+        (self.categories.is_empty() || self.categories.contains(&EventCategory::of(event)))
+            && key_matches
+            && modifiers.contains(self.modifiers)
+            && self.app.as_ref().is_none_or(AppMatcher::is_focused)
+    }
+}
+
+/// A filter together with the modifiers held according to the events it has seen.
+struct Tracked {
+    filter: EventFilter,
+    pressed: Mutex<HashSet<Key>>,
+}
+
+impl Tracked {
+    fn new(filter: EventFilter) -> Self {
+        Self {
+            filter,
+            pressed: Mutex::default(),
+        }
+    }
+
+    /// Records `event` and returns `true` if it matches.
+    fn matches(&self, event: &Event) -> bool {
+        let modifiers = {
+            let mut pressed = self.pressed.lock().unwrap_or_else(PoisonError::into_inner);
+            match *event {
+                Event::KeyDown { key, .. } if Modifiers::from_key(key).is_some() => {
+                    pressed.insert(key);
+                }
+                Event::KeyUp { key, .. } => {
+                    pressed.remove(&key);
+                }
+                _ => {}
+            }
+            pressed
+                .iter()
+                .filter_map(|&key| Modifiers::from_key(key))
+                .fold(Modifiers::NONE, |acc, m| acc | m)
+        };
+        self.filter.matches(event, modifiers)
+    }
+}
+
+/// Subscribes `callback` to the events `filter` matches.
+pub(crate) fn subscribe<F>(filter: EventFilter, callback: F) -> SubscriptionHandle
+where
+    F: Fn(Event) + Send + Sync + 'static,
+{
+    let tracked = Tracked::new(filter);
+    Listen::subscribe(move |event| {
+        if tracked.matches(&event) {
+            callback(event);
+        }
+    })
+}
+
+/// Blocks the events `filter` matches, after every other pipeline stage.
+pub(crate) fn grab(filter: EventFilter) -> MiddlewareHandle {
+    let tracked = Tracked::new(filter);
+    Pipeline::add(
+        i32::MAX,
+        move |event: &mut Event, ctx: &mut PipelineContext| {
+            // Every event is recorded, so the modifiers stay known while injected ones pass
+            let matches = tracked.matches(event);
+            let passes = ctx.is_injected() && PlatformGrab::flags().pass_injected;
+            match matches && !passes {
+                true => Verdict::Block,
+                false => Verdict::Continue,
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{MouseButton, Point, fixtures::key_down};
+
+    fn wheel() -> Event {
+        Event::MouseWheel {
+            delta: Point { x: 0.0, y: 1.0 },
+            raw: None,
+        }
+    }
+
+    #[test]
+    fn test_empty_filter_matches_everything() {
+        let filter = EventFilter::new();
+        assert!(filter.matches(&wheel(), Modifiers::NONE));
+        assert!(filter.matches(&Event::FocusChanged { pid: 1 }, Modifiers::NONE));
+    }
+
+    #[test]
+    fn test_conditions_combine() {
+        let filter = EventFilter {
+            categories: vec![EventCategory::Keyboard, EventCategory::MouseWheel],
+            keys: vec![Key::KeyK],
+            modifiers: Modifiers::CONTROL,
+            app: None,
+        };
+        assert!(filter.matches(&key_down(Key::KeyK), Modifiers::CONTROL | Modifiers::SHIFT));
+        assert!(!filter.matches(&key_down(Key::KeyK), Modifiers::SHIFT));
+        assert!(!filter.matches(&key_down(Key::KeyJ), Modifiers::CONTROL));
+        // Keys only restrict keyboard events
+        assert!(filter.matches(&wheel(), Modifiers::CONTROL));
+        let down = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        assert!(!filter.matches(&down, Modifiers::CONTROL));
+    }
+
+    #[test]
+    fn test_tracked_modifiers() {
+        let tracked = Tracked::new(EventFilter {
+            categories: vec![EventCategory::MouseWheel],
+            modifiers: Modifiers::CONTROL,
+            ..Default::default()
+        });
+        assert!(!tracked.matches(&wheel()));
+        assert!(!tracked.matches(&key_down(Key::ControlRight)));
+        assert!(tracked.matches(&wheel()));
+        tracked.matches(&Event::KeyUp {
+            key: Key::ControlRight,
+            code: None,
+        });
+        assert!(!tracked.matches(&wheel()));
+    }
+}
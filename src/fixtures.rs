@@ -0,0 +1,27 @@
+//! Builders shared by the unit tests.
+
+use crate::MonitorInfo;
+#[cfg(feature = "listen")]
+use crate::{Event, Key, Point};
+
+/// A monitor at `(x, y)` with a scale factor of 1, primary if it sits at the origin.
+pub(crate) fn monitor(name: &str, x: f64, y: f64, w: f64, h: f64) -> MonitorInfo {
+    MonitorInfo {
+        name: name.to_string(),
+        is_primary: x == 0.0 && y == 0.0,
+        offset: (x, y),
+        size: (w, h),
+        scale_factor: 1.0,
+    }
+}
+
+#[cfg(feature = "listen")]
+pub(crate) fn point(x: f64, y: f64) -> Point {
+    Point { x, y }
+}
+
+/// A key press without a platform key code.
+#[cfg(feature = "listen")]
+pub(crate) fn key_down(key: Key) -> Event {
+    Event::KeyDown { key, code: None }
+}
@@ -0,0 +1,229 @@
+use std::sync::{
+    Arc, Mutex, MutexGuard, PoisonError,
+    atomic::{AtomicBool, Ordering},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::platform::{ForegroundImpl, PlatformForeground};
+
+/// The application owning the foreground window, which receives keyboard input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct ForegroundApp {
+    /// Process id.
+    pub pid: u32,
+    /// File name of the executable, e.g. `Code.exe` or `Safari`.
+    pub exe: Option<String>,
+    /// Bundle identifier, e.g. `com.apple.Safari`. Always `None` on Windows.
+    pub bundle_id: Option<String>,
+    /// Title of the foreground window. Reading window titles on macOS requires the
+    /// Screen Recording permission, so it is usually `None` there.
+    pub title: Option<String>,
+}
+
+/// Identifies an application for [`Listen::only_when_app`](crate::Listen::only_when_app)
+/// and [`Grab::only_when_app`](crate::Grab::only_when_app).
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{AppMatcher, Key, Remap, RemapRule};
+///
+/// // Remap keys only inside a specific game
+/// let game = AppMatcher::exe("game.exe");
+/// Remap::add_rule(RemapRule::new(Key::KeyW, Key::ArrowUp).when(move || game.is_focused()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum AppMatcher {
+    /// Matches the executable file name, ignoring ASCII case.
+    Exe(String),
+    /// Matches the bundle identifier exactly. Never matches on Windows.
+    BundleId(String),
+}
+
+impl AppMatcher {
+    pub fn exe(name: impl Into<String>) -> Self {
+        AppMatcher::Exe(name.into())
+    }
+
+    pub fn bundle_id(id: impl Into<String>) -> Self {
+        AppMatcher::BundleId(id.into())
+    }
+
+    /// Returns `true` if `app` is the application this matcher identifies.
+    pub fn matches(&self, app: &ForegroundApp) -> bool {
+        match self {
+            AppMatcher::Exe(name) => app
+                .exe
+                .as_ref()
+                .is_some_and(|exe| exe.eq_ignore_ascii_case(name)),
+            AppMatcher::BundleId(id) => app.bundle_id.as_ref() == Some(id),
+        }
+    }
+
+    /// Returns `true` if the application has the focus.
+    pub fn is_focused(&self) -> bool {
+        current().is_some_and(|app| self.matches(&app))
+    }
+}
+
+/// Restrictions set by `only_when_app`, with flags so the hooks skip the lock without one.
+static LISTEN_RULE: Mutex<Option<AppMatcher>> = Mutex::new(None);
+static HAS_LISTEN_RULE: AtomicBool = AtomicBool::new(false);
+static GRAB_RULE: Mutex<Option<AppMatcher>> = Mutex::new(None);
+static HAS_GRAB_RULE: AtomicBool = AtomicBool::new(false);
+
+fn set_rule(rule: &Mutex<Option<AppMatcher>>, flag: &AtomicBool, matcher: Option<AppMatcher>) {
+    let mut rule = rule.lock().unwrap_or_else(PoisonError::into_inner);
+    flag.store(matcher.is_some(), Ordering::SeqCst);
+    *rule = matcher;
+}
+
+fn allows(rule: &Mutex<Option<AppMatcher>>, flag: &AtomicBool) -> bool {
+    if !flag.load(Ordering::Relaxed) {
+        return true;
+    }
+    let rule = rule.lock().unwrap_or_else(PoisonError::into_inner);
+    rule.as_ref().is_none_or(AppMatcher::is_focused)
+}
+
+/// Restricts listening to the given application, or lifts the restriction with `None`.
+pub(crate) fn set_listen_rule(matcher: Option<AppMatcher>) {
+    set_rule(&LISTEN_RULE, &HAS_LISTEN_RULE, matcher);
+}
+
+/// Restricts grabbing to the given application, or lifts the restriction with `None`.
+pub(crate) fn set_grab_rule(matcher: Option<AppMatcher>) {
+    set_rule(&GRAB_RULE, &HAS_GRAB_RULE, matcher);
+}
+
+/// Returns `true` if input events may be delivered to subscribers right now.
+#[inline]
+pub(crate) fn listen_allowed() -> bool {
+    allows(&LISTEN_RULE, &HAS_LISTEN_RULE)
+}
+
+/// Returns `true` if `Grab` may block input right now.
+#[inline]
+pub(crate) fn grab_allowed() -> bool {
+    allows(&GRAB_RULE, &HAS_GRAB_RULE)
+}
+
+/// Set by [`Listen::pause_during_secure_input`](crate::Listen::pause_during_secure_input).
+static SECURE_INPUT_GUARD: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_secure_input_guard(enable: bool) {
+    SECURE_INPUT_GUARD.store(enable, Ordering::SeqCst);
+}
+
+/// Returns `true` while keyboard events are withheld during secure input.
+#[inline]
+pub(crate) fn secure_input_guard() -> bool {
+    SECURE_INPUT_GUARD.load(Ordering::Relaxed)
+}
+
+/// Returns `true` if keyboard events must be withheld from subscribers because the user
+/// is typing into a password field.
+#[inline]
+pub(crate) fn keyboard_withheld() -> bool {
+    secure_input_guard() && PlatformForeground::is_secure_input()
+}
+
+/// The last known foreground application, valid while [`WATCHING`] is set.
+static CURRENT: Mutex<Option<Arc<ForegroundApp>>> = Mutex::new(None);
+
+/// Set while the backend reports focus changes through [`changed`], so [`current`] can
+/// serve the cache instead of querying the OS.
+static WATCHING: AtomicBool = AtomicBool::new(false);
+
+fn lock() -> MutexGuard<'static, Option<Arc<ForegroundApp>>> {
+    CURRENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the foreground application, from the cache while focus changes are watched.
+pub(crate) fn current() -> Option<Arc<ForegroundApp>> {
+    if WATCHING.load(Ordering::Relaxed)
+        && let Some(app) = lock().as_ref()
+    {
+        return Some(app.clone());
+    }
+    let app = PlatformForeground::query().map(Arc::new);
+    if WATCHING.load(Ordering::Relaxed) {
+        *lock() = app.clone();
+    }
+    app
+}
+
+/// Records a focus change. Called by the platform backends.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn changed(app: Option<ForegroundApp>) {
+    *lock() = app.map(Arc::new);
+}
+
+/// Starts or stops serving [`current`] from the cache. Called by the platform backends
+/// when they install or remove their focus change notifications.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn set_watching(watching: bool) {
+    let mut current = lock();
+    *current = None;
+    WATCHING.store(watching, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    fn app(pid: u32) -> ForegroundApp {
+        ForegroundApp {
+            pid,
+            exe: Some(format!("App{pid}.exe")),
+            bundle_id: Some(format!("com.example.app{pid}")),
+            title: None,
+        }
+    }
+
+    #[serial]
+    #[test]
+    fn test_cache_follows_focus_changes() {
+        set_watching(true);
+        changed(Some(app(1)));
+        assert_eq!(current().as_deref(), Some(&app(1)));
+        changed(Some(app(2)));
+        assert_eq!(current().as_deref(), Some(&app(2)));
+
+        set_watching(false);
+        changed(Some(app(3)));
+        assert_eq!(current(), PlatformForeground::query().map(Arc::new));
+    }
+
+    #[test]
+    fn test_app_matcher() {
+        assert!(AppMatcher::exe("app1.EXE").matches(&app(1)));
+        assert!(!AppMatcher::exe("app2.exe").matches(&app(1)));
+        assert!(AppMatcher::bundle_id("com.example.app1").matches(&app(1)));
+        assert!(!AppMatcher::bundle_id("com.example.APP1").matches(&app(1)));
+    }
+
+    #[serial]
+    #[test]
+    fn test_rules_follow_focus() {
+        assert!(listen_allowed() && grab_allowed());
+        set_watching(true);
+        set_listen_rule(Some(AppMatcher::exe("app1.exe")));
+        set_grab_rule(Some(AppMatcher::exe("app2.exe")));
+
+        changed(Some(app(1)));
+        assert!(listen_allowed() && !grab_allowed());
+        changed(Some(app(2)));
+        assert!(!listen_allowed() && grab_allowed());
+
+        set_listen_rule(None);
+        set_grab_rule(None);
+        set_watching(false);
+        assert!(listen_allowed() && grab_allowed());
+    }
+}
@@ -0,0 +1,275 @@
+use std::{
+    f64::consts::PI,
+    fmt,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Listen,
+    event::{Event, MouseButton, Point},
+    subscription::SubscriptionHandle,
+};
+
+/// The direction of a single stroke of a [`Gesture`], in screen orientation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    /// Classifies a movement, into four or eight sectors.
+    fn of(delta: Point, diagonals: bool) -> Direction {
+        use Direction::*;
+        // Screen coordinates grow downwards
+        let angle = (-delta.y).atan2(delta.x);
+        if diagonals {
+            let sector = ((angle / (PI / 4.0)).round() as i32).rem_euclid(8);
+            [Right, UpRight, Up, UpLeft, Left, DownLeft, Down, DownRight][sector as usize]
+        } else {
+            let sector = ((angle / (PI / 2.0)).round() as i32).rem_euclid(4);
+            [Right, Up, Left, Down][sector as usize]
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// A completed mouse gesture, reported by [`MouseGesture`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Gesture {
+    /// The button held down while drawing the gesture.
+    pub button: MouseButton,
+    /// The strokes in the order they were drawn. Consecutive strokes differ.
+    pub strokes: Vec<Direction>,
+}
+
+impl fmt::Display for Gesture {
+    /// Formats as e.g. `Right+Down,DownRight`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}+", self.button)?;
+        for (i, stroke) in self.strokes.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{stroke}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Follows the mouse while the trigger button is held.
+#[derive(Debug)]
+pub(crate) struct Tracker {
+    button: MouseButton,
+    min_stroke: f64,
+    diagonals: bool,
+    /// `Some` while the trigger button is down.
+    strokes: Option<Vec<Direction>>,
+    /// Movement not yet long enough to form a stroke.
+    pending: Point,
+}
+
+impl Tracker {
+    pub(crate) fn new(button: MouseButton, min_stroke: f64, diagonals: bool) -> Self {
+        Self {
+            button,
+            min_stroke,
+            diagonals,
+            strokes: None,
+            pending: Point { x: 0.0, y: 0.0 },
+        }
+    }
+
+    /// Handles an event, returning the gesture it completes.
+    pub(crate) fn handle(&mut self, event: Event) -> Option<Gesture> {
+        match event {
+            Event::MouseDown { button } if button == self.button => {
+                self.strokes = Some(Vec::new());
+                self.pending = Point { x: 0.0, y: 0.0 };
+                None
+            }
+            Event::MouseMove { delta } => {
+                let strokes = self.strokes.as_mut()?;
+                self.pending.x += delta.x;
+                self.pending.y += delta.y;
+                if self.pending.x.hypot(self.pending.y) >= self.min_stroke {
+                    let direction = Direction::of(self.pending, self.diagonals);
+                    if strokes.last() != Some(&direction) {
+                        strokes.push(direction);
+                    }
+                    self.pending = Point { x: 0.0, y: 0.0 };
+                }
+                None
+            }
+            Event::MouseUp { button } if button == self.button => {
+                let strokes = self.strokes.take()?;
+                (!strokes.is_empty()).then_some(Gesture { button, strokes })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Recognizes mouse gestures: movement while a trigger button is held, split into
+/// direction strokes, for mouse-gesture launchers.
+///
+/// The gesture is reported when the button is released, if the mouse moved at least one
+/// stroke length. A click without movement produces no gesture. The button events still
+/// reach other applications.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Direction, MouseButton, MouseGesture};
+///
+/// let handle = MouseGesture::new(MouseButton::Right).subscribe(|gesture| {
+///     if gesture.strokes == [Direction::Down, Direction::Right] {
+///         println!("Close tab");
+///     }
+/// });
+///
+/// // Later
+/// handle.unsubscribe();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MouseGesture {
+    button: MouseButton,
+    min_stroke: f64,
+    diagonals: bool,
+}
+
+impl MouseGesture {
+    /// Creates a recognizer triggered by `button`, with 30 unit strokes and diagonals.
+    pub fn new(button: MouseButton) -> Self {
+        Self {
+            button,
+            min_stroke: 30.0,
+            diagonals: true,
+        }
+    }
+
+    /// Sets how far the mouse must move for a stroke, in the units of
+    /// [`Event::MouseMove`] deltas.
+    pub fn min_stroke(mut self, distance: f64) -> Self {
+        self.min_stroke = distance;
+        self
+    }
+
+    /// Whether strokes may be diagonal. Without, only the four main directions are used.
+    pub fn diagonals(mut self, enable: bool) -> Self {
+        self.diagonals = enable;
+        self
+    }
+
+    /// Starts recognizing gestures. Requires mouse move and button listening.
+    ///
+    /// The callback runs on the listener thread.
+    pub fn subscribe<F>(self, callback: F) -> MouseGestureHandle
+    where
+        F: Fn(Gesture) + Send + Sync + 'static,
+    {
+        let tracker = Mutex::new(Tracker::new(self.button, self.min_stroke, self.diagonals));
+        let tracker = Arc::new(tracker);
+
+        let handle = Listen::subscribe(move |event| {
+            let gesture = tracker
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .handle(event);
+            if let Some(gesture) = gesture {
+                callback(gesture);
+            }
+        });
+
+        MouseGestureHandle { handle }
+    }
+}
+
+/// A handle to a running [`MouseGesture`] recognizer.
+pub struct MouseGestureHandle {
+    handle: SubscriptionHandle,
+}
+
+impl MouseGestureHandle {
+    /// Stops recognizing gestures.
+    pub fn unsubscribe(self) {
+        self.handle.unsubscribe();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(x: f64, y: f64) -> Event {
+        Event::MouseMove {
+            delta: Point { x, y },
+        }
+    }
+
+    fn down(button: MouseButton) -> Event {
+        Event::MouseDown { button }
+    }
+
+    fn up(button: MouseButton) -> Event {
+        Event::MouseUp { button }
+    }
+
+    #[test]
+    fn test_strokes_while_button_held() {
+        let mut tracker = Tracker::new(MouseButton::Right, 30.0, true);
+
+        // Ignored without the trigger button
+        assert_eq!(tracker.handle(mv(100.0, 0.0)), None);
+
+        tracker.handle(down(MouseButton::Right));
+        for _ in 0..4 {
+            tracker.handle(mv(0.0, 10.0));
+        }
+        tracker.handle(mv(20.0, 20.0));
+        tracker.handle(mv(20.0, 20.0));
+        tracker.handle(mv(40.0, 0.0));
+        tracker.handle(mv(40.0, 0.0));
+
+        let gesture = tracker.handle(up(MouseButton::Right)).unwrap();
+        assert_eq!(
+            gesture.strokes,
+            [Direction::Down, Direction::DownRight, Direction::Right]
+        );
+        assert_eq!(gesture.to_string(), "Right+Down,DownRight,Right");
+    }
+
+    #[test]
+    fn test_click_without_movement_is_no_gesture() {
+        let mut tracker = Tracker::new(MouseButton::Right, 30.0, true);
+        tracker.handle(down(MouseButton::Right));
+        tracker.handle(mv(5.0, 5.0));
+        assert_eq!(tracker.handle(up(MouseButton::Left)), None);
+        assert_eq!(tracker.handle(up(MouseButton::Right)), None);
+    }
+
+    #[test]
+    fn test_direction_sectors() {
+        let point = |x, y| Point { x, y };
+        assert_eq!(Direction::of(point(0.0, -1.0), true), Direction::Up);
+        assert_eq!(Direction::of(point(-1.0, -1.0), true), Direction::UpLeft);
+        assert_eq!(Direction::of(point(-1.0, 1.0), true), Direction::DownLeft);
+        assert_eq!(Direction::of(point(-1.0, 1.0), false), Direction::Left);
+        assert_eq!(Direction::of(point(1.0, 0.3), false), Direction::Right);
+    }
+}
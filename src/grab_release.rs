@@ -0,0 +1,84 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::{
+    event::{Event, MouseButton},
+    key::Key,
+    platform::{PlatformSimulate, SimulateImpl},
+};
+
+/// What kept an event from the applications.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Blocker {
+    /// The `Grab` flags.
+    Grab,
+    /// The grab scope with this id.
+    Scope(u64),
+    /// The pipeline stage with this id, which includes grab filters.
+    Stage(u64),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Input {
+    Key(Key),
+    Button(MouseButton),
+}
+
+/// The keys and buttons the applications saw pressed, with what blocked their release,
+/// if anything did.
+static HELD: Mutex<Vec<(Input, Option<Blocker>)>> = Mutex::new(Vec::new());
+
+/// Records whether a press or release reached the applications, or what blocked it.
+/// Other events are ignored.
+pub(crate) fn track(event: &Event, blocker: Option<Blocker>) {
+    let (input, down) = match *event {
+        Event::KeyDown { key, .. } => (Input::Key(key), true),
+        Event::KeyUp { key, .. } => (Input::Key(key), false),
+        Event::MouseDown { button } => (Input::Button(button), true),
+        Event::MouseUp { button } => (Input::Button(button), false),
+        _ => return,
+    };
+    let mut held = HELD.lock().unwrap_or_else(PoisonError::into_inner);
+    let index = held.iter().position(|&(held, _)| held == input);
+    match (index, down, blocker) {
+        // The applications see the input held from now on
+        (None, true, None) => held.push((input, None)),
+        (Some(index), true, None) => held[index].1 = None,
+        // They saw it released
+        (Some(index), false, None) => {
+            held.swap_remove(index);
+        }
+        // They still see it held, and need the release once the blocker goes away
+        (Some(index), false, Some(blocker)) if held[index].1.is_none() => {
+            held[index].1 = Some(blocker)
+        }
+        // Blocked presses, and releases of presses they never saw, need nothing
+        _ => {}
+    }
+}
+
+/// Simulates the releases `blocker` kept from the applications, so none of them keeps a
+/// key or button held after `Grab` stops or a grab scope or filter is removed.
+pub(crate) fn release(blocker: Blocker) {
+    let released: Vec<Input> = {
+        let mut held = HELD.lock().unwrap_or_else(PoisonError::into_inner);
+        let released = held
+            .iter()
+            .filter(|&&(_, by)| by == Some(blocker))
+            .map(|&(input, _)| input)
+            .collect();
+        held.retain(|&(_, by)| by != Some(blocker));
+        released
+    };
+    for input in released {
+        match input {
+            Input::Key(key) => PlatformSimulate::keyboard(key, false),
+            Input::Button(button) => PlatformSimulate::mouse_button(button, false),
+        }
+    }
+}
+
+/// Forgets every key and button, for a fresh mock environment.
+#[cfg(feature = "mock")]
+pub(crate) fn reset() {
+    HELD.lock().unwrap_or_else(PoisonError::into_inner).clear();
+}
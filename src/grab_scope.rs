@@ -0,0 +1,143 @@
+use std::sync::{
+    Arc, PoisonError, RwLock,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    event::Event,
+    grab_release::{self, Blocker},
+};
+
+type Decide = Arc<dyn Fn(&Event, bool) -> GrabDecision + Send + Sync + 'static>;
+
+/// What a grab scope decides for an event, see [`Grab::add_scope`](crate::Grab::add_scope).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum GrabDecision {
+    /// Leaves the decision to lower-priority scopes and the `Grab` flags.
+    Abstain,
+    /// Lets the event through, whatever lower-priority scopes and the `Grab` flags say.
+    Pass,
+    /// Blocks the event, whatever lower-priority scopes and the `Grab` flags say.
+    Block,
+}
+
+#[derive(Clone)]
+struct Scope {
+    id: u64,
+    priority: i32,
+    decide: Decide,
+}
+
+/// The registered scopes, sorted by descending priority, then by registration. Replaced
+/// wholesale on every change so the hook can run them without holding the lock.
+static SCOPES: Lazy<RwLock<Arc<Vec<Scope>>>> = Lazy::new(RwLock::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Set while scopes are registered, so the hook can skip them.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` while grab scopes are registered.
+pub(crate) fn is_active() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Returns `Some` with the scope that blocks `event`, `Some(None)` to let it through, or
+/// `None` when every scope abstains and the `Grab` flags decide.
+pub(crate) fn decide(event: &Event, injected: bool) -> Option<Option<Blocker>> {
+    if !is_active() {
+        return None;
+    }
+    let scopes = SCOPES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    scopes
+        .iter()
+        .find_map(|scope| match (scope.decide)(event, injected) {
+            GrabDecision::Abstain => None,
+            GrabDecision::Pass => Some(None),
+            GrabDecision::Block => Some(Some(Blocker::Scope(scope.id))),
+        })
+}
+
+/// Registers a scope, see [`Grab::add_scope`](crate::Grab::add_scope).
+pub(crate) fn add<F>(priority: i32, decide: F) -> GrabScopeHandle
+where
+    F: Fn(&Event, bool) -> GrabDecision + Send + Sync + 'static,
+{
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    update(|scopes| {
+        let index = scopes.partition_point(|scope| scope.priority >= priority);
+        scopes.insert(
+            index,
+            Scope {
+                id,
+                priority,
+                decide: Arc::new(decide),
+            },
+        );
+    });
+    GrabScopeHandle { id }
+}
+
+fn update(f: impl FnOnce(&mut Vec<Scope>)) {
+    let mut guard = SCOPES.write().unwrap_or_else(PoisonError::into_inner);
+    let mut scopes = guard.as_ref().clone();
+    f(&mut scopes);
+    ENABLED.store(!scopes.is_empty(), Ordering::SeqCst);
+    *guard = Arc::new(scopes);
+}
+
+/// A handle to a scope added with [`Grab::add_scope`](crate::Grab::add_scope).
+pub struct GrabScopeHandle {
+    id: u64,
+}
+
+impl GrabScopeHandle {
+    /// Removes the scope. Releases it blocked are sent, so no application keeps a key or
+    /// button held.
+    pub fn remove(self) {
+        update(|scopes| scopes.retain(|scope| scope.id != self.id));
+        grab_release::release(Blocker::Scope(self.id));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{fixtures::key_down, key::Key};
+
+    #[serial]
+    #[test]
+    fn test_higher_priority_wins() {
+        let kiosk = add(0, |_: &Event, _| GrabDecision::Block);
+        let hotkeys = add(10, |event: &Event, _| match event {
+            Event::KeyDown { key: Key::F1, .. } => GrabDecision::Pass,
+            _ => GrabDecision::Abstain,
+        });
+        assert_eq!(decide(&key_down(Key::F1), false), Some(None));
+        assert_eq!(
+            decide(&key_down(Key::KeyA), false),
+            Some(Some(Blocker::Scope(kiosk.id)))
+        );
+
+        kiosk.remove();
+        assert_eq!(decide(&key_down(Key::KeyA), false), None);
+        hotkeys.remove();
+        assert!(!is_active());
+    }
+
+    #[serial]
+    #[test]
+    fn test_equal_priority_runs_in_registration_order() {
+        let first = add(5, |_: &Event, _| GrabDecision::Pass);
+        let second = add(5, |_: &Event, _| GrabDecision::Block);
+        assert_eq!(decide(&key_down(Key::KeyA), false), Some(None));
+        first.remove();
+        second.remove();
+    }
+}
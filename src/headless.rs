@@ -0,0 +1,205 @@
+use std::sync::{Mutex, OnceLock, PoisonError, RwLock};
+
+use crate::{
+    CursorImage, CursorPosition, MonitorInfo, Point, monitors,
+    platform::{DisplayImpl, PlatformDisplay},
+};
+
+/// Environment variable holding the headless monitors, see [`parse`].
+pub(crate) const ENV_VAR: &str = "RAW_INPUT_HEADLESS_MONITORS";
+
+/// Monitors set with [`Display::set_headless_monitors`](crate::Display::set_headless_monitors).
+static CONFIGURED: RwLock<Option<Vec<MonitorInfo>>> = RwLock::new(None);
+/// Monitors from [`ENV_VAR`], read once.
+static FROM_ENV: OnceLock<Option<Vec<MonitorInfo>>> = OnceLock::new();
+/// The virtual cursor, in physical pixels. Starts at the centre of the primary monitor.
+static CURSOR: Mutex<Option<Point>> = Mutex::new(None);
+
+/// Serves `Display` from the headless monitors.
+pub(crate) struct HeadlessDisplay;
+
+/// Replaces the monitors set through the API, falling back to [`ENV_VAR`] with `None`.
+pub(crate) fn set(monitors: Option<Vec<MonitorInfo>>) {
+    *CONFIGURED.write().unwrap_or_else(PoisonError::into_inner) = monitors;
+    *CURSOR.lock().unwrap_or_else(PoisonError::into_inner) = None;
+    monitors::invalidate();
+}
+
+/// Returns the configured headless monitors, whether or not a display is available.
+fn configured() -> Option<Vec<MonitorInfo>> {
+    let configured = CONFIGURED
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    configured
+        .or_else(|| {
+            FROM_ENV
+                .get_or_init(|| parse(&std::env::var(ENV_VAR).ok()?))
+                .clone()
+        })
+        .filter(|monitors| !monitors.is_empty())
+}
+
+/// Returns `true` if headless monitors are configured and the platform reports none.
+pub(crate) fn is_active() -> bool {
+    configured().is_some() && PlatformDisplay::get_available_monitors().is_empty()
+}
+
+/// Parses a monitor list such as `1920x1080;2560x1440+1920+0@2`: monitors separated by
+/// `;`, each `WIDTHxHEIGHT`, optionally followed by its signed offset (`+X+Y`, `-X+Y`,
+/// ...) and `@SCALE`. The first monitor is the primary one. Returns `None` if any part
+/// is malformed.
+pub(crate) fn parse(spec: &str) -> Option<Vec<MonitorInfo>> {
+    spec.split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .enumerate()
+        .map(|(index, entry)| {
+            let (geometry, scale_factor) = match entry.split_once('@') {
+                Some((geometry, scale)) => (geometry, scale.trim().parse().ok()?),
+                None => (entry, 1.0),
+            };
+            // The offset starts at the first sign after the size
+            let split = geometry.find(['+', '-']).unwrap_or(geometry.len());
+            let (size, offset) = geometry.split_at(split);
+            let (width, height) = size.split_once('x')?;
+            let offset = match offset {
+                "" => (0.0, 0.0),
+                offset => {
+                    let second = offset[1..].find(['+', '-'])? + 1;
+                    let (x, y) = offset.split_at(second);
+                    (x.parse().ok()?, y.parse().ok()?)
+                }
+            };
+            Some(MonitorInfo {
+                name: format!("Headless {}", index + 1),
+                is_primary: index == 0,
+                offset,
+                size: (width.trim().parse().ok()?, height.trim().parse().ok()?),
+                scale_factor,
+            })
+        })
+        .collect()
+}
+
+/// Coordinates are physical, with logical ones derived from the primary monitor's scale
+/// factor as on Windows.
+impl DisplayImpl for HeadlessDisplay {
+    fn get_scale_factor() -> f64 {
+        Self::get_primary_monitor().map_or(1.0, |m| m.scale_factor)
+    }
+
+    fn get_cursor_position() -> Option<(f64, f64)> {
+        let mut cursor = CURSOR.lock().unwrap_or_else(PoisonError::into_inner);
+        if cursor.is_none() {
+            let primary = Self::get_primary_monitor()?;
+            *cursor = Some(primary.center());
+        }
+        cursor.map(|cursor| (cursor.x, cursor.y))
+    }
+
+    fn set_cursor_position(x: f64, y: f64) {
+        *CURSOR.lock().unwrap_or_else(PoisonError::into_inner) = Some(Point { x, y });
+    }
+
+    fn get_cursor() -> Option<CursorPosition> {
+        let (x, y) = Self::get_cursor_position()?;
+        let scale_factor = Self::get_scale_factor();
+        Some(CursorPosition {
+            physical: Point { x, y },
+            logical: Point {
+                x: x / scale_factor,
+                y: y / scale_factor,
+            },
+            monitor: Self::get_monitor_from_point(x, y),
+        })
+    }
+
+    /// The virtual cursor has no image.
+    fn get_cursor_image() -> Option<CursorImage> {
+        None
+    }
+
+    fn get_primary_screen_size() -> (f64, f64) {
+        Self::get_primary_monitor().map_or((0.0, 0.0), |m| m.size)
+    }
+
+    fn get_virtual_screen_size() -> (f64, f64) {
+        let (_, _, w, h) = Self::get_virtual_screen_bounds();
+        (w, h)
+    }
+
+    /// The bounding box of all monitors.
+    fn get_virtual_screen_bounds() -> (f64, f64, f64, f64) {
+        let bounds = Self::get_available_monitors()
+            .iter()
+            .map(MonitorInfo::bounds)
+            .reduce(|bounds, monitor| bounds.union(&monitor))
+            .unwrap_or_default();
+        (bounds.x, bounds.y, bounds.width, bounds.height)
+    }
+
+    fn get_available_monitors() -> Vec<MonitorInfo> {
+        configured().unwrap_or_default()
+    }
+
+    fn get_primary_monitor() -> Option<MonitorInfo> {
+        Self::get_available_monitors()
+            .into_iter()
+            .find(|m| m.is_primary)
+    }
+
+    fn get_current_monitor() -> Option<MonitorInfo> {
+        Self::get_cursor_position().and_then(|(x, y)| Self::get_monitor_from_point(x, y))
+    }
+
+    fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo> {
+        Self::get_available_monitors()
+            .into_iter()
+            .find(|m| m.contains(Point { x, y }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let monitors = parse("1920x1080; 2560x1440-2560+0@2").unwrap();
+        assert_eq!(monitors.len(), 2);
+        assert!(monitors[0].is_primary);
+        assert_eq!(monitors[0].offset, (0.0, 0.0));
+        assert_eq!(monitors[0].scale_factor, 1.0);
+        assert_eq!(monitors[1].offset, (-2560.0, 0.0));
+        assert_eq!(monitors[1].size, (2560.0, 1440.0));
+        assert_eq!(monitors[1].scale_factor, 2.0);
+
+        assert_eq!(parse("1920x1080+10"), None);
+        assert_eq!(parse("1920by1080"), None);
+        assert_eq!(parse("1920x1080@big"), None);
+    }
+
+    #[serial]
+    #[test]
+    fn test_headless_display() {
+        set(parse("1920x1080@1.5;1280x1024+1920+0"));
+
+        assert_eq!(HeadlessDisplay::get_scale_factor(), 1.5);
+        assert_eq!(
+            HeadlessDisplay::get_virtual_screen_bounds(),
+            (0.0, 0.0, 3200.0, 1080.0)
+        );
+        let cursor = HeadlessDisplay::get_cursor().unwrap();
+        assert_eq!(cursor.physical, Point { x: 960.0, y: 540.0 });
+        assert_eq!(cursor.logical, Point { x: 640.0, y: 360.0 });
+
+        HeadlessDisplay::set_cursor_position(2000.0, 10.0);
+        let current = HeadlessDisplay::get_current_monitor().unwrap();
+        assert_eq!(current.name, "Headless 2");
+
+        set(None);
+    }
+}
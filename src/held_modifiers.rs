@@ -0,0 +1,89 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::{
+    event::Event,
+    key::{Key, Modifiers},
+};
+
+/// The modifier keys tracked, one bit each in [`HELD`].
+const KEYS: [Key; 8] = [
+    Key::ControlLeft,
+    Key::ControlRight,
+    Key::ShiftLeft,
+    Key::ShiftRight,
+    Key::AltLeft,
+    Key::AltRight,
+    Key::MetaLeft,
+    Key::MetaRight,
+];
+
+/// The modifier keys held, as seen by the backends.
+static HELD: AtomicU8 = AtomicU8::new(0);
+
+/// Tracks the modifier key of a key event. Returns the modifiers held afterwards if the
+/// event changed them. Called by the platform backends for every event they handle.
+pub(crate) fn update(event: &Event) -> Option<Modifiers> {
+    let (key, down) = match *event {
+        Event::KeyDown { key, .. } => (key, true),
+        Event::KeyUp { key, .. } => (key, false),
+        _ => return None,
+    };
+    let bit = 1 << KEYS.iter().position(|&k| k == key)?;
+    let old = if down {
+        HELD.fetch_or(bit, Ordering::SeqCst)
+    } else {
+        HELD.fetch_and(!bit, Ordering::SeqCst)
+    };
+    let new = if down { old | bit } else { old & !bit };
+
+    let (old, new) = (modifiers(old), modifiers(new));
+    (old != new).then_some(new)
+}
+
+/// Forgets the modifier keys held, whose releases go unseen once the core stops.
+pub(crate) fn clear() {
+    HELD.store(0, Ordering::SeqCst);
+}
+
+/// Returns the modifiers of the held keys in `held`.
+fn modifiers(held: u8) -> Modifiers {
+    KEYS.iter()
+        .enumerate()
+        .filter(|&(i, _)| held & 1 << i != 0)
+        .filter_map(|(_, &key)| Modifiers::from_key(key))
+        .fold(Modifiers::NONE, |acc, modifier| acc | modifier)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    fn down(key: Key) -> Event {
+        Event::KeyDown { key, code: None }
+    }
+
+    fn up(key: Key) -> Event {
+        Event::KeyUp { key, code: None }
+    }
+
+    #[test]
+    #[serial]
+    fn test_sides_count_as_one_modifier() {
+        clear();
+        assert_eq!(update(&down(Key::ShiftLeft)), Some(Modifiers::SHIFT));
+        assert_eq!(update(&down(Key::ShiftRight)), None);
+        // Auto-repeat changes nothing
+        assert_eq!(update(&down(Key::ShiftLeft)), None);
+        assert_eq!(update(&down(Key::KeyA)), None);
+        assert_eq!(
+            update(&down(Key::ControlRight)),
+            Some(Modifiers::SHIFT | Modifiers::CONTROL)
+        );
+        assert_eq!(update(&up(Key::ShiftLeft)), None);
+        assert_eq!(update(&up(Key::ShiftRight)), Some(Modifiers::CONTROL));
+        assert_eq!(update(&up(Key::ControlRight)), Some(Modifiers::NONE));
+        assert_eq!(update(&Event::FocusChanged { pid: 1 }), None);
+    }
+}
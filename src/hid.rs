@@ -0,0 +1,203 @@
+use std::sync::{
+    Arc, Mutex, PoisonError,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    device::DeviceId,
+    platform::{HidImpl, PlatformHid},
+};
+
+type Callback = Arc<dyn Fn(&HidReport) + Send + Sync + 'static>;
+
+/// A HID top-level collection, identified by its usage page and usage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct HidUsage {
+    pub page: u16,
+    pub usage: u16,
+}
+
+impl HidUsage {
+    /// Mice, delivered as [`Event`](crate::Event)s rather than HID reports.
+    pub const MOUSE: HidUsage = HidUsage::new(0x01, 0x02);
+    /// Joysticks, including most foot pedals.
+    pub const JOYSTICK: HidUsage = HidUsage::new(0x01, 0x04);
+    /// Gamepads.
+    pub const GAMEPAD: HidUsage = HidUsage::new(0x01, 0x05);
+    /// Keyboards, delivered as [`Event`](crate::Event)s rather than HID reports.
+    pub const KEYBOARD: HidUsage = HidUsage::new(0x01, 0x06);
+    /// Power, sleep and wake buttons.
+    pub const SYSTEM_CONTROL: HidUsage = HidUsage::new(0x01, 0x80);
+    /// Media keys, volume controls and most remote controls.
+    pub const CONSUMER_CONTROL: HidUsage = HidUsage::new(0x0C, 0x01);
+
+    pub const fn new(page: u16, usage: u16) -> Self {
+        Self { page, usage }
+    }
+}
+
+/// An input report received from a HID device.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct HidReport<'a> {
+    /// The subscribed usage the device matched.
+    pub usage: HidUsage,
+    /// The device that sent the report.
+    pub device: DeviceId,
+    /// The report as sent by the device, starting with the report ID if it uses them.
+    pub data: &'a [u8],
+}
+
+/// Raw input reports from HID devices such as consumer controls, remote controls and
+/// foot pedals.
+///
+/// Devices are registered through the Raw Input window on Windows and an
+/// `IOHIDManager` on macOS, and only while a subscription for their usage exists.
+/// Mice and keyboards are delivered as events and never produce reports here. Requires
+/// `Core` to be running; on macOS the process needs the Input Monitoring permission.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Hid, HidUsage};
+///
+/// let handle = Hid::subscribe(HidUsage::CONSUMER_CONTROL, |report| {
+///     println!("{:?}: {:02x?}", report.device, report.data);
+/// });
+///
+/// // Later
+/// handle.remove();
+/// ```
+pub struct Hid;
+
+impl Hid {
+    /// Delivers every report of devices with the given usage to `callback`.
+    ///
+    /// The callback runs on the core thread, so it must return quickly.
+    pub fn subscribe<F>(usage: HidUsage, callback: F) -> HidHandle
+    where
+        F: Fn(&HidReport) + Send + Sync + 'static,
+    {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        {
+            let mut subscriptions = lock();
+            subscriptions.push((id, usage, Arc::new(callback)));
+            HAS_SUBSCRIPTIONS.store(true, Ordering::SeqCst);
+        }
+        PlatformHid::update();
+        HidHandle { id }
+    }
+}
+
+/// A handle to a subscription created with [`Hid::subscribe`].
+pub struct HidHandle {
+    id: u64,
+}
+
+impl HidHandle {
+    /// Removes the subscription. Devices no other subscription needs are unregistered.
+    pub fn remove(self) {
+        {
+            let mut subscriptions = lock();
+            subscriptions.retain(|(id, _, _)| *id != self.id);
+            HAS_SUBSCRIPTIONS.store(!subscriptions.is_empty(), Ordering::SeqCst);
+        }
+        PlatformHid::update();
+    }
+}
+
+static SUBSCRIPTIONS: Lazy<Mutex<Vec<(u64, HidUsage, Callback)>>> = Lazy::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Set while at least one subscription exists, so raw input can skip HID processing.
+static HAS_SUBSCRIPTIONS: AtomicBool = AtomicBool::new(false);
+
+fn lock() -> std::sync::MutexGuard<'static, Vec<(u64, HidUsage, Callback)>> {
+    SUBSCRIPTIONS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns `true` while reports have a subscriber.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn is_active() -> bool {
+    HAS_SUBSCRIPTIONS.load(Ordering::Relaxed)
+}
+
+/// Returns `true` while a subscription exists for `usage`.
+#[cfg_attr(any(feature = "mock", not(target_os = "windows")), allow(dead_code))]
+pub(crate) fn is_subscribed(usage: HidUsage) -> bool {
+    lock().iter().any(|&(_, subscribed, _)| subscribed == usage)
+}
+
+/// Returns the distinct usages the backend must register, without mice and keyboards,
+/// which the core already handles.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn usages() -> Vec<HidUsage> {
+    let mut usages: Vec<HidUsage> = Vec::new();
+    for &(_, usage, _) in lock().iter() {
+        if usage != HidUsage::MOUSE && usage != HidUsage::KEYBOARD && !usages.contains(&usage) {
+            usages.push(usage);
+        }
+    }
+    usages
+}
+
+/// Delivers a report to the subscribers of its usage. Called by the platform backends.
+#[cfg_attr(target_os = "android", allow(dead_code))]
+pub(crate) fn dispatch(report: &HidReport) {
+    // Callbacks run outside the lock so they may subscribe or unsubscribe
+    let callbacks: Vec<Callback> = lock()
+        .iter()
+        .filter(|(_, usage, _)| *usage == report.usage)
+        .map(|(_, _, callback)| callback.clone())
+        .collect();
+    for callback in callbacks {
+        callback(report);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    #[serial]
+    #[test]
+    fn test_dispatch_by_usage() {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let consumer = Hid::subscribe(HidUsage::CONSUMER_CONTROL, move |report| {
+            sink.lock().unwrap().push(report.data.to_vec());
+        });
+        let pedal = Hid::subscribe(HidUsage::JOYSTICK, |_| panic!("wrong usage"));
+        assert!(is_active());
+
+        dispatch(&HidReport {
+            usage: HidUsage::CONSUMER_CONTROL,
+            device: DeviceId(1),
+            data: &[0x01, 0xE9, 0x00],
+        });
+        assert_eq!(*received.lock().unwrap(), vec![vec![0x01, 0xE9, 0x00]]);
+
+        consumer.remove();
+        pedal.remove();
+        assert!(!is_active());
+    }
+
+    #[serial]
+    #[test]
+    fn test_usages_are_deduplicated() {
+        let a = Hid::subscribe(HidUsage::CONSUMER_CONTROL, |_| {});
+        let b = Hid::subscribe(HidUsage::CONSUMER_CONTROL, |_| {});
+        let keyboard = Hid::subscribe(HidUsage::KEYBOARD, |_| {});
+        assert_eq!(usages(), vec![HidUsage::CONSUMER_CONTROL]);
+        assert!(is_subscribed(HidUsage::KEYBOARD));
+
+        a.remove();
+        assert_eq!(usages(), vec![HidUsage::CONSUMER_CONTROL]);
+        b.remove();
+        keyboard.remove();
+        assert!(usages().is_empty());
+        assert!(!is_subscribed(HidUsage::KEYBOARD));
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{Display, MonitorInfo, edge::Edge, event::Point};
+
+/// How often the cursor position is sampled while a hot zone is active.
+const POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// A corner of a monitor.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+/// A screen region that can be watched by a [`HotZone`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Zone {
+    /// A square of `margin` size in a corner of the monitor.
+    Corner(Corner),
+    /// A strip of `margin` thickness along a side of the monitor.
+    Edge(Edge),
+}
+
+/// Emitted when the cursor has lingered inside a hot zone for the configured dwell time.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct HotZoneEvent {
+    /// The zone that was activated.
+    pub zone: Zone,
+    /// The monitor on which the zone was activated.
+    pub monitor: MonitorInfo,
+    /// The cursor position at activation time.
+    pub position: Point,
+}
+
+/// Hot corner and edge zone detection.
+///
+/// Zones are evaluated against the monitor under the cursor, so every monitor has its
+/// own set of corners and edges. The event fires once per visit, after the cursor has
+/// stayed inside the zone for the dwell time; it re-arms when the cursor leaves.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use raw_input::{Corner, HotZone, Zone};
+///
+/// let handle = HotZone::new(Zone::Corner(Corner::TopLeft))
+///     .margin(4.0)
+///     .dwell(Duration::from_millis(300))
+///     .subscribe(|event| println!("Hot corner on {}", event.monitor.name));
+///
+/// // Later
+/// handle.stop();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct HotZone {
+    zone: Zone,
+    margin: f64,
+    dwell: Duration,
+}
+
+impl HotZone {
+    /// Creates a hot zone with a 2 pixel margin and a 250 ms dwell time.
+    pub fn new(zone: Zone) -> Self {
+        Self {
+            zone,
+            margin: 2.0,
+            dwell: Duration::from_millis(250),
+        }
+    }
+
+    /// Sets the size of the zone, measured inward from the monitor edges.
+    pub fn margin(mut self, margin: f64) -> Self {
+        self.margin = margin.max(1.0);
+        self
+    }
+
+    /// Sets how long the cursor must stay inside the zone before the event fires.
+    pub fn dwell(mut self, dwell: Duration) -> Self {
+        self.dwell = dwell;
+        self
+    }
+
+    /// Starts watching the zone on a background thread.
+    ///
+    /// The returned handle stops the watcher when [`HotZoneHandle::stop`] is called.
+    pub fn subscribe<F>(self, callback: F) -> HotZoneHandle
+    where
+        F: Fn(HotZoneEvent) + Send + 'static,
+    {
+        let running = Arc::new(AtomicBool::new(true));
+        let flag = running.clone();
+
+        thread::Builder::new()
+            .name("raw-input-hot-zone".to_string())
+            .spawn(move || {
+                let mut entered_at: Option<Instant> = None;
+                let mut fired = false;
+
+                while flag.load(Ordering::Relaxed) {
+                    thread::sleep(POLL_INTERVAL);
+
+                    let hit = Display::get_cursor_position().and_then(|(x, y)| {
+                        let monitor = Display::get_monitor_from_point(x, y)?;
+                        let position = Point { x, y };
+                        self.contains(&monitor, position)
+                            .then_some((monitor, position))
+                    });
+
+                    let Some((monitor, position)) = hit else {
+                        entered_at = None;
+                        fired = false;
+                        continue;
+                    };
+
+                    let since = *entered_at.get_or_insert_with(Instant::now);
+                    if !fired && since.elapsed() >= self.dwell {
+                        fired = true;
+                        callback(HotZoneEvent {
+                            zone: self.zone,
+                            monitor,
+                            position,
+                        });
+                    }
+                }
+            })
+            .expect("failed to spawn thread");
+
+        HotZoneHandle { running }
+    }
+
+    /// Checks whether `point` lies inside this zone on `monitor`.
+    pub(crate) fn contains(&self, monitor: &MonitorInfo, point: Point) -> bool {
+        let bounds = monitor.bounds();
+        let (left, top, right, bottom) = (bounds.x, bounds.y, bounds.right(), bounds.bottom());
+
+        let near_left = point.x < left + self.margin;
+        let near_right = point.x >= right - self.margin;
+        let near_top = point.y < top + self.margin;
+        let near_bottom = point.y >= bottom - self.margin;
+
+        match self.zone {
+            Zone::Corner(Corner::TopLeft) => near_top && near_left,
+            Zone::Corner(Corner::TopRight) => near_top && near_right,
+            Zone::Corner(Corner::BottomLeft) => near_bottom && near_left,
+            Zone::Corner(Corner::BottomRight) => near_bottom && near_right,
+            Zone::Edge(Edge::Left) => near_left,
+            Zone::Edge(Edge::Right) => near_right,
+            Zone::Edge(Edge::Top) => near_top,
+            Zone::Edge(Edge::Bottom) => near_bottom,
+        }
+    }
+}
+
+/// A handle that stops a running [`HotZone`] watcher.
+pub struct HotZoneHandle {
+    running: Arc<AtomicBool>,
+}
+
+impl HotZoneHandle {
+    /// Stops watching the zone. The callback will not be called again.
+    pub fn stop(self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{self, point};
+
+    fn monitor() -> MonitorInfo {
+        fixtures::monitor("main", 0.0, 0.0, 1920.0, 1080.0)
+    }
+
+    #[test]
+    fn test_corner_contains() {
+        let zone = HotZone::new(Zone::Corner(Corner::TopLeft)).margin(5.0);
+        assert!(zone.contains(&monitor(), point(0.0, 0.0)));
+        assert!(zone.contains(&monitor(), point(4.0, 4.0)));
+        assert!(!zone.contains(&monitor(), point(5.0, 0.0)));
+        assert!(!zone.contains(&monitor(), point(0.0, 500.0)));
+
+        let zone = HotZone::new(Zone::Corner(Corner::BottomRight)).margin(5.0);
+        assert!(zone.contains(&monitor(), point(1919.0, 1079.0)));
+        assert!(!zone.contains(&monitor(), point(1919.0, 0.0)));
+    }
+
+    #[test]
+    fn test_edge_contains() {
+        let zone = HotZone::new(Zone::Edge(Edge::Bottom)).margin(10.0);
+        assert!(zone.contains(&monitor(), point(960.0, 1075.0)));
+        assert!(zone.contains(&monitor(), point(0.0, 1079.0)));
+        assert!(!zone.contains(&monitor(), point(960.0, 1000.0)));
+    }
+
+    #[test]
+    fn test_margin_is_clamped() {
+        let zone = HotZone::new(Zone::Edge(Edge::Left)).margin(0.0);
+        assert!(zone.contains(&monitor(), point(0.0, 500.0)));
+    }
+
+    #[test]
+    fn test_contains_respects_monitor_offset() {
+        let mut second = monitor();
+        second.offset = (1920.0, -200.0);
+        let zone = HotZone::new(Zone::Corner(Corner::TopLeft));
+        assert!(zone.contains(&second, point(1920.0, -200.0)));
+        assert!(!zone.contains(&second, point(0.0, 0.0)));
+    }
+}
@@ -0,0 +1,713 @@
+use std::{
+    collections::{HashMap, HashSet},
+    str::FromStr,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    event::Event,
+    key::{Key, Modifiers},
+    platform::{CoreImpl, HotkeyImpl, PlatformCore, PlatformHotkeys},
+    polling,
+    shortcut::{ParseShortcutError, Shortcut},
+};
+
+type Callback = Arc<dyn Fn() + Send + Sync + 'static>;
+type ResetCallback = Arc<dyn Fn(SequenceReset) + Send + Sync + 'static>;
+
+/// Default time allowed between two chords of a [`HotkeySequence`].
+const DEFAULT_SEQUENCE_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Registration {
+    steps: Vec<Shortcut>,
+    timeout: Duration,
+    suppress: bool,
+    callback: Callback,
+    on_reset: Option<ResetCallback>,
+    /// Number of chords already matched.
+    progress: usize,
+    /// When the last chord was matched.
+    last_step: Option<Instant>,
+    /// Bumped on every state change, so stale timeout timers can be recognised.
+    generation: u64,
+}
+
+impl Registration {
+    fn reset(&mut self) {
+        self.progress = 0;
+        self.last_step = None;
+        self.generation += 1;
+    }
+}
+
+#[derive(Default)]
+struct State {
+    hotkeys: HashMap<u64, Registration>,
+    /// Keys currently held down, used to derive the active modifiers and detect auto-repeat.
+    pressed: HashSet<Key>,
+    /// Keys whose press was consumed; their repeats and release are consumed as well.
+    suppressed: HashSet<Key>,
+}
+
+impl State {
+    fn modifiers(&self, except: Key) -> Modifiers {
+        self.pressed
+            .iter()
+            .filter(|&&k| k != except)
+            .filter_map(|&k| Modifiers::from_key(k))
+            .fold(Modifiers::NONE, |acc, m| acc | m)
+    }
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Set while at least one hotkey is registered, so the hook can skip hotkey processing.
+static HAS_HOTKEYS: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` while the built-in hotkey stage has work to do.
+pub(crate) fn is_active() -> bool {
+    HAS_HOTKEYS.load(Ordering::Relaxed)
+}
+
+/// Returns the hotkeys to register with the OS hotkey service, by id: the single-chord
+/// ones, while neither the hooks nor the polling fallback match them. Modifier keys never
+/// make a hotkey of their own there.
+pub(crate) fn native() -> Vec<(u64, Shortcut)> {
+    if PlatformCore::hooks_installed() || polling::is_active() {
+        return Vec::new();
+    }
+    let mut hotkeys: Vec<(u64, Shortcut)> = lock()
+        .hotkeys
+        .iter()
+        .filter(|(_, reg)| reg.steps.len() == 1 && Modifiers::from_key(reg.steps[0].key).is_none())
+        .map(|(&id, reg)| (id, reg.steps[0]))
+        .collect();
+    hotkeys.sort_by_key(|&(id, _)| id);
+    hotkeys
+}
+
+/// Runs the callback of a hotkey the OS hotkey service reported. Called by the platform
+/// backends.
+pub(crate) fn fire(id: u64) {
+    let callback = lock().hotkeys.get(&id).map(|reg| reg.callback.clone());
+    if let Some(callback) = callback {
+        callback();
+    }
+}
+
+/// A global keyboard shortcut.
+///
+/// Hotkeys are matched inside the platform hook, so they only require `Core` to be running.
+/// A hotkey fires when its key is pressed while exactly its modifiers are held; auto-repeat
+/// does not fire it again.
+///
+/// A hotkey also works while `Core` is stopped, for applications that need nothing but a
+/// few shortcuts: it is then registered with the OS hotkey service, which needs no hooks.
+/// On Windows that is `RegisterHotKey` and the callback runs on a thread of the crate; on
+/// macOS it is `RegisterEventHotKey`, which needs no Accessibility permission, and the
+/// callback runs on the main thread while it runs the application's event loop. The
+/// shortcut is always consumed that way, and one another application registered first
+/// never fires. This covers [`Hotkey`]s, not [`HotkeySequence`]s of several chords.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Hotkey, Key, Modifiers};
+///
+/// // Consume Ctrl+Shift+K so the focused application never sees it
+/// let handle = Hotkey::new(Modifiers::CONTROL | Modifiers::SHIFT, Key::KeyK)
+///     .suppress(true)
+///     .register(|| println!("Hotkey pressed"));
+///
+/// // Shortcuts can also be parsed from strings
+/// let shortcut = "Ctrl+Alt+Delete".parse().unwrap();
+/// let other = Hotkey::from_shortcut(shortcut).register(|| println!("Other pressed"));
+///
+/// // Later
+/// handle.unregister();
+/// other.unregister();
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Hotkey {
+    shortcut: Shortcut,
+    suppress: bool,
+}
+
+impl Hotkey {
+    /// Creates a hotkey for `key` pressed with exactly `modifiers` held.
+    pub fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self::from_shortcut(Shortcut::new(modifiers, key))
+    }
+
+    /// Creates a hotkey for a [`Shortcut`].
+    pub fn from_shortcut(shortcut: Shortcut) -> Self {
+        Self {
+            shortcut,
+            suppress: false,
+        }
+    }
+
+    /// Consumes the triggering key press, its auto-repeats, and its release so they never
+    /// reach other applications. Modifier keys themselves are not consumed.
+    pub fn suppress(mut self, suppress: bool) -> Self {
+        self.suppress = suppress;
+        self
+    }
+
+    /// Registers the hotkey. The callback runs on the core thread.
+    pub fn register<F>(self, callback: F) -> HotkeyHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        HotkeySequence::new([self.shortcut])
+            .suppress(self.suppress)
+            .register(callback)
+    }
+}
+
+impl From<Shortcut> for Hotkey {
+    fn from(shortcut: Shortcut) -> Self {
+        Self::from_shortcut(shortcut)
+    }
+}
+
+/// Why a partially entered [`HotkeySequence`] was abandoned.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SequenceReset {
+    /// The next chord was not pressed within the sequence timeout.
+    Timeout,
+    /// A key that does not continue the sequence was pressed.
+    Mismatch,
+}
+
+/// A multi-step global shortcut, such as `Ctrl+K` followed by `D`.
+///
+/// Each chord must be pressed within the timeout of the previous one. Pressing any other
+/// non-modifier key abandons the sequence; if that key starts the sequence again, matching
+/// restarts from it. Modifier presses between chords are ignored.
+///
+/// When suppression is enabled, every chord of the sequence is consumed, including the
+/// leading chords of a sequence that is later abandoned.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use raw_input::HotkeySequence;
+///
+/// let handle = "Ctrl+K D"
+///     .parse::<HotkeySequence>()
+///     .unwrap()
+///     .timeout(Duration::from_millis(800))
+///     .on_reset(|reason| println!("Sequence abandoned: {:?}", reason))
+///     .register(|| println!("Ctrl+K D pressed"));
+///
+/// // Later
+/// handle.unregister();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HotkeySequence {
+    steps: Vec<Shortcut>,
+    timeout: Duration,
+    suppress: bool,
+}
+
+impl HotkeySequence {
+    /// Creates a sequence from its chords, with a one second inter-chord timeout.
+    ///
+    /// # Panics
+    /// Panics if `steps` is empty.
+    pub fn new(steps: impl IntoIterator<Item = Shortcut>) -> Self {
+        let steps: Vec<Shortcut> = steps.into_iter().collect();
+        assert!(
+            !steps.is_empty(),
+            "a hotkey sequence needs at least one chord"
+        );
+        Self {
+            steps,
+            timeout: DEFAULT_SEQUENCE_TIMEOUT,
+            suppress: false,
+        }
+    }
+
+    /// Sets the maximum time allowed between two consecutive chords.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Consumes the key presses of every chord, as [`Hotkey::suppress`] does.
+    pub fn suppress(mut self, suppress: bool) -> Self {
+        self.suppress = suppress;
+        self
+    }
+
+    /// Adds a callback that runs when a partially entered sequence is abandoned.
+    pub fn on_reset<F>(self, callback: F) -> SequenceBuilder
+    where
+        F: Fn(SequenceReset) + Send + Sync + 'static,
+    {
+        SequenceBuilder {
+            sequence: self,
+            on_reset: Arc::new(callback),
+        }
+    }
+
+    /// Registers the sequence. The callback runs on the core thread.
+    pub fn register<F>(self, callback: F) -> HotkeyHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        register(self, Arc::new(callback), None)
+    }
+}
+
+/// Parses whitespace-separated chords, e.g. `"Ctrl+K D"` or `"Ctrl+X Ctrl+S"`.
+impl FromStr for HotkeySequence {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let steps = s
+            .split_whitespace()
+            .map(str::parse)
+            .collect::<Result<Vec<Shortcut>, _>>()?;
+        if steps.is_empty() {
+            return Err(ParseShortcutError::Empty);
+        }
+        Ok(Self::new(steps))
+    }
+}
+
+/// A [`HotkeySequence`] with a reset callback attached, ready to be registered.
+pub struct SequenceBuilder {
+    sequence: HotkeySequence,
+    on_reset: ResetCallback,
+}
+
+impl SequenceBuilder {
+    /// Registers the sequence. Both callbacks run on the core thread, or on a timer
+    /// thread when the reset is caused by a timeout.
+    pub fn register<F>(self, callback: F) -> HotkeyHandle
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        register(self.sequence, Arc::new(callback), Some(self.on_reset))
+    }
+}
+
+fn register(
+    sequence: HotkeySequence,
+    callback: Callback,
+    on_reset: Option<ResetCallback>,
+) -> HotkeyHandle {
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let mut state = lock();
+    state.hotkeys.insert(
+        id,
+        Registration {
+            steps: sequence.steps,
+            timeout: sequence.timeout,
+            suppress: sequence.suppress,
+            callback,
+            on_reset,
+            progress: 0,
+            last_step: None,
+            generation: 0,
+        },
+    );
+    HAS_HOTKEYS.store(true, Ordering::SeqCst);
+    drop(state);
+    PlatformHotkeys::update();
+    HotkeyHandle { id }
+}
+
+/// A handle to a registered [`Hotkey`] or [`HotkeySequence`].
+pub struct HotkeyHandle {
+    id: u64,
+}
+
+impl HotkeyHandle {
+    /// Removes the hotkey. Its callback will not be called again.
+    pub fn unregister(self) {
+        let mut state = lock();
+        state.hotkeys.remove(&self.id);
+        HAS_HOTKEYS.store(!state.hotkeys.is_empty(), Ordering::SeqCst);
+        drop(state);
+        PlatformHotkeys::update();
+    }
+}
+
+fn lock() -> std::sync::MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Resets a pending sequence once its timeout elapses without further progress.
+fn schedule_timeout(id: u64, generation: u64, timeout: Duration) {
+    thread::Builder::new()
+        .name("raw-input-hotkey-timer".to_string())
+        .spawn(move || {
+            thread::sleep(timeout);
+            let on_reset = {
+                let mut state = lock();
+                let Some(reg) = state.hotkeys.get_mut(&id) else {
+                    return;
+                };
+                if reg.generation != generation || reg.progress == 0 {
+                    return;
+                }
+                reg.reset();
+                reg.on_reset.clone()
+            };
+            if let Some(on_reset) = on_reset {
+                on_reset(SequenceReset::Timeout);
+            }
+        })
+        .expect("failed to spawn thread");
+}
+
+/// Feeds a translated event to the hotkey matcher.
+///
+/// Called by the built-in pipeline stage for every input event. Returns `true` if the event
+/// belongs to a suppressing hotkey and must be blocked.
+pub(crate) fn process(event: &Event) -> bool {
+    if !HAS_HOTKEYS.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    let (key, down) = match *event {
+        Event::KeyDown { key, .. } => (key, true),
+        Event::KeyUp { key, .. } => (key, false),
+        _ => return false,
+    };
+
+    let mut fired = Vec::new();
+    let mut resets = Vec::new();
+    let mut timers = Vec::new();
+    let block = {
+        let mut state = lock();
+        if down {
+            let repeat = !state.pressed.insert(key);
+            if repeat {
+                state.suppressed.contains(&key)
+            } else {
+                let chord = Shortcut::new(state.modifiers(key), key);
+                let is_modifier = Modifiers::from_key(key).is_some();
+                let now = Instant::now();
+                let mut suppress = false;
+
+                for (&id, reg) in state.hotkeys.iter_mut() {
+                    if reg.progress > 0 {
+                        let expired = reg
+                            .last_step
+                            .is_some_and(|at| now.duration_since(at) > reg.timeout);
+                        if expired {
+                            reg.reset();
+                            resets
+                                .extend(reg.on_reset.clone().map(|f| (f, SequenceReset::Timeout)));
+                        } else if reg.steps[reg.progress] != chord && !is_modifier {
+                            reg.reset();
+                            resets
+                                .extend(reg.on_reset.clone().map(|f| (f, SequenceReset::Mismatch)));
+                        }
+                    }
+
+                    if reg.steps[reg.progress] != chord {
+                        continue;
+                    }
+                    suppress |= reg.suppress;
+                    if reg.progress + 1 == reg.steps.len() {
+                        reg.reset();
+                        fired.push(reg.callback.clone());
+                    } else {
+                        reg.progress += 1;
+                        reg.last_step = Some(now);
+                        reg.generation += 1;
+                        timers.push((id, reg.generation, reg.timeout));
+                    }
+                }
+
+                if suppress {
+                    state.suppressed.insert(key);
+                }
+                suppress
+            }
+        } else {
+            state.pressed.remove(&key);
+            state.suppressed.remove(&key)
+        }
+    };
+
+    for (id, generation, timeout) in timers {
+        schedule_timeout(id, generation, timeout);
+    }
+
+    // Run callbacks outside the lock so they may register or unregister hotkeys
+    for (on_reset, reason) in resets {
+        on_reset(reason);
+    }
+    for callback in fired {
+        callback();
+    }
+
+    block
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use serial_test::serial;
+
+    use super::*;
+
+    fn reset() {
+        *lock() = State::default();
+        HAS_HOTKEYS.store(false, Ordering::SeqCst);
+    }
+
+    fn down(key: Key) -> bool {
+        process(&Event::KeyDown { key, code: None })
+    }
+
+    fn up(key: Key) -> bool {
+        process(&Event::KeyUp { key, code: None })
+    }
+
+    fn counter() -> (Arc<AtomicUsize>, impl Fn() + Send + Sync + 'static) {
+        let count = Arc::new(AtomicUsize::new(0));
+        let clone = count.clone();
+        (count, move || {
+            clone.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+
+    #[serial]
+    #[test]
+    fn test_hotkey_fires_with_exact_modifiers() {
+        reset();
+        let (count, callback) = counter();
+        let handle = Hotkey::new(Modifiers::CONTROL, Key::KeyK).register(callback);
+
+        // Without modifiers
+        down(Key::KeyK);
+        up(Key::KeyK);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        // With Ctrl
+        down(Key::ControlLeft);
+        down(Key::KeyK);
+        up(Key::KeyK);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // With Ctrl+Shift
+        down(Key::ShiftLeft);
+        down(Key::KeyK);
+        up(Key::KeyK);
+        up(Key::ShiftLeft);
+        up(Key::ControlLeft);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_hotkey_ignores_auto_repeat() {
+        reset();
+        let (count, callback) = counter();
+        let handle = Hotkey::new(Modifiers::NONE, Key::F5).register(callback);
+
+        down(Key::F5);
+        down(Key::F5);
+        down(Key::F5);
+        up(Key::F5);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_hotkey_suppression_blocks_press_repeat_and_release() {
+        reset();
+        let (_, callback) = counter();
+        let handle = Hotkey::new(Modifiers::ALT, Key::Space)
+            .suppress(true)
+            .register(callback);
+
+        assert!(!down(Key::AltLeft), "modifiers are never consumed");
+        assert!(down(Key::Space));
+        assert!(down(Key::Space));
+        assert!(up(Key::Space));
+        assert!(!up(Key::AltLeft));
+
+        // Unrelated keys pass through
+        assert!(!down(Key::KeyA));
+        assert!(!up(Key::KeyA));
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_hotkey_without_suppression_passes_through() {
+        reset();
+        let (count, callback) = counter();
+        let handle = Hotkey::new(Modifiers::NONE, Key::F1).register(callback);
+
+        assert!(!down(Key::F1));
+        assert!(!up(Key::F1));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_unregister_clears_active_flag() {
+        reset();
+        let handle = Hotkey::new(Modifiers::NONE, Key::F1).register(|| {});
+        assert!(HAS_HOTKEYS.load(Ordering::SeqCst));
+        handle.unregister();
+        assert!(!HAS_HOTKEYS.load(Ordering::SeqCst));
+        reset();
+    }
+
+    fn shortcut(s: &str) -> Shortcut {
+        s.parse().unwrap()
+    }
+
+    fn tap(key: Key) {
+        down(key);
+        up(key);
+    }
+
+    fn resets() -> (
+        Arc<Mutex<Vec<SequenceReset>>>,
+        impl Fn(SequenceReset) + Send + Sync + 'static,
+    ) {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let clone = log.clone();
+        (log, move |reason| clone.lock().unwrap().push(reason))
+    }
+
+    #[serial]
+    #[test]
+    fn test_sequence_fires_after_all_chords() {
+        reset();
+        let (count, callback) = counter();
+        let handle = "Ctrl+K D"
+            .parse::<HotkeySequence>()
+            .unwrap()
+            .register(callback);
+
+        down(Key::ControlLeft);
+        tap(Key::KeyK);
+        up(Key::ControlLeft);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        tap(Key::KeyD);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        // The sequence starts over afterwards
+        tap(Key::KeyD);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_sequence_mismatch_resets() {
+        reset();
+        let (count, callback) = counter();
+        let (log, on_reset) = resets();
+        let handle = HotkeySequence::new([shortcut("Ctrl+K"), shortcut("D")])
+            .on_reset(on_reset)
+            .register(callback);
+
+        down(Key::ControlLeft);
+        tap(Key::KeyK);
+        up(Key::ControlLeft);
+        tap(Key::KeyX);
+        tap(Key::KeyD);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        assert_eq!(*log.lock().unwrap(), vec![SequenceReset::Mismatch]);
+
+        // A mismatching key that starts the sequence restarts it
+        down(Key::ControlLeft);
+        tap(Key::KeyK);
+        tap(Key::KeyK);
+        up(Key::ControlLeft);
+        tap(Key::KeyD);
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_sequence_timeout_resets() {
+        reset();
+        let (count, callback) = counter();
+        let (log, on_reset) = resets();
+        let handle = "F1 F2"
+            .parse::<HotkeySequence>()
+            .unwrap()
+            .timeout(Duration::from_millis(20))
+            .on_reset(on_reset)
+            .register(callback);
+
+        tap(Key::F1);
+        thread::sleep(Duration::from_millis(100));
+        assert_eq!(*log.lock().unwrap(), vec![SequenceReset::Timeout]);
+
+        tap(Key::F2);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+
+        handle.unregister();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_sequence_suppresses_every_chord() {
+        reset();
+        let (_, callback) = counter();
+        let handle = "Ctrl+X Ctrl+S"
+            .parse::<HotkeySequence>()
+            .unwrap()
+            .suppress(true)
+            .register(callback);
+
+        assert!(!down(Key::ControlLeft));
+        assert!(down(Key::KeyX));
+        assert!(up(Key::KeyX));
+        assert!(down(Key::KeyS));
+        assert!(up(Key::KeyS));
+        assert!(!up(Key::ControlLeft));
+
+        handle.unregister();
+        reset();
+    }
+
+    #[test]
+    fn test_sequence_parse_errors() {
+        assert_eq!(
+            "  ".parse::<HotkeySequence>(),
+            Err(ParseShortcutError::Empty)
+        );
+        assert!("Ctrl+K Banana".parse::<HotkeySequence>().is_err());
+    }
+}
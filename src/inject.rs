@@ -0,0 +1,126 @@
+use std::time::Duration;
+
+use crate::{
+    dispatcher,
+    event::Event,
+    foreground,
+    grab_release::{self, Blocker},
+    pipeline,
+    platform::{GrabImpl, PlatformGrab, PlatformListen},
+    sequence::{self, EventSource},
+    state,
+};
+
+/// Runs an event through the subscribers, the middleware pipeline and the grab filter
+/// the way the platform hooks do, handing the events the pipeline synthesizes to
+/// `inject`. Returns `true` if the event may reach other applications unchanged.
+pub(crate) fn process(event: Event, injected: bool, inject: fn(Event)) -> bool {
+    state::observed();
+
+    let blocker = blocks(event, injected, inject);
+    // Remember the presses the applications saw, and who blocks their releases
+    grab_release::track(&event, blocker);
+    blocker.is_none()
+}
+
+/// Returns what consumes the event: the pipeline, a grab scope or the `Grab` flags.
+fn blocks(event: Event, injected: bool, inject: fn(Event)) -> Option<Blocker> {
+    if dispatcher::has_active() || !pipeline::is_idle() {
+        sequence::with_origin(EventSource::Hook, Duration::ZERO, || {
+            PlatformListen::handle(event, injected)
+        });
+
+        // Run the middleware pipeline (remapping, scroll transforms, hotkeys, ...)
+        let outcome = pipeline::run(event, injected);
+        for emitted in outcome.emitted {
+            inject(emitted);
+        }
+        if let Some(stage) = outcome.block {
+            return Some(stage);
+        }
+        if let Some((replaced, stage)) = outcome.replaced {
+            inject(replaced);
+            return Some(stage);
+        }
+        // A grab scope's decision overrides the `Grab` flags
+        if let Some(block) = outcome.grab {
+            return block;
+        }
+    }
+
+    grabs(&event, injected).then_some(Blocker::Grab)
+}
+
+/// Returns `true` if `Grab` blocks the event.
+fn grabs(event: &Event, injected: bool) -> bool {
+    let flags = PlatformGrab::flags();
+    if !flags.running || (injected && flags.pass_injected) || !foreground::grab_allowed() {
+        return false;
+    }
+
+    match event {
+        Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. } => flags.mouse_move,
+        Event::MouseDown { .. } | Event::MouseUp { .. } => flags.mouse_button,
+        Event::MouseWheel { .. } => flags.mouse_wheel,
+        Event::KeyDown { .. } | Event::KeyUp { .. } => flags.keyboard,
+        Event::FocusChanged { .. } | Event::ModifiersChanged { .. } => false,
+    }
+}
+
+/// Feeds events the pipeline synthesizes for [`Listen::inject_for_test`] back in as
+/// injected events, instead of injecting them into the OS.
+///
+/// [`Listen::inject_for_test`]: crate::Listen::inject_for_test
+#[cfg(feature = "test-util")]
+pub(crate) fn replay(event: Event) {
+    process(event, true, replay);
+}
+
+#[cfg(all(test, feature = "test-util"))]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{Key, Listen, Remap};
+
+    #[serial]
+    #[test]
+    fn test_inject_runs_the_pipeline() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        Listen::start().ok();
+        let handle = Listen::subscribe(move |event| sink.lock().unwrap().push(event));
+        let remap = Remap::add(Key::CapsLock, Key::Escape);
+
+        let caps = Event::KeyDown {
+            key: Key::CapsLock,
+            code: None,
+        };
+        assert!(!Listen::inject_for_test(caps));
+        assert!(Listen::inject_for_test(Event::KeyUp {
+            key: Key::KeyA,
+            code: None
+        }));
+        // Releases the remapped key, so the remapper goes idle again
+        assert!(!Listen::inject_for_test(Event::KeyUp {
+            key: Key::CapsLock,
+            code: None
+        }));
+
+        remap.remove();
+        handle.unsubscribe();
+        Listen::stop();
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0], caps);
+        assert!(matches!(
+            seen[1],
+            Event::KeyDown {
+                key: Key::Escape,
+                ..
+            }
+        ));
+        assert_eq!(seen.len(), 5);
+    }
+}
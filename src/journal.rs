@@ -0,0 +1,347 @@
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Listen, event::Event, subscription::SubscriptionHandle, wire};
+
+/// Leading bytes of a binary journal file.
+const BINARY_MAGIC: &[u8; 4] = b"RIJL";
+
+/// Size of the magic and version that start a binary journal file.
+const BINARY_HEADER_LEN: u64 = BINARY_MAGIC.len() as u64 + 2;
+
+/// On-disk representation used by a [`Journal`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum JournalFormat {
+    /// One JSON object per line, easy to inspect and to process with line-based tools.
+    ///
+    /// `{ "time_us": 1700000000000000, "event": { ... } }`
+    Jsonl,
+    /// Compact binary: the `RIJL` magic and the little-endian `u16` [`wire::VERSION`],
+    /// then for each event a `u64` microsecond timestamp and the event in the [`wire`]
+    /// encoding. Appending to a file never rewrites the header.
+    #[default]
+    Binary,
+}
+
+/// Options for [`Journal::start`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub struct JournalOptions {
+    pub format: JournalFormat,
+    /// Rotates the file before it grows beyond this many bytes. `None`, the default,
+    /// never rotates.
+    pub max_file_size: Option<u64>,
+    /// How many rotated files to keep, as `<path>.1` (the newest) to `<path>.<n>`. Older
+    /// ones are deleted; with `0` the journal starts over in a fresh file.
+    pub max_files: usize,
+}
+
+/// An event read back from a journal with [`Journal::read`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct JournalEntry {
+    /// When the event was captured, with microsecond precision.
+    pub time: SystemTime,
+    pub event: Event,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonEntry {
+    time_us: u64,
+    event: Event,
+}
+
+/// Appends captured events to a file, for debugging and forensic capture without any
+/// I/O in the application's own callbacks.
+///
+/// Events are timestamped when they are dispatched and written by a thread of their
+/// own, so a slow disk never delays the hooks. An existing file is appended to. Requires
+/// `Core` and `Listen` to be running.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Journal, JournalFormat, JournalOptions};
+///
+/// let journal = Journal::start("input.jsonl", JournalOptions {
+///     format: JournalFormat::Jsonl,
+///     max_file_size: Some(10 * 1024 * 1024),
+///     max_files: 5,
+/// })
+/// .expect("failed to open the journal");
+///
+/// // Later
+/// journal.stop().expect("failed to write the journal");
+/// ```
+pub struct Journal {
+    handle: SubscriptionHandle,
+    thread: JoinHandle<io::Result<()>>,
+}
+
+impl Journal {
+    /// Opens or creates the file at `path` and starts appending events to it.
+    pub fn start(path: impl AsRef<Path>, options: JournalOptions) -> io::Result<Journal> {
+        let mut writer = Writer::open(path.as_ref().to_path_buf(), options)?;
+        let (sender, receiver) = mpsc::channel::<(SystemTime, Event)>();
+
+        // The thread ends once the subscription, and with it the sender, is dropped
+        let thread = thread::Builder::new()
+            .name("raw-input-journal".to_string())
+            .spawn(move || {
+                // Flushed whenever the queue runs empty, so bursts are written in one go
+                while let Ok((time, event)) = receiver.recv() {
+                    writer.append(time, &event)?;
+                    while let Ok((time, event)) = receiver.try_recv() {
+                        writer.append(time, &event)?;
+                    }
+                    writer.file.flush()?;
+                }
+                Ok(())
+            })
+            .expect("failed to spawn thread");
+
+        let handle = Listen::subscribe(move |event| {
+            let _ = sender.send((SystemTime::now(), event));
+        });
+        Ok(Journal { handle, thread })
+    }
+
+    /// Stops capturing and waits until every captured event is written.
+    ///
+    /// Returns the error that stopped the journal early, if writing failed.
+    pub fn stop(self) -> io::Result<()> {
+        self.handle.unsubscribe();
+        self.thread
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("journal thread panicked")))
+    }
+
+    /// Reads the entries of a journal file in either format. Rotated files are read one
+    /// at a time.
+    pub fn read(path: impl AsRef<Path>) -> io::Result<Vec<JournalEntry>> {
+        let bytes = fs::read(path)?;
+        if let Some(rest) = bytes.strip_prefix(BINARY_MAGIC) {
+            return read_binary(rest).ok_or_else(|| invalid_data("malformed binary journal"));
+        }
+        bytes
+            .split(|&b| b == b'\n')
+            .filter(|line| !line.trim_ascii().is_empty())
+            .map(|line| {
+                let entry: JsonEntry = serde_json::from_slice(line).map_err(io::Error::other)?;
+                Ok(JournalEntry {
+                    time: UNIX_EPOCH + Duration::from_micros(entry.time_us),
+                    event: entry.event,
+                })
+            })
+            .collect()
+    }
+}
+
+fn read_binary(mut input: &[u8]) -> Option<Vec<JournalEntry>> {
+    let version = u16::from_le_bytes(wire::take(&mut input)?);
+    if version == 0 || version > wire::VERSION {
+        return None;
+    }
+    let mut entries = Vec::new();
+    while !input.is_empty() {
+        let time = Duration::from_micros(u64::from_le_bytes(wire::take(&mut input)?));
+        if let Some(event) = wire::decode(&mut input).ok()? {
+            entries.push(JournalEntry {
+                time: UNIX_EPOCH + time,
+                event,
+            });
+        }
+    }
+    Some(entries)
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// The file a journal is written to, rotated by size.
+struct Writer {
+    path: PathBuf,
+    options: JournalOptions,
+    file: BufWriter<File>,
+    size: u64,
+    /// Size of the file header, so a file holding only the header is never rotated.
+    header: u64,
+    record: Vec<u8>,
+}
+
+impl Writer {
+    fn open(path: PathBuf, options: JournalOptions) -> io::Result<Self> {
+        let header = match options.format {
+            JournalFormat::Jsonl => 0,
+            JournalFormat::Binary => BINARY_HEADER_LEN,
+        };
+        let (file, size) = open_file(&path, options.format)?;
+        Ok(Writer {
+            path,
+            options,
+            file,
+            size,
+            header,
+            record: Vec::new(),
+        })
+    }
+
+    fn append(&mut self, time: SystemTime, event: &Event) -> io::Result<()> {
+        let time_us = time
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_micros() as u64;
+        self.record.clear();
+        match self.options.format {
+            JournalFormat::Jsonl => {
+                let entry = JsonEntry {
+                    time_us,
+                    event: *event,
+                };
+                serde_json::to_writer(&mut self.record, &entry).map_err(io::Error::other)?;
+                self.record.push(b'\n');
+            }
+            JournalFormat::Binary => {
+                self.record.extend_from_slice(&time_us.to_le_bytes());
+                wire::encode(event, &mut self.record);
+            }
+        }
+
+        let len = self.record.len() as u64;
+        if let Some(max) = self.options.max_file_size
+            && self.size > self.header
+            && self.size + len > max
+        {
+            self.rotate()?;
+        }
+        self.file.write_all(&self.record)?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Moves the file to `<path>.1`, shifting older files up, and opens a fresh one.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let keep = self.options.max_files;
+        if keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            for n in (1..keep).rev() {
+                match fs::rename(rotated(&self.path, n), rotated(&self.path, n + 1)) {
+                    Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+                    _ => {}
+                }
+            }
+            fs::rename(&self.path, rotated(&self.path, 1))?;
+        }
+        (self.file, self.size) = open_file(&self.path, self.options.format)?;
+        Ok(())
+    }
+}
+
+/// Opens `path` for appending, writing the header of a binary journal to an empty file.
+/// Returns the file and its size.
+fn open_file(path: &Path, format: JournalFormat) -> io::Result<(BufWriter<File>, u64)> {
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let mut size = file.metadata()?.len();
+    let mut file = BufWriter::new(file);
+    if format == JournalFormat::Binary && size == 0 {
+        file.write_all(BINARY_MAGIC)?;
+        file.write_all(&wire::VERSION.to_le_bytes())?;
+        size = BINARY_HEADER_LEN;
+    }
+    Ok((file, size))
+}
+
+/// Returns the path of the `n`th rotated file, `<path>.<n>`.
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::Key;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("raw-input-journal-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(name);
+        for n in 0..4 {
+            let _ = fs::remove_file(if n == 0 {
+                path.clone()
+            } else {
+                rotated(&path, n)
+            });
+        }
+        path
+    }
+
+    fn key_down(n: usize) -> Event {
+        Event::KeyDown {
+            key: [Key::KeyA, Key::KeyB, Key::KeyC, Key::KeyD][n % 4],
+            code: None,
+        }
+    }
+
+    fn write(path: &Path, options: JournalOptions, count: usize) {
+        let mut writer = Writer::open(path.to_path_buf(), options).unwrap();
+        for n in 0..count {
+            let time = UNIX_EPOCH + Duration::from_micros(n as u64);
+            writer.append(time, &key_down(n)).unwrap();
+        }
+        writer.file.flush().unwrap();
+    }
+
+    #[test]
+    fn test_round_trip_and_append() {
+        for (name, format) in [
+            ("a.jsonl", JournalFormat::Jsonl),
+            ("a.bin", JournalFormat::Binary),
+        ] {
+            let path = temp_path(name);
+            let options = JournalOptions {
+                format,
+                ..Default::default()
+            };
+            write(&path, options, 2);
+            write(&path, options, 1);
+
+            let entries = Journal::read(&path).unwrap();
+            let events: Vec<Event> = entries.iter().map(|entry| entry.event).collect();
+            assert_eq!(events, [key_down(0), key_down(1), key_down(0)]);
+            assert_eq!(entries[1].time, UNIX_EPOCH + Duration::from_micros(1));
+        }
+    }
+
+    #[test]
+    fn test_rotation_keeps_max_files() {
+        let path = temp_path("rotated.bin");
+        let mut record = Vec::new();
+        wire::encode(&key_down(0), &mut record);
+        // The header and two records fit, the third rotates
+        let options = JournalOptions {
+            format: JournalFormat::Binary,
+            max_file_size: Some(BINARY_HEADER_LEN + 2 * (8 + record.len() as u64)),
+            max_files: 2,
+        };
+        write(&path, options, 7);
+
+        let read = |path: &Path| Journal::read(path).unwrap().len();
+        assert_eq!(read(&path), 1);
+        assert_eq!(read(&rotated(&path, 1)), 2);
+        assert_eq!(read(&rotated(&path, 2)), 2);
+        assert!(!rotated(&path, 3).exists());
+        let newest = Journal::read(&path).unwrap();
+        assert_eq!(newest[0].event, key_down(6));
+    }
+}
@@ -1,3 +1,5 @@
+use std::{fmt, str::FromStr};
+
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
 
@@ -152,6 +154,281 @@ pub enum Key {
     // Katakana,
     #[default]
     Unidentified,
+    /// A key outside the mapping tables, identified by its raw platform key code.
+    ///
+    /// Captured keys that have no named variant are reported as `Raw` instead of
+    /// [`Key::Unidentified`], and simulating a `Raw` key presses the same physical key
+    /// again. Raw codes are platform-specific and must not be exchanged between platforms.
+    Raw(RawKey),
+}
+
+/// A raw platform key code: the virtual-key code on Windows, the `CGKeyCode` on macOS.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct RawKey(pub KeyCode);
+
+impl Key {
+    /// Every named key, in declaration order. [`Key::Raw`] is not included.
+    #[rustfmt::skip]
+    pub const ALL: &'static [Key] = &[
+        // --- Writing System Keys ---
+        Key::Backquote, Key::Backslash, Key::BracketLeft, Key::BracketRight, Key::Comma,
+        Key::Digit0, Key::Digit1, Key::Digit2, Key::Digit3, Key::Digit4, Key::Digit5, Key::Digit6,
+        Key::Digit7, Key::Digit8, Key::Digit9, Key::Equal, Key::IntlBackslash, Key::IntlRo,
+        Key::IntlYen, Key::KeyA, Key::KeyB, Key::KeyC, Key::KeyD, Key::KeyE, Key::KeyF, Key::KeyG,
+        Key::KeyH, Key::KeyI, Key::KeyJ, Key::KeyK, Key::KeyL, Key::KeyM, Key::KeyN, Key::KeyO,
+        Key::KeyP, Key::KeyQ, Key::KeyR, Key::KeyS, Key::KeyT, Key::KeyU, Key::KeyV, Key::KeyW,
+        Key::KeyX, Key::KeyY, Key::KeyZ, Key::Minus, Key::Period, Key::Quote, Key::Semicolon,
+        Key::Slash,
+
+        // --- Functional Keys ---
+        Key::AltLeft, Key::AltRight, Key::Backspace, Key::CapsLock, Key::ContextMenu,
+        Key::ControlLeft, Key::ControlRight, Key::Enter, Key::MetaLeft, Key::MetaRight,
+        Key::ShiftLeft, Key::ShiftRight, Key::Space, Key::Tab, Key::Convert, Key::NonConvert,
+
+        // --- Control Pad Section ---
+        Key::Delete, Key::End, Key::Help, Key::Home, Key::Insert, Key::PageDown, Key::PageUp,
+
+        // --- Arrow Pad Section ---
+        Key::ArrowDown, Key::ArrowLeft, Key::ArrowRight, Key::ArrowUp,
+
+        // --- Numpad Section ---
+        Key::NumLock, Key::Numpad0, Key::Numpad1, Key::Numpad2, Key::Numpad3, Key::Numpad4,
+        Key::Numpad5, Key::Numpad6, Key::Numpad7, Key::Numpad8, Key::Numpad9, Key::NumpadAdd,
+        Key::NumpadDecimal, Key::NumpadDivide, Key::NumpadEnter, Key::NumpadMultiply,
+        Key::NumpadSubtract,
+
+        // --- Function Section ---
+        Key::Escape, Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7, Key::F8,
+        Key::F9, Key::F10, Key::F11, Key::F12, Key::F13, Key::F14, Key::F15, Key::F16, Key::F17,
+        Key::F18, Key::F19, Key::F20, Key::F21, Key::F22, Key::F23, Key::F24, Key::PrintScreen,
+        Key::ScrollLock, Key::Pause,
+
+        // --- Legacy/Special ---
+        Key::Unidentified,
+    ];
+
+    /// Converts a legacy key name to the corresponding W3C `code` key.
+    ///
+    /// Earlier versions of this crate, and the `rdev` crate its keycode tables originate
+    /// from, named keys after their US layout legends (`Num0`, `Dot`, `Return`,
+    /// `UpArrow`, `KpPlus`, ...). Names that only differ from the W3C name in case, such as
+    /// `BackQuote`, are not listed here; [`FromStr`] accepts both.
+    ///
+    /// # Example
+    /// ```
+    /// use raw_input::Key;
+    ///
+    /// assert_eq!(Key::from_legacy_name("Return"), Some(Key::Enter));
+    /// assert_eq!(Key::from_legacy_name("Num7"), Some(Key::Digit7));
+    /// assert_eq!(Key::from_legacy_name("Enter"), None);
+    /// ```
+    pub fn from_legacy_name(name: &str) -> Option<Key> {
+        LEGACY_NAMES
+            .iter()
+            .find(|(legacy, _)| legacy.eq_ignore_ascii_case(name))
+            .map(|&(_, key)| key)
+    }
 }
 
+/// Legacy key names and the keys they map to, see [`Key::from_legacy_name`].
+#[rustfmt::skip]
+const LEGACY_NAMES: &[(&str, Key)] = &[
+    ("LeftBracket", Key::BracketLeft), ("RightBracket", Key::BracketRight),
+    ("Num0", Key::Digit0), ("Num1", Key::Digit1), ("Num2", Key::Digit2), ("Num3", Key::Digit3),
+    ("Num4", Key::Digit4), ("Num5", Key::Digit5), ("Num6", Key::Digit6), ("Num7", Key::Digit7),
+    ("Num8", Key::Digit8), ("Num9", Key::Digit9),
+    ("Dot", Key::Period),
+    ("Alt", Key::AltLeft), ("AltGr", Key::AltRight), ("Return", Key::Enter),
+    ("UpArrow", Key::ArrowUp), ("DownArrow", Key::ArrowDown),
+    ("LeftArrow", Key::ArrowLeft), ("RightArrow", Key::ArrowRight),
+    ("Kp0", Key::Numpad0), ("Kp1", Key::Numpad1), ("Kp2", Key::Numpad2), ("Kp3", Key::Numpad3),
+    ("Kp4", Key::Numpad4), ("Kp5", Key::Numpad5), ("Kp6", Key::Numpad6), ("Kp7", Key::Numpad7),
+    ("Kp8", Key::Numpad8), ("Kp9", Key::Numpad9),
+    ("KpPlus", Key::NumpadAdd), ("KpMinus", Key::NumpadSubtract),
+    ("KpMultiply", Key::NumpadMultiply), ("KpDivide", Key::NumpadDivide),
+    ("KpDelete", Key::NumpadDecimal), ("KpReturn", Key::NumpadEnter),
+    ("Unknown", Key::Unidentified),
+];
+
+/// Formats the key as its W3C `code` name, e.g. `KeyA`, `ArrowUp` or `Numpad4`.
+///
+/// Raw keys are formatted as `Raw(<code>)`, e.g. `Raw(255)`.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Key::Raw(RawKey(code)) => write!(f, "Raw({})", code),
+            _ => fmt::Debug::fmt(self, f),
+        }
+    }
+}
+
+/// Parses a W3C `code` name, ignoring ASCII case.
+///
+/// Legacy names accepted by [`Key::from_legacy_name`] are parsed as well, so
+/// configurations written against the old names keep working.
+///
+/// # Example
+/// ```
+/// use raw_input::Key;
+///
+/// assert_eq!("ArrowUp".parse::<Key>(), Ok(Key::ArrowUp));
+/// assert_eq!("keya".parse::<Key>(), Ok(Key::KeyA));
+/// assert_eq!("Return".parse::<Key>(), Ok(Key::Enter));
+/// assert_eq!("Raw(255)".parse::<Key>(), Ok(Key::Raw(raw_input::RawKey(255))));
+/// ```
+impl FromStr for Key {
+    type Err = ParseKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Key::ALL
+            .iter()
+            .find(|key| key.to_string().eq_ignore_ascii_case(s))
+            .copied()
+            .or_else(|| Key::from_legacy_name(s))
+            .or_else(|| parse_raw(s))
+            .ok_or_else(|| ParseKeyError(s.to_string()))
+    }
+}
+
+/// Parses the `Raw(<code>)` form produced by [`Key`]'s `Display` implementation.
+fn parse_raw(s: &str) -> Option<Key> {
+    let prefix = s.get(..4)?;
+    if !prefix.eq_ignore_ascii_case("raw(") {
+        return None;
+    }
+    let code = s[4..].strip_suffix(')')?.trim().parse().ok()?;
+    Some(Key::Raw(RawKey(code)))
+}
+
+/// Error returned when a string does not name a known [`Key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseKeyError(pub String);
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown key name: {:?}", self.0)
+    }
+}
 
+impl std::error::Error for ParseKeyError {}
+
+/// A set of modifier keys, without distinguishing left and right.
+///
+/// # Example
+/// ```
+/// use raw_input::Modifiers;
+///
+/// let mods = Modifiers::CONTROL | Modifiers::SHIFT;
+/// assert!(mods.contains(Modifiers::SHIFT));
+/// assert!(!mods.contains(Modifiers::ALT));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const NONE: Modifiers = Modifiers(0);
+    /// Control key.
+    pub const CONTROL: Modifiers = Modifiers(1 << 0);
+    /// Shift key.
+    pub const SHIFT: Modifiers = Modifiers(1 << 1);
+    /// Alt key, Option on macOS.
+    pub const ALT: Modifiers = Modifiers(1 << 2);
+    /// Windows key, Command on macOS, or Super.
+    pub const META: Modifiers = Modifiers(1 << 3);
+
+    /// Returns `true` if all modifiers in `other` are set.
+    #[inline]
+    pub const fn contains(self, other: Modifiers) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `true` if no modifier is set.
+    #[inline]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Sets the modifiers in `other`.
+    #[inline]
+    pub fn insert(&mut self, other: Modifiers) {
+        self.0 |= other.0;
+    }
+
+    /// Clears the modifiers in `other`.
+    #[inline]
+    pub fn remove(&mut self, other: Modifiers) {
+        self.0 &= !other.0;
+    }
+
+    /// Returns the modifiers as bits: `1` for Control, `2` for Shift, `4` for Alt and `8`
+    /// for Meta.
+    #[inline]
+    pub const fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Creates modifiers from [`bits`](Self::bits), ignoring bits without a modifier.
+    #[inline]
+    pub const fn from_bits_truncate(bits: u8) -> Modifiers {
+        Modifiers(bits & 0x0F)
+    }
+
+    /// Returns the modifier a key corresponds to, if it is a modifier key.
+    pub const fn from_key(key: Key) -> Option<Modifiers> {
+        match key {
+            Key::ControlLeft | Key::ControlRight => Some(Modifiers::CONTROL),
+            Key::ShiftLeft | Key::ShiftRight => Some(Modifiers::SHIFT),
+            Key::AltLeft | Key::AltRight => Some(Modifiers::ALT),
+            Key::MetaLeft | Key::MetaRight => Some(Modifiers::META),
+            _ => None,
+        }
+    }
+}
+
+impl std::ops::BitOr for Modifiers {
+    type Output = Modifiers;
+
+    fn bitor(self, rhs: Modifiers) -> Modifiers {
+        Modifiers(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Modifiers) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_names() {
+        assert_eq!("Num0".parse::<Key>(), Ok(Key::Digit0));
+        assert_eq!("kpreturn".parse::<Key>(), Ok(Key::NumpadEnter));
+        assert_eq!(Key::from_legacy_name("Banana"), None);
+
+        // Legacy names never shadow a W3C name
+        for (legacy, _) in LEGACY_NAMES {
+            assert!(
+                !Key::ALL
+                    .iter()
+                    .any(|key| key.to_string().eq_ignore_ascii_case(legacy)),
+                "{legacy} is also a W3C name"
+            );
+        }
+    }
+
+    #[test]
+    fn test_raw_key_display_and_parse() {
+        let key = Key::Raw(RawKey(255));
+        assert_eq!(key.to_string(), "Raw(255)");
+        assert_eq!("Raw(255)".parse::<Key>(), Ok(key));
+        assert_eq!("raw( 255 )".parse::<Key>(), Ok(key));
+        assert!("Raw(-1)".parse::<Key>().is_err());
+        assert!("Raw(".parse::<Key>().is_err());
+    }
+}
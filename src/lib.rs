@@ -24,11 +24,12 @@
 //!     Core::start().expect("Failed to start raw-input core");
 //! });
 //!
-//! // 2. Subscribe to global events
-//! Listen::start();
+//! // 2. Subscribe to global events. The core thread may still be starting up, which
+//! // `start` reports as `StateError::NotStarted`
+//! let _ = Listen::start();
 //! let handle = Listen::subscribe(|event| {
 //!     match event {
-//!         Event::KeyDown { key } => println!("Key pressed: {:?}", key),
+//!         Event::KeyDown { key, .. } => println!("Key pressed: {:?}", key),
 //!         Event::MouseMove { delta } => println!("Mouse moved by: {}, {}", delta.x, delta.y),
 //!         _ => {},
 //!     }
@@ -47,24 +48,182 @@
 //! Core::stop();
 //! ```
 
+#[cfg(feature = "listen")]
+mod analytics;
+#[cfg(feature = "barrier")]
+pub mod barrier;
+#[cfg(feature = "listen")]
+mod batch;
+#[cfg(feature = "listen")]
+mod block;
+#[cfg(any(feature = "postcard", feature = "bincode"))]
+pub mod codec;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "listen")]
+mod cursor_poll;
+#[cfg(feature = "listen")]
+mod device;
+#[cfg(feature = "listen")]
 mod dispatcher;
+#[cfg(feature = "listen")]
+mod edge;
+mod error;
 mod event;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "listen")]
+mod filter;
+#[cfg(test)]
+mod fixtures;
+#[cfg(feature = "listen")]
+mod foreground;
+#[cfg(feature = "listen")]
+mod gesture;
+#[cfg(feature = "listen")]
+mod grab_release;
+#[cfg(feature = "listen")]
+mod grab_scope;
+mod headless;
+#[cfg(feature = "listen")]
+mod held_modifiers;
+#[cfg(feature = "listen")]
+mod hid;
+#[cfg(feature = "listen")]
+mod hot_zone;
+#[cfg(feature = "listen")]
+mod hotkey;
+#[cfg(any(feature = "mock", feature = "test-util"))]
+mod inject;
+#[cfg(all(feature = "listen", feature = "serialize"))]
+mod journal;
 mod key;
+#[cfg(feature = "listen")]
+mod managed;
+#[cfg(feature = "mock")]
+pub mod mock;
+mod monitors;
+#[cfg(feature = "listen")]
+mod motion;
+#[cfg(feature = "listen")]
+mod pipeline;
 mod platform;
+#[cfg(feature = "listen")]
+mod polling;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+mod pressed;
+#[cfg(feature = "listen")]
+mod profile;
+#[cfg(feature = "listen")]
+mod raw_event;
+mod recording;
+mod rect;
+#[cfg(feature = "listen")]
+mod remap;
+#[cfg(feature = "remote")]
+pub mod remote;
+#[cfg(feature = "simulate")]
+mod sandbox;
+#[cfg(feature = "serialize")]
+mod schema;
+#[cfg(feature = "script")]
+pub mod script;
+#[cfg(feature = "listen")]
+mod scroll;
+#[cfg(feature = "listen")]
+mod sequence;
+#[cfg(feature = "listen")]
+mod shake;
+mod shortcut;
+#[cfg(feature = "listen")]
+mod state;
+#[cfg(feature = "listen")]
 mod subscription;
+#[cfg(feature = "listen")]
+mod tap_hold;
+mod topology;
+pub mod wire;
+#[cfg(feature = "winit")]
+pub mod winit;
 
-#[rustfmt::skip]
-use crate::platform::{
-    PlatformCore, CoreImpl, 
-    PlatformDisplay, DisplayImpl, 
-    PlatformGrab, GrabImpl,
-    PlatformListen, ListenImpl,
-    PlatformSimulate, SimulateImpl,
-};
+use crate::headless::HeadlessDisplay;
+#[cfg(feature = "listen")]
+use crate::platform::{CoreImpl, GrabImpl, ListenImpl, PlatformCore, PlatformGrab, PlatformListen};
+use crate::platform::{DisplayImpl, MouseImpl, PlatformDisplay, PlatformMouse};
+#[cfg(any(feature = "listen", feature = "simulate"))]
+use crate::platform::{KeyboardImpl, PlatformKeyboard};
+#[cfg(feature = "simulate")]
+use crate::platform::{PlatformSimulate, SimulateImpl};
 
+#[cfg(feature = "listen")]
+pub use crate::analytics::{Analytics, InputStats};
+#[cfg(feature = "listen")]
+pub use crate::block::InputBlock;
+#[cfg(feature = "listen")]
+pub use crate::cursor_poll::CursorPollHandle;
+#[cfg(feature = "listen")]
+pub use crate::device::{DeviceId, DeviceInfo, DeviceKind, Devices, KeyboardInfo, MouseInfo};
+#[cfg(feature = "listen")]
+pub use crate::dispatcher::PerformanceMode;
+#[cfg(feature = "listen")]
+pub use crate::edge::{Edge, EdgeEvent};
+pub use crate::error::{CoreError, OsError, StateError};
 pub use crate::event::{Event, MouseButton, Point};
-pub use crate::key::{Key, KeyCode};
-pub use crate::subscription::SubscriptionHandle;
+#[cfg(feature = "listen")]
+pub use crate::filter::{EventCategory, EventFilter};
+#[cfg(feature = "listen")]
+pub use crate::foreground::{AppMatcher, ForegroundApp};
+#[cfg(feature = "listen")]
+pub use crate::gesture::{Direction, Gesture, MouseGesture, MouseGestureHandle};
+#[cfg(feature = "listen")]
+pub use crate::grab_scope::{GrabDecision, GrabScopeHandle};
+#[cfg(feature = "listen")]
+pub use crate::hid::{Hid, HidHandle, HidReport, HidUsage};
+#[cfg(feature = "listen")]
+pub use crate::hot_zone::{Corner, HotZone, HotZoneEvent, HotZoneHandle, Zone};
+#[cfg(feature = "listen")]
+pub use crate::hotkey::{Hotkey, HotkeyHandle, HotkeySequence, SequenceBuilder, SequenceReset};
+#[cfg(all(feature = "listen", feature = "serialize"))]
+pub use crate::journal::{Journal, JournalEntry, JournalFormat, JournalOptions};
+pub use crate::key::{Key, KeyCode, Modifiers, ParseKeyError, RawKey};
+#[cfg(feature = "listen")]
+pub use crate::motion::MotionEvent;
+#[cfg(feature = "listen")]
+pub use crate::pipeline::{Middleware, MiddlewareHandle, Pipeline, PipelineContext, Verdict};
+#[cfg(feature = "listen")]
+pub use crate::polling::{CaptureMode, Limitations};
+#[cfg(feature = "listen")]
+pub use crate::profile::{Profile, ProfileEvent, ProfileHandle, Profiles};
+#[cfg(feature = "listen")]
+pub use crate::raw_event::RawEvent;
+#[cfg(feature = "listen")]
+pub use crate::recording::Recorder;
+#[cfg(feature = "simulate")]
+pub use crate::recording::{PlayOptions, PlaybackHandle};
+#[cfg(feature = "serialize")]
+pub use crate::recording::{RECORDING_VERSION, RecordingFormat};
+pub use crate::recording::{RecordedEvent, Recording};
+pub use crate::rect::Rect;
+#[cfg(feature = "listen")]
+pub use crate::remap::{Remap, RemapHandle, RemapRule};
+#[cfg(feature = "simulate")]
+pub use crate::sandbox::Injection;
+#[cfg(feature = "serialize")]
+pub use crate::schema::SCHEMA_VERSION;
+#[cfg(feature = "listen")]
+pub use crate::scroll::{ScrollTransform, ScrollTransformHandle};
+#[cfg(feature = "listen")]
+pub use crate::sequence::{EventSource, SequencedEvent};
+#[cfg(feature = "listen")]
+pub use crate::shake::{Shake, ShakeEvent, ShakeHandle};
+pub use crate::shortcut::{ParseShortcutError, Shortcut};
+#[cfg(feature = "listen")]
+pub use crate::state::{CoreState, GrabFlags, Health, ListenFlags};
+#[cfg(feature = "listen")]
+pub use crate::subscription::{AutoUnsubscribe, SubscriptionHandle};
+#[cfg(feature = "listen")]
+pub use crate::tap_hold::{TapHold, TapHoldHandle};
+pub use crate::topology::Neighbors;
 
 #[cfg(feature = "serialize")]
 use serde::{Deserialize, Serialize};
@@ -95,36 +254,118 @@ impl MonitorInfo {
     pub fn height(&self) -> f64 {
         self.size.1
     }
+
+    /// Returns the area the monitor covers in the global physical coordinate system.
+    pub fn bounds(&self) -> Rect {
+        Rect::new(self.offset.0, self.offset.1, self.size.0, self.size.1)
+    }
+
+    /// Returns `true` if `point` lies on the monitor.
+    pub fn contains(&self, point: Point) -> bool {
+        self.bounds().contains(point)
+    }
+
+    /// Returns the centre of the monitor.
+    pub fn center(&self) -> Point {
+        self.bounds().center()
+    }
+
+    /// Returns the part of `rect` that lies on the monitor, or `None` if it lies
+    /// elsewhere.
+    pub fn intersection(&self, rect: &Rect) -> Option<Rect> {
+        self.bounds().intersection(rect)
+    }
+
+    /// Converts a point in the global coordinate system to one relative to the top-left
+    /// corner of the monitor. The point need not lie on the monitor.
+    ///
+    /// # Example
+    /// ```
+    /// use raw_input::{MonitorInfo, Point};
+    ///
+    /// let monitor = MonitorInfo {
+    ///     name: "Right".to_string(),
+    ///     is_primary: false,
+    ///     offset: (1920.0, -120.0),
+    ///     size: (2560.0, 1440.0),
+    ///     scale_factor: 1.0,
+    /// };
+    /// let local = monitor.to_local(Point { x: 2020.0, y: 0.0 });
+    /// assert_eq!(local, Point { x: 100.0, y: 120.0 });
+    /// assert_eq!(monitor.to_global(local), Point { x: 2020.0, y: 0.0 });
+    /// ```
+    pub fn to_local(&self, point: Point) -> Point {
+        Point {
+            x: point.x - self.offset.0,
+            y: point.y - self.offset.1,
+        }
+    }
+
+    /// Converts a point relative to the top-left corner of the monitor to the global
+    /// coordinate system, the inverse of [`MonitorInfo::to_local`].
+    pub fn to_global(&self, point: Point) -> Point {
+        Point {
+            x: point.x + self.offset.0,
+            y: point.y + self.offset.1,
+        }
+    }
+}
+
+/// The cursor position expressed in both coordinate spaces, together with its monitor.
+///
+/// `logical` uses the same units as [`Simulate::mouse_move_to`], so it can be fed back
+/// to the simulator directly. `physical` is in device pixels.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct CursorPosition {
+    /// The position in physical pixels.
+    pub physical: Point,
+    /// The position in logical (scaled) units.
+    pub logical: Point,
+    /// The monitor containing the cursor, if it could be determined.
+    pub monitor: Option<MonitorInfo>,
 }
 
-/// Errors that occur when trying to capture OS events.
-///
-/// # Note on macOS
-/// On macOS, failing to set Accessibility permissions does not necessarily
-/// trigger an error; the system may simply ignore events or return empty data.
-#[derive(Debug)]
-#[non_exhaustive]
-pub enum CoreError {
-    /// macOS: Failed to create an event tap.
-    MacEventTapError,
-    /// macOS: Failed to add the event tap to the run loop.
-    MacLoopSourceError,
-    /// Linux: Could not open the X11 display.
-    LinuxMissingDisplayError,
-    /// Linux: Keyboard-related error in the X11 or Wayland backend.
-    LinuxKeyboardError,
-    /// Linux: Failed to enable the XRecord context.
-    LinuxRecordContextEnablingError,
-    /// Linux: General XRecord context error.
-    LinuxRecordContextError,
-    /// Linux: The XRecord extension is missing or incompatible.
-    LinuxXRecordExtensionError,
-    /// Windows: Failed to set a low-level keyboard hook.
-    WindowsKeyHookError(String),
-    /// Windows: Failed to set a low-level mouse hook.
-    WindowsMouseHookError(String),
-    /// Windows: Failed to register for Raw Input devices.
-    WindowsRegisterRawInputError(String),
+/// The image of the mouse cursor, see [`Display::get_cursor_image`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct CursorImage {
+    /// The width in pixels.
+    pub width: u32,
+    /// The height in pixels.
+    pub height: u32,
+    /// The pixel at the cursor position, from the top-left corner of the image.
+    pub hotspot: (u32, u32),
+    /// RGBA pixels with straight alpha, row by row from the top: `width * height * 4`
+    /// bytes.
+    pub pixels: Vec<u8>,
+}
+
+/// Where the macOS event tap is installed, see [`Core::tap_location`].
+#[cfg(feature = "listen")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum TapLocation {
+    /// Where HID events enter the window server, before any session sees them. Captures
+    /// the most input. The default.
+    #[default]
+    Hid,
+    /// Where HID and remote control events enter the login session.
+    Session,
+    /// Where session events have been annotated with the application they flow to.
+    AnnotatedSession,
+}
+
+/// Scheduling priority of the core thread, see [`Core::thread_priority`].
+#[cfg(feature = "listen")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum ThreadPriority {
+    /// Leaves the thread's priority unchanged. The default.
+    #[default]
+    Normal,
+    /// `THREAD_PRIORITY_HIGHEST` on Windows, the user-initiated QoS class on macOS.
+    High,
+    /// `THREAD_PRIORITY_TIME_CRITICAL` on Windows, the user-interactive QoS class on macOS.
+    TimeCritical,
 }
 
 /// The system background engine manager.
@@ -133,7 +374,7 @@ pub enum CoreError {
 ///
 /// # Example
 /// ```no_run
-/// use raw_input::Core;
+/// use raw_input::{Core, Listen};
 ///
 /// // Initialize and start the background event loop
 /// std::thread::spawn(|| {
@@ -142,42 +383,269 @@ pub enum CoreError {
 ///     }
 /// });
 ///
-/// Listen::start();
+/// let _ = Listen::start();
 /// // ... your application logic ...
 /// ```
+#[cfg(feature = "listen")]
 pub struct Core;
 
+#[cfg(feature = "listen")]
 impl Core {
     /// Starts the core engine.
     ///
     /// **This is a blocking operation**
     #[inline]
     pub fn start() -> Result<(), CoreError> {
+        if polling::is_active() {
+            return Err(CoreError::AlreadyRunning);
+        }
         PlatformCore::start()
     }
 
-    /// Checks if the core engine is runing.
+    /// Starts the core engine without hooks, sampling the keyboard, the mouse buttons
+    /// and the cursor every `interval` instead: a degraded fallback for when
+    /// [`Core::start`] fails, e.g. with [`CoreError::PermissionDenied`] or in restricted
+    /// environments.
+    ///
+    /// **This is a blocking operation**, ended by [`Core::stop`] like `Core::start`.
+    /// Subscribers receive key, button and move events, which the pipeline sees too, so
+    /// hotkeys fire. [`Core::state`] reports the polling and its [`Limitations`]: nothing
+    /// can be blocked, the wheel is not captured and short presses are missed.
+    ///
+    /// On Windows the states are read with `GetAsyncKeyState`; on macOS from the HID
+    /// event source state, which recent versions only report with Input Monitoring.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::{thread, time::Duration};
+    /// use raw_input::{Core, CoreError};
+    ///
+    /// thread::spawn(|| match Core::start() {
+    ///     Err(CoreError::PermissionDenied) => Core::start_polling(Duration::from_millis(10)),
+    ///     result => result,
+    /// });
+    /// ```
+    pub fn start_polling(interval: std::time::Duration) -> Result<(), CoreError> {
+        polling::run(interval)
+    }
+
+    /// Starts the core engine on the calling thread's event loop and returns immediately,
+    /// instead of requiring a dedicated blocking thread.
+    ///
+    /// The caller must keep running its event loop for events to arrive, and
+    /// [`Core::stop`] removes the hooks again without stopping it.
+    ///
+    /// - **Windows**: the hooks and the raw input window are driven by the thread's
+    ///   message loop, e.g. a winit or Win32 GUI thread. Keep the loop responsive, or
+    ///   Windows silently removes the low-level hooks.
+    /// - **macOS**: call it from the main thread once the application is set up; the event
+    ///   tap then runs on the `NSApplication` run loop alongside the UI. Focus tracking
+    ///   needs the main run loop anyway. `Display` only uses Core Graphics and is safe to
+    ///   call from any thread.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Core, Listen};
+    ///
+    /// // On the GUI thread, once its event loop is set up
+    /// Core::attach_to_current_thread().expect("failed to install the hooks");
+    /// Listen::start().expect("failed to start listening");
+    /// ```
+    #[inline]
+    pub fn attach_to_current_thread() -> Result<(), CoreError> {
+        if polling::is_active() {
+            return Err(CoreError::AlreadyRunning);
+        }
+        PlatformCore::attach()
+    }
+
+    /// Processes pending input on the calling thread, waiting at most `timeout` for some
+    /// to arrive, for integrations where no thread may block in [`Core::start`].
+    ///
+    /// The first call installs the core on the calling thread, like
+    /// [`Core::attach_to_current_thread`]; after that, events are only delivered while
+    /// `poll` is called. Call it often: Windows removes low-level hooks that are not
+    /// serviced within about a second, and a slow macOS event tap gets disabled.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::{Core, Listen};
+    ///
+    /// // The core is only installed by the first `poll`
+    /// let _ = Listen::start();
+    /// let _handle = Listen::subscribe(|event| println!("{:?}", event));
+    /// loop {
+    ///     Core::poll(Duration::from_millis(16)).expect("failed to install the hooks");
+    ///     // ... render a frame ...
+    /// }
+    /// ```
+    #[inline]
+    pub fn poll(timeout: std::time::Duration) -> Result<(), CoreError> {
+        if polling::is_active() {
+            return Err(CoreError::AlreadyRunning);
+        }
+        PlatformCore::poll(timeout)
+    }
+
+    /// Checks if the core engine is runing, with hooks or polling.
     #[inline]
     pub fn is_runing() -> bool {
-        PlatformCore::is_runing()
+        PlatformCore::is_runing() || polling::is_running()
+    }
+
+    /// Returns a snapshot of the core engine, for displaying or debugging its state.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Core;
+    ///
+    /// let state = Core::state();
+    /// if state.tap_enabled == Some(false) {
+    ///     eprintln!("the event tap was disabled by the system");
+    /// }
+    /// ```
+    pub fn state() -> CoreState {
+        CoreState::current()
+    }
+
+    /// Checks whether the core is healthy, so long-running daemons can restart it or
+    /// alert.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::Core;
+    ///
+    /// // Called periodically; a user may well be idle for a few minutes
+    /// let health = Core::health();
+    /// if !health.is_healthy(Duration::from_secs(300)) {
+    ///     eprintln!("raw-input looks unhealthy: {:?}", health);
+    /// }
+    /// ```
+    pub fn health() -> Health {
+        Health::check()
     }
 
     /// Pauses the core engine.
     #[inline]
     pub fn pause() {
         PlatformCore::pause();
+        polling::pause();
     }
 
     /// Resumes the core engine.
     #[inline]
     pub fn resume() {
         PlatformCore::resume();
+        polling::resume();
     }
 
     /// Stops the core engine.
     #[inline]
     pub fn stop() {
+        polling::stop();
         PlatformCore::stop();
+        held_modifiers::clear();
+    }
+
+    /// Enables managed mode, in which the first [`Listen::subscribe`], [`Grab::start`] or
+    /// `Simulate` call spawns a core thread and waits until its hooks are installed, so
+    /// simple tools never manage the thread themselves.
+    ///
+    /// Nothing is spawned while a core already runs. Failures to start are not reported;
+    /// check [`Core::state`] if in doubt. On macOS focus tracking needs the main thread,
+    /// which a managed core does not run on.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Core, Listen};
+    ///
+    /// Core::managed(true);
+    /// let _handle = Listen::subscribe(|event| println!("{:?}", event));
+    /// let _ = Listen::start();
+    /// ```
+    #[inline]
+    pub fn managed(enable: bool) {
+        managed::set_enabled(enable);
+    }
+
+    /// Uses a listen-only event tap on macOS while `Grab` is not started.
+    ///
+    /// A listen-only tap only observes input, so the system is less likely to disable it
+    /// for slow callbacks. Remapping and blocking hotkeys cannot drop or change events
+    /// through it; the core switches to an active tap whenever `Grab` starts and back when
+    /// it stops. This has no effect on Windows.
+    #[inline]
+    pub fn listen_only(enable: bool) {
+        PlatformCore::listen_only(enable);
+    }
+
+    /// Chooses where the event tap is installed on macOS. A running core reinstalls its
+    /// tap at the new location.
+    ///
+    /// Session-level taps are sufficient for most applications and less invasive than
+    /// the default HID-level tap. This has no effect on Windows.
+    #[inline]
+    pub fn tap_location(location: TapLocation) {
+        PlatformCore::tap_location(location);
+    }
+
+    /// Sets the scheduling priority that [`Core::start`] gives its thread, so grab and
+    /// remap latency stays low even under system load. Takes effect on the next start;
+    /// threads of an attached core belong to the application and are left alone.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::thread;
+    /// use raw_input::{Core, ThreadPriority};
+    ///
+    /// Core::thread_priority(ThreadPriority::TimeCritical);
+    /// thread::spawn(|| Core::start());
+    /// ```
+    #[inline]
+    pub fn thread_priority(priority: ThreadPriority) {
+        PlatformCore::thread_priority(priority);
+    }
+}
+
+/// Translation between characters and physical keys on the active keyboard layout.
+///
+/// Keys are physical positions, so the key typing a character depends on the layout:
+/// `z` is [`Key::KeyY`] on a German keyboard. Hotkey UIs use this to show and record
+/// shortcuts by their characters, text automation to find the keys to press.
+///
+/// On Windows the layout of the foreground window is used and AltGr is reported as
+/// Ctrl+Alt; on macOS, where AltGr is Option, the current input source is used. Call
+/// these from the main thread on macOS, which the input source APIs require on recent
+/// versions.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Key, Keyboard, Modifiers};
+///
+/// if let Some((key, modifiers)) = Keyboard::key_for_char('@') {
+///     println!("'@' is {:?} with {:?}", key, modifiers);
+/// }
+/// assert_eq!(Keyboard::char_for_key(Key::KeyA, Modifiers::SHIFT), Some('A'));
+/// ```
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub struct Keyboard;
+
+#[cfg(any(feature = "listen", feature = "simulate"))]
+impl Keyboard {
+    /// Finds the key and modifiers that type `ch`, preferring the fewest modifiers.
+    ///
+    /// Characters produced by a dead key map to the dead key itself. Returns `None` for
+    /// characters the layout cannot type with a single key.
+    pub fn key_for_char(ch: char) -> Option<(Key, Modifiers)> {
+        PlatformKeyboard::key_for_char(ch)
+    }
+
+    /// Returns the character `key` types with `modifiers` held, or the accent of a dead
+    /// key. Returns `None` for keys that type no character, such as arrows.
+    pub fn char_for_key(key: Key, modifiers: Modifiers) -> Option<char> {
+        PlatformKeyboard::char_for_key(key, modifiers)
     }
 }
 
@@ -201,24 +669,106 @@ impl Display {
     /// Returns the UI scale factor of the primary monitor.
     #[inline]
     pub fn get_scale_factor() -> f64 {
+        if headless::is_active() {
+            return HeadlessDisplay::get_scale_factor();
+        }
         PlatformDisplay::get_scale_factor()
     }
 
     /// Returns the current cursor position on the screen.
     #[inline]
     pub fn get_cursor_position() -> Option<(f64, f64)> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_cursor_position();
+        }
         PlatformDisplay::get_cursor_position()
     }
 
+    /// Moves the cursor to the given position without generating an input event.
+    ///
+    /// Coordinates use the same space as [`Display::get_cursor_position`]. Unlike
+    /// [`Simulate::mouse_move_to`], hooks and listeners do not observe the warp, which
+    /// makes it suitable for pointer-lock style recentering.
+    #[inline]
+    pub fn set_cursor_position(x: f64, y: f64) {
+        if headless::is_active() {
+            HeadlessDisplay::set_cursor_position(x, y);
+            return;
+        }
+        PlatformDisplay::set_cursor_position(x, y);
+    }
+
+    /// Returns the current cursor position in both physical and logical coordinates,
+    /// along with the monitor it is on.
+    #[inline]
+    pub fn get_cursor() -> Option<CursorPosition> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_cursor();
+        }
+        PlatformDisplay::get_cursor()
+    }
+
+    /// Returns the cursor position relative to the top-left corner of `monitor`, or
+    /// `None` if the cursor is on another monitor.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Display;
+    ///
+    /// for monitor in Display::get_available_monitors() {
+    ///     if let Some(position) = Display::cursor_position_on(&monitor) {
+    ///         println!("Cursor at {:?} on {}", position, monitor.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn cursor_position_on(monitor: &MonitorInfo) -> Option<Point> {
+        let (x, y) = Self::get_cursor_position()?;
+        let position = Point { x, y };
+        monitor
+            .contains(position)
+            .then(|| monitor.to_local(position))
+    }
+
+    /// Returns the image of the cursor, for screen-sharing and KVM tools drawing the
+    /// pointer of a remote machine. Returns `None` while the cursor is hidden or the image
+    /// cannot be read.
+    ///
+    /// On Windows the cursor is copied with `CopyIcon`; monochrome cursors come out black
+    /// and white, with the pixels that invert the screen drawn black. On macOS the image
+    /// is that of `NSCursor.currentSystemCursor`; reading it needs the `listen` feature,
+    /// which brings in AppKit.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Display;
+    ///
+    /// if let Some(image) = Display::get_cursor_image() {
+    ///     println!("{}x{} cursor, hotspot at {:?}", image.width, image.height, image.hotspot);
+    /// }
+    /// ```
+    #[inline]
+    pub fn get_cursor_image() -> Option<CursorImage> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_cursor_image();
+        }
+        PlatformDisplay::get_cursor_image()
+    }
+
     /// Returns the size of the primary monitor.
     #[inline]
     pub fn get_primary_screen_size() -> (f64, f64) {
+        if headless::is_active() {
+            return HeadlessDisplay::get_primary_screen_size();
+        }
         PlatformDisplay::get_primary_screen_size()
     }
 
     /// Returns the size of the virtual screen (all monitors combined).
     #[inline]
     pub fn get_virtual_screen_size() -> (f64, f64) {
+        if headless::is_active() {
+            return HeadlessDisplay::get_virtual_screen_size();
+        }
         PlatformDisplay::get_virtual_screen_size()
     }
 
@@ -226,32 +776,172 @@ impl Display {
     /// (x, y, width, height) in logical units
     #[inline]
     pub fn get_virtual_screen_bounds() -> (f64, f64, f64, f64) {
+        if headless::is_active() {
+            return HeadlessDisplay::get_virtual_screen_bounds();
+        }
         PlatformDisplay::get_virtual_screen_bounds()
     }
 
     /// Returns a list of all connected monitors.
     #[inline]
     pub fn get_available_monitors() -> Vec<MonitorInfo> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_available_monitors();
+        }
         PlatformDisplay::get_available_monitors()
     }
 
     /// Returns information about the primary monitor.
     #[inline]
     pub fn get_primary_monitor() -> Option<MonitorInfo> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_primary_monitor();
+        }
         PlatformDisplay::get_primary_monitor()
     }
 
     /// Returns information about the monitor where the cursor currently is.
     #[inline]
     pub fn get_current_monitor() -> Option<MonitorInfo> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_current_monitor();
+        }
         PlatformDisplay::get_current_monitor()
     }
 
     /// Returns information about the monitor that contains the specified point.
     #[inline]
     pub fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo> {
+        if headless::is_active() {
+            return HeadlessDisplay::get_monitor_from_point(x, y);
+        }
         PlatformDisplay::get_monitor_from_point(x, y)
     }
+
+    /// Returns the monitors adjacent to the given monitor on each side.
+    ///
+    /// Adjacency is computed from the monitor rectangles reported by
+    /// [`Display::get_available_monitors`].
+    #[inline]
+    pub fn neighbors(monitor: &MonitorInfo) -> Neighbors {
+        topology::neighbors(&Self::get_available_monitors(), monitor)
+    }
+
+    /// Sets the virtual monitors `Display` reports while the platform reports none, e.g.
+    /// on a headless CI machine. With `None` they are read from the
+    /// `RAW_INPUT_HEADLESS_MONITORS` environment variable instead.
+    ///
+    /// The variable lists the monitors separated by `;`, each as `WIDTHxHEIGHT`
+    /// optionally followed by its offset and `@SCALE`, e.g. `1920x1080;2560x1440+1920+0@2`.
+    /// Offsets are signed and sizes physical; the first monitor is the primary one.
+    ///
+    /// The cursor becomes virtual as well, starting at the centre of the primary monitor
+    /// and moved only by [`Display::set_cursor_position`].
+    ///
+    /// # Example
+    /// ```
+    /// use raw_input::{Display, MonitorInfo};
+    ///
+    /// Display::set_headless_monitors(Some(vec![MonitorInfo {
+    ///     name: "CI".to_string(),
+    ///     is_primary: true,
+    ///     offset: (0.0, 0.0),
+    ///     size: (1920.0, 1080.0),
+    ///     scale_factor: 1.0,
+    /// }]));
+    /// if Display::is_headless() {
+    ///     assert_eq!(Display::get_primary_screen_size(), (1920.0, 1080.0));
+    /// }
+    /// ```
+    pub fn set_headless_monitors(monitors: Option<Vec<MonitorInfo>>) {
+        headless::set(monitors);
+    }
+
+    /// Returns `true` if `Display` reports the headless monitors, see
+    /// [`Display::set_headless_monitors`].
+    pub fn is_headless() -> bool {
+        headless::is_active()
+    }
+
+    /// Discards the cached monitor list, so the next query enumerates the monitors again.
+    ///
+    /// Monitors are enumerated once and cached. While `Core` runs on Windows, and always
+    /// on macOS, the cache is dropped automatically when monitors are added, removed,
+    /// rearranged or rescaled; otherwise call this after a display change.
+    pub fn refresh() {
+        monitors::invalidate();
+    }
+}
+
+/// System pointer settings.
+///
+/// Remote-control software reads these to turn the relative movement it forwards into
+/// the distance the pointer travels, or turns acceleration off while it drives the
+/// pointer. Changes apply to the whole system but are not saved to the user's settings,
+/// so they last until logout.
+///
+/// Speeds range from 0.0 (slowest) to 1.0 (fastest). On Windows this spans the 20 steps
+/// of `SPI_SETMOUSESPEED`, 0.47 being the default. On macOS it spans the mouse scaling
+/// behind "Tracking speed" (0 to 3), and acceleration cannot be turned off without
+/// moving the pointer linearly: while it is off, [`Mouse::get_speed`] returns `None`
+/// and [`Mouse::set_speed`] turns it back on.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::Mouse;
+///
+/// let speed = Mouse::get_speed();
+/// let accelerated = Mouse::get_acceleration();
+/// println!("Speed: {:?}, acceleration: {:?}", speed, accelerated);
+///
+/// // 1:1 movement while forwarding input, restored afterwards
+/// Mouse::set_acceleration(false).expect("failed to disable acceleration");
+/// // ...
+/// if let Some(accelerated) = accelerated {
+///     Mouse::set_acceleration(accelerated).ok();
+/// }
+/// ```
+pub struct Mouse;
+
+impl Mouse {
+    /// Returns the pointer speed, from 0.0 (slowest) to 1.0 (fastest), or `None` if it
+    /// cannot be read.
+    #[inline]
+    pub fn get_speed() -> Option<f64> {
+        PlatformMouse::get_speed()
+    }
+
+    /// Sets the pointer speed, from 0.0 (slowest) to 1.0 (fastest). Values outside the
+    /// range are clamped.
+    #[inline]
+    pub fn set_speed(speed: f64) -> Result<(), OsError> {
+        PlatformMouse::set_speed(speed)
+    }
+
+    /// Returns `true` if pointer acceleration ("Enhance pointer precision" on Windows)
+    /// is enabled, or `None` if it cannot be read.
+    #[inline]
+    pub fn get_acceleration() -> Option<bool> {
+        PlatformMouse::get_acceleration()
+    }
+
+    /// Enables or disables pointer acceleration.
+    #[inline]
+    pub fn set_acceleration(enable: bool) -> Result<(), OsError> {
+        PlatformMouse::set_acceleration(enable)
+    }
+
+    /// Returns how far one wheel notch scrolls: characters horizontally (`x`) and lines
+    /// vertically (`y`), as set by the user.
+    ///
+    /// On Windows these are `SPI_GETWHEELSCROLLCHARS` and `SPI_GETWHEELSCROLLLINES`,
+    /// `None` when a notch scrolls a whole page. macOS has no such setting, its
+    /// "Scrolling speed" preference accelerates the wheel deltas themselves, so this
+    /// always returns `None` there.
+    #[inline]
+    pub fn get_scroll_lines() -> Option<Point> {
+        PlatformMouse::get_scroll_lines()
+    }
 }
 
 /// Input interceptor for blocking or modifying events.
@@ -266,7 +956,7 @@ impl Display {
 /// // Core::start(); // This is a blocking operation
 ///
 /// // Block all keyboard input
-/// Grab::start();  // defaults to blocking all input
+/// Grab::start().expect("failed to start grabbing");  // defaults to blocking all input
 ///
 /// // customizable
 /// // Grab::mouse_move(true);
@@ -277,13 +967,23 @@ impl Display {
 /// // Stop grabbing later
 /// // Grab::stop();
 /// ```
+#[cfg(feature = "listen")]
 pub struct Grab;
 
+#[cfg(feature = "listen")]
 impl Grab {
     /// Starts grabbing (blocking) events.
+    ///
+    /// Returns [`StateError::AlreadyRunning`] without changes if grabbing already runs.
+    /// Grabbing is enabled despite the other errors, which report why nothing will be
+    /// blocked yet: the core is not running, or the process may not block input.
     #[inline]
-    pub fn start() {
-        PlatformGrab::start();
+    pub fn start() -> Result<(), StateError> {
+        managed::ensure_core();
+        if !PlatformGrab::start() {
+            return Err(StateError::AlreadyRunning);
+        }
+        state::check_core(true)
     }
 
     /// Checks if grabbing is active.
@@ -292,6 +992,12 @@ impl Grab {
         PlatformGrab::is_runing()
     }
 
+    /// Returns which event categories are enabled and whether the grabber runs.
+    #[inline]
+    pub fn flags() -> GrabFlags {
+        PlatformGrab::flags()
+    }
+
     /// Pauses grabbing events.
     #[inline]
     pub fn pause() {
@@ -305,9 +1011,14 @@ impl Grab {
     }
 
     /// Stops grabbing events.
+    ///
+    /// Releases of keys and mouse buttons blocked while grabbing, by the `Grab` flags, a
+    /// grab scope or middleware, are simulated now, so an application that saw a press
+    /// before it was blocked does not keep it held.
     #[inline]
     pub fn stop() {
         PlatformGrab::stop();
+        grab_release::release(grab_release::Blocker::Grab);
     }
 
     /// Mouse move grab
@@ -328,6 +1039,146 @@ impl Grab {
         PlatformGrab::mouse_button(enable);
     }
 
+    /// Blocks all physical keyboard and mouse input until the returned [`InputBlock`]
+    /// is dropped or `timeout` expires, whichever comes first.
+    ///
+    /// This is a blunt instrument for cases where the category and application filters
+    /// of the hook-based grabber are not enough, such as unattended automation that no
+    /// stray input may interfere with. The timeout keeps a stuck caller from locking the
+    /// user out; keep it short.
+    ///
+    /// On Windows this wraps `BlockInput`, which needs no running core but fails with
+    /// [`StateError::PermissionMissing`] unless the process may own the input desktop,
+    /// typically when elevated; Ctrl+Alt+Del always lifts it. On macOS, which has no
+    /// equivalent, the event tap drops the input instead, so the core must run with the
+    /// Accessibility permission. There, as with the `mock` backend, input injected by
+    /// [`Simulate`] still gets through.
+    ///
+    /// Returns [`StateError::AlreadyRunning`] while another block is active.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::Grab;
+    ///
+    /// let block = Grab::block_input(Duration::from_secs(5)).expect("failed to block input");
+    /// // ... drive the UI without interference
+    /// block.release();
+    /// ```
+    pub fn block_input(timeout: std::time::Duration) -> Result<InputBlock, StateError> {
+        block::start(timeout)
+    }
+
+    /// Only blocks input while the given application has the focus, or everywhere again
+    /// with `None`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{AppMatcher, Grab};
+    ///
+    /// Grab::only_when_app(Some(AppMatcher::exe("game.exe")));
+    /// Grab::keyboard(true);
+    /// ```
+    pub fn only_when_app(matcher: Option<AppMatcher>) {
+        foreground::set_grab_rule(matcher);
+    }
+
+    /// Blocks the events `filter` matches, for rules loaded from a configuration.
+    ///
+    /// The filter runs as the last [`Pipeline`] stage, so it only requires `Core` and is
+    /// independent of [`Grab::start`] and the other `Grab` settings except
+    /// [`Grab::pass_injected`]. Remove it with the returned handle.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{EventCategory, EventFilter, Grab, Key};
+    ///
+    /// // Swallow the Windows keys
+    /// let handle = Grab::add_filter(EventFilter {
+    ///     categories: vec![EventCategory::Keyboard],
+    ///     keys: vec![Key::MetaLeft, Key::MetaRight],
+    ///     ..Default::default()
+    /// });
+    ///
+    /// // Later
+    /// handle.remove();
+    /// ```
+    pub fn add_filter(filter: EventFilter) -> MiddlewareHandle {
+        filter::grab(filter)
+    }
+
+    /// Adds an independent grab scope, so several components can block input without
+    /// fighting over the global `Grab` flags.
+    ///
+    /// `decide` is called with each event and whether it was injected by `Simulate`. The
+    /// scopes are consulted from the highest `priority` down, scopes of equal priority in
+    /// registration order, and the first to [`Pass`](GrabDecision::Pass) or
+    /// [`Block`](GrabDecision::Block) wins. The `Grab` flags only decide when every
+    /// scope abstains. Scopes see events after the [`Pipeline`], require `Core` but not
+    /// [`Grab::start`], and run inside the hook, so they must return quickly.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Event, Grab, GrabDecision, Key};
+    ///
+    /// // A kiosk blocker, and a hotkey suppressor that keeps F1 working above it
+    /// let kiosk = Grab::add_scope(0, |_: &Event, _| GrabDecision::Block);
+    /// let help = Grab::add_scope(10, |event: &Event, _| match event {
+    ///     Event::KeyDown { key: Key::F1, .. } | Event::KeyUp { key: Key::F1, .. } => {
+    ///         GrabDecision::Pass
+    ///     }
+    ///     _ => GrabDecision::Abstain,
+    /// });
+    ///
+    /// // Later
+    /// kiosk.remove();
+    /// help.remove();
+    /// ```
+    pub fn add_scope<F>(priority: i32, decide: F) -> GrabScopeHandle
+    where
+        F: Fn(&Event, bool) -> GrabDecision + Send + Sync + 'static,
+    {
+        grab_scope::add(priority, decide)
+    }
+
+    /// Never blocks input headed for windows of the current process, so an application
+    /// with its own settings UI cannot lock itself out while grabbing.
+    ///
+    /// Mouse events are matched by the window under the cursor, keyboard events by the
+    /// foreground window.
+    #[inline]
+    pub fn exclude_own_windows(enable: bool) {
+        PlatformGrab::exclude_own_windows(enable);
+    }
+
+    /// Never blocks events injected by this crate's [`Simulate`], so a remapper can block
+    /// the real input and still deliver the replacement it synthesizes.
+    ///
+    /// Events are recognized by the marker `Simulate` attaches to them; input injected
+    /// by other programs is still blocked.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Event, Grab, Key, Listen, Simulate};
+    ///
+    /// Grab::pass_injected(true);
+    /// Grab::keyboard(true);
+    /// Grab::start().ok();
+    ///
+    /// // Real key presses are blocked, except that A reaches applications as B
+    /// Listen::ignore_injected(true);
+    /// Listen::subscribe(|event| {
+    ///     if let Event::KeyDown { key: Key::KeyA, .. } = event {
+    ///         Simulate::keyboard(Key::KeyB, true);
+    ///         Simulate::keyboard(Key::KeyB, false);
+    ///     }
+    /// });
+    /// ```
+    #[inline]
+    pub fn pass_injected(enable: bool) {
+        PlatformGrab::pass_injected(enable);
+    }
+
     /// Keyboard grab
     #[inline]
     pub fn keyboard(enable: bool) {
@@ -347,7 +1198,7 @@ impl Grab {
 /// // Core::start(); // This is a blocking operation
 ///
 /// // Start listening to all input
-/// Listen::start();  // defaults to listening to all input
+/// Listen::start().expect("failed to start listening");  // defaults to listening to all input
 ///
 /// // customizable
 /// // Listen::mouse_move(true);
@@ -368,13 +1219,23 @@ impl Grab {
 ///
 /// Listen::stop();
 /// ```
+#[cfg(feature = "listen")]
 pub struct Listen;
 
+#[cfg(feature = "listen")]
 impl Listen {
     /// Starts listening for events.
+    ///
+    /// Returns [`StateError::AlreadyRunning`] without changes if listening already runs.
+    /// Listening is enabled despite the other errors, which report why no events will
+    /// arrive yet: the core is not running, or the process may not observe input. So
+    /// starting the listener while the core thread is still coming up is fine.
     #[inline]
-    pub fn start() {
-        PlatformListen::start();
+    pub fn start() -> Result<(), StateError> {
+        if !PlatformListen::start() {
+            return Err(StateError::AlreadyRunning);
+        }
+        state::check_core(false)
     }
 
     /// Checks if listening for events is active.
@@ -383,6 +1244,12 @@ impl Listen {
         PlatformListen::is_runing()
     }
 
+    /// Returns which event categories are enabled and whether the listener runs.
+    #[inline]
+    pub fn flags() -> ListenFlags {
+        PlatformListen::flags()
+    }
+
     /// Pauses listening for events.
     #[inline]
     pub fn pause() {
@@ -425,20 +1292,416 @@ impl Listen {
         PlatformListen::keyboard(enable);
     }
 
+    /// Scan code ("game mode") keyboard listening.
+    ///
+    /// On Windows, keyboard events are read from Raw Input instead of the low-level hook:
+    /// the key is derived from the scan code reported by the keyboard driver, before any
+    /// layout or virtual-key translation, and `code` holds that scan code (`0xE0`/`0xE1`
+    /// prefixed for extended keys) rather than a virtual-key code. Hotkeys, remapping and
+    /// grabbing keep working on the hook. On macOS key codes are already physical, so this
+    /// has no effect.
+    #[inline]
+    pub fn scancode_mode(enable: bool) {
+        PlatformListen::scancode_mode(enable);
+    }
+
+    /// Withholds events injected by this crate's [`Simulate`] from subscribers.
+    ///
+    /// A program that both listens and simulates, such as a remapper or a KVM client,
+    /// otherwise receives its own output and may react to it again in a loop. Events are
+    /// recognized by the marker `Simulate` attaches to them; input injected by other
+    /// programs is still delivered. Only subscribers are affected; the middleware
+    /// pipeline still sees injected events.
+    #[inline]
+    pub fn ignore_injected(enable: bool) {
+        PlatformListen::ignore_injected(enable);
+    }
+
+    /// Only delivers input events while the given application has the focus, or always
+    /// again with `None`. [`Event::FocusChanged`] is always delivered.
+    ///
+    /// Applications are matched against the foreground application cached while `Core`
+    /// runs, so this adds no OS query per event.
+    pub fn only_when_app(matcher: Option<AppMatcher>) {
+        foreground::set_listen_rule(matcher);
+    }
+
+    /// Withholds keyboard events from subscribers while the user types into a password
+    /// field, as a privacy guard. Mouse events are still delivered, and the middleware
+    /// pipeline, hotkeys and `Grab` are not affected.
+    ///
+    /// - **macOS**: follows secure event input, which password fields enable while they
+    ///   have the focus. The OS already hides those keystrokes from event taps; the guard
+    ///   also covers the modifier and function keys that remain visible.
+    /// - **Windows**: asks UI Automation whether the focused element is a password field.
+    ///   The answer is updated on focus changes while `Core` runs, so enable the guard
+    ///   before the user reaches the field.
+    pub fn pause_during_secure_input(enable: bool) {
+        foreground::set_secure_input_guard(enable);
+    }
+
     /// Subscribe to input events
     #[inline]
     pub fn subscribe<F>(callback: F) -> SubscriptionHandle
     where
         F: Fn(Event) + Send + Sync + 'static,
     {
+        managed::ensure_core();
         PlatformListen::subscribe(callback)
     }
 
+    /// Pauses every subscription put in `group` with
+    /// [`SubscriptionHandle::in_group`], including those put in it later, until
+    /// [`Listen::resume_group`] is called.
+    pub fn pause_group(group: &str) {
+        dispatcher::set_group_paused(group, true);
+    }
+
+    /// Resumes the subscriptions of a group paused with [`Listen::pause_group`].
+    /// Subscriptions paused through their own handle stay paused.
+    pub fn resume_group(group: &str) {
+        dispatcher::set_group_paused(group, false);
+    }
+
+    /// Subscribe to input events in batches, for consumers such as recorders and network
+    /// forwarders that handle bursts better in one call than event by event.
+    ///
+    /// Events are queued and delivered on a thread of their own: each batch holds the
+    /// first queued event and whatever follows within `interval`, in order. With
+    /// `Duration::ZERO` a batch holds whatever was queued at that moment. Queued events
+    /// are still delivered after the subscription is removed, then the thread ends.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::Listen;
+    ///
+    /// let handle = Listen::subscribe_batch(Duration::from_millis(16), |events| {
+    ///     println!("{} events", events.len());
+    /// });
+    ///
+    /// // Later
+    /// handle.unsubscribe();
+    /// ```
+    pub fn subscribe_batch<F>(interval: std::time::Duration, callback: F) -> SubscriptionHandle
+    where
+        F: FnMut(&[Event]) + Send + 'static,
+    {
+        batch::subscribe(interval, callback)
+    }
+
+    /// Subscribe to input events along with their [`EventSource`], a per-source sequence
+    /// number and the time the OS recorded them.
+    ///
+    /// Events are delivered as they are dispatched, which is not always the order they
+    /// happened in across sources; the timestamps allow restoring it. See
+    /// [`Listen::subscribe_ordered`] to have it restored.
+    pub fn subscribe_sequenced<F>(callback: F) -> SubscriptionHandle
+    where
+        F: Fn(SequencedEvent) + Send + Sync + 'static,
+    {
+        sequence::subscribe(callback)
+    }
+
+    /// Subscribe to input events merged across sources in the order they happened, for
+    /// recorders and remote forwarding.
+    ///
+    /// Each event is held back until `window` after the OS recorded it, so events from a
+    /// source that reports later can still be sorted in front of it. A few milliseconds
+    /// cover the `WM_INPUT` queue on Windows. Events are delivered on a thread of their
+    /// own; held events are still delivered after the subscription is removed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::Listen;
+    ///
+    /// let handle = Listen::subscribe_ordered(Duration::from_millis(8), |sequenced| {
+    ///     println!("{:?} from {:?}", sequenced.event, sequenced.source);
+    /// });
+    ///
+    /// // Later
+    /// handle.unsubscribe();
+    /// ```
+    pub fn subscribe_ordered<F>(window: std::time::Duration, callback: F) -> SubscriptionHandle
+    where
+        F: FnMut(SequencedEvent) + Send + 'static,
+    {
+        sequence::subscribe_ordered(window, callback)
+    }
+
+    /// Subscribe to input events along with the OS data they were translated from:
+    /// the `KBDLLHOOKSTRUCT`, `MSLLHOOKSTRUCT` or `RAWINPUT` on Windows and the
+    /// `CGEvent` on macOS. Meant for reading fields the crate does not model.
+    ///
+    /// Only events translated from OS input are delivered, so events of the polling
+    /// fallback, injected for tests or reported as [`Event::FocusChanged`] are not.
+    ///
+    /// # Safety
+    ///
+    /// The [`RawEvent`] pointers are only valid until `callback` returns. The callback
+    /// must not keep them, nor write through them.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Listen, RawEvent};
+    ///
+    /// let handle = unsafe {
+    ///     Listen::subscribe_raw(|event, raw| {
+    ///         if let RawEvent::KeyboardHook { data, .. } = raw {
+    ///             // `vkCode` is the first field of `KBDLLHOOKSTRUCT`
+    ///             let vk_code = unsafe { *data.cast::<u32>() };
+    ///             println!("{event:?} from virtual key {vk_code:#x}");
+    ///         }
+    ///     })
+    /// };
+    ///
+    /// // Later
+    /// handle.unsubscribe();
+    /// ```
+    pub unsafe fn subscribe_raw<F>(callback: F) -> SubscriptionHandle
+    where
+        F: Fn(Event, RawEvent) + Send + Sync + 'static,
+    {
+        raw_event::subscribe(callback)
+    }
+
+    /// Subscribe to input events for as long as `owner` lives.
+    ///
+    /// The subscription only holds a weak reference to `owner` and passes it to
+    /// `callback` with each event. Once every other reference is dropped, the
+    /// subscription removes itself with the next event, so a closed window's callback
+    /// does not keep firing.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::sync::{Arc, Mutex};
+    /// use raw_input::Listen;
+    ///
+    /// struct Overlay {
+    ///     events: Mutex<usize>,
+    /// }
+    ///
+    /// let overlay = Arc::new(Overlay { events: Mutex::new(0) });
+    /// Listen::subscribe_weak(&overlay, |overlay, _| *overlay.events.lock().unwrap() += 1);
+    ///
+    /// // Unsubscribes with the next event
+    /// drop(overlay);
+    /// ```
+    pub fn subscribe_weak<T, F>(owner: &std::sync::Arc<T>, callback: F) -> SubscriptionHandle
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&T, Event) + Send + Sync + 'static,
+    {
+        subscription::subscribe_weak(owner, callback)
+    }
+
+    /// Subscribe to the input events `filter` matches, see [`EventFilter`].
+    ///
+    /// Modifiers are tracked from the keyboard events the subscriber receives, so
+    /// modifier requirements need keyboard listening.
+    pub fn subscribe_filtered<F>(filter: EventFilter, callback: F) -> SubscriptionHandle
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        filter::subscribe(filter, callback)
+    }
+
+    /// Subscribe to input events together with the application that has the focus.
+    ///
+    /// The foreground application is cached while `Core` runs and refreshed whenever
+    /// another application comes to the front, so no OS query happens per event. A title
+    /// change within the same application does not refresh it. Outside `Core` every event
+    /// queries the OS.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Event, Listen};
+    ///
+    /// Listen::subscribe_with_context(|event, app| {
+    ///     if let (Event::KeyDown { key, .. }, Some(app)) = (event, app) {
+    ///         println!("{:?} pressed in {:?}", key, app.exe);
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe_with_context<F>(callback: F) -> SubscriptionHandle
+    where
+        F: Fn(Event, Option<&ForegroundApp>) + Send + Sync + 'static,
+    {
+        Self::subscribe(move |event| {
+            callback(event, foreground::current().as_deref());
+        })
+    }
+
+    /// Returns the application that has the focus.
+    ///
+    /// Cached while `Core` runs, see [`Event::FocusChanged`].
+    pub fn foreground() -> Option<ForegroundApp> {
+        foreground::current().map(|app| (*app).clone())
+    }
+
+    /// Subscribe to cursor pushes against the outer edges of the virtual desktop.
+    ///
+    /// The callback fires for every mouse movement that pushes the cursor outward while
+    /// it sits on an edge with no adjacent monitor. Requires mouse move listening.
+    pub fn subscribe_edges<F>(callback: F) -> SubscriptionHandle
+    where
+        F: Fn(EdgeEvent) + Send + Sync + 'static,
+    {
+        Self::subscribe(move |event| {
+            let Event::MouseMove { delta } = event else {
+                return;
+            };
+            let Some((x, y)) = Display::get_cursor_position() else {
+                return;
+            };
+            let monitors = monitors::snapshot(Display::get_available_monitors);
+            if let Some(edge_event) = edge::detect(&monitors, Point { x, y }, delta) {
+                callback(edge_event);
+            }
+        })
+    }
+
+    /// Samples the absolute cursor position every `interval` on a background thread and
+    /// passes it to `callback` whenever it changed.
+    ///
+    /// For consumers that want coordinates rather than the raw deltas of
+    /// [`Event::MouseMove`], which are unaccelerated and arrive at the mouse's polling
+    /// rate. Positions come from [`Display::get_cursor`], so neither `Core` nor mouse
+    /// move listening is needed, and moves made by other means (warps, touch, remote
+    /// control) are seen as well.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::Listen;
+    ///
+    /// let handle = Listen::cursor_positions(Duration::from_millis(16), |cursor| {
+    ///     println!("Cursor at {:?}", cursor.logical);
+    /// });
+    ///
+    /// // Later
+    /// handle.stop();
+    /// ```
+    pub fn cursor_positions<F>(interval: std::time::Duration, callback: F) -> CursorPollHandle
+    where
+        F: Fn(CursorPosition) + Send + 'static,
+    {
+        cursor_poll::start(interval, callback)
+    }
+
+    /// Subscribe to mouse movements augmented with velocity and acceleration, averaged
+    /// over a sliding `window`.
+    ///
+    /// Useful for gesture detection and for telling intentional flicks from drift. The
+    /// first movement after the mouse rested longer than `window` reports zero speed.
+    /// Requires mouse move listening.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::Listen;
+    ///
+    /// Listen::subscribe_motion(Duration::from_millis(50), |motion| {
+    ///     if motion.speed > 5000.0 {
+    ///         println!("Flick: {:?}", motion.velocity);
+    ///     }
+    /// });
+    /// ```
+    pub fn subscribe_motion<F>(window: std::time::Duration, callback: F) -> SubscriptionHandle
+    where
+        F: Fn(MotionEvent) + Send + Sync + 'static,
+    {
+        let tracker = std::sync::Mutex::new(motion::Tracker::new(window));
+        Self::subscribe(move |event| {
+            let Event::MouseMove { delta } = event else {
+                return;
+            };
+            let motion = tracker
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .movement(delta, std::time::Instant::now());
+            callback(motion);
+        })
+    }
+
     /// Unsubscribe all listeners
     #[inline]
     pub fn unsubscribe_all() {
         PlatformListen::unsubscribe_all();
     }
+
+    /// Tunes event delivery for high-polling-rate mice.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use raw_input::{Listen, PerformanceMode};
+    ///
+    /// // Deliver at most one mouse move per millisecond to subscribers
+    /// Listen::set_performance_mode(PerformanceMode {
+    ///     coalesce_moves: Some(Duration::from_millis(1)),
+    /// });
+    /// ```
+    pub fn set_performance_mode(mode: PerformanceMode) {
+        dispatcher::set_performance_mode(mode);
+    }
+
+    /// Returns the current performance mode.
+    pub fn performance_mode() -> PerformanceMode {
+        dispatcher::performance_mode()
+    }
+
+    /// Multiplies the wheel deltas delivered to subscribers by the user's scroll setting,
+    /// see [`Mouse::get_scroll_lines`], so they report the lines an application would
+    /// scroll rather than notches.
+    ///
+    /// The setting is read when this is enabled; enable it again to pick up a change.
+    /// Where there is no setting the deltas are left as they are. `raw` is never scaled,
+    /// and the pipeline and `Grab` keep seeing notches.
+    pub fn scroll_lines(enable: bool) {
+        dispatcher::set_scroll_lines(enable.then(PlatformMouse::get_scroll_lines).flatten());
+    }
+
+    /// Handles `event` as if the user produced it: subscribers, the middleware pipeline
+    /// (remapping, hotkeys, ...) and the `Grab` filter see it exactly like an event from
+    /// the hooks. Returns `true` if it would reach other applications unchanged.
+    ///
+    /// Runs on the calling thread and needs neither `Core` nor any permission. Events the
+    /// pipeline synthesizes are handled the same way, marked as injected, instead of
+    /// being injected into the OS. Requires the `test-util` feature.
+    ///
+    /// # Example
+    /// ```
+    /// use raw_input::{Event, Key, Listen, Remap};
+    ///
+    /// let remap = Remap::add(Key::CapsLock, Key::Escape);
+    /// let handle = Listen::subscribe(|event| println!("{:?}", event));
+    ///
+    /// // Prints the CapsLock press, then the Escape press it is remapped to
+    /// let passed = Listen::inject_for_test(Event::KeyDown { key: Key::CapsLock, code: None });
+    /// assert!(!passed);
+    ///
+    /// handle.unsubscribe();
+    /// remap.remove();
+    /// ```
+    #[cfg(feature = "test-util")]
+    pub fn inject_for_test(event: Event) -> bool {
+        inject::process(event, false, inject::replay)
+    }
+}
+
+/// How [`Simulate`] resolves a [`Key`], see [`Simulate::key_semantics`].
+#[cfg(feature = "simulate")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub enum KeySemantics {
+    /// A key is a physical position, like captured keys. `Key::KeyQ` presses the key
+    /// that is labeled A on an AZERTY keyboard. The default.
+    #[default]
+    Physical,
+    /// A key stands for the character it names on the active layout. `Key::KeyA` presses
+    /// the key labeled A on any layout, which is where Q is on QWERTY for AZERTY.
+    Character,
 }
 
 /// Input simulator for synthesizing events.
@@ -450,47 +1713,284 @@ impl Listen {
 /// use raw_input::{Simulate, Event, Key};
 ///
 /// // Simulate pressing the 'A' key
-/// Simulate::simulate(Event::KeyDown { key: Key::KeyA });
+/// Simulate::simulate(Event::KeyDown { key: Key::KeyA, code: None });
 ///
 /// // Convenience methods for mouse
 /// Simulate::mouse_move(100, 100);
 /// ```
+#[cfg(feature = "simulate")]
 pub struct Simulate;
 
+#[cfg(feature = "simulate")]
 impl Simulate {
     /// Simulates an input event.
     #[inline]
     pub fn simulate(event: Event) {
+        if sandbox::intercept(|| Injection::Event(event)) {
+            return;
+        }
+        pressed::track(&event);
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
         PlatformSimulate::simulate(event);
     }
 
     /// Simulates mouse movement by a delta.
     #[inline]
     pub fn mouse_move(delta_x: f64, delta_y: f64) {
+        let delta = Point {
+            x: delta_x,
+            y: delta_y,
+        };
+        if sandbox::intercept(|| Injection::Event(Event::MouseMove { delta })) {
+            return;
+        }
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
         PlatformSimulate::mouse_move(delta_x, delta_y);
     }
 
     /// Simulates moving the mouse to an absolute position.
     #[inline]
     pub fn mouse_move_to(x: f64, y: f64) {
+        if sandbox::intercept(|| Injection::MoveTo(Point { x, y })) {
+            return;
+        }
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
         PlatformSimulate::mouse_move_to(x, y);
     }
 
     /// Simulates mouse wheel scrolling.
     #[inline]
     pub fn mouse_wheel(delta_x: f64, delta_y: f64) {
+        let delta = Point {
+            x: delta_x,
+            y: delta_y,
+        };
+        if sandbox::intercept(|| Injection::Event(Event::MouseWheel { delta, raw: None })) {
+            return;
+        }
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
         PlatformSimulate::mouse_wheel(delta_x, delta_y);
     }
 
     /// Simulates mouse button press or release.
     #[inline]
     pub fn mouse_button(button: MouseButton, down: bool) {
+        let event = match down {
+            true => Event::MouseDown { button },
+            false => Event::MouseUp { button },
+        };
+        if sandbox::intercept(|| Injection::Event(event)) {
+            return;
+        }
+        pressed::track(&event);
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
         PlatformSimulate::mouse_button(button, down);
     }
 
     /// Simulates key up or down.
     #[inline]
     pub fn keyboard(key: Key, down: bool) {
+        let event = match down {
+            true => Event::KeyDown { key, code: None },
+            false => Event::KeyUp { key, code: None },
+        };
+        if sandbox::intercept(|| Injection::Event(event)) {
+            return;
+        }
+        pressed::track(&event);
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
         PlatformSimulate::keyboard(key, down);
     }
+
+    /// Types `text` into the focused application.
+    ///
+    /// On Windows, characters the active keyboard layout produces with a single key,
+    /// optionally with Shift or AltGr (such as `@` on German layouts), are typed as key
+    /// presses, which also reach applications that ignore Unicode input. Shift is chosen
+    /// according to the CapsLock state, which is left as it is. Other characters,
+    /// including those the layout only produces through a dead key such as `´` or `~`,
+    /// are injected as Unicode so no accent composition is started. On macOS all text is
+    /// injected as Unicode strings, so characters without a key code, such as emoji or
+    /// CJK text, are typed as well.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Simulate;
+    ///
+    /// Simulate::text("Grüße, señor!\n");
+    /// ```
+    pub fn text(text: &str) {
+        if sandbox::intercept(|| Injection::Text(text.to_string())) {
+            return;
+        }
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
+        PlatformSimulate::text(text);
+    }
+
+    /// Types `text` as Unicode input, bypassing the keyboard layout entirely.
+    ///
+    /// Meant for characters no key on the active layout produces, such as emoji or CJK
+    /// text without an input method. On Windows every character is injected as a
+    /// `VK_PACKET` key event, which applications receive as `WM_CHAR` but which some
+    /// games and remote desktop clients ignore; prefer [`Simulate::text`] for text the
+    /// layout can type. On macOS this is the same as [`Simulate::text`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Simulate;
+    ///
+    /// Simulate::unicode("こんにちは 👋");
+    /// ```
+    pub fn unicode(text: &str) {
+        if sandbox::intercept(|| Injection::Unicode(text.to_string())) {
+            return;
+        }
+        #[cfg(feature = "listen")]
+        managed::ensure_core();
+        PlatformSimulate::unicode(text);
+    }
+
+    /// Releases every key and mouse button believed to be down, to recover from an
+    /// interrupted macro that left keys stuck.
+    ///
+    /// Covers the presses simulated through `Simulate` and those the hooks observe while
+    /// there are subscribers or middleware. Releases are simulated in reverse order of the
+    /// presses, keys before buttons, and nothing is believed to be down afterwards.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Key, Simulate};
+    ///
+    /// Simulate::keyboard(Key::ControlLeft, true);
+    /// // ... the macro fails before releasing Control ...
+    /// Simulate::release_all();
+    /// ```
+    pub fn release_all() {
+        let (keys, buttons) = pressed::take();
+        for key in keys.into_iter().rev() {
+            Simulate::keyboard(key, false);
+        }
+        for button in buttons.into_iter().rev() {
+            Simulate::mouse_button(button, false);
+        }
+    }
+
+    /// Runs `f` with the modifier keys the user holds released, and presses them again
+    /// afterwards. Returns what `f` returns.
+    ///
+    /// Keeps a physically held Shift or Control from turning simulated keys into other
+    /// characters or shortcuts. The held keys are read from the OS, so no core needs to
+    /// run. Releases and presses are simulated like any other key, so subscribers see
+    /// them as injected events.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Key, Simulate};
+    ///
+    /// // Ctrl+V, even while the user holds Shift
+    /// Simulate::with_clean_modifiers(|| {
+    ///     Simulate::keyboard(Key::ControlLeft, true);
+    ///     Simulate::keyboard(Key::KeyV, true);
+    ///     Simulate::keyboard(Key::KeyV, false);
+    ///     Simulate::keyboard(Key::ControlLeft, false);
+    /// });
+    /// ```
+    pub fn with_clean_modifiers<R>(f: impl FnOnce() -> R) -> R {
+        use crate::platform::{PlatformPoll, PollImpl};
+
+        /// Presses the modifiers again, also when `f` panics.
+        struct Repress(Vec<Key>);
+
+        impl Drop for Repress {
+            fn drop(&mut self) {
+                for &key in &self.0 {
+                    Simulate::keyboard(key, true);
+                }
+            }
+        }
+
+        let held: Vec<Key> = PlatformPoll::pressed_keys()
+            .into_iter()
+            .map(|(key, _)| key)
+            .filter(|&key| Modifiers::from_key(key).is_some())
+            .collect();
+        for &key in &held {
+            Simulate::keyboard(key, false);
+        }
+        let _repress = Repress(held);
+        f()
+    }
+
+    /// Reports all injection to `callback` instead of performing it, until
+    /// [`Simulate::unsandbox`] is called.
+    ///
+    /// Lets automation scripts run safely, for example in CI, while checking what they
+    /// would type. Only calls of `Simulate` are sandboxed: keys injected by remapping
+    /// still reach the OS. The callback runs on the thread that called `Simulate`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Simulate;
+    ///
+    /// Simulate::sandbox(|injection| println!("Would inject {:?}", injection));
+    /// Simulate::text("rm -rf /");
+    /// Simulate::unsandbox();
+    /// ```
+    pub fn sandbox<F>(callback: F)
+    where
+        F: Fn(Injection) + Send + Sync + 'static,
+    {
+        sandbox::set(Some(std::sync::Arc::new(callback)));
+    }
+
+    /// Ends the sandbox started with [`Simulate::sandbox`], so input is injected again.
+    pub fn unsandbox() {
+        sandbox::set(None);
+    }
+
+    /// Runs `f` and returns what it would have injected through `Simulate`, without
+    /// injecting anything.
+    ///
+    /// Only calls on the current thread are collected, so tests running in parallel do
+    /// not see each other's input. Takes precedence over [`Simulate::sandbox`].
+    ///
+    /// # Example
+    /// ```
+    /// use raw_input::{Event, Injection, Key, Simulate};
+    ///
+    /// let injected = Simulate::dry_run(|| Simulate::keyboard(Key::Enter, true));
+    /// assert_eq!(injected, [Injection::Event(Event::KeyDown { key: Key::Enter, code: None })]);
+    /// ```
+    pub fn dry_run<F: FnOnce()>(f: F) -> Vec<Injection> {
+        sandbox::record(f)
+    }
+
+    /// Chooses whether simulated keys are physical positions or the characters they
+    /// name on the active keyboard layout.
+    ///
+    /// [`KeySemantics::Character`] resolves the scan code through the layout of the
+    /// focused window, so automation written against QWERTY key names types the same
+    /// letters on AZERTY or Dvorak. Applies to all key injection, including remapping.
+    /// macOS key codes always are physical positions, so this has no effect there.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::{Key, KeySemantics, Simulate};
+    ///
+    /// Simulate::key_semantics(KeySemantics::Character);
+    /// // Types "a", whatever the layout
+    /// Simulate::keyboard(Key::KeyA, true);
+    /// Simulate::keyboard(Key::KeyA, false);
+    /// ```
+    #[inline]
+    pub fn key_semantics(semantics: KeySemantics) {
+        PlatformSimulate::key_semantics(semantics);
+    }
 }
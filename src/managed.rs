@@ -0,0 +1,53 @@
+use std::{
+    sync::{
+        Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    platform::{CoreImpl, PlatformCore},
+    polling,
+};
+
+/// Set by [`Core::managed`](crate::Core::managed).
+static MANAGED: AtomicBool = AtomicBool::new(false);
+
+/// Serializes the startup, so concurrent first calls spawn a single core thread.
+static STARTING: Mutex<()> = Mutex::new(());
+
+/// How long a first call waits for the core thread to install its hooks.
+const READY_TIMEOUT: Duration = Duration::from_secs(2);
+
+pub(crate) fn set_enabled(enable: bool) {
+    MANAGED.store(enable, Ordering::SeqCst);
+}
+
+/// Spawns the core thread in managed mode, unless the core already runs or polls, and
+/// waits until its hooks are installed or it failed to start.
+pub(crate) fn ensure_core() {
+    if !MANAGED.load(Ordering::Relaxed) || PlatformCore::hooks_installed() || polling::is_active() {
+        return;
+    }
+
+    let _starting = STARTING.lock().unwrap_or_else(PoisonError::into_inner);
+    if PlatformCore::hooks_installed() {
+        return;
+    }
+
+    let core = thread::Builder::new()
+        .name("raw-input-core".to_string())
+        .spawn(|| {
+            let _ = PlatformCore::start();
+        });
+    let Ok(core) = core else {
+        return;
+    };
+
+    let deadline = Instant::now() + READY_TIMEOUT;
+    while !PlatformCore::hooks_installed() && !core.is_finished() && Instant::now() < deadline {
+        thread::sleep(Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,1129 @@
+//! Controls the in-memory backend enabled by the `mock` feature.
+//!
+//! With `mock` the crate never touches the OS: [`Core`](crate::Core),
+//! [`Listen`](crate::Listen), [`Grab`](crate::Grab), [`Simulate`](crate::Simulate) and
+//! [`Display`](crate::Display) run against a virtual environment instead, so input
+//! handling can be unit-tested without hooks, permissions or a display.
+//!
+//! [`input`] plays the role of the user: the event passes through the same
+//! subscribers, middleware and grab filter as real input. Whatever gets past them reaches
+//! the environment, where [`take_output`] and [`take_text`] read it back. Simulated
+//! events take the same path, marked as injected.
+//!
+//! Events are handled on the calling thread, so everything has happened by the time
+//! [`input`] returns. The environment is shared by the whole process; run tests that
+//! use it serially.
+//!
+//! # Example
+//! ```
+//! use raw_input::{Core, Event, Grab, Key, Listen, mock};
+//!
+//! Core::attach_to_current_thread().unwrap();
+//! Grab::start().unwrap();
+//! Grab::keyboard(true);
+//!
+//! // The grab keeps the key from the focused application
+//! assert!(!mock::input(Event::KeyDown { key: Key::KeyA, code: None }));
+//! assert!(mock::take_output().is_empty());
+//!
+//! Grab::stop();
+//! Core::stop();
+//! ```
+
+use std::sync::atomic::Ordering;
+
+use crate::{
+    CursorImage, DeviceId, DeviceInfo, DeviceKind, Event, ForegroundApp, HidReport, MonitorInfo,
+    foreground, hid,
+    platform::{PlatformListen, mock::common},
+};
+
+/// Restores the default environment: a single 1920x1080 monitor with the cursor at its
+/// centre and no cursor image, no devices, no focused application, a pointer speed of
+/// 0.5 with acceleration, three lines per wheel notch, all permissions granted, and no
+/// output.
+///
+/// The state of [`Core`](crate::Core), [`Listen`](crate::Listen) and
+/// [`Grab`](crate::Grab) is kept.
+pub fn reset() {
+    common::reset();
+}
+
+/// Feeds an event as if the user produced it. Returns `true` if it got past the hooks
+/// and reached the environment, `false` if it was blocked or replaced.
+///
+/// Without a running core the event reaches the environment directly.
+pub fn input(event: Event) -> bool {
+    common::hook(event, false)
+}
+
+/// Returns the events that reached the environment since the last call, in order.
+pub fn take_output() -> Vec<Event> {
+    std::mem::take(&mut common::environment().output)
+}
+
+/// Returns the text typed into the focused application since the last call.
+///
+/// Key events type the characters of a US QWERTY layout, with Shift applied.
+/// [`Simulate::unicode`](crate::Simulate::unicode) and characters missing from the
+/// layout are typed directly.
+pub fn take_text() -> String {
+    std::mem::take(&mut common::environment().text)
+}
+
+/// Replaces the connected monitors. Offsets and sizes are in physical pixels.
+///
+/// Like a display change on a real system, this invalidates the monitors cached by
+/// `Display`.
+pub fn set_monitors(monitors: Vec<MonitorInfo>) {
+    common::environment().monitors = monitors;
+    crate::monitors::invalidate();
+}
+
+/// Sets the image [`Display::get_cursor_image`](crate::Display::get_cursor_image)
+/// returns, or hides the cursor with `None`.
+pub fn set_cursor_image(image: Option<CursorImage>) {
+    common::environment().cursor_image = image;
+}
+
+/// Connects a virtual device, listed by [`Devices::list`](crate::Devices::list) with no
+/// vendor, product or capabilities. Returns its id, for [`hid_report`].
+pub fn connect(kind: DeviceKind) -> DeviceId {
+    let mut env = common::environment();
+    let id = DeviceId(
+        env.devices
+            .iter()
+            .map(|device| device.id.0 + 1)
+            .max()
+            .unwrap_or(1),
+    );
+    env.devices.push(DeviceInfo {
+        id,
+        kind,
+        vendor_id: None,
+        product_id: None,
+        manufacturer: None,
+        product: None,
+        path: None,
+        mouse: None,
+        keyboard: None,
+    });
+    id
+}
+
+/// Disconnects a device added with [`connect`].
+pub fn disconnect(id: DeviceId) {
+    common::environment()
+        .devices
+        .retain(|device| device.id != id);
+}
+
+/// Focuses `app`, or no application with `None`. Subscribers receive an
+/// [`Event::FocusChanged`] while the core runs.
+pub fn focus(app: Option<ForegroundApp>) {
+    let pid = app.as_ref().map(|app| app.pid);
+    common::environment().foreground = app.clone();
+    foreground::changed(app);
+    if let Some(pid) = pid
+        && common::IS_INSTALLED.load(Ordering::SeqCst)
+    {
+        PlatformListen::handle(Event::FocusChanged { pid }, false);
+    }
+}
+
+/// Simulates a password field gaining or losing the focus, for
+/// [`Listen::pause_during_secure_input`](crate::Listen::pause_during_secure_input).
+pub fn set_secure_input(enable: bool) {
+    common::environment().secure_input = enable;
+}
+
+/// Sends a report from a virtual HID device. Returns `true` if the report's usage is
+/// registered and the core runs, in which case it reaches the [`Hid`](crate::Hid)
+/// subscribers of that usage.
+pub fn hid_report(report: &HidReport) -> bool {
+    let registered = common::environment().hid_usages.contains(&report.usage);
+    if !registered || !common::IS_INSTALLED.load(Ordering::SeqCst) || !hid::is_active() {
+        return false;
+    }
+    hid::dispatch(report);
+    true
+}
+
+/// Grants or revokes the input permissions. Without them the core fails to start with
+/// [`CoreError::PermissionDenied`](crate::CoreError::PermissionDenied).
+pub fn set_permission(granted: bool) {
+    common::HAS_PERMISSION.store(granted, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        thread,
+        time::Duration,
+    };
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{
+        CaptureMode, Core, CoreError, Devices, Display, EventCategory, EventFilter, EventSource,
+        Grab, GrabDecision, GrabFlags, Hid, HidUsage, Hotkey, HotkeySequence, Key, Listen,
+        Modifiers, Mouse, MouseButton, Point, Profile, Profiles, Simulate, StateError,
+    };
+
+    #[serial]
+    #[test]
+    fn test_listen_grab_and_simulate() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        let handle = Listen::subscribe(move |event| sink.lock().unwrap().push(event));
+        Grab::start().unwrap();
+        Grab::mouse_move(false);
+        Grab::mouse_wheel(false);
+        Grab::mouse_button(false);
+        Grab::pass_injected(true);
+
+        let down = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        assert!(input(down));
+        assert!(!input(Event::KeyDown {
+            key: Key::KeyA,
+            code: None
+        }));
+        Simulate::text("Hi!");
+
+        // Shift, held for "H" and "!", is reported as held and released twice
+        assert_eq!(seen.lock().unwrap().len(), 2 + 10 + 4);
+        assert_eq!(take_output().len(), 1 + 10);
+        assert_eq!(take_text(), "Hi!");
+
+        handle.unsubscribe();
+        Grab::pass_injected(false);
+        Grab::stop();
+        Listen::stop();
+        Core::stop();
+        assert!(input(down));
+        assert_eq!(take_output(), [down]);
+    }
+
+    #[serial]
+    #[test]
+    fn test_virtual_display() {
+        reset();
+        set_monitors(vec![
+            MonitorInfo {
+                name: "Left".to_string(),
+                is_primary: true,
+                offset: (0.0, 0.0),
+                size: (2560.0, 1440.0),
+                scale_factor: 2.0,
+            },
+            MonitorInfo {
+                name: "Right".to_string(),
+                is_primary: false,
+                offset: (2560.0, 0.0),
+                size: (1920.0, 1080.0),
+                scale_factor: 1.0,
+            },
+        ]);
+
+        Simulate::mouse_move_to(1500.0, 100.0);
+        let cursor = Display::get_cursor().unwrap();
+        assert_eq!(
+            cursor.physical,
+            Point {
+                x: 3000.0,
+                y: 200.0
+            }
+        );
+        let right = cursor.monitor.unwrap();
+        assert_eq!(right.name, "Right");
+        assert_eq!(
+            Display::cursor_position_on(&right),
+            Some(Point { x: 440.0, y: 200.0 })
+        );
+        let left = Display::get_primary_monitor().unwrap();
+        assert_eq!(Display::cursor_position_on(&left), None);
+
+        // The cursor stays on the virtual screen
+        Simulate::mouse_move(5000.0, 5000.0);
+        assert_eq!(Display::get_cursor_position(), Some((4479.0, 1439.0)));
+        assert_eq!(take_output().len(), 2);
+
+        Simulate::simulate(Event::MouseMoveAbsolute {
+            position: Point { x: 100.0, y: 50.0 },
+        });
+        assert_eq!(Display::get_cursor_position(), Some((100.0, 50.0)));
+
+        assert_eq!(Display::get_cursor_image(), None);
+        let image = CursorImage {
+            width: 1,
+            height: 1,
+            hotspot: (0, 0),
+            pixels: vec![0, 0, 0, 255],
+        };
+        set_cursor_image(Some(image.clone()));
+        assert_eq!(Display::get_cursor_image(), Some(image));
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_batch_subscription() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let handle = Listen::subscribe_batch(Duration::from_millis(300), move |events| {
+            sender.send(events.to_vec()).unwrap();
+        });
+        let events: Vec<Event> = [Key::KeyA, Key::KeyB, Key::KeyC]
+            .into_iter()
+            .map(|key| Event::KeyDown { key, code: None })
+            .collect();
+        for &event in &events {
+            input(event);
+        }
+
+        let batch = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(batch, events);
+        handle.unsubscribe();
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[cfg(feature = "serialize")]
+    #[serial]
+    #[test]
+    fn test_journal() {
+        use crate::{Journal, JournalFormat, JournalOptions};
+
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let path = std::env::temp_dir().join(format!("raw-input-{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let options = JournalOptions {
+            format: JournalFormat::Jsonl,
+            ..Default::default()
+        };
+        let journal = Journal::start(&path, options).unwrap();
+        let down = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        input(down);
+        input(Event::KeyUp {
+            key: Key::KeyA,
+            code: None,
+        });
+        journal.stop().unwrap();
+        input(down);
+
+        let entries = Journal::read(&path).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].event, down);
+        std::fs::remove_file(&path).unwrap();
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_weak_subscription() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let subscribers = Core::state().subscribers;
+        let owner = Arc::new(Mutex::new(0));
+        let counter = owner.clone();
+        Listen::subscribe_weak(&owner, |count, _| *count.lock().unwrap() += 1);
+        let down = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+
+        input(down);
+        assert_eq!(*counter.lock().unwrap(), 1);
+        drop((owner, counter));
+        assert_eq!(Core::state().subscribers, subscribers + 1);
+        input(down);
+        assert_eq!(Core::state().subscribers, subscribers);
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_native_hotkeys_without_core() {
+        reset();
+        let count = Arc::new(Mutex::new(0));
+        let counter = count.clone();
+        let hotkey = Hotkey::new(Modifiers::CONTROL, Key::KeyK)
+            .register(move || *counter.lock().unwrap() += 1);
+        let sequence = HotkeySequence::new(["Ctrl+K".parse().unwrap(), "Ctrl+C".parse().unwrap()])
+            .register(|| {});
+        let key = |key, down| match down {
+            true => Event::KeyDown { key, code: None },
+            false => Event::KeyUp { key, code: None },
+        };
+
+        let press = || {
+            input(key(Key::ControlLeft, true));
+            let passed = input(key(Key::KeyK, true));
+            input(key(Key::KeyK, false));
+            input(key(Key::ControlLeft, false));
+            passed
+        };
+
+        // The OS hotkey consumes the press
+        assert!(!press());
+        assert_eq!(*count.lock().unwrap(), 1);
+
+        // The hooks take over while the core runs
+        Core::attach_to_current_thread().unwrap();
+        assert!(press());
+        assert_eq!(*count.lock().unwrap(), 2);
+        Core::stop();
+
+        assert!(!press());
+        assert_eq!(*count.lock().unwrap(), 3);
+        hotkey.unregister();
+        sequence.unregister();
+        assert!(input(key(Key::KeyK, true)));
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_polling_fallback() {
+        reset();
+        let core = thread::spawn(|| Core::start_polling(Duration::from_millis(1)));
+        while Core::state().capture == CaptureMode::Hooks {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(Core::state().limitations.no_blocking);
+        assert_eq!(
+            Core::attach_to_current_thread(),
+            Err(CoreError::AlreadyRunning)
+        );
+        assert_eq!(Listen::start(), Ok(()));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let handle = Listen::subscribe(move |event| {
+            let _ = sender.send(event);
+        });
+        // Lets the first sample set the baseline
+        thread::sleep(Duration::from_millis(20));
+
+        let down = Event::KeyDown {
+            key: Key::KeyA,
+            code: Some(65),
+        };
+        assert!(input(down));
+        assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(down));
+        input(Event::MouseMove {
+            delta: Point { x: 5.0, y: 0.0 },
+        });
+        assert_eq!(
+            receiver.recv_timeout(Duration::from_secs(5)),
+            Ok(Event::MouseMove {
+                delta: Point { x: 5.0, y: 0.0 }
+            })
+        );
+
+        Core::stop();
+        assert_eq!(core.join().unwrap(), Ok(()));
+        assert_eq!(Core::state().capture, CaptureMode::Hooks);
+        handle.unsubscribe();
+        Listen::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_sequenced_subscriptions() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let handle = Listen::subscribe_sequenced(move |sequenced| {
+            sink.lock().unwrap().push(sequenced);
+        });
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let ordered = Listen::subscribe_ordered(Duration::from_millis(5), move |sequenced| {
+            sender.send(sequenced.event).unwrap();
+        });
+        let down = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        let up = Event::MouseUp {
+            button: MouseButton::Left,
+        };
+
+        input(down);
+        input(up);
+        focus(Some(ForegroundApp {
+            pid: 7,
+            exe: None,
+            bundle_id: None,
+            title: None,
+        }));
+        let received = received.lock().unwrap().clone();
+        let sources: Vec<EventSource> = received.iter().map(|s| s.source).collect();
+        assert_eq!(
+            sources,
+            [EventSource::Hook, EventSource::Hook, EventSource::System]
+        );
+        assert_eq!(received[1].sequence, received[0].sequence + 1);
+        assert!(received[0].time <= received[1].time);
+        for event in [down, up, Event::FocusChanged { pid: 7 }] {
+            assert_eq!(receiver.recv_timeout(Duration::from_secs(5)), Ok(event));
+        }
+
+        handle.unsubscribe();
+        ordered.unsubscribe();
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_modifiers_changed() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let handle = Listen::subscribe(move |event| {
+            if let Event::ModifiersChanged { modifiers } = event {
+                sink.lock().unwrap().push(modifiers);
+            }
+        });
+        let key = |key, down| match down {
+            true => Event::KeyDown { key, code: None },
+            false => Event::KeyUp { key, code: None },
+        };
+
+        input(key(Key::ShiftLeft, true));
+        input(key(Key::ShiftRight, true));
+        input(key(Key::KeyA, true));
+        input(key(Key::KeyA, false));
+        input(key(Key::ShiftLeft, false));
+        Simulate::keyboard(Key::MetaLeft, true);
+        Simulate::keyboard(Key::MetaLeft, false);
+        input(key(Key::ShiftRight, false));
+        assert_eq!(
+            *received.lock().unwrap(),
+            [
+                Modifiers::SHIFT,
+                Modifiers::SHIFT | Modifiers::META,
+                Modifiers::SHIFT,
+                Modifiers::NONE,
+            ]
+        );
+
+        // Reported with keyboard listening only
+        Listen::keyboard(false);
+        input(key(Key::ControlLeft, true));
+        input(key(Key::ControlLeft, false));
+        assert_eq!(received.lock().unwrap().len(), 4);
+
+        handle.unsubscribe();
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_with_clean_modifiers() {
+        reset();
+        input(Event::KeyDown {
+            key: Key::ShiftRight,
+            code: None,
+        });
+        take_output();
+
+        let result = Simulate::with_clean_modifiers(|| {
+            Simulate::keyboard(Key::KeyA, true);
+            Simulate::keyboard(Key::KeyA, false);
+            42
+        });
+        assert_eq!(result, 42);
+        assert_eq!(take_text(), "a");
+        assert_eq!(
+            take_output(),
+            [
+                Event::KeyUp {
+                    key: Key::ShiftRight,
+                    code: None
+                },
+                Event::KeyDown {
+                    key: Key::KeyA,
+                    code: None
+                },
+                Event::KeyUp {
+                    key: Key::KeyA,
+                    code: None
+                },
+                Event::KeyDown {
+                    key: Key::ShiftRight,
+                    code: None
+                },
+            ]
+        );
+
+        // Shift is held again
+        Simulate::keyboard(Key::KeyA, true);
+        assert_eq!(take_text(), "A");
+        Simulate::keyboard(Key::KeyA, false);
+        take_output();
+
+        // A panic in `f` still presses Shift again
+        let panicked = std::panic::catch_unwind(|| {
+            Simulate::with_clean_modifiers(|| panic!("failed"));
+        });
+        assert!(panicked.is_err());
+        assert_eq!(
+            take_output(),
+            [
+                Event::KeyUp {
+                    key: Key::ShiftRight,
+                    code: None
+                },
+                Event::KeyDown {
+                    key: Key::ShiftRight,
+                    code: None
+                },
+            ]
+        );
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_release_all() {
+        reset();
+        Simulate::release_all();
+        take_output();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let handle = Listen::subscribe(|_| {});
+
+        Simulate::keyboard(Key::ControlLeft, true);
+        Simulate::mouse_button(MouseButton::Left, true);
+        input(Event::KeyDown {
+            key: Key::ShiftLeft,
+            code: None,
+        });
+        Simulate::keyboard(Key::KeyC, true);
+        Simulate::keyboard(Key::KeyC, false);
+        take_output();
+
+        Simulate::release_all();
+        assert_eq!(
+            take_output(),
+            [
+                Event::KeyUp {
+                    key: Key::ShiftLeft,
+                    code: None
+                },
+                Event::KeyUp {
+                    key: Key::ControlLeft,
+                    code: None
+                },
+                Event::MouseUp {
+                    button: MouseButton::Left
+                },
+            ]
+        );
+        // Nothing is left to release
+        Simulate::release_all();
+        assert!(take_output().is_empty());
+
+        handle.unsubscribe();
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_grab_stop_sends_blocked_releases() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        let shift = |down| match down {
+            true => Event::KeyDown {
+                key: Key::ShiftLeft,
+                code: None,
+            },
+            false => Event::KeyUp {
+                key: Key::ShiftLeft,
+                code: None,
+            },
+        };
+        let button = MouseButton::Left;
+
+        // Pressed before the grab, released during it
+        assert!(input(shift(true)));
+        Grab::start().unwrap();
+        assert!(!input(shift(false)));
+        // Pressed and released during the grab
+        assert!(!input(Event::MouseDown { button }));
+        assert!(!input(Event::MouseUp { button }));
+        assert_eq!(take_output(), [shift(true)]);
+
+        Grab::stop();
+        assert_eq!(take_output(), [shift(false)]);
+        // Sent once
+        Grab::start().unwrap();
+        Grab::stop();
+        assert!(take_output().is_empty());
+
+        // Released during the grab, then pressed again and held once it let the key through
+        assert!(input(shift(true)));
+        Grab::start().unwrap();
+        assert!(!input(shift(false)));
+        Grab::keyboard(false);
+        assert!(input(shift(true)));
+        Grab::stop();
+        assert_eq!(take_output(), [shift(true), shift(true)]);
+
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_scope_removal_sends_blocked_releases() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        let key = |key, down| match down {
+            true => Event::KeyDown { key, code: None },
+            false => Event::KeyUp { key, code: None },
+        };
+
+        // Pressed before the scope, released while it blocks everything
+        assert!(input(key(Key::KeyA, true)));
+        let scope = Grab::add_scope(0, |_: &Event, _| GrabDecision::Block);
+        assert!(!input(key(Key::KeyA, false)));
+        assert_eq!(take_output(), [key(Key::KeyA, true)]);
+
+        scope.remove();
+        assert_eq!(take_output(), [key(Key::KeyA, false)]);
+
+        // Released while blocked by a filter; removing a scope or stopping `Grab` leaves
+        // the release to the filter
+        assert!(input(key(Key::CapsLock, true)));
+        let filter = Grab::add_filter(EventFilter {
+            keys: vec![Key::CapsLock],
+            ..Default::default()
+        });
+        assert!(!input(key(Key::CapsLock, false)));
+        Grab::add_scope(0, |_: &Event, _| GrabDecision::Abstain).remove();
+        Grab::start().unwrap();
+        Grab::stop();
+        assert_eq!(take_output(), [key(Key::CapsLock, true)]);
+        filter.remove();
+        assert_eq!(take_output(), [key(Key::CapsLock, false)]);
+
+        // A press blocked by the scope needs no release
+        let scope = Grab::add_scope(0, |_: &Event, _| GrabDecision::Block);
+        assert!(!input(key(Key::KeyB, true)));
+        assert!(!input(key(Key::KeyB, false)));
+        scope.remove();
+        assert!(take_output().is_empty());
+
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_pause_during_secure_input() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Listen::start().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let sink = received.clone();
+        let handle = Listen::subscribe(move |event| sink.lock().unwrap().push(event));
+        let key = Event::KeyDown {
+            key: Key::KeyA,
+            code: None,
+        };
+        let click = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+
+        Listen::pause_during_secure_input(true);
+        input(key);
+        set_secure_input(true);
+        input(key);
+        input(click);
+        assert_eq!(*received.lock().unwrap(), [key, click]);
+        // The keystroke still reaches the password field
+        assert_eq!(
+            take_output().iter().filter(|&&event| event == key).count(),
+            2
+        );
+
+        Listen::pause_during_secure_input(false);
+        input(key);
+        assert_eq!(received.lock().unwrap().len(), 3);
+        handle.unsubscribe();
+        Listen::stop();
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_grab_filter() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        let handle = Grab::add_filter(EventFilter {
+            categories: vec![EventCategory::MouseWheel],
+            modifiers: Modifiers::CONTROL,
+            ..Default::default()
+        });
+        let wheel = Event::MouseWheel {
+            delta: Point { x: 0.0, y: 1.0 },
+            raw: None,
+        };
+        assert!(input(wheel));
+        assert!(input(Event::KeyDown {
+            key: Key::ControlLeft,
+            code: None
+        }));
+        assert!(!input(wheel));
+
+        handle.remove();
+        assert!(input(wheel));
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_grab_scopes() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Grab::start().unwrap();
+        let key = |key| Event::KeyDown { key, code: None };
+        let help = Grab::add_scope(10, |event: &Event, _| match event {
+            Event::KeyDown { key: Key::F1, .. } => GrabDecision::Pass,
+            _ => GrabDecision::Abstain,
+        });
+        assert!(input(key(Key::F1)));
+        assert!(!input(key(Key::KeyA)));
+
+        // Scopes block without the `Grab` flags, below the higher-priority one
+        Grab::stop();
+        let kiosk = Grab::add_scope(0, |_: &Event, _| GrabDecision::Block);
+        assert!(input(key(Key::F1)));
+        assert!(!input(key(Key::KeyA)));
+
+        kiosk.remove();
+        help.remove();
+        assert!(input(key(Key::KeyA)));
+        Core::stop();
+        reset();
+    }
+
+    #[cfg(feature = "config")]
+    #[serial]
+    #[test]
+    fn test_apply_config() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        let config = crate::config::parse(
+            "[grab]\nmouse_wheel = true\n\n[[hotkey]]\nname = \"save\"\nkeys = \"F5\"\n\n\
+             [remap]\nCapsLock = \"Escape\"",
+        )
+        .unwrap();
+        let fired = Arc::new(Mutex::new(Vec::new()));
+        let sink = fired.clone();
+        let handle = config.apply(move |name| sink.lock().unwrap().push(name.to_string()));
+
+        let tap = |key| {
+            let down = input(Event::KeyDown { key, code: None });
+            input(Event::KeyUp { key, code: None });
+            down
+        };
+        assert!(!tap(Key::CapsLock));
+        assert_eq!(
+            take_output(),
+            [
+                Event::KeyDown {
+                    key: Key::Escape,
+                    code: None
+                },
+                Event::KeyUp {
+                    key: Key::Escape,
+                    code: None
+                }
+            ]
+        );
+        tap(Key::F5);
+        assert_eq!(*fired.lock().unwrap(), ["save"]);
+        assert!(Grab::flags().mouse_wheel);
+
+        handle.remove();
+        assert!(tap(Key::CapsLock));
+        Grab::mouse_wheel(false);
+        Core::stop();
+        reset();
+    }
+
+    #[cfg(feature = "script")]
+    #[serial]
+    #[test]
+    fn test_script() {
+        use crate::script::{self, ScriptError};
+
+        reset();
+        script::compile("tap(\"Shift+H\");\ntext(\"i\");\nmove_to(10, 20.5);\nclick(\"Right\");")
+            .unwrap()
+            .run()
+            .unwrap();
+        assert_eq!(take_text(), "Hi");
+        assert_eq!(Display::get_cursor_position(), Some((10.0, 20.5)));
+        assert!(take_output().ends_with(&[
+            Event::MouseDown {
+                button: MouseButton::Right
+            },
+            Event::MouseUp {
+                button: MouseButton::Right
+            },
+        ]));
+
+        let run = |source| script::compile(source).unwrap().max_operations(1000).run();
+        assert!(matches!(
+            script::compile("tap("),
+            Err(ScriptError::Syntax(_))
+        ));
+        assert!(matches!(run("tap(\"Nope\")"), Err(ScriptError::Runtime(_))));
+        assert!(matches!(
+            run("import \"os\" as os;"),
+            Err(ScriptError::Runtime(_))
+        ));
+        assert!(matches!(run("loop {}"), Err(ScriptError::Runtime(_))));
+        assert!(script::compile("eval(\"1\")").is_err());
+        assert!(take_output().is_empty());
+        reset();
+    }
+
+    #[cfg(feature = "script")]
+    #[serial]
+    #[test]
+    fn test_script_hotkey() {
+        reset();
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let sink = errors.clone();
+        let handle = crate::script::compile(
+            "hotkey(\"Ctrl+J\", || { sleep(10); text(\"j\"); hotkey(\"F1\", || {}) });",
+        )
+        .unwrap()
+        .on_error(move |err| sink.lock().unwrap().push(err.to_string()))
+        .run()
+        .unwrap();
+
+        input(Event::KeyDown {
+            key: Key::ControlLeft,
+            code: None,
+        });
+        // Consumed by the hotkey, whose callback runs on the script's thread
+        assert!(!input(Event::KeyDown {
+            key: Key::KeyJ,
+            code: None,
+        }));
+        input(Event::KeyUp {
+            key: Key::KeyJ,
+            code: None,
+        });
+        input(Event::KeyUp {
+            key: Key::ControlLeft,
+            code: None,
+        });
+        for _ in 0..500 {
+            if !errors.lock().unwrap().is_empty() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(take_text(), "j");
+        // Hotkeys can't be registered from a callback
+        assert_eq!(errors.lock().unwrap().len(), 1);
+
+        handle.stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_switch_profiles() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        Profiles::define(
+            "gaming",
+            Profile {
+                grab: Some(GrabFlags {
+                    mouse_wheel: true,
+                    ..Default::default()
+                }),
+                remaps: vec![(Key::CapsLock, Key::Escape)],
+                ..Default::default()
+            },
+        );
+        Profiles::define("typing", Profile::default());
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        let sink = changes.clone();
+        let handle = Profiles::subscribe(move |event| {
+            sink.lock().unwrap().push((event.previous, event.current))
+        });
+        let caps = Event::KeyDown {
+            key: Key::CapsLock,
+            code: None,
+        };
+        let caps_up = Event::KeyUp {
+            key: Key::CapsLock,
+            code: None,
+        };
+
+        assert!(!Profiles::activate("missing"));
+        assert!(Profiles::activate("gaming"));
+        assert!(Profiles::activate("gaming"));
+        assert_eq!(Profiles::active().as_deref(), Some("gaming"));
+        assert!(Grab::flags().mouse_wheel);
+        assert!(!input(caps));
+        input(caps_up);
+        take_output();
+
+        assert!(Profiles::activate("typing"));
+        // Flags the profile leaves unset are kept, remaps are not
+        assert!(Grab::flags().mouse_wheel);
+        assert!(input(caps));
+        input(caps_up);
+        Profiles::remove("typing");
+        assert_eq!(Profiles::active(), None);
+        assert_eq!(Profiles::names(), ["gaming"]);
+
+        let name = |name: &str| Some(name.to_string());
+        assert_eq!(
+            *changes.lock().unwrap(),
+            [
+                (None, name("gaming")),
+                (name("gaming"), name("typing")),
+                (name("typing"), None)
+            ]
+        );
+        handle.unsubscribe();
+        Profiles::remove("gaming");
+        Grab::mouse_wheel(false);
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_block_input() {
+        reset();
+        let block = Grab::block_input(Duration::from_secs(60)).unwrap();
+        assert!(matches!(
+            Grab::block_input(Duration::from_secs(60)),
+            Err(StateError::AlreadyRunning)
+        ));
+        let down = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        assert!(!input(down));
+        Simulate::simulate(down);
+        assert_eq!(take_output(), [down]);
+
+        block.release();
+        assert!(input(down));
+
+        // The timeout lifts a block nobody releases
+        let block = Grab::block_input(Duration::from_millis(10)).unwrap();
+        while block.is_active() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert!(input(down));
+        drop(block);
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_pointer_settings() {
+        reset();
+        assert_eq!(Mouse::get_speed(), Some(0.5));
+        Mouse::set_speed(2.0).unwrap();
+        Mouse::set_acceleration(false).unwrap();
+        assert_eq!(Mouse::get_speed(), Some(1.0));
+        assert_eq!(Mouse::get_acceleration(), Some(false));
+        assert_eq!(Mouse::get_scroll_lines(), Some(Point { x: 3.0, y: 3.0 }));
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_devices_and_hid_reports() {
+        reset();
+        Core::attach_to_current_thread().unwrap();
+        let pedal = connect(DeviceKind::Hid(HidUsage::JOYSTICK));
+        let keyboard = connect(DeviceKind::Keyboard);
+        disconnect(keyboard);
+        assert_eq!(
+            Devices::list().iter().map(|d| d.id).collect::<Vec<_>>(),
+            [pedal]
+        );
+
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+        let handle = Hid::subscribe(HidUsage::JOYSTICK, move |report| {
+            sink.lock().unwrap().push(report.data.to_vec())
+        });
+        let report = |usage| HidReport {
+            usage,
+            device: pedal,
+            data: &[1, 2],
+        };
+        assert!(hid_report(&report(HidUsage::JOYSTICK)));
+        assert!(!hid_report(&report(HidUsage::GAMEPAD)));
+        assert_eq!(*reports.lock().unwrap(), [vec![1, 2]]);
+
+        handle.remove();
+        assert!(!hid_report(&report(HidUsage::JOYSTICK)));
+        Core::stop();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_core_lifecycle() {
+        reset();
+        set_permission(false);
+        assert_eq!(Core::start(), Err(CoreError::PermissionDenied));
+        set_permission(true);
+
+        let core = thread::spawn(Core::start);
+        while !Core::state().hooks_installed {
+            thread::sleep(Duration::from_millis(1));
+        }
+        assert_eq!(
+            Core::attach_to_current_thread(),
+            Err(CoreError::AlreadyRunning)
+        );
+        Core::stop();
+        assert_eq!(core.join().unwrap(), Ok(()));
+    }
+}
@@ -0,0 +1,109 @@
+use std::sync::{
+    Arc, PoisonError, RwLock,
+    atomic::{AtomicU64, Ordering},
+};
+
+use crate::MonitorInfo;
+
+/// The last enumerated monitors, with the [`GENERATION`] they were enumerated in.
+static CACHE: RwLock<Option<(u64, Vec<MonitorInfo>)>> = RwLock::new(None);
+/// Bumped by every [`invalidate`], so an enumeration racing with a display change is
+/// not cached.
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+/// The monitors `Display` last reported, shared by callers on hot paths until the next
+/// [`invalidate`].
+static SNAPSHOT: RwLock<Option<(u64, Arc<Vec<MonitorInfo>>)>> = RwLock::new(None);
+
+/// Returns the cached monitors, filling the cache with `enumerate` if it is empty.
+pub(crate) fn cached(enumerate: impl FnOnce() -> Vec<MonitorInfo>) -> Vec<MonitorInfo> {
+    if let Some((_, monitors)) = CACHE
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+    {
+        return monitors.clone();
+    }
+
+    let generation = GENERATION.load(Ordering::SeqCst);
+    let monitors = enumerate();
+    let mut cache = CACHE.write().unwrap_or_else(PoisonError::into_inner);
+    if GENERATION.load(Ordering::SeqCst) == generation {
+        *cache = Some((generation, monitors.clone()));
+    }
+    monitors
+}
+
+/// Returns the monitors `read` reports, reusing them until the next [`invalidate`]
+/// instead of cloning the list on every call.
+pub(crate) fn snapshot(read: impl FnOnce() -> Vec<MonitorInfo>) -> Arc<Vec<MonitorInfo>> {
+    let generation = GENERATION.load(Ordering::SeqCst);
+    if let Some((snapshot_generation, monitors)) = SNAPSHOT
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+    {
+        if *snapshot_generation == generation {
+            return Arc::clone(monitors);
+        }
+    }
+
+    let monitors = Arc::new(read());
+    let mut snapshot = SNAPSHOT.write().unwrap_or_else(PoisonError::into_inner);
+    if GENERATION.load(Ordering::SeqCst) == generation {
+        *snapshot = Some((generation, Arc::clone(&monitors)));
+    }
+    monitors
+}
+
+/// Drops the cached monitors. Called by the backends when the display configuration
+/// changes, and by [`Display::refresh`](crate::Display::refresh).
+pub(crate) fn invalidate() {
+    let mut cache = CACHE.write().unwrap_or_else(PoisonError::into_inner);
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    *cache = None;
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::fixtures;
+
+    fn monitor(name: &str) -> MonitorInfo {
+        fixtures::monitor(name, 0.0, 0.0, 1920.0, 1080.0)
+    }
+
+    #[serial]
+    #[test]
+    fn test_cache_until_invalidated() {
+        invalidate();
+        assert_eq!(cached(|| vec![monitor("A")])[0].name, "A");
+        assert_eq!(cached(|| unreachable!())[0].name, "A");
+
+        invalidate();
+        assert_eq!(cached(|| vec![monitor("B")])[0].name, "B");
+
+        // A change during enumeration leaves the cache empty
+        invalidate();
+        let racing = cached(|| {
+            invalidate();
+            vec![monitor("C")]
+        });
+        assert_eq!(racing[0].name, "C");
+        assert_eq!(cached(|| vec![monitor("D")])[0].name, "D");
+        invalidate();
+    }
+
+    #[serial]
+    #[test]
+    fn test_snapshot_until_invalidated() {
+        invalidate();
+        let first = snapshot(|| vec![monitor("A")]);
+        assert!(Arc::ptr_eq(&first, &snapshot(|| unreachable!())));
+
+        invalidate();
+        assert_eq!(snapshot(|| vec![monitor("B")])[0].name, "B");
+        invalidate();
+    }
+}
@@ -0,0 +1,154 @@
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::Point;
+
+/// A mouse movement with its velocity and acceleration, see
+/// [`Listen::subscribe_motion`](crate::Listen::subscribe_motion).
+///
+/// Distances are in the units of [`Event::MouseMove`](crate::Event::MouseMove) deltas.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct MotionEvent {
+    /// The movement, as reported by `Event::MouseMove`.
+    pub delta: Point,
+    /// Average velocity over the window, per second.
+    pub velocity: Point,
+    /// The magnitude of `velocity`.
+    pub speed: f64,
+    /// How fast `speed` changed over the window, per second squared. Negative while the
+    /// mouse slows down.
+    pub acceleration: f64,
+}
+
+/// A movement remembered for the sliding window.
+#[derive(Debug)]
+struct Sample {
+    at: Instant,
+    delta: Point,
+    speed: f64,
+}
+
+/// Derives velocity and acceleration from the movements within a sliding window.
+#[derive(Debug)]
+pub(crate) struct Tracker {
+    window: Duration,
+    samples: VecDeque<Sample>,
+}
+
+impl Tracker {
+    pub(crate) fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    pub(crate) fn movement(&mut self, delta: Point, now: Instant) -> MotionEvent {
+        while self
+            .samples
+            .front()
+            .is_some_and(|sample| now.duration_since(sample.at) > self.window)
+        {
+            self.samples.pop_front();
+        }
+
+        // The oldest movement happened before its timestamp, so only the later ones
+        // count towards the distance covered since then
+        let (velocity, speed, acceleration) = match self.samples.front() {
+            Some(oldest) if now > oldest.at => {
+                let elapsed = now.duration_since(oldest.at).as_secs_f64();
+                let (x, y) = self
+                    .samples
+                    .iter()
+                    .skip(1)
+                    .fold((delta.x, delta.y), |(x, y), sample| {
+                        (x + sample.delta.x, y + sample.delta.y)
+                    });
+                let velocity = Point {
+                    x: x / elapsed,
+                    y: y / elapsed,
+                };
+                let speed = velocity.x.hypot(velocity.y);
+                (velocity, speed, (speed - oldest.speed) / elapsed)
+            }
+            _ => (Point { x: 0.0, y: 0.0 }, 0.0, 0.0),
+        };
+
+        self.samples.push_back(Sample {
+            at: now,
+            delta,
+            speed,
+        });
+        MotionEvent {
+            delta,
+            velocity,
+            speed,
+            acceleration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::point;
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_constant_movement() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Duration::from_millis(50));
+
+        // Nothing to compare the first movement with
+        assert_eq!(tracker.movement(point(10.0, 0.0), start).speed, 0.0);
+
+        for i in 1..=10 {
+            let motion = tracker.movement(point(10.0, 0.0), ms(start, i * 10));
+            assert_eq!(motion.delta, point(10.0, 0.0));
+            assert!((motion.velocity.x - 1000.0).abs() < 1e-6);
+            assert_eq!(motion.velocity.y, 0.0);
+            if i > 5 {
+                // The window holds constant speeds only
+                assert!(motion.acceleration.abs() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn test_acceleration_sign() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Duration::from_millis(100));
+
+        let mut motion = tracker.movement(point(0.0, 1.0), start);
+        for i in 1..=5 {
+            motion = tracker.movement(point(0.0, i as f64 * 10.0), ms(start, i * 10));
+        }
+        assert!(motion.velocity.y > 0.0);
+        assert!(motion.acceleration > 0.0);
+
+        for i in 6..=15 {
+            motion = tracker.movement(point(0.0, 1.0), ms(start, i * 10));
+        }
+        assert!(motion.acceleration < 0.0);
+    }
+
+    #[test]
+    fn test_idle_gap_resets_window() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Duration::from_millis(50));
+        tracker.movement(point(100.0, 0.0), start);
+        tracker.movement(point(100.0, 0.0), ms(start, 10));
+
+        let motion = tracker.movement(point(5.0, 0.0), ms(start, 1000));
+        assert_eq!(motion.speed, 0.0);
+    }
+}
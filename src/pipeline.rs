@@ -0,0 +1,444 @@
+use std::sync::{
+    Arc, PoisonError, RwLock,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    event::{Event, Point},
+    grab_release::{self, Blocker},
+    grab_scope, hotkey, remap, scroll,
+};
+
+/// What a [`Middleware`] decides for the event it processed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    /// Hands the (possibly modified) event to the next stage.
+    Continue,
+    /// Blocks the event; later stages and the grab filter do not see it.
+    Block,
+}
+
+/// Per-event state shared by the stages of the [`Pipeline`].
+#[derive(Debug)]
+pub struct PipelineContext {
+    injected: bool,
+    emitted: Vec<Event>,
+    /// Accumulated wheel scaling, used by backends that rewrite wheel events in place.
+    pub(crate) wheel_scale: Point,
+}
+
+impl PipelineContext {
+    fn new(injected: bool) -> Self {
+        Self {
+            injected,
+            emitted: Vec::new(),
+            wheel_scale: Point { x: 1.0, y: 1.0 },
+        }
+    }
+
+    /// Returns `true` if the event was injected by this crate's `Simulate`.
+    pub fn is_injected(&self) -> bool {
+        self.injected
+    }
+
+    /// Queues a synthesized event, injected once the pipeline has finished.
+    ///
+    /// Synthesized events carry the injection marker, so they travel through the pipeline
+    /// again with [`is_injected`](Self::is_injected) set.
+    pub fn emit(&mut self, event: Event) {
+        self.emitted.push(event);
+    }
+}
+
+/// A stage of the event [`Pipeline`].
+///
+/// Stages may inspect the event, modify it in place, block it, or synthesize new events
+/// through the context. They run inside the platform hook, so they must return quickly.
+pub trait Middleware: Send + Sync + 'static {
+    fn process(&self, event: &mut Event, ctx: &mut PipelineContext) -> Verdict;
+}
+
+impl<F> Middleware for F
+where
+    F: Fn(&mut Event, &mut PipelineContext) -> Verdict + Send + Sync + 'static,
+{
+    fn process(&self, event: &mut Event, ctx: &mut PipelineContext) -> Verdict {
+        self(event, ctx)
+    }
+}
+
+#[derive(Clone)]
+struct Stage {
+    id: u64,
+    order: i32,
+    middleware: Arc<dyn Middleware>,
+}
+
+/// The registered stages, sorted by `(order, id)`. Replaced wholesale on every change so
+/// the hook can take a cheap snapshot and run the stages without holding the lock.
+static STAGES: Lazy<RwLock<Arc<Vec<Stage>>>> = Lazy::new(|| {
+    let builtin: [(i32, Arc<dyn Middleware>); BUILTIN_STAGES] = [
+        (Pipeline::REMAP, Arc::new(remap_stage)),
+        (Pipeline::SCROLL, Arc::new(scroll_stage)),
+        (Pipeline::HOTKEY, Arc::new(hotkey_stage)),
+    ];
+    let stages = builtin
+        .into_iter()
+        .map(|(order, middleware)| Stage {
+            id: NEXT_ID.fetch_add(1, Ordering::SeqCst),
+            order,
+            middleware,
+        })
+        .collect();
+    RwLock::new(Arc::new(stages))
+});
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+const BUILTIN_STAGES: usize = 3;
+
+/// Set while stages other than the built-in ones are registered.
+static HAS_CUSTOM_STAGES: AtomicBool = AtomicBool::new(false);
+
+/// Ordered event middleware, run by the platform hook for every input event.
+///
+/// Stages run in ascending `order`; stages with the same order run in registration order.
+/// The built-in features are stages too, so custom middleware can be placed around them:
+/// key remapping runs at [`REMAP`](Self::REMAP), scroll transforms at
+/// [`SCROLL`](Self::SCROLL) and hotkeys at [`HOTKEY`](Self::HOTKEY).
+///
+/// After the pipeline, a blocked event is dropped; a modified event replaces the original;
+/// anything else goes on to the grab scopes and the grab filter. Listeners observe the
+/// original event before the pipeline runs. Requires `Core` to be running.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Event, Key, Pipeline, PipelineContext, Verdict};
+///
+/// // Swallow Insert everywhere, before remapping gets a chance to see it
+/// let handle = Pipeline::add(Pipeline::REMAP - 1, |event: &mut Event, _: &mut PipelineContext| {
+///     match event {
+///         Event::KeyDown { key: Key::Insert, .. } | Event::KeyUp { key: Key::Insert, .. } => {
+///             Verdict::Block
+///         }
+///         _ => Verdict::Continue,
+///     }
+/// });
+///
+/// // Later
+/// handle.remove();
+/// ```
+pub struct Pipeline;
+
+impl Pipeline {
+    /// Order of the built-in key remapping stage.
+    pub const REMAP: i32 = -200;
+    /// Order of the built-in scroll transform stage.
+    pub const SCROLL: i32 = -100;
+    /// Order of the built-in hotkey stage.
+    pub const HOTKEY: i32 = 100;
+
+    /// Adds a stage at the given order.
+    pub fn add<M: Middleware>(order: i32, middleware: M) -> MiddlewareHandle {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        update(|stages| {
+            let index = stages.partition_point(|stage| stage.order <= order);
+            stages.insert(
+                index,
+                Stage {
+                    id,
+                    order,
+                    middleware: Arc::new(middleware),
+                },
+            );
+        });
+        MiddlewareHandle { id }
+    }
+}
+
+/// A handle to a stage added with [`Pipeline::add`].
+pub struct MiddlewareHandle {
+    id: u64,
+}
+
+impl MiddlewareHandle {
+    /// Removes the stage from the pipeline. Releases it blocked are sent, so no
+    /// application keeps a key or button held.
+    pub fn remove(self) {
+        update(|stages| stages.retain(|stage| stage.id != self.id));
+        grab_release::release(Blocker::Stage(self.id));
+    }
+}
+
+fn update(f: impl FnOnce(&mut Vec<Stage>)) {
+    let mut guard = STAGES.write().unwrap_or_else(PoisonError::into_inner);
+    let mut stages = guard.as_ref().clone();
+    f(&mut stages);
+    HAS_CUSTOM_STAGES.store(stages.len() > BUILTIN_STAGES, Ordering::SeqCst);
+    *guard = Arc::new(stages);
+}
+
+/// The result of running an event through the pipeline.
+#[derive(Debug)]
+pub(crate) struct Outcome {
+    /// The stage that blocked the event, which must not reach other applications.
+    pub(crate) block: Option<Blocker>,
+    /// The event was modified and must be delivered in this form instead of the original,
+    /// which the first stage to modify it blocks.
+    pub(crate) replaced: Option<(Event, Blocker)>,
+    /// Events synthesized by the stages, to be injected by the backend.
+    pub(crate) emitted: Vec<Event>,
+    /// The decision of the grab scopes, which overrides the `Grab` flags when set: the
+    /// scope that blocks the event, or `None` to let it through.
+    pub(crate) grab: Option<Option<Blocker>>,
+    /// Accumulated wheel scaling applied by the built-in scroll stage.
+    #[cfg_attr(any(not(target_os = "macos"), feature = "mock"), allow(dead_code))]
+    pub(crate) wheel_scale: Point,
+}
+
+/// Returns `true` when no stage or grab scope would look at an event, so the hooks may
+/// skip both translating the event and running the pipeline.
+pub(crate) fn is_idle() -> bool {
+    !HAS_CUSTOM_STAGES.load(Ordering::Relaxed)
+        && !remap::is_active()
+        && !scroll::is_active()
+        && !hotkey::is_active()
+        && !grab_scope::is_active()
+}
+
+/// Runs an event through every stage. Called by the platform hooks.
+pub(crate) fn run(event: Event, injected: bool) -> Outcome {
+    let stages = STAGES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+
+    let mut ctx = PipelineContext::new(injected);
+    let mut current = event;
+    let mut block = None;
+    let mut modified_by = None;
+    for stage in stages.iter() {
+        let before = current;
+        if stage.middleware.process(&mut current, &mut ctx) == Verdict::Block {
+            block = Some(Blocker::Stage(stage.id));
+            break;
+        }
+        if modified_by.is_none() && current != before {
+            modified_by = Some(Blocker::Stage(stage.id));
+        }
+    }
+    // A stage that changed the wheel delta but not its raw value leaves the raw value
+    // stale, and `Simulate` would inject that instead
+    if let (
+        Event::MouseWheel {
+            delta: old,
+            raw: Some(old_raw),
+        },
+        Event::MouseWheel { delta, raw },
+    ) = (event, &mut current)
+        && *delta != old
+        && *raw == Some(old_raw)
+    {
+        *raw = None;
+    }
+
+    Outcome {
+        block,
+        replaced: match (block, modified_by) {
+            (None, Some(stage)) if current != event => Some((current, stage)),
+            _ => None,
+        },
+        emitted: ctx.emitted,
+        grab: match block {
+            Some(_) => None,
+            None => grab_scope::decide(&current, injected),
+        },
+        wheel_scale: ctx.wheel_scale,
+    }
+}
+
+fn remap_stage(event: &mut Event, ctx: &mut PipelineContext) -> Verdict {
+    // Injected events are never remapped again, which prevents feedback loops
+    if ctx.is_injected() {
+        return Verdict::Continue;
+    }
+    let remapped = remap::process(event, |key, down| {
+        ctx.emit(match down {
+            true => Event::KeyDown { key, code: None },
+            false => Event::KeyUp { key, code: None },
+        })
+    });
+    match remapped {
+        true => Verdict::Block,
+        false => Verdict::Continue,
+    }
+}
+
+fn scroll_stage(event: &mut Event, ctx: &mut PipelineContext) -> Verdict {
+    if let Event::MouseWheel { delta, raw } = event
+        && !ctx.is_injected()
+        && let Some(factors) = scroll::factors()
+    {
+        delta.x *= factors.x;
+        delta.y *= factors.y;
+        if let Some(raw) = raw {
+            raw.x *= factors.x;
+            raw.y *= factors.y;
+        }
+        ctx.wheel_scale.x *= factors.x;
+        ctx.wheel_scale.y *= factors.y;
+    }
+    Verdict::Continue
+}
+
+fn hotkey_stage(event: &mut Event, _: &mut PipelineContext) -> Verdict {
+    match hotkey::process(event) {
+        true => Verdict::Block,
+        false => Verdict::Continue,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{fixtures::key_down, key::Key};
+
+    #[serial]
+    #[test]
+    fn test_unmodified_event_passes_through() {
+        let outcome = run(key_down(Key::KeyA), false);
+        assert_eq!(outcome.block, None);
+        assert_eq!(outcome.replaced, None);
+        assert!(outcome.emitted.is_empty());
+    }
+
+    #[serial]
+    #[test]
+    fn test_stages_run_in_order() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let stage = |name: &'static str| {
+            let log = log.clone();
+            move |_: &mut Event, _: &mut PipelineContext| {
+                log.lock().unwrap().push(name);
+                Verdict::Continue
+            }
+        };
+
+        let late = Pipeline::add(10, stage("late"));
+        let early = Pipeline::add(-10, stage("early"));
+        let also_late = Pipeline::add(10, stage("also late"));
+
+        run(key_down(Key::KeyA), false);
+        assert_eq!(*log.lock().unwrap(), vec!["early", "late", "also late"]);
+
+        late.remove();
+        early.remove();
+        also_late.remove();
+
+        log.lock().unwrap().clear();
+        run(key_down(Key::KeyA), false);
+        assert!(log.lock().unwrap().is_empty());
+    }
+
+    #[serial]
+    #[test]
+    fn test_block_stops_later_stages() {
+        let reached = Arc::new(Mutex::new(false));
+        let flag = reached.clone();
+        let blocker = Pipeline::add(0, |_: &mut Event, _: &mut PipelineContext| Verdict::Block);
+        let after = Pipeline::add(1, move |_: &mut Event, _: &mut PipelineContext| {
+            *flag.lock().unwrap() = true;
+            Verdict::Continue
+        });
+
+        let outcome = run(key_down(Key::KeyA), false);
+        assert_eq!(outcome.block, Some(Blocker::Stage(blocker.id)));
+        assert_eq!(outcome.replaced, None);
+        assert!(!*reached.lock().unwrap());
+
+        blocker.remove();
+        after.remove();
+    }
+
+    #[serial]
+    #[test]
+    fn test_modified_event_replaces_original() {
+        let handle = Pipeline::add(0, |event: &mut Event, _: &mut PipelineContext| {
+            if let Event::MouseWheel { delta, .. } = event {
+                delta.y = -delta.y;
+            }
+            Verdict::Continue
+        });
+
+        let outcome = run(
+            Event::MouseWheel {
+                delta: Point { x: 0.0, y: 1.0 },
+                raw: None,
+            },
+            false,
+        );
+        assert_eq!(
+            outcome.replaced,
+            Some((
+                Event::MouseWheel {
+                    delta: Point { x: 0.0, y: -1.0 },
+                    raw: None,
+                },
+                Blocker::Stage(handle.id)
+            ))
+        );
+
+        // The raw value the middleware left alone no longer matches the delta
+        let outcome = run(
+            Event::MouseWheel {
+                delta: Point { x: 0.0, y: 1.0 },
+                raw: Some(Point { x: 0.0, y: 120.0 }),
+            },
+            false,
+        );
+        assert_eq!(
+            outcome.replaced.map(|(event, _)| event),
+            Some(Event::MouseWheel {
+                delta: Point { x: 0.0, y: -1.0 },
+                raw: None,
+            })
+        );
+
+        handle.remove();
+    }
+
+    #[serial]
+    #[test]
+    fn test_emit_and_injected_flag() {
+        let handle = Pipeline::add(0, |event: &mut Event, ctx: &mut PipelineContext| {
+            if !ctx.is_injected() {
+                ctx.emit(*event);
+            }
+            Verdict::Continue
+        });
+
+        let outcome = run(key_down(Key::KeyA), false);
+        assert_eq!(outcome.emitted, vec![key_down(Key::KeyA)]);
+        let outcome = run(key_down(Key::KeyA), true);
+        assert!(outcome.emitted.is_empty());
+
+        handle.remove();
+    }
+
+    #[serial]
+    #[test]
+    fn test_idle_until_custom_stage_added() {
+        assert!(is_idle());
+        let handle = Pipeline::add(0, |_: &mut Event, _: &mut PipelineContext| {
+            Verdict::Continue
+        });
+        assert!(!is_idle());
+        handle.remove();
+        assert!(is_idle());
+    }
+}
@@ -0,0 +1,53 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+// --- Global Runtime States ---
+
+/// Indicates if the core engine is currently active.
+pub static IS_CORE_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Whether the core accepts the events of the accessibility service, cleared by
+/// `Core::stop`.
+pub static IS_INSTALLED: AtomicBool = AtomicBool::new(false);
+/// Whether the accessibility service is connected, as reported through JNI.
+pub static IS_SERVICE_CONNECTED: AtomicBool = AtomicBool::new(false);
+/// Indicates if the input listener is active.
+pub static IS_LISTEN_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Indicates if the input grabber is active.
+pub static IS_GRAB_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Reported by `GrabFlags`; the service filters the keys of every window alike.
+pub static GRAB_EXCLUDE_OWN_WINDOWS: AtomicBool = AtomicBool::new(false);
+/// Lets injected events through the grabber.
+pub static GRAB_PASS_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Withholds injected events from subscribers.
+pub static LISTEN_IGNORE_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Reported by `ListenFlags`; keys are always identified by their scan codes.
+pub static SCANCODE_MODE: AtomicBool = AtomicBool::new(false);
+/// Set while `Grab::block_input` consumes all key events that were not injected.
+pub static IS_INPUT_BLOCKED: AtomicBool = AtomicBool::new(false);
+
+// --- Listen Flags: Define which events to monitor ---
+
+pub static LISTEN_FLAG: AtomicU32 = AtomicU32::new(0);
+pub const LISTEN_MOUSE_MOVE: u32 = 1 << 0;
+pub const LISTEN_MOUSE_BUTTON: u32 = 1 << 1;
+pub const LISTEN_MOUSE_WHEEL: u32 = 1 << 2;
+pub const LISTEN_KEYBOARD: u32 = 1 << 3;
+pub const LISTENS_ALL: u32 =
+    LISTEN_MOUSE_MOVE | LISTEN_MOUSE_BUTTON | LISTEN_MOUSE_WHEEL | LISTEN_KEYBOARD;
+
+// --- Grab Flags: Define which events to intercept/block ---
+
+pub static GRAB_FLAG: AtomicU32 = AtomicU32::new(0);
+pub const GRAB_MOUSE_MOVE: u32 = 1 << 0;
+pub const GRAB_MOUSE_BUTTON: u32 = 1 << 1;
+pub const GRAB_MOUSE_WHEEL: u32 = 1 << 2;
+pub const GRAB_KEYBOARD: u32 = 1 << 3;
+pub const GRAB_ALL: u32 = GRAB_MOUSE_MOVE | GRAB_MOUSE_BUTTON | GRAB_MOUSE_WHEEL | GRAB_KEYBOARD;
+
+/// Sets or clears `bit` in `atomic`.
+pub fn update_state(atomic: &AtomicU32, bit: u32, enable: bool) {
+    if enable {
+        atomic.fetch_or(bit, Ordering::SeqCst);
+    } else {
+        atomic.fetch_and(!bit, Ordering::SeqCst);
+    }
+}
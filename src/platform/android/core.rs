@@ -0,0 +1,173 @@
+use std::{
+    sync::{Condvar, Mutex, PoisonError, atomic::Ordering},
+    time::Duration,
+};
+
+use crate::{
+    CoreError, Event, KeyCode, TapLocation, ThreadPriority,
+    grab_release::{self, Blocker},
+    pipeline,
+    platform::{
+        CoreImpl, HotkeyImpl, PlatformCore, PlatformGrab, PlatformHotkeys, PlatformListen,
+        PlatformSimulate, SimulateImpl,
+        android::{
+            common::{IS_CORE_RUNNING, IS_INPUT_BLOCKED, IS_INSTALLED, IS_SERVICE_CONNECTED},
+            hotkey,
+            keycode::code_to_key,
+        },
+    },
+    sequence::{self, EventSource},
+    state,
+};
+
+/// Guards [`IS_INSTALLED`] for [`STOPPED`].
+static LOOP: Mutex<()> = Mutex::new(());
+/// Wakes `start` once the core is stopped.
+static STOPPED: Condvar = Condvar::new();
+
+impl CoreImpl for PlatformCore {
+    /// Accepts the events of the accessibility service and blocks until
+    /// [`Core::stop`](crate::Core::stop).
+    fn start() -> Result<(), CoreError> {
+        Self::attach()?;
+
+        let mut guard = LOOP.lock().unwrap_or_else(PoisonError::into_inner);
+        while IS_INSTALLED.load(Ordering::SeqCst) {
+            guard = STOPPED.wait(guard).unwrap_or_else(PoisonError::into_inner);
+        }
+        Ok(())
+    }
+
+    /// Accepts the events of the accessibility service and returns. They are handled on
+    /// the thread the service calls in on, so there is no loop to drive.
+    ///
+    /// The service may connect later, once the user enables it; until then
+    /// [`Core::state`](crate::Core::state) reports the permission as missing.
+    fn attach() -> Result<(), CoreError> {
+        // Ensure only one instance is running
+        if IS_CORE_RUNNING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(CoreError::AlreadyRunning);
+        }
+
+        let guard = LOOP.lock().unwrap_or_else(PoisonError::into_inner);
+        IS_INSTALLED.store(true, Ordering::SeqCst);
+        drop(guard);
+        PlatformHotkeys::update();
+        Ok(())
+    }
+
+    /// Attaches on first use; the service delivers its events on its own thread.
+    fn poll(_timeout: Duration) -> Result<(), CoreError> {
+        if !IS_INSTALLED.load(Ordering::SeqCst) {
+            Self::attach()?;
+        }
+        Ok(())
+    }
+
+    fn is_runing() -> bool {
+        IS_CORE_RUNNING.load(Ordering::SeqCst)
+    }
+
+    fn pause() {
+        IS_CORE_RUNNING.store(false, Ordering::SeqCst);
+    }
+
+    fn resume() {
+        IS_CORE_RUNNING.store(true, Ordering::SeqCst);
+    }
+
+    fn stop() {
+        Self::pause();
+
+        let guard = LOOP.lock().unwrap_or_else(PoisonError::into_inner);
+        IS_INSTALLED.store(false, Ordering::SeqCst);
+        STOPPED.notify_all();
+        drop(guard);
+        PlatformHotkeys::update();
+    }
+
+    /// The accessibility service always filters key events, so this has no effect.
+    fn listen_only(_enable: bool) {}
+
+    /// There are no event taps on Android, so this has no effect.
+    fn tap_location(_location: TapLocation) {}
+
+    /// `start` runs no loop, so this has no effect.
+    fn thread_priority(_priority: ThreadPriority) {}
+
+    fn hooks_installed() -> bool {
+        IS_INSTALLED.load(Ordering::SeqCst)
+    }
+
+    fn tap_enabled() -> Option<bool> {
+        None
+    }
+
+    /// Granted while the user has the accessibility service enabled.
+    fn has_permission(_grab: bool) -> bool {
+        IS_SERVICE_CONNECTED.load(Ordering::SeqCst)
+    }
+}
+
+impl PlatformCore {
+    /// Handles a key event the accessibility service filters, identified by its Linux
+    /// input event code. Returns `true` if the service must consume the event.
+    #[cfg_attr(not(feature = "android-jni"), allow(dead_code))]
+    pub(crate) fn handle_key(down: bool, code: KeyCode, injected: bool) -> bool {
+        let key = code_to_key(code);
+        if !IS_INSTALLED.load(Ordering::Relaxed) {
+            return hotkey::handle(down, key);
+        }
+        if !IS_CORE_RUNNING.load(Ordering::Relaxed) {
+            return false;
+        }
+        state::observed();
+
+        // A hard block consumes physical input before anything sees it
+        if IS_INPUT_BLOCKED.load(Ordering::Relaxed) && !injected {
+            return true;
+        }
+
+        let code = Some(code);
+        let event = if down {
+            Event::KeyDown { key, code }
+        } else {
+            Event::KeyUp { key, code }
+        };
+        sequence::with_origin(EventSource::Hook, Duration::ZERO, || {
+            PlatformListen::handle(event, injected)
+        });
+        let blocker = Self::blocks_key(event, injected);
+        // Remember the presses the applications saw, and who blocks their releases
+        grab_release::track(&event, blocker);
+        blocker.is_some()
+    }
+
+    /// Runs a key event through the middleware pipeline and the grab filter. Returns
+    /// what blocks it if it must not reach other applications.
+    fn blocks_key(event: Event, injected: bool) -> Option<Blocker> {
+        let grabs = || PlatformGrab::should_block_key(injected).then_some(Blocker::Grab);
+        if pipeline::is_idle() {
+            return grabs();
+        }
+
+        // Run the middleware pipeline (remapping, hotkeys, ...)
+        let outcome = pipeline::run(event, injected);
+        for emitted in outcome.emitted {
+            PlatformSimulate::simulate(emitted);
+        }
+        if let Some(stage) = outcome.block {
+            return Some(stage);
+        }
+        if let Some((replaced, stage)) = outcome.replaced {
+            PlatformSimulate::simulate(replaced);
+            return Some(stage);
+        }
+
+        // A grab scope's decision overrides the `Grab` flags
+        outcome.grab.unwrap_or_else(grabs)
+    }
+}
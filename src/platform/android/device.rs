@@ -0,0 +1,11 @@
+use crate::{
+    device::DeviceInfo,
+    platform::{DevicesImpl, PlatformDevices},
+};
+
+impl DevicesImpl for PlatformDevices {
+    /// The accessibility service does not report devices, so the list is empty.
+    fn list() -> Vec<DeviceInfo> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::{PoisonError, RwLock};
+
+use crate::{
+    MonitorInfo, Point, monitors,
+    platform::{CursorImage, CursorPosition, DisplayImpl, PlatformDisplay},
+};
+
+/// Size in pixels and density of the default display, as reported by the bridge.
+static METRICS: RwLock<Option<((f64, f64), f64)>> = RwLock::new(None);
+
+/// Records the metrics of the default display. Until the bridge reports them there is
+/// no monitor.
+#[cfg_attr(not(feature = "android-jni"), allow(dead_code))]
+pub(crate) fn set_metrics(width: f64, height: f64, density: f64) {
+    *METRICS.write().unwrap_or_else(PoisonError::into_inner) = Some(((width, height), density));
+    monitors::invalidate();
+}
+
+/// Coordinates are physical pixels; the scale factor is `DisplayMetrics.density`, which
+/// is 1.0 at 160 dpi. Native code cannot read the pointer position.
+impl DisplayImpl for PlatformDisplay {
+    fn get_scale_factor() -> f64 {
+        Self::get_primary_monitor().map_or(1.0, |m| m.scale_factor)
+    }
+
+    fn get_cursor_position() -> Option<(f64, f64)> {
+        None
+    }
+
+    /// Android does not let applications warp the pointer, so this has no effect.
+    fn set_cursor_position(_x: f64, _y: f64) {}
+
+    fn get_cursor() -> Option<CursorPosition> {
+        None
+    }
+
+    fn get_cursor_image() -> Option<CursorImage> {
+        None
+    }
+
+    fn get_primary_screen_size() -> (f64, f64) {
+        Self::get_primary_monitor().map_or((0.0, 0.0), |m| m.size)
+    }
+
+    fn get_virtual_screen_size() -> (f64, f64) {
+        Self::get_primary_screen_size()
+    }
+
+    fn get_virtual_screen_bounds() -> (f64, f64, f64, f64) {
+        let (w, h) = Self::get_primary_screen_size();
+        (0.0, 0.0, w, h)
+    }
+
+    fn get_available_monitors() -> Vec<MonitorInfo> {
+        monitors::cached(|| {
+            let metrics = *METRICS.read().unwrap_or_else(PoisonError::into_inner);
+            metrics
+                .map(|(size, density)| MonitorInfo {
+                    name: "default".to_string(),
+                    is_primary: true,
+                    offset: (0.0, 0.0),
+                    size,
+                    scale_factor: density,
+                })
+                .into_iter()
+                .collect()
+        })
+    }
+
+    fn get_primary_monitor() -> Option<MonitorInfo> {
+        Self::get_available_monitors().into_iter().next()
+    }
+
+    fn get_current_monitor() -> Option<MonitorInfo> {
+        Self::get_primary_monitor()
+    }
+
+    fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo> {
+        Self::get_primary_monitor().filter(|m| m.contains(Point { x, y }))
+    }
+}
@@ -0,0 +1,15 @@
+use crate::{
+    ForegroundApp,
+    platform::{ForegroundImpl, PlatformForeground},
+};
+
+impl ForegroundImpl for PlatformForeground {
+    /// Android hides other applications from native code, so there is none.
+    fn query() -> Option<ForegroundApp> {
+        None
+    }
+
+    fn is_secure_input() -> bool {
+        false
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::atomic::Ordering;
+
+use crate::{
+    GrabFlags, StateError, foreground,
+    platform::{
+        GrabImpl, PlatformGrab,
+        android::common::{
+            GRAB_ALL, GRAB_EXCLUDE_OWN_WINDOWS, GRAB_FLAG, GRAB_KEYBOARD, GRAB_MOUSE_BUTTON,
+            GRAB_MOUSE_MOVE, GRAB_MOUSE_WHEEL, GRAB_PASS_INJECTED, IS_GRAB_RUNNING,
+            IS_INPUT_BLOCKED, update_state,
+        },
+    },
+};
+
+impl GrabImpl for PlatformGrab {
+    fn start() -> bool {
+        if IS_GRAB_RUNNING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return false;
+        }
+
+        GRAB_FLAG.fetch_or(GRAB_ALL, Ordering::SeqCst);
+        true
+    }
+
+    fn is_runing() -> bool {
+        IS_GRAB_RUNNING.load(Ordering::SeqCst)
+    }
+
+    fn pause() {
+        IS_GRAB_RUNNING.store(false, Ordering::SeqCst);
+    }
+
+    fn resume() {
+        IS_GRAB_RUNNING.store(true, Ordering::SeqCst);
+    }
+
+    fn stop() {
+        Self::pause();
+        GRAB_FLAG.store(0, Ordering::SeqCst);
+    }
+
+    /// Pointer input never reaches the accessibility service, so this only changes the
+    /// reported flags.
+    fn mouse_move(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_MOUSE_MOVE, enable);
+    }
+
+    /// See [`mouse_move`](Self::mouse_move).
+    fn mouse_wheel(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_MOUSE_WHEEL, enable);
+    }
+
+    /// See [`mouse_move`](Self::mouse_move).
+    fn mouse_button(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_MOUSE_BUTTON, enable);
+    }
+
+    fn keyboard(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_KEYBOARD, enable);
+    }
+
+    /// The service filters the keys of every window alike, so this only changes the
+    /// reported flags.
+    fn exclude_own_windows(enable: bool) {
+        GRAB_EXCLUDE_OWN_WINDOWS.store(enable, Ordering::SeqCst);
+    }
+
+    fn pass_injected(enable: bool) {
+        GRAB_PASS_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
+    fn flags() -> GrabFlags {
+        let state = GRAB_FLAG.load(Ordering::SeqCst);
+        GrabFlags {
+            running: Self::is_runing(),
+            mouse_move: state & GRAB_MOUSE_MOVE != 0,
+            mouse_button: state & GRAB_MOUSE_BUTTON != 0,
+            mouse_wheel: state & GRAB_MOUSE_WHEEL != 0,
+            keyboard: state & GRAB_KEYBOARD != 0,
+            exclude_own_windows: GRAB_EXCLUDE_OWN_WINDOWS.load(Ordering::SeqCst),
+            pass_injected: GRAB_PASS_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Consumes the key events of the service that were not injected. Pointer input and
+    /// keys the service does not filter, such as power, still get through.
+    fn block_input(block: bool) -> Result<(), StateError> {
+        IS_INPUT_BLOCKED.store(block, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+impl PlatformGrab {
+    /// Returns `true` if a key event must be consumed by the service.
+    #[inline]
+    pub(crate) fn should_block_key(injected: bool) -> bool {
+        if !IS_GRAB_RUNNING.load(Ordering::Relaxed) {
+            return false;
+        }
+        if injected && GRAB_PASS_INJECTED.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        GRAB_FLAG.load(Ordering::Relaxed) & GRAB_KEYBOARD != 0 && foreground::grab_allowed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    fn set_state(running: bool, flags: u32) {
+        IS_GRAB_RUNNING.store(running, Ordering::SeqCst);
+        GRAB_FLAG.store(flags, Ordering::SeqCst);
+    }
+
+    #[test]
+    #[serial]
+    fn test_should_block_key() {
+        set_state(false, GRAB_ALL);
+        assert!(!PlatformGrab::should_block_key(false));
+
+        set_state(true, GRAB_MOUSE_BUTTON);
+        assert!(!PlatformGrab::should_block_key(false));
+
+        set_state(true, GRAB_KEYBOARD);
+        assert!(PlatformGrab::should_block_key(false));
+        assert!(PlatformGrab::should_block_key(true));
+        GRAB_PASS_INJECTED.store(true, Ordering::SeqCst);
+        assert!(!PlatformGrab::should_block_key(true));
+        assert!(PlatformGrab::should_block_key(false));
+
+        GRAB_PASS_INJECTED.store(false, Ordering::SeqCst);
+        set_state(false, 0);
+    }
+}
@@ -0,0 +1,6 @@
+use crate::platform::{HidImpl, PlatformHid};
+
+impl HidImpl for PlatformHid {
+    /// HID reports do not reach the accessibility service, so this has no effect.
+    fn update() {}
+}
@@ -0,0 +1,67 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::{
+    Key, Modifiers, hotkey,
+    platform::{HotkeyImpl, PlatformHotkeys},
+    shortcut::Shortcut,
+};
+
+/// The hotkeys to match while the core is stopped, with the keyboard state they are
+/// matched against.
+static STATE: Mutex<State> = Mutex::new(State {
+    hotkeys: Vec::new(),
+    modifiers: Modifiers::NONE,
+    held: Vec::new(),
+});
+
+struct State {
+    hotkeys: Vec<(u64, Shortcut)>,
+    modifiers: Modifiers,
+    held: Vec<Key>,
+}
+
+impl HotkeyImpl for PlatformHotkeys {
+    /// Android has no hotkey service, but the accessibility service keeps forwarding
+    /// keys while the core is stopped, so the hotkeys are matched against those.
+    fn update() {
+        let hotkeys = hotkey::native();
+        STATE.lock().unwrap_or_else(PoisonError::into_inner).hotkeys = hotkeys;
+    }
+}
+
+/// Tracks a key event of the service and fires the hotkey a press completes. Returns
+/// `true` if one fired, in which case the press is consumed as `RegisterHotKey` does.
+pub(crate) fn handle(down: bool, key: Key) -> bool {
+    let mut state = STATE.lock().unwrap_or_else(PoisonError::into_inner);
+    let modifier = Modifiers::from_key(key);
+    if !down {
+        state.held.retain(|&held| held != key);
+        if let Some(modifier) = modifier {
+            state.modifiers.remove(modifier);
+        }
+        return false;
+    }
+    if let Some(modifier) = modifier {
+        state.modifiers.insert(modifier);
+    }
+    // Auto-repeat does not fire a hotkey again
+    if state.held.contains(&key) {
+        return false;
+    }
+    state.held.push(key);
+
+    let id = state
+        .hotkeys
+        .iter()
+        .find(|(_, shortcut)| shortcut.key == key && shortcut.modifiers == state.modifiers)
+        .map(|&(id, _)| id);
+    // Released first, so the callback may change the hotkeys
+    drop(state);
+    match id {
+        Some(id) => {
+            hotkey::fire(id);
+            true
+        }
+        None => false,
+    }
+}
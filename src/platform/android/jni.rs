@@ -0,0 +1,84 @@
+//! JNI entry points for the Java side of the accessibility service bridge.
+//!
+//! The crate has no JNI dependency; the entry points take primitives only. The
+//! application declares them in a class `rs.rawinput.RawInputBridge`, loads the library
+//! built from this crate and calls them from its `AccessibilityService`, whose
+//! configuration needs `android:canRequestFilterKeyEvents="true"` and
+//! `FLAG_REQUEST_FILTER_KEY_EVENTS`:
+//!
+//! ```java
+//! package rs.rawinput;
+//!
+//! public final class RawInputBridge {
+//!     public static native void nativeServiceConnected(boolean connected);
+//!     public static native boolean nativeKeyEvent(boolean down, int scanCode, boolean injected);
+//!     public static native void nativeDisplayChanged(int width, int height, float density);
+//! }
+//!
+//! // In the service:
+//! protected void onServiceConnected() {
+//!     RawInputBridge.nativeServiceConnected(true);
+//! }
+//!
+//! public boolean onUnbind(Intent intent) {
+//!     RawInputBridge.nativeServiceConnected(false);
+//!     return false;
+//! }
+//!
+//! protected boolean onKeyEvent(KeyEvent event) {
+//!     if (event.getAction() == KeyEvent.ACTION_MULTIPLE) return false;
+//!     InputDevice device = event.getDevice();
+//!     boolean injected = device != null && "raw-input".equals(device.getName());
+//!     return RawInputBridge.nativeKeyEvent(
+//!         event.getAction() == KeyEvent.ACTION_DOWN, event.getScanCode(), injected);
+//! }
+//! ```
+//!
+//! `nativeDisplayChanged` reports `DisplayMetrics.widthPixels`, `heightPixels` and
+//! `density` of the default display, at start and on every configuration change.
+
+use std::{ffi::c_void, sync::atomic::Ordering};
+
+use crate::platform::{
+    PlatformCore,
+    android::{common::IS_SERVICE_CONNECTED, display},
+};
+
+type JBoolean = u8;
+type JInt = i32;
+type JFloat = f32;
+
+/// `RawInputBridge.nativeServiceConnected(boolean connected)`
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_rs_rawinput_RawInputBridge_nativeServiceConnected(
+    _env: *mut c_void,
+    _class: *mut c_void,
+    connected: JBoolean,
+) {
+    IS_SERVICE_CONNECTED.store(connected != 0, Ordering::SeqCst);
+}
+
+/// `RawInputBridge.nativeKeyEvent(boolean down, int scanCode, boolean injected)`.
+/// Returns `true` if the service must consume the event.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_rs_rawinput_RawInputBridge_nativeKeyEvent(
+    _env: *mut c_void,
+    _class: *mut c_void,
+    down: JBoolean,
+    scan_code: JInt,
+    injected: JBoolean,
+) -> JBoolean {
+    PlatformCore::handle_key(down != 0, scan_code as u32, injected != 0) as JBoolean
+}
+
+/// `RawInputBridge.nativeDisplayChanged(int width, int height, float density)`
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_rs_rawinput_RawInputBridge_nativeDisplayChanged(
+    _env: *mut c_void,
+    _class: *mut c_void,
+    width: JInt,
+    height: JInt,
+    density: JFloat,
+) {
+    display::set_metrics(width as f64, height as f64, density as f64);
+}
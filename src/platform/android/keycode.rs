@@ -0,0 +1,159 @@
+use crate::key::{Key, KeyCode, RawKey};
+
+macro_rules! keymap {
+    ($($key:ident => $code:expr),+ $(,)?) => {
+        pub fn key_to_code(key: Key) -> Option<KeyCode> {
+            match key {
+                $(Key::$key => Some($code),)+
+                Key::Raw(RawKey(code)) => Some(code),
+                _ => None,
+            }
+        }
+
+        #[cfg_attr(not(feature = "listen"), allow(dead_code))]
+        pub fn code_to_key(scancode: KeyCode) -> Key {
+            match scancode {
+                $($code => Key::$key,)+
+                _ => Key::Raw(RawKey(scancode)),
+            }
+        }
+    };
+}
+
+// Linux input event codes (`KEY_*` of linux/input-event-codes.h), which are the scan
+// codes Android reports in `KeyEvent.getScanCode()`
+keymap! {
+    // --- Writing System Keys ---
+    Backquote => 41,
+    Backslash => 43,
+    BracketLeft => 26,
+    BracketRight => 27,
+    Comma => 51,
+    Digit1 => 2,
+    Digit2 => 3,
+    Digit3 => 4,
+    Digit4 => 5,
+    Digit5 => 6,
+    Digit6 => 7,
+    Digit7 => 8,
+    Digit8 => 9,
+    Digit9 => 10,
+    Digit0 => 11,
+    Equal => 13,
+    IntlBackslash => 86,
+    IntlRo => 89,
+    IntlYen => 124,
+    KeyQ => 16,
+    KeyW => 17,
+    KeyE => 18,
+    KeyR => 19,
+    KeyT => 20,
+    KeyY => 21,
+    KeyU => 22,
+    KeyI => 23,
+    KeyO => 24,
+    KeyP => 25,
+    KeyA => 30,
+    KeyS => 31,
+    KeyD => 32,
+    KeyF => 33,
+    KeyG => 34,
+    KeyH => 35,
+    KeyJ => 36,
+    KeyK => 37,
+    KeyL => 38,
+    KeyZ => 44,
+    KeyX => 45,
+    KeyC => 46,
+    KeyV => 47,
+    KeyB => 48,
+    KeyN => 49,
+    KeyM => 50,
+    Minus => 12,
+    Period => 52,
+    Quote => 40,
+    Semicolon => 39,
+    Slash => 53,
+
+    // --- Functional Keys ---
+    AltLeft => 56,
+    AltRight => 100,
+    Backspace => 14,
+    CapsLock => 58,
+    ContextMenu => 127,
+    ControlLeft => 29,
+    ControlRight => 97,
+    Enter => 28,
+    MetaLeft => 125,
+    MetaRight => 126,
+    ShiftLeft => 42,
+    ShiftRight => 54,
+    Space => 57,
+    Tab => 15,
+    Convert => 92,
+    NonConvert => 94,
+
+    // --- Control Pad Section ---
+    Delete => 111,
+    End => 107,
+    Help => 138,
+    Home => 102,
+    Insert => 110,
+    PageDown => 109,
+    PageUp => 104,
+
+    // --- Arrow Pad Section ---
+    ArrowDown => 108,
+    ArrowLeft => 105,
+    ArrowRight => 106,
+    ArrowUp => 103,
+
+    // --- Numpad Section ---
+    NumLock => 69,
+    Numpad0 => 82,
+    Numpad1 => 79,
+    Numpad2 => 80,
+    Numpad3 => 81,
+    Numpad4 => 75,
+    Numpad5 => 76,
+    Numpad6 => 77,
+    Numpad7 => 71,
+    Numpad8 => 72,
+    Numpad9 => 73,
+    NumpadAdd => 78,
+    NumpadDecimal => 83,
+    NumpadDivide => 98,
+    NumpadEnter => 96,
+    NumpadMultiply => 55,
+    NumpadSubtract => 74,
+
+    // --- Function Section ---
+    Escape => 1,
+    F1 => 59,
+    F2 => 60,
+    F3 => 61,
+    F4 => 62,
+    F5 => 63,
+    F6 => 64,
+    F7 => 65,
+    F8 => 66,
+    F9 => 67,
+    F10 => 68,
+    F11 => 87,
+    F12 => 88,
+    F13 => 183,
+    F14 => 184,
+    F15 => 185,
+    F16 => 186,
+    F17 => 187,
+    F18 => 188,
+    F19 => 189,
+    F20 => 190,
+    F21 => 191,
+    F22 => 192,
+    F23 => 193,
+    F24 => 194,
+    PrintScreen => 99,
+    ScrollLock => 70,
+    Pause => 119,
+}
@@ -0,0 +1,35 @@
+//! Android backend.
+//!
+//! `Simulate` writes to a uinput device named [`simulate::DEVICE_NAME`], which needs a
+//! rooted device. Android lets no application observe input globally, so `Listen` and
+//! `Grab` only see the key events an AccessibilityService with
+//! `FLAG_REQUEST_FILTER_KEY_EVENTS` forwards through the JNI entry points of the
+//! `android-jni` feature, see [`jni`]. Pointer input never reaches such a service.
+#[cfg(feature = "listen")]
+mod common;
+#[cfg(feature = "listen")]
+pub(crate) mod core;
+#[cfg(feature = "listen")]
+pub(crate) mod device;
+pub(crate) mod display;
+#[cfg(feature = "listen")]
+pub(crate) mod foreground;
+#[cfg(feature = "listen")]
+pub(crate) mod grab;
+#[cfg(feature = "listen")]
+pub(crate) mod hid;
+#[cfg(feature = "listen")]
+pub(crate) mod hotkey;
+#[cfg(feature = "android-jni")]
+pub(crate) mod jni;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) mod keycode;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) mod layout;
+#[cfg(feature = "listen")]
+pub(crate) mod listen;
+pub(crate) mod mouse;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) mod poll;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) mod simulate;
@@ -0,0 +1,31 @@
+use crate::{
+    OsError, Point,
+    platform::{MouseImpl, PlatformMouse},
+};
+
+/// The pointer settings belong to the system settings, which applications cannot write.
+impl MouseImpl for PlatformMouse {
+    fn get_speed() -> Option<f64> {
+        None
+    }
+
+    fn set_speed(_speed: f64) -> Result<(), OsError> {
+        Err(OsError {
+            code: libc::EOPNOTSUPP,
+        })
+    }
+
+    fn get_acceleration() -> Option<bool> {
+        None
+    }
+
+    fn set_acceleration(_enable: bool) -> Result<(), OsError> {
+        Err(OsError {
+            code: libc::EOPNOTSUPP,
+        })
+    }
+
+    fn get_scroll_lines() -> Option<Point> {
+        None
+    }
+}
@@ -0,0 +1,19 @@
+#[cfg(feature = "listen")]
+use crate::MouseButton;
+use crate::{
+    Key, KeyCode,
+    platform::{PlatformPoll, PollImpl},
+};
+
+/// Android does not expose the input state to applications, so the polling fallback
+/// sees nothing pressed.
+impl PollImpl for PlatformPoll {
+    fn pressed_keys() -> Vec<(Key, KeyCode)> {
+        Vec::new()
+    }
+
+    #[cfg(feature = "listen")]
+    fn pressed_buttons() -> Vec<MouseButton> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,216 @@
+use std::{
+    ffi::c_ulong,
+    fs::{File, OpenOptions},
+    io::Write,
+    mem,
+    os::fd::AsRawFd,
+    slice,
+    sync::{Mutex, OnceLock, PoisonError},
+};
+
+use crate::{
+    Key, Modifiers, MouseButton,
+    event::Event,
+    platform::{
+        DisplayImpl, KeyboardImpl, PlatformDisplay, PlatformKeyboard, PlatformSimulate,
+        SimulateImpl, android::keycode::key_to_code,
+    },
+};
+
+/// The name of the uinput device, which the bridge compares `InputDevice.getName()`
+/// against to tell injected key events apart.
+pub const DEVICE_NAME: &str = "raw-input";
+
+// linux/uinput.h
+const UI_SET_EVBIT: c_ulong = 0x4004_5564;
+const UI_SET_KEYBIT: c_ulong = 0x4004_5565;
+const UI_SET_RELBIT: c_ulong = 0x4004_5566;
+const UI_DEV_CREATE: c_ulong = 0x5501;
+
+// linux/input-event-codes.h
+const EV_SYN: u16 = 0x00;
+const EV_KEY: u16 = 0x01;
+const EV_REL: u16 = 0x02;
+const SYN_REPORT: u16 = 0;
+const REL_X: u16 = 0x00;
+const REL_Y: u16 = 0x01;
+const REL_HWHEEL: u16 = 0x06;
+const REL_WHEEL: u16 = 0x08;
+const BTN_LEFT: u16 = 0x110;
+const BTN_RIGHT: u16 = 0x111;
+const BTN_MIDDLE: u16 = 0x112;
+const BTN_SIDE: u16 = 0x113;
+const BTN_EXTRA: u16 = 0x114;
+const BUS_VIRTUAL: u16 = 0x06;
+
+/// Keyboard keys the device declares; the codes above are buttons.
+const KEY_CODES: std::ops::Range<u16> = 1..0x100;
+
+/// The uinput device, created with the first simulated event. `None` if `/dev/uinput`
+/// cannot be opened, which needs root.
+static DEVICE: OnceLock<Option<Mutex<File>>> = OnceLock::new();
+
+/// Events go through a virtual uinput device, so Android treats them as hardware input.
+impl SimulateImpl for PlatformSimulate {
+    fn simulate(event: Event) {
+        match event {
+            Event::MouseMove { delta, .. } => Self::mouse_move(delta.x, delta.y),
+            Event::MouseMoveAbsolute { position } => Self::mouse_move_to(position.x, position.y),
+            Event::MouseWheel { delta, .. } => Self::mouse_wheel(delta.x, delta.y),
+            Event::MouseDown { button, .. } => Self::mouse_button(button, true),
+            Event::MouseUp { button, .. } => Self::mouse_button(button, false),
+            Event::KeyDown { key, .. } => Self::keyboard(key, true),
+            Event::KeyUp { key, .. } => Self::keyboard(key, false),
+            Event::FocusChanged { .. } | Event::ModifiersChanged { .. } => {}
+        }
+    }
+
+    fn mouse_move(delta_x: f64, delta_y: f64) {
+        emit(&[
+            (EV_REL, REL_X, delta_x as i32),
+            (EV_REL, REL_Y, delta_y as i32),
+        ]);
+    }
+
+    /// Native code cannot read or set the pointer position, so this pushes the pointer
+    /// into the top left corner and moves it from there. Pointer acceleration can make
+    /// the result inexact.
+    fn mouse_move_to(x: f64, y: f64) {
+        let scale_factor = PlatformDisplay::get_scale_factor();
+        let (width, height) = PlatformDisplay::get_primary_screen_size();
+        Self::mouse_move(-(width.max(4096.0)), -(height.max(4096.0)));
+        Self::mouse_move(x * scale_factor, y * scale_factor);
+    }
+
+    /// Scrolls by notches, positive up and right.
+    fn mouse_wheel(delta_x: f64, delta_y: f64) {
+        emit(&[
+            (EV_REL, REL_WHEEL, delta_y as i32),
+            (EV_REL, REL_HWHEEL, delta_x as i32),
+        ]);
+    }
+
+    fn mouse_button(button: MouseButton, down: bool) {
+        let code = match button {
+            MouseButton::Left => BTN_LEFT,
+            MouseButton::Right => BTN_RIGHT,
+            MouseButton::Middle => BTN_MIDDLE,
+            MouseButton::Back => BTN_SIDE,
+            MouseButton::Forward => BTN_EXTRA,
+        };
+        emit(&[(EV_KEY, code, down as i32)]);
+    }
+
+    fn keyboard(key: Key, down: bool) {
+        let Some(code) = key_to_code(key) else {
+            return;
+        };
+        emit(&[(EV_KEY, code as u16, down as i32)]);
+    }
+
+    /// Presses the keys of the layout for the characters it has, holding Shift where
+    /// needed. A keyboard device cannot type other characters, so they are skipped.
+    fn text(text: &str) {
+        for ch in text.chars() {
+            let Some((key, modifiers)) = PlatformKeyboard::key_for_char(ch) else {
+                continue;
+            };
+            let shift = modifiers.contains(Modifiers::SHIFT);
+            if shift {
+                Self::keyboard(Key::ShiftLeft, true);
+            }
+            Self::keyboard(key, true);
+            Self::keyboard(key, false);
+            if shift {
+                Self::keyboard(Key::ShiftLeft, false);
+            }
+        }
+    }
+
+    /// The same as `text`: uinput has no way to inject characters without keys.
+    fn unicode(text: &str) {
+        Self::text(text);
+    }
+
+    /// Key codes always are physical positions, so this has no effect.
+    #[cfg(feature = "simulate")]
+    fn key_semantics(_semantics: crate::KeySemantics) {}
+}
+
+/// Writes the events, followed by a report, to the uinput device.
+fn emit(events: &[(u16, u16, i32)]) {
+    let Some(device) = DEVICE.get_or_init(|| create().map(Mutex::new)) else {
+        return;
+    };
+    let mut buffer: Vec<libc::input_event> = events
+        .iter()
+        .filter(|&&(type_, _, value)| type_ == EV_KEY || value != 0)
+        .map(|&(type_, code, value)| input_event(type_, code, value))
+        .collect();
+    if buffer.is_empty() {
+        return;
+    }
+    buffer.push(input_event(EV_SYN, SYN_REPORT, 0));
+
+    let bytes = unsafe {
+        slice::from_raw_parts(
+            buffer.as_ptr().cast::<u8>(),
+            mem::size_of_val(buffer.as_slice()),
+        )
+    };
+    let mut device = device.lock().unwrap_or_else(PoisonError::into_inner);
+    let _ = device.write_all(bytes);
+}
+
+fn input_event(type_: u16, code: u16, value: i32) -> libc::input_event {
+    libc::input_event {
+        // Stamped by the kernel
+        time: libc::timeval {
+            tv_sec: 0,
+            tv_usec: 0,
+        },
+        type_,
+        code,
+        value,
+    }
+}
+
+/// Creates the uinput device with the keys, buttons and axes `Simulate` uses.
+fn create() -> Option<File> {
+    let file = OpenOptions::new().write(true).open("/dev/uinput").ok()?;
+    let fd = file.as_raw_fd();
+
+    let mut ok = true;
+    unsafe {
+        for type_ in [EV_KEY, EV_REL, EV_SYN] {
+            ok &= libc::ioctl(fd, UI_SET_EVBIT as _, type_ as libc::c_int) >= 0;
+        }
+        for code in KEY_CODES.chain([BTN_LEFT, BTN_RIGHT, BTN_MIDDLE, BTN_SIDE, BTN_EXTRA]) {
+            ok &= libc::ioctl(fd, UI_SET_KEYBIT as _, code as libc::c_int) >= 0;
+        }
+        for code in [REL_X, REL_Y, REL_WHEEL, REL_HWHEEL] {
+            ok &= libc::ioctl(fd, UI_SET_RELBIT as _, code as libc::c_int) >= 0;
+        }
+    }
+    if !ok {
+        return None;
+    }
+
+    let mut setup: libc::uinput_user_dev = unsafe { mem::zeroed() };
+    for (dst, src) in setup.name.iter_mut().zip(DEVICE_NAME.bytes()) {
+        *dst = src as libc::c_char;
+    }
+    setup.id.bustype = BUS_VIRTUAL;
+    setup.id.version = 1;
+    let bytes = unsafe {
+        slice::from_raw_parts(
+            (&raw const setup).cast::<u8>(),
+            mem::size_of::<libc::uinput_user_dev>(),
+        )
+    };
+    (&file).write_all(bytes).ok()?;
+    if unsafe { libc::ioctl(fd, UI_DEV_CREATE as _) } < 0 {
+        return None;
+    }
+    Some(file)
+}
@@ -1,4 +1,4 @@
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicU32, Ordering};
 
 use core_graphics::event::CGEventType;
 
@@ -10,6 +10,20 @@ pub static IS_CORE_RUNNING: AtomicBool = AtomicBool::new(false);
 pub static IS_LISTEN_RUNNING: AtomicBool = AtomicBool::new(false);
 /// Indicates if the input grabber (interceptor) is active.
 pub static IS_GRAB_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Lets events for windows of the current process through the grabber.
+pub static GRAB_EXCLUDE_OWN_WINDOWS: AtomicBool = AtomicBool::new(false);
+/// Set while `Grab::block_input` drops all input that was not injected by `Simulate`.
+pub static IS_INPUT_BLOCKED: AtomicBool = AtomicBool::new(false);
+/// Lets events carrying the crate's injection marker through the grabber.
+pub static GRAB_PASS_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Withholds events carrying the crate's injection marker from subscribers.
+pub static LISTEN_IGNORE_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Requests a listen-only event tap while the grabber is not started.
+pub static LISTEN_ONLY_TAP: AtomicBool = AtomicBool::new(false);
+/// Indicates if the installed event tap is listen-only, so it cannot drop or modify events.
+pub static IS_TAP_LISTEN_ONLY: AtomicBool = AtomicBool::new(false);
+/// The [`TapLocation`](crate::TapLocation) of the event tap, as its discriminant.
+pub static TAP_LOCATION: AtomicU8 = AtomicU8::new(0);
 
 pub const INTERESTED_EVENTS: &[CGEventType] = &[
     CGEventType::MouseMoved,
@@ -1,30 +1,106 @@
+use std::ffi::{c_int, c_uint};
 use std::sync::Mutex;
-use std::sync::atomic::Ordering;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::time::Duration;
 
-use core_foundation::runloop::kCFRunLoopCommonModes;
-use core_foundation::runloop::{CFRunLoop, CFRunLoopRun};
+use core_foundation::base::TCFType;
+use core_foundation::mach_port::{CFMachPort, CFMachPortRef};
+use core_foundation::runloop::{CFRunLoop, CFRunLoopRun, CFRunLoopSource};
+use core_foundation::runloop::{kCFRunLoopCommonModes, kCFRunLoopDefaultMode};
 use core_graphics::display::CGWarpMouseCursorPosition;
 use core_graphics::event::{
     CGEvent, CGEventTapLocation, CGEventTapOptions, CGEventTapPlacement, CGEventType,
     CallbackResult,
 };
-use core_graphics::event::{CGEventTap, CGEventTapProxy};
+use core_graphics::event::{CGEventTap, CGEventTapProxy, EventField};
+use foreign_types::ForeignType;
 
-use crate::platform::{
-    CoreError, CoreImpl, GrabImpl, ListenImpl, PlatformCore, PlatformGrab, PlatformListen,
-    macos::common::{GRAB_FLAG, GRAB_MOUSE_MOVE, INTERESTED_EVENTS, IS_CORE_RUNNING},
+use crate::{
+    Event, Point, RawEvent, TapLocation, ThreadPriority, dispatcher,
+    grab_release::{self, Blocker},
+    pipeline,
+    platform::{
+        CoreError, CoreImpl, GrabImpl, HidImpl, HotkeyImpl, ListenImpl, PlatformCore,
+        PlatformForeground, PlatformGrab, PlatformHid, PlatformHotkeys, PlatformListen,
+        PlatformSimulate, SimulateImpl,
+        macos::common::{
+            GRAB_FLAG, GRAB_MOUSE_MOVE, INTERESTED_EVENTS, IS_CORE_RUNNING, IS_INPUT_BLOCKED,
+            IS_TAP_LISTEN_ONLY, LISTEN_ONLY_TAP, TAP_LOCATION,
+        },
+    },
+    raw_event,
+    sequence::{self, EventSource},
+    state,
 };
 
-static CORE_RUN_LOOP: Mutex<Option<CFRunLoop>> = Mutex::new(None);
+/// The run loop of the core thread, while the core runs.
+pub(crate) static CORE_RUN_LOOP: Mutex<Option<CFRunLoop>> = Mutex::new(None);
+
+/// Asks the core thread to reinstall the event tap when its run loop returns.
+static RETAP: AtomicBool = AtomicBool::new(false);
+
+/// The tap installed by `attach`, on a run loop run by the application.
+static ATTACHED: Mutex<Option<InstalledTap>> = Mutex::new(None);
+
+/// An event tap added to a run loop. Dropping it invalidates the tap.
+struct InstalledTap {
+    _tap: CGEventTap<'static>,
+    source: CFRunLoopSource,
+}
+
+// SAFETY: the tap only holds its mach port and a plain function pointer callback, and
+// Core Foundation objects may be released and removed from run loops on any thread.
+unsafe impl Send for InstalledTap {}
+
+impl InstalledTap {
+    fn remove(self, run_loop: &CFRunLoop) {
+        *TAP_PORT.lock().unwrap() = None;
+        run_loop.remove_source(&self.source, unsafe { kCFRunLoopCommonModes });
+        PlatformHotkeys::update();
+    }
+}
+
+/// The mach port of the installed event tap, for querying its state from any thread.
+static TAP_PORT: Mutex<Option<TapPort>> = Mutex::new(None);
+
+struct TapPort(CFMachPort);
+
+// SAFETY: mach ports are reference counted Core Foundation objects, usable on any thread.
+unsafe impl Send for TapPort {}
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGEventTapIsEnabled(tap: CFMachPortRef) -> bool;
+    fn CGPreflightListenEventAccess() -> bool;
+}
+
+/// The [`ThreadPriority`] `start` gives the core thread, as its discriminant.
+static THREAD_PRIORITY: AtomicU8 = AtomicU8::new(0);
+
+const QOS_CLASS_USER_INTERACTIVE: c_uint = 0x21;
+const QOS_CLASS_USER_INITIATED: c_uint = 0x19;
+
+unsafe extern "C" {
+    fn pthread_set_qos_class_self_np(qos_class: c_uint, relative_priority: c_int) -> c_int;
+}
+
+#[link(name = "ApplicationServices", kind = "framework")]
+unsafe extern "C" {
+    fn AXIsProcessTrusted() -> bool;
+}
 
 impl CoreImpl for PlatformCore {
     fn start() -> Result<(), CoreError> {
         // Ensure only one instance is running
         if Self::is_run() {
-            return Ok(());
+            return Err(CoreError::AlreadyRunning);
         }
 
-        Self::set_hook()?;
+        Self::apply_thread_priority();
+        if let Err(err) = Self::set_hook() {
+            Self::pause();
+            return Err(err);
+        }
 
         // Perform cleanup after the message loop exits
         Self::stop();
@@ -43,6 +119,64 @@ impl CoreImpl for PlatformCore {
         IS_CORE_RUNNING.store(true, Ordering::SeqCst);
     }
 
+    fn listen_only(enable: bool) {
+        LISTEN_ONLY_TAP.store(enable, Ordering::SeqCst);
+        Self::update_tap();
+    }
+
+    fn attach() -> Result<(), CoreError> {
+        // Ensure only one instance is running
+        if Self::is_run() {
+            return Err(CoreError::AlreadyRunning);
+        }
+
+        let run_loop = CFRunLoop::get_current();
+        let installed = match Self::install_tap(&run_loop) {
+            Ok(installed) => installed,
+            Err(err) => {
+                Self::pause();
+                return Err(err);
+            }
+        };
+        *ATTACHED.lock().unwrap() = Some(installed);
+        Self::attach_run_loop(&run_loop);
+        Ok(())
+    }
+
+    fn poll(timeout: Duration) -> Result<(), CoreError> {
+        if ATTACHED.lock().unwrap().is_none() {
+            Self::attach()?;
+        }
+        CFRunLoop::run_in_mode(unsafe { kCFRunLoopDefaultMode }, timeout, false);
+        Ok(())
+    }
+
+    fn hooks_installed() -> bool {
+        TAP_PORT.lock().unwrap().is_some()
+    }
+
+    fn tap_enabled() -> Option<bool> {
+        let port = TAP_PORT.lock().unwrap();
+        port.as_ref()
+            .map(|port| unsafe { CGEventTapIsEnabled(port.0.as_concrete_TypeRef()) })
+    }
+
+    fn has_permission(grab: bool) -> bool {
+        // Accessibility covers listening too
+        unsafe { AXIsProcessTrusted() || (!grab && CGPreflightListenEventAccess()) }
+    }
+
+    fn tap_location(location: TapLocation) {
+        let previous = TAP_LOCATION.swap(location as u8, Ordering::SeqCst);
+        if previous != location as u8 {
+            Self::retap();
+        }
+    }
+
+    fn thread_priority(priority: ThreadPriority) {
+        THREAD_PRIORITY.store(priority as u8, Ordering::SeqCst);
+    }
+
     /// Stops the core engine, unhooks all listeners, and terminates the message loop.
     fn stop() {
         Self::pause();
@@ -61,46 +195,152 @@ impl PlatformCore {
             .is_err()
     }
 
+    /// Moves the calling thread to the configured QoS class. Failures are ignored, the
+    /// core works at any priority.
+    fn apply_thread_priority() {
+        let qos_class = match THREAD_PRIORITY.load(Ordering::SeqCst) {
+            1 => QOS_CLASS_USER_INITIATED,
+            2 => QOS_CLASS_USER_INTERACTIVE,
+            _ => return,
+        };
+        unsafe {
+            pthread_set_qos_class_self_np(qos_class, 0);
+        }
+    }
+
     /// Wraps the macOS CGEventTap API.
     fn set_hook() -> Result<(), CoreError> {
+        let run_loop = CFRunLoop::get_current();
+        Self::attach_run_loop(&run_loop);
+
+        // The tap options cannot change, so switching modes replaces the whole tap
+        let result = loop {
+            let installed = match Self::install_tap(&run_loop) {
+                Ok(installed) => installed,
+                Err(err) => break Err(err),
+            };
+
+            unsafe { CFRunLoopRun() };
+
+            installed.remove(&run_loop);
+            if !RETAP.swap(false, Ordering::SeqCst) {
+                break Ok(());
+            }
+        };
+
+        Self::detach_run_loop();
+        result
+    }
+
+    /// Makes `run_loop` the core run loop, which also hosts HID and focus tracking.
+    fn attach_run_loop(run_loop: &CFRunLoop) {
+        {
+            let mut guard = CORE_RUN_LOOP.lock().unwrap();
+            *guard = Some(run_loop.clone());
+        }
+        PlatformHid::update();
+        // Track the frontmost application, so it is known without a query per event
+        PlatformForeground::watch();
+    }
+
+    fn detach_run_loop() {
+        IS_TAP_LISTEN_ONLY.store(false, Ordering::SeqCst);
+        {
+            let mut guard = CORE_RUN_LOOP.lock().unwrap();
+            *guard = None;
+        }
+        PlatformHid::update();
+        PlatformForeground::unwatch();
+    }
+
+    /// Creates an event tap with the current configuration and adds it to `run_loop`.
+    fn install_tap(run_loop: &CFRunLoop) -> Result<InstalledTap, CoreError> {
+        let listen_only = Self::wants_listen_only();
+        let options = if listen_only {
+            CGEventTapOptions::ListenOnly
+        } else {
+            CGEventTapOptions::Default
+        };
+        let location = match TAP_LOCATION.load(Ordering::SeqCst) {
+            1 => CGEventTapLocation::Session,
+            2 => CGEventTapLocation::AnnotatedSession,
+            _ => CGEventTapLocation::HID,
+        };
         let tap = match CGEventTap::new(
-            CGEventTapLocation::HID,
+            location,
             CGEventTapPlacement::HeadInsertEventTap,
-            CGEventTapOptions::Default,
+            options,
             INTERESTED_EVENTS.to_vec(),
             hook_event_callback,
         ) {
             Ok(tap) => tap,
+            // Without the permission the system refuses to create the tap
+            Err(_) if !Self::has_permission(!listen_only) => {
+                return Err(CoreError::PermissionDenied);
+            }
             Err(_) => return Err(CoreError::MacEventTapError),
         };
 
-        let run_loop_source = match tap.mach_port().create_runloop_source(0) {
-            Ok(run_loop_source) => run_loop_source,
+        let source = match tap.mach_port().create_runloop_source(0) {
+            Ok(source) => source,
             Err(_) => return Err(CoreError::MacLoopSourceError),
         };
 
-        let run_loop = CFRunLoop::get_current();
-        run_loop.add_source(&run_loop_source, unsafe { kCFRunLoopCommonModes });
-        {
-            let mut guard = CORE_RUN_LOOP.lock().unwrap();
-            *guard = Some(run_loop.clone());
-        }
-
+        run_loop.add_source(&source, unsafe { kCFRunLoopCommonModes });
+        IS_TAP_LISTEN_ONLY.store(listen_only, Ordering::SeqCst);
+        *TAP_PORT.lock().unwrap() = Some(TapPort(tap.mach_port().clone()));
         tap.enable();
+        // The tap matches hotkeys from now on
+        PlatformHotkeys::update();
+        Ok(InstalledTap { _tap: tap, source })
+    }
 
-        unsafe { CFRunLoopRun() };
+    /// Whether the event tap should be listen-only: requested, and nothing to grab or
+    /// block.
+    #[inline]
+    fn wants_listen_only() -> bool {
+        LISTEN_ONLY_TAP.load(Ordering::SeqCst)
+            && !PlatformGrab::is_runing()
+            && !IS_INPUT_BLOCKED.load(Ordering::SeqCst)
+    }
 
-        {
-            let mut guard = CORE_RUN_LOOP.lock().unwrap();
-            *guard = None;
+    /// Reinstalls the event tap of a running core if its mode no longer fits, e.g. an
+    /// active tap once `Grab` starts.
+    pub(crate) fn update_tap() {
+        if Self::wants_listen_only() != IS_TAP_LISTEN_ONLY.load(Ordering::SeqCst) {
+            Self::retap();
         }
+    }
 
-        Ok(())
+    /// Makes a running core reinstall its event tap with the current configuration.
+    fn retap() {
+        let Some(rl) = CORE_RUN_LOOP.lock().unwrap().clone() else {
+            return;
+        };
+
+        // An attached tap is swapped in place, the run loop belongs to the application
+        let mut attached = ATTACHED.lock().unwrap();
+        if let Some(installed) = attached.take() {
+            installed.remove(&rl);
+            *attached = Self::install_tap(&rl).ok();
+        } else {
+            RETAP.store(true, Ordering::SeqCst);
+            rl.stop();
+        }
     }
 
     /// Safely removes a hook and resets the atomic pointer.
     fn unhook() {
-        if let Some(rl) = CORE_RUN_LOOP.lock().unwrap().as_ref() {
+        let Some(rl) = CORE_RUN_LOOP.lock().unwrap().clone() else {
+            return;
+        };
+
+        let attached = ATTACHED.lock().unwrap().take();
+        if let Some(installed) = attached {
+            installed.remove(&rl);
+            Self::detach_run_loop();
+        } else {
+            RETAP.store(false, Ordering::SeqCst);
             rl.stop();
         }
     }
@@ -114,18 +354,152 @@ fn hook_event_callback(
     if !IS_CORE_RUNNING.load(Ordering::Relaxed) {
         return CallbackResult::Keep;
     }
+    state::observed();
+
+    // A hard block drops physical input before anything sees it, as `BlockInput` does
+    if IS_INPUT_BLOCKED.load(Ordering::Relaxed)
+        && !IS_TAP_LISTEN_ONLY.load(Ordering::Relaxed)
+        && !PlatformListen::is_injected(event)
+    {
+        return CallbackResult::Drop;
+    }
+
+    // With no subscribers and an idle pipeline only presses, releases and modifier
+    // changes are translated: for `grab_release`, and to keep the modifier tracking of
+    // `translate` up to date. Moves and wheel turns are never even read
+    let processed = dispatcher::has_active() || !pipeline::is_idle();
+    let wanted = processed
+        || !matches!(
+            event_type,
+            CGEventType::MouseMoved
+                | CGEventType::LeftMouseDragged
+                | CGEventType::RightMouseDragged
+                | CGEventType::OtherMouseDragged
+                | CGEventType::ScrollWheel
+        );
+    let translated = wanted
+        .then(|| PlatformListen::translate(event_type, event))
+        .flatten();
+    let blocker = filter_event(event_type, event, translated.filter(|_| processed));
+    // Remember the presses the applications saw, and who blocks their releases
+    if let Some(translated) = translated {
+        grab_release::track(&translated, blocker);
+    }
+    match blocker {
+        Some(_) => CallbackResult::Drop,
+        None => CallbackResult::Keep,
+    }
+}
+
+/// Runs a tapped event through the subscribers, the middleware pipeline and the grab
+/// filter. Returns what blocks it if it must not reach other applications.
+fn filter_event(
+    event_type: CGEventType,
+    event: &CGEvent,
+    translated: Option<Event>,
+) -> Option<Blocker> {
+    if let Some(translated) = translated {
+        let injected = PlatformListen::is_injected(event);
+        // Events reach the tap in the order they happened, so the dispatch time will do
+        let raw = RawEvent::CGEvent {
+            event_type: event_type as u32,
+            event: event.as_ptr().cast_const().cast(),
+        };
+        raw_event::with_raw(raw, || {
+            sequence::with_origin(EventSource::Hook, Duration::ZERO, || {
+                PlatformListen::handle(translated, injected)
+            })
+        });
+
+        // Run the middleware pipeline (remapping, scroll transforms, hotkeys, ...)
+        let outcome = pipeline::run(translated, injected);
+        for emitted in outcome.emitted {
+            PlatformSimulate::simulate(emitted);
+        }
+        // A listen-only tap can neither drop nor modify the event
+        if IS_TAP_LISTEN_ONLY.load(Ordering::Relaxed) {
+            return None;
+        }
+        if let Some(stage) = outcome.block {
+            return Some(stage);
+        }
 
-    PlatformListen::handle(event_type, event);
+        match (translated, outcome.replaced) {
+            // Wheel events are rewritten in place so pixel and continuous deltas stay
+            // consistent, including trackpad scrolls too small to register a whole line
+            (Event::MouseWheel { delta: old, .. }, None | Some((Event::MouseWheel { .. }, _))) => {
+                let new = match outcome.replaced {
+                    Some((Event::MouseWheel { delta, .. }, _)) => delta,
+                    _ => old,
+                };
+                let ratio = |new: f64, old: f64, fallback: f64| {
+                    if old == 0.0 { fallback } else { new / old }
+                };
+                let factors = Point {
+                    x: ratio(new.x, old.x, outcome.wheel_scale.x),
+                    y: ratio(new.y, old.y, outcome.wheel_scale.y),
+                };
+                if factors != (Point { x: 1.0, y: 1.0 }) {
+                    scale_scroll(event, factors);
+                }
+            }
+            (_, None) => {}
+            (_, Some((replaced, stage))) => {
+                PlatformSimulate::simulate(replaced);
+                return Some(stage);
+            }
+        }
 
-    if PlatformGrab::should_block(event_type) {
+        // A grab scope's decision overrides the `Grab` flags
+        if let Some(blocker) = outcome.grab {
+            if blocker.is_some() && matches!(translated, Event::MouseMove { .. }) {
+                unsafe {
+                    CGWarpMouseCursorPosition(event.location());
+                }
+            }
+            return blocker;
+        }
+    }
+
+    if PlatformGrab::should_block(event_type)
+        && !PlatformGrab::targets_own_window(event)
+        && !PlatformGrab::passes_injected(event)
+    {
         if (GRAB_FLAG.load(Ordering::Relaxed) & GRAB_MOUSE_MOVE) != 0 {
             unsafe {
                 CGWarpMouseCursorPosition(event.location());
             }
         }
 
-        return CallbackResult::Drop;
+        return Some(Blocker::Grab);
     }
 
-    CallbackResult::Keep
+    None
+}
+
+/// Scales every delta representation of a scroll wheel event by the given per-axis factors.
+fn scale_scroll(event: &CGEvent, factors: Point) {
+    let axes = [
+        (
+            factors.y,
+            EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_1,
+            EventField::SCROLL_WHEEL_EVENT_FIXED_POINT_DELTA_AXIS_1,
+            EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_1,
+        ),
+        (
+            factors.x,
+            EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_2,
+            EventField::SCROLL_WHEEL_EVENT_FIXED_POINT_DELTA_AXIS_2,
+            EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_2,
+        ),
+    ];
+
+    for (factor, lines, fixed, points) in axes {
+        let value = event.get_integer_value_field(lines) as f64 * factor;
+        event.set_integer_value_field(lines, value.round() as i64);
+        let value = event.get_double_value_field(fixed) * factor;
+        event.set_double_value_field(fixed, value);
+        let value = event.get_integer_value_field(points) as f64 * factor;
+        event.set_integer_value_field(points, value.round() as i64);
+    }
 }
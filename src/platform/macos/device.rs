@@ -0,0 +1,137 @@
+use std::ffi::{CStr, c_char};
+
+use core_foundation::{
+    base::{CFRelease, CFType, CFTypeRef, TCFType, kCFAllocatorDefault},
+    dictionary::CFDictionaryRef,
+    number::CFNumber,
+    set::{CFSetGetCount, CFSetGetValues, CFSetRef},
+    string::{CFString, CFStringRef},
+};
+
+use crate::{
+    device::{DeviceId, DeviceInfo, DeviceKind, KeyboardInfo, MouseInfo},
+    hid::HidUsage,
+    platform::{
+        DevicesImpl, PlatformDevices,
+        macos::hid::{IOHIDDeviceRef, IOHIDManagerCreate, IOHIDManagerRef},
+    },
+};
+
+impl DevicesImpl for PlatformDevices {
+    fn list() -> Vec<DeviceInfo> {
+        unsafe {
+            let manager = IOHIDManagerCreate(kCFAllocatorDefault, 0);
+            if manager.is_null() {
+                return Vec::new();
+            }
+            // A null dictionary matches every device
+            IOHIDManagerSetDeviceMatching(manager, std::ptr::null());
+            let set = IOHIDManagerCopyDevices(manager);
+            let devices = if set.is_null() {
+                Vec::new()
+            } else {
+                let mut devices = vec![std::ptr::null(); CFSetGetCount(set) as usize];
+                CFSetGetValues(set, devices.as_mut_ptr());
+                let devices = devices
+                    .into_iter()
+                    .filter_map(|device| device_info(device as IOHIDDeviceRef))
+                    .collect();
+                CFRelease(set as CFTypeRef);
+                devices
+            };
+            CFRelease(manager);
+            devices
+        }
+    }
+}
+
+/// Identifies a device by its IORegistry entry ID, which outlives the `IOHIDDevice`
+/// objects of individual managers.
+pub(crate) fn device_id(device: IOHIDDeviceRef) -> DeviceId {
+    let mut id = 0;
+    let found = unsafe {
+        let service = IOHIDDeviceGetService(device);
+        service != 0 && IORegistryEntryGetRegistryEntryID(service, &mut id) == 0
+    };
+    DeviceId(if found { id } else { device as u64 })
+}
+
+fn device_info(device: IOHIDDeviceRef) -> Option<DeviceInfo> {
+    let page = number_property(device, "PrimaryUsagePage")?;
+    let usage = number_property(device, "PrimaryUsage")?;
+    let usage = HidUsage::new(u16::try_from(page).ok()?, u16::try_from(usage).ok()?);
+
+    let (kind, mouse, keyboard) = match usage {
+        HidUsage::MOUSE => {
+            let mouse = MouseInfo {
+                // 16.16 fixed point counts per inch
+                dpi: number_property(device, "HIDPointerResolution")
+                    .map(|resolution| (resolution >> 16) as u32)
+                    .filter(|&dpi| dpi != 0),
+                // Microseconds between reports
+                polling_rate: number_property(device, "ReportInterval")
+                    .filter(|&interval| interval > 0)
+                    .map(|interval| (1_000_000 / interval) as u32),
+                buttons: None,
+                has_horizontal_wheel: None,
+            };
+            (DeviceKind::Mouse, Some(mouse), None)
+        }
+        // IOKit reports none of the keyboard counts Raw Input has
+        HidUsage::KEYBOARD => (DeviceKind::Keyboard, None, Some(KeyboardInfo::default())),
+        usage => (DeviceKind::Hid(usage), None, None),
+    };
+
+    Some(DeviceInfo {
+        id: device_id(device),
+        kind,
+        vendor_id: number_property(device, "VendorID").and_then(|id| u16::try_from(id).ok()),
+        product_id: number_property(device, "ProductID").and_then(|id| u16::try_from(id).ok()),
+        manufacturer: string_property(device, "Manufacturer"),
+        product: string_property(device, "Product"),
+        path: registry_path(device),
+        mouse,
+        keyboard,
+    })
+}
+
+fn property(device: IOHIDDeviceRef, key: &'static str) -> Option<CFType> {
+    let key = CFString::from_static_string(key);
+    let value = unsafe { IOHIDDeviceGetProperty(device, key.as_concrete_TypeRef()) };
+    (!value.is_null()).then(|| unsafe { CFType::wrap_under_get_rule(value) })
+}
+
+fn string_property(device: IOHIDDeviceRef, key: &'static str) -> Option<String> {
+    let value = property(device, key)?.downcast::<CFString>()?.to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+/// Reads the IOService plane path of the device, e.g.
+/// `IOService:/AppleARMPE/.../IOUSBHostInterface@0/AppleUserUSBHostHIDDevice`.
+fn registry_path(device: IOHIDDeviceRef) -> Option<String> {
+    let mut path = [0 as c_char; 512];
+    let found = unsafe {
+        let service = IOHIDDeviceGetService(device);
+        service != 0
+            && IORegistryEntryGetPath(service, c"IOService".as_ptr(), path.as_mut_ptr()) == 0
+    };
+    found.then(|| {
+        unsafe { CStr::from_ptr(path.as_ptr()) }
+            .to_string_lossy()
+            .into_owned()
+    })
+}
+
+fn number_property(device: IOHIDDeviceRef, key: &'static str) -> Option<i64> {
+    property(device, key)?.downcast::<CFNumber>()?.to_i64()
+}
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    fn IOHIDManagerSetDeviceMatching(manager: IOHIDManagerRef, matching: CFDictionaryRef);
+    fn IOHIDManagerCopyDevices(manager: IOHIDManagerRef) -> CFSetRef;
+    fn IOHIDDeviceGetProperty(device: IOHIDDeviceRef, key: CFStringRef) -> CFTypeRef;
+    fn IOHIDDeviceGetService(device: IOHIDDeviceRef) -> u32;
+    fn IORegistryEntryGetRegistryEntryID(entry: u32, id: *mut u64) -> i32;
+    fn IORegistryEntryGetPath(entry: u32, plane: *const c_char, path: *mut c_char) -> i32;
+}
@@ -1,21 +1,36 @@
-use crate::platform::{DisplayImpl, MonitorInfo, PlatformDisplay};
+use std::{ffi::c_void, sync::Once};
+
+use crate::{
+    Point, monitors,
+    platform::{CursorImage, CursorPosition, DisplayImpl, MonitorInfo, PlatformDisplay},
+};
 
-use core_foundation::{base::CFRelease, uuid::CFUUIDRef};
 use core_graphics::{
-    display::{CGDirectDisplayID, CGDisplay},
+    display::{CGDirectDisplayID, CGDisplay, CGWarpMouseCursorPosition},
     event::CGEvent,
     event_source::{CGEventSource, CGEventSourceStateID},
+    geometry::CGPoint,
 };
-use objc2::{msg_send, runtime::AnyObject};
-use objc2_app_kit::NSScreen;
-use objc2_foundation::{MainThreadMarker, NSArray, NSString, NSUInteger};
+
+type CGDisplayReconfigurationCallBack =
+    extern "C" fn(display: CGDirectDisplayID, flags: u32, user_info: *mut c_void);
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGDisplayRegisterReconfigurationCallback(
+        callback: CGDisplayReconfigurationCallBack,
+        user_info: *mut c_void,
+    ) -> i32;
+}
+
+/// Registers [`display_reconfigured`] the first time the monitors are enumerated.
+static WATCH_RECONFIGURATION: Once = Once::new();
 
 // public functions
 impl DisplayImpl for PlatformDisplay {
+    /// Served from the monitor cache.
     fn get_scale_factor() -> f64 {
-        let mtm = unsafe { MainThreadMarker::new_unchecked() };
-        let screens = NSScreen::screens(mtm);
-        Self::match_scale_factor(CGDisplay::main().id, &screens)
+        Self::get_primary_monitor().map_or(1.0, |m| m.scale_factor)
     }
 
     fn get_cursor_position() -> Option<(f64, f64)> {
@@ -25,6 +40,85 @@ impl DisplayImpl for PlatformDisplay {
         Some((point.x, point.y))
     }
 
+    fn set_cursor_position(x: f64, y: f64) {
+        // Warping does not post a mouse event, so the event tap never sees it.
+        unsafe {
+            CGWarpMouseCursorPosition(CGPoint::new(x, y));
+        }
+    }
+
+    fn get_cursor() -> Option<CursorPosition> {
+        // Quartz reports points, which are already logical; backing pixels depend on
+        // the scale factor of the monitor under the cursor.
+        let (x, y) = Self::get_cursor_position()?;
+        let monitor = Self::get_monitor_from_point(x, y);
+        let scale_factor = monitor.as_ref().map_or(1.0, |m| m.scale_factor);
+        Some(CursorPosition {
+            physical: Point {
+                x: x * scale_factor,
+                y: y * scale_factor,
+            },
+            logical: Point { x, y },
+            monitor,
+        })
+    }
+
+    /// Reads `NSCursor.currentSystemCursor`, the cursor on screen whichever application
+    /// set it, from a bitmap of its image with the colors converted to sRGB. The hotspot
+    /// is in points, so it is scaled to the pixels of the bitmap.
+    #[cfg(feature = "listen")]
+    fn get_cursor_image() -> Option<CursorImage> {
+        use objc2_app_kit::{NSBitmapImageRep, NSColorSpace, NSCursor};
+
+        let cursor = NSCursor::currentSystemCursor()?;
+        let image = cursor.image();
+        let hotspot = cursor.hotSpot();
+        let size = image.size();
+        let bitmap = NSBitmapImageRep::imageRepWithData(&image.TIFFRepresentation()?)?;
+        let (width, height) = (bitmap.pixelsWide(), bitmap.pixelsHigh());
+        if width <= 0 || height <= 0 || size.width <= 0.0 || size.height <= 0.0 {
+            return None;
+        }
+
+        let srgb = NSColorSpace::sRGBColorSpace();
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height {
+            for x in 0..width {
+                let rgba = bitmap
+                    .colorAtX_y(x, y)
+                    .and_then(|color| color.colorUsingColorSpace(&srgb))
+                    .map_or([0.0; 4], |color| {
+                        [
+                            color.redComponent(),
+                            color.greenComponent(),
+                            color.blueComponent(),
+                            color.alphaComponent(),
+                        ]
+                    });
+                pixels.extend(rgba.map(|component| (component * 255.0).round() as u8));
+            }
+        }
+        let scale = Point {
+            x: width as f64 / size.width,
+            y: height as f64 / size.height,
+        };
+        Some(CursorImage {
+            width: width as u32,
+            height: height as u32,
+            hotspot: (
+                (hotspot.x * scale.x) as u32,
+                (hotspot.y * scale.y) as u32,
+            ),
+            pixels,
+        })
+    }
+
+    /// Reading the cursor needs AppKit, which only the `listen` feature brings in.
+    #[cfg(not(feature = "listen"))]
+    fn get_cursor_image() -> Option<CursorImage> {
+        None
+    }
+
     fn get_primary_screen_size() -> (f64, f64) {
         let display = CGDisplay::main();
         let bounds = display.bounds();
@@ -62,30 +156,12 @@ impl DisplayImpl for PlatformDisplay {
         (min_x, min_y, max_x - min_x, max_y - min_y)
     }
 
+    /// The list is cached until the display configuration changes.
     fn get_available_monitors() -> Vec<MonitorInfo> {
-        let mut monitors = Vec::new();
-        let mtm = unsafe { MainThreadMarker::new_unchecked() };
-
-        let all_screens = NSScreen::screens(mtm);
-        let main_id = CGDisplay::main().id;
-
-        if let Ok(active_displays) = CGDisplay::active_displays() {
-            for display_id in active_displays {
-                let display = CGDisplay::new(display_id);
-                let bounds = display.bounds();
-
-                let scale_factor = Self::match_scale_factor(display_id, &all_screens);
-
-                monitors.push(MonitorInfo {
-                    name: format!("Monitor #{}", display.model_number()),
-                    is_primary: display_id == main_id,
-                    offset: (bounds.origin.x, bounds.origin.y),
-                    size: (bounds.size.width, bounds.size.height),
-                    scale_factor,
-                });
-            }
-        }
-        monitors
+        WATCH_RECONFIGURATION.call_once(|| unsafe {
+            CGDisplayRegisterReconfigurationCallback(display_reconfigured, std::ptr::null_mut());
+        });
+        monitors::cached(Self::enumerate_monitors)
     }
 
     fn get_primary_monitor() -> Option<MonitorInfo> {
@@ -101,54 +177,56 @@ impl DisplayImpl for PlatformDisplay {
     }
 
     fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo> {
-        Self::get_available_monitors().into_iter().find(|m| {
-            x >= m.offset.0
-                && x < m.offset.0 + m.size.0
-                && y >= m.offset.1
-                && y < m.offset.1 + m.size.1
-        })
+        Self::get_available_monitors()
+            .into_iter()
+            .find(|m| m.contains(Point { x, y }))
     }
 }
 
 // private functions
 impl PlatformDisplay {
-    fn match_scale_factor(id: CGDirectDisplayID, screens: &NSArray<NSScreen>) -> f64 {
-        let key = NSString::from_str("NSScreenNumber");
-        unsafe {
-            let target_uuid = CGDisplayCreateUUIDFromDisplayID(id);
-            let mut scale = 1.0;
-
-            for i in 0..screens.count() {
-                let screen = screens.objectAtIndex(i);
-                let device_description = screen.deviceDescription();
-                let value: *mut AnyObject = msg_send![&device_description, objectForKey: &*key];
-
-                if !value.is_null() {
-                    let other_native_id: NSUInteger = msg_send![value, unsignedIntegerValue];
-                    let other_uuid =
-                        CGDisplayCreateUUIDFromDisplayID(other_native_id as CGDirectDisplayID);
-
-                    if target_uuid == other_uuid {
-                        scale = screen.backingScaleFactor();
-                        if !other_uuid.is_null() {
-                            CFRelease(other_uuid as _);
-                        }
-                        break;
-                    }
-                    if !other_uuid.is_null() {
-                        CFRelease(other_uuid as _);
-                    }
-                }
-            }
-            if !target_uuid.is_null() {
-                CFRelease(target_uuid as _);
+    fn enumerate_monitors() -> Vec<MonitorInfo> {
+        let mut monitors = Vec::new();
+        let main_id = CGDisplay::main().id;
+
+        if let Ok(active_displays) = CGDisplay::active_displays() {
+            for display_id in active_displays {
+                let display = CGDisplay::new(display_id);
+                let bounds = display.bounds();
+
+                let scale_factor = Self::scale_factor(display_id);
+
+                monitors.push(MonitorInfo {
+                    name: format!("Monitor #{}", display.model_number()),
+                    is_primary: display_id == main_id,
+                    offset: (bounds.origin.x, bounds.origin.y),
+                    size: (bounds.size.width, bounds.size.height),
+                    scale_factor,
+                });
             }
-            scale
         }
+        monitors
+    }
+
+    /// The ratio of backing pixels to points in the display's current mode, which is
+    /// what `NSScreen::backingScaleFactor` reports, without AppKit's main-thread
+    /// requirement.
+    fn scale_factor(id: CGDirectDisplayID) -> f64 {
+        let Some(mode) = CGDisplay::new(id).display_mode() else {
+            return 1.0;
+        };
+        if mode.width() == 0 {
+            return 1.0;
+        }
+        mode.pixel_width() as f64 / mode.width() as f64
     }
 }
 
-#[link(name = "ApplicationServices", kind = "framework")]
-unsafe extern "C" {
-    unsafe fn CGDisplayCreateUUIDFromDisplayID(display: CGDirectDisplayID) -> CFUUIDRef;
+/// Drops the monitor cache when a display is added, removed, moved or changes mode.
+extern "C" fn display_reconfigured(
+    _display: CGDirectDisplayID,
+    _flags: u32,
+    _user_info: *mut c_void,
+) {
+    monitors::invalidate();
 }
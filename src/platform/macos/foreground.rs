@@ -0,0 +1,153 @@
+use std::sync::{Mutex, PoisonError};
+
+use core_foundation::{
+    base::{CFType, TCFType},
+    dictionary::CFDictionary,
+    number::CFNumber,
+    string::CFString,
+};
+use core_graphics::window::{
+    kCGNullWindowID, kCGWindowLayer, kCGWindowListExcludeDesktopElements,
+    kCGWindowListOptionOnScreenOnly, kCGWindowName, kCGWindowOwnerPID,
+};
+use objc2::{ClassType, define_class, msg_send, rc::Retained, runtime::NSObject, sel};
+use objc2_app_kit::{
+    NSRunningApplication, NSWorkspace, NSWorkspaceApplicationKey,
+    NSWorkspaceDidActivateApplicationNotification,
+};
+use objc2_foundation::NSNotification;
+
+use crate::{
+    Event,
+    foreground::{self, ForegroundApp},
+    platform::{ForegroundImpl, PlatformForeground, PlatformListen},
+};
+
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    /// Returns `true` while any application has secure event input enabled, as password
+    /// fields do while they have the focus.
+    fn IsSecureEventInputEnabled() -> bool;
+}
+
+/// The registered activation observer, while the core runs.
+static OBSERVER: Mutex<Option<Observer>> = Mutex::new(None);
+
+struct Observer(Retained<FocusObserver>);
+
+// SAFETY: the observer has no state and is only passed back to the notification center
+unsafe impl Send for Observer {}
+
+define_class!(
+    // SAFETY: NSObject has no subclassing requirements and the class does not implement Drop
+    #[unsafe(super(NSObject))]
+    #[name = "RawInputFocusObserver"]
+    struct FocusObserver;
+
+    impl FocusObserver {
+        #[unsafe(method(applicationActivated:))]
+        fn application_activated(&self, notification: &NSNotification) {
+            let app = notification
+                .userInfo()
+                .and_then(|info| info.objectForKey(unsafe { NSWorkspaceApplicationKey }))
+                .and_then(|app| app.downcast::<NSRunningApplication>().ok());
+            let app = app.and_then(|app| PlatformForeground::app(&app));
+            let pid = app.as_ref().map(|app| app.pid);
+            foreground::changed(app);
+            if let Some(pid) = pid {
+                PlatformListen::handle(Event::FocusChanged { pid }, false);
+            }
+        }
+    }
+);
+
+impl ForegroundImpl for PlatformForeground {
+    fn query() -> Option<ForegroundApp> {
+        let app = NSWorkspace::sharedWorkspace().frontmostApplication()?;
+        Self::app(&app)
+    }
+
+    fn is_secure_input() -> bool {
+        unsafe { IsSecureEventInputEnabled() }
+    }
+}
+
+impl PlatformForeground {
+    /// Starts tracking application activations.
+    ///
+    /// NSWorkspace posts its notifications on the main thread, so they only arrive while
+    /// the main thread runs a run loop, e.g. when `Core` runs on it.
+    pub(crate) fn watch() {
+        let observer: Retained<FocusObserver> = unsafe { msg_send![FocusObserver::class(), new] };
+        unsafe {
+            NSWorkspace::sharedWorkspace()
+                .notificationCenter()
+                .addObserver_selector_name_object(
+                    &observer,
+                    sel!(applicationActivated:),
+                    Some(NSWorkspaceDidActivateApplicationNotification),
+                    None,
+                );
+        }
+        *OBSERVER.lock().unwrap_or_else(PoisonError::into_inner) = Some(Observer(observer));
+        foreground::set_watching(true);
+    }
+
+    /// Stops tracking application activations.
+    pub(crate) fn unwatch() {
+        let observer = OBSERVER
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take();
+        if let Some(Observer(observer)) = observer {
+            foreground::set_watching(false);
+            unsafe {
+                NSWorkspace::sharedWorkspace()
+                    .notificationCenter()
+                    .removeObserver(&observer);
+            }
+        }
+    }
+
+    fn app(app: &NSRunningApplication) -> Option<ForegroundApp> {
+        let pid = u32::try_from(app.processIdentifier()).ok()?;
+        let exe = app
+            .executableURL()
+            .and_then(|url| url.lastPathComponent())
+            .map(|name| name.to_string());
+        Some(ForegroundApp {
+            pid,
+            exe,
+            bundle_id: app.bundleIdentifier().map(|id| id.to_string()),
+            title: Self::title(pid),
+        })
+    }
+
+    /// Reads the title of the frontmost window of a process. Window titles of other
+    /// processes are withheld without the Screen Recording permission.
+    fn title(pid: u32) -> Option<String> {
+        let windows = core_graphics::window::copy_window_info(
+            kCGWindowListOptionOnScreenOnly | kCGWindowListExcludeDesktopElements,
+            kCGNullWindowID,
+        )?;
+        let number = |window: &CFDictionary<CFString, CFType>, key| {
+            let key = unsafe { CFString::wrap_under_get_rule(key) };
+            window.find(&key)?.downcast::<CFNumber>()?.to_i64()
+        };
+
+        // Windows are listed front to back; layer 0 holds regular application windows
+        windows
+            .get_all_values()
+            .into_iter()
+            .map(|window| unsafe { CFDictionary::wrap_under_get_rule(window as _) })
+            .find(|window| {
+                number(window, unsafe { kCGWindowOwnerPID }) == Some(i64::from(pid))
+                    && number(window, unsafe { kCGWindowLayer }) == Some(0)
+            })
+            .and_then(|window| {
+                let key = unsafe { CFString::wrap_under_get_rule(kCGWindowName) };
+                let title = window.find(&key)?.downcast::<CFString>()?.to_string();
+                (!title.is_empty()).then_some(title)
+            })
+    }
+}
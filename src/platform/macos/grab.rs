@@ -1,21 +1,25 @@
 use std::sync::atomic::Ordering;
 
-use core_graphics::event::CGEventType;
+use core_graphics::event::{CGEvent, CGEventType, EventField};
 
+use crate::{GrabFlags, StateError, foreground, state};
 use crate::platform::{
-    PlatformGrab, GrabImpl,
+    PlatformCore, PlatformGrab, PlatformListen, GrabImpl,
     macos::common::{
         GRAB_ALL, GRAB_FLAG, GRAB_KEYBOARD, GRAB_MOUSE_BUTTON, GRAB_MOUSE_MOVE, GRAB_MOUSE_WHEEL,
-        IS_GRAB_RUNNING, update_state,
+        GRAB_EXCLUDE_OWN_WINDOWS, GRAB_PASS_INJECTED, IS_GRAB_RUNNING, IS_INPUT_BLOCKED,
+        update_state,
     },
 };
 
 impl GrabImpl for PlatformGrab {
-    fn start() {
+    fn start() -> bool {
         if Self::is_run() {
-            return;
+            return false;
         }
         GRAB_FLAG.fetch_or(GRAB_ALL, Ordering::SeqCst);
+        PlatformCore::update_tap();
+        true
     }
 
     fn is_runing() -> bool {
@@ -24,10 +28,12 @@ impl GrabImpl for PlatformGrab {
 
     fn pause() {
         IS_GRAB_RUNNING.store(false, Ordering::SeqCst);
+        PlatformCore::update_tap();
     }
 
     fn resume() {
         IS_GRAB_RUNNING.store(true, Ordering::SeqCst);
+        PlatformCore::update_tap();
     }
 
     fn stop() {
@@ -50,6 +56,38 @@ impl GrabImpl for PlatformGrab {
     fn keyboard(enable: bool) {
         update_state(&GRAB_FLAG, GRAB_KEYBOARD, enable);
     }
+
+    fn exclude_own_windows(enable: bool) {
+        GRAB_EXCLUDE_OWN_WINDOWS.store(enable, Ordering::SeqCst);
+    }
+
+    fn pass_injected(enable: bool) {
+        GRAB_PASS_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
+    fn flags() -> GrabFlags {
+        let state = GRAB_FLAG.load(Ordering::SeqCst);
+        GrabFlags {
+            running: Self::is_runing(),
+            mouse_move: state & GRAB_MOUSE_MOVE != 0,
+            mouse_button: state & GRAB_MOUSE_BUTTON != 0,
+            mouse_wheel: state & GRAB_MOUSE_WHEEL != 0,
+            keyboard: state & GRAB_KEYBOARD != 0,
+            exclude_own_windows: GRAB_EXCLUDE_OWN_WINDOWS.load(Ordering::SeqCst),
+            pass_injected: GRAB_PASS_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    /// There is no `BlockInput` on macOS: the event tap drops the input instead, so the
+    /// core must run with the Accessibility permission.
+    fn block_input(block: bool) -> Result<(), StateError> {
+        if block {
+            state::check_core(true)?;
+        }
+        IS_INPUT_BLOCKED.store(block, Ordering::SeqCst);
+        PlatformCore::update_tap();
+        Ok(())
+    }
 }
 
 impl PlatformGrab {
@@ -67,7 +105,7 @@ impl PlatformGrab {
         }
 
         let state = GRAB_FLAG.load(Ordering::Relaxed);
-        if state == 0 {
+        if state == 0 || !foreground::grab_allowed() {
             return false;
         }
 
@@ -96,6 +134,32 @@ impl PlatformGrab {
             _ => false,
         }
     }
+
+    /// Returns `true` if injected events are let through and the event was injected by
+    /// this crate's `Simulate`.
+    #[inline]
+    pub(crate) fn passes_injected(event: &CGEvent) -> bool {
+        GRAB_PASS_INJECTED.load(Ordering::Relaxed) && PlatformListen::is_injected(event)
+    }
+
+    /// Returns `true` if own windows are excluded and the event is headed for one.
+    #[inline]
+    pub(crate) fn targets_own_window(event: &CGEvent) -> bool {
+        if !GRAB_EXCLUDE_OWN_WINDOWS.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        // The target is not always known at the session tap, the focused app receives keys
+        let pid = event.get_integer_value_field(EventField::EVENT_TARGET_UNIX_PROCESS_ID) as u32;
+        let pid = match pid {
+            0 => match foreground::current() {
+                Some(app) => app.pid,
+                None => return false,
+            },
+            pid => pid,
+        };
+        pid == std::process::id()
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,175 @@
+use std::{
+    ffi::c_void,
+    sync::{Mutex, PoisonError},
+};
+
+use core_foundation::{
+    array::{CFArray, CFArrayRef},
+    base::{Boolean, CFAllocatorRef, CFIndex, CFRelease, TCFType, kCFAllocatorDefault},
+    dictionary::CFDictionary,
+    number::CFNumber,
+    runloop::{CFRunLoop, CFRunLoopRef, kCFRunLoopCommonModes},
+    string::{CFString, CFStringRef},
+};
+
+use crate::{
+    hid::{self, HidReport, HidUsage},
+    platform::{
+        HidImpl, PlatformHid,
+        macos::{core::CORE_RUN_LOOP, device::device_id},
+    },
+};
+
+pub(crate) type IOHIDManagerRef = *mut c_void;
+pub(crate) type IOHIDDeviceRef = *mut c_void;
+type IOReturn = i32;
+type IOHIDReportCallback = extern "C" fn(
+    context: *mut c_void,
+    result: IOReturn,
+    sender: IOHIDDeviceRef,
+    report_type: u32,
+    report_id: u32,
+    report: *mut u8,
+    report_length: CFIndex,
+);
+
+/// The manager matching the subscribed usages, scheduled on the core run loop.
+static MANAGER: Mutex<Option<Manager>> = Mutex::new(None);
+
+struct Manager {
+    manager: IOHIDManagerRef,
+    run_loop: CFRunLoop,
+}
+
+// SAFETY: IOHIDManager and CFRunLoop references may be used from any thread
+unsafe impl Send for Manager {}
+
+impl Manager {
+    fn open(usages: &[HidUsage], run_loop: CFRunLoop) -> Option<Self> {
+        let matching: Vec<CFDictionary<CFString, CFNumber>> = usages
+            .iter()
+            .map(|usage| {
+                CFDictionary::from_CFType_pairs(&[
+                    (
+                        CFString::from_static_string("DeviceUsagePage"),
+                        CFNumber::from(i32::from(usage.page)),
+                    ),
+                    (
+                        CFString::from_static_string("DeviceUsage"),
+                        CFNumber::from(i32::from(usage.usage)),
+                    ),
+                ])
+            })
+            .collect();
+        let matching = CFArray::from_CFTypes(&matching);
+
+        unsafe {
+            let manager = IOHIDManagerCreate(kCFAllocatorDefault, 0);
+            if manager.is_null() {
+                return None;
+            }
+            let manager = Manager { manager, run_loop };
+            IOHIDManagerSetDeviceMatchingMultiple(manager.manager, matching.as_concrete_TypeRef());
+            IOHIDManagerRegisterInputReportCallback(
+                manager.manager,
+                report_callback,
+                std::ptr::null_mut(),
+            );
+            IOHIDManagerScheduleWithRunLoop(
+                manager.manager,
+                manager.run_loop.as_concrete_TypeRef(),
+                kCFRunLoopCommonModes,
+            );
+            // Fails without the Input Monitoring permission
+            if IOHIDManagerOpen(manager.manager, 0) != 0 {
+                return None;
+            }
+            Some(manager)
+        }
+    }
+}
+
+impl Drop for Manager {
+    fn drop(&mut self) {
+        unsafe {
+            IOHIDManagerUnscheduleFromRunLoop(
+                self.manager,
+                self.run_loop.as_concrete_TypeRef(),
+                kCFRunLoopCommonModes,
+            );
+            IOHIDManagerClose(self.manager, 0);
+            CFRelease(self.manager);
+        }
+    }
+}
+
+impl HidImpl for PlatformHid {
+    /// Replaces the manager with one matching the current usages, while the core runs.
+    fn update() {
+        let run_loop = CORE_RUN_LOOP
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let mut manager = MANAGER.lock().unwrap_or_else(PoisonError::into_inner);
+        *manager = None;
+
+        let usages = hid::usages();
+        if let Some(run_loop) = run_loop
+            && !usages.is_empty()
+        {
+            *manager = Manager::open(&usages, run_loop);
+        }
+    }
+}
+
+extern "C" fn report_callback(
+    _context: *mut c_void,
+    _result: IOReturn,
+    sender: IOHIDDeviceRef,
+    _report_type: u32,
+    _report_id: u32,
+    report: *mut u8,
+    report_length: CFIndex,
+) {
+    if !hid::is_active() || sender.is_null() || report.is_null() {
+        return;
+    }
+    // A device matched through one of its usages; report the first subscribed one
+    let usage = hid::usages().into_iter().find(|usage| unsafe {
+        IOHIDDeviceConformsTo(sender, u32::from(usage.page), u32::from(usage.usage)) != 0
+    });
+    let Some(usage) = usage else {
+        return;
+    };
+
+    let data = unsafe { std::slice::from_raw_parts(report, report_length as usize) };
+    hid::dispatch(&HidReport {
+        usage,
+        device: device_id(sender),
+        data,
+    });
+}
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    pub(crate) fn IOHIDManagerCreate(allocator: CFAllocatorRef, options: u32) -> IOHIDManagerRef;
+    fn IOHIDManagerSetDeviceMatchingMultiple(manager: IOHIDManagerRef, multiple: CFArrayRef);
+    fn IOHIDManagerRegisterInputReportCallback(
+        manager: IOHIDManagerRef,
+        callback: IOHIDReportCallback,
+        context: *mut c_void,
+    );
+    fn IOHIDManagerScheduleWithRunLoop(
+        manager: IOHIDManagerRef,
+        run_loop: CFRunLoopRef,
+        mode: CFStringRef,
+    );
+    fn IOHIDManagerUnscheduleFromRunLoop(
+        manager: IOHIDManagerRef,
+        run_loop: CFRunLoopRef,
+        mode: CFStringRef,
+    );
+    fn IOHIDManagerOpen(manager: IOHIDManagerRef, options: u32) -> IOReturn;
+    fn IOHIDManagerClose(manager: IOHIDManagerRef, options: u32) -> IOReturn;
+    fn IOHIDDeviceConformsTo(device: IOHIDDeviceRef, page: u32, usage: u32) -> Boolean;
+}
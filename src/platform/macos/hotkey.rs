@@ -0,0 +1,239 @@
+use std::{cell::RefCell, collections::HashMap, ffi::c_void, ptr};
+
+use crate::{
+    hotkey,
+    key::Modifiers,
+    platform::{HotkeyImpl, PlatformHotkeys, macos::keycode::key_to_code},
+    shortcut::Shortcut,
+};
+
+type OSStatus = i32;
+type EventTargetRef = *mut c_void;
+type EventRef = *mut c_void;
+type EventHandlerRef = *mut c_void;
+type EventHandlerCallRef = *mut c_void;
+type EventHotKeyRef = *mut c_void;
+type EventHandlerUPP =
+    extern "C" fn(call: EventHandlerCallRef, event: EventRef, user_data: *mut c_void) -> OSStatus;
+
+#[repr(C)]
+struct EventTypeSpec {
+    event_class: u32,
+    event_kind: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct EventHotKeyID {
+    signature: u32,
+    id: u32,
+}
+
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    fn GetApplicationEventTarget() -> EventTargetRef;
+    fn InstallEventHandler(
+        target: EventTargetRef,
+        handler: EventHandlerUPP,
+        num_types: u32,
+        list: *const EventTypeSpec,
+        user_data: *mut c_void,
+        out_ref: *mut EventHandlerRef,
+    ) -> OSStatus;
+    fn RegisterEventHotKey(
+        key_code: u32,
+        modifiers: u32,
+        id: EventHotKeyID,
+        target: EventTargetRef,
+        options: u32,
+        out_ref: *mut EventHotKeyRef,
+    ) -> OSStatus;
+    fn UnregisterEventHotKey(hotkey: EventHotKeyRef) -> OSStatus;
+    fn GetEventParameter(
+        event: EventRef,
+        name: u32,
+        desired_type: u32,
+        actual_type: *mut u32,
+        buffer_size: usize,
+        actual_size: *mut usize,
+        data: *mut c_void,
+    ) -> OSStatus;
+}
+
+unsafe extern "C" {
+    static _dispatch_main_q: c_void;
+    fn dispatch_async_f(
+        queue: *const c_void,
+        context: *mut c_void,
+        work: extern "C" fn(*mut c_void),
+    );
+    fn pthread_main_np() -> i32;
+}
+
+const NO_ERR: OSStatus = 0;
+/// Passes an event on to the next handler.
+const EVENT_NOT_HANDLED_ERR: OSStatus = -9874;
+const K_EVENT_CLASS_KEYBOARD: u32 = u32::from_be_bytes(*b"keyb");
+const K_EVENT_HOT_KEY_PRESSED: u32 = 5;
+const K_EVENT_PARAM_DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+const TYPE_EVENT_HOT_KEY_ID: u32 = u32::from_be_bytes(*b"hkid");
+
+/// Tells the hotkeys of this crate apart from other registrations of the application.
+const SIGNATURE: u32 = u32::from_be_bytes(*b"RwIn");
+
+// Carbon modifier flags
+const CMD_KEY: u32 = 0x0100;
+const SHIFT_KEY: u32 = 0x0200;
+const OPTION_KEY: u32 = 0x0800;
+const CONTROL_KEY: u32 = 0x1000;
+
+thread_local! {
+    /// The registered hotkeys by their Carbon id, on the main thread.
+    static REGISTERED: RefCell<Option<Registered>> = const { RefCell::new(None) };
+}
+
+#[derive(Default)]
+struct Registered {
+    hotkeys: HashMap<u32, (u64, EventHotKeyRef)>,
+    next_id: u32,
+}
+
+impl HotkeyImpl for PlatformHotkeys {
+    /// Carbon hotkeys belong to the main thread, so the registrations change there.
+    fn update() {
+        if unsafe { pthread_main_np() } != 0 {
+            apply();
+        } else {
+            unsafe {
+                dispatch_async_f(&raw const _dispatch_main_q, ptr::null_mut(), apply_on_main);
+            }
+        }
+    }
+}
+
+extern "C" fn apply_on_main(_context: *mut c_void) {
+    apply();
+}
+
+/// Brings the registrations in line with `hotkey::native`. Runs on the main thread.
+fn apply() {
+    let wanted = hotkey::native();
+    REGISTERED.with_borrow_mut(|registered| {
+        if registered.is_none() && wanted.is_empty() {
+            return;
+        }
+        let registered = registered.get_or_insert_with(|| {
+            install_handler();
+            Registered::default()
+        });
+
+        registered.hotkeys.retain(|_, &mut (id, hotkey_ref)| {
+            let keep = wanted.iter().any(|&(wanted_id, _)| wanted_id == id);
+            if !keep {
+                unsafe {
+                    UnregisterEventHotKey(hotkey_ref);
+                }
+            }
+            keep
+        });
+
+        for (id, shortcut) in wanted {
+            if registered
+                .hotkeys
+                .values()
+                .any(|&(registered_id, _)| registered_id == id)
+            {
+                continue;
+            }
+            let Some(key_code) = key_to_code(shortcut.key) else {
+                continue;
+            };
+            registered.next_id = registered.next_id.wrapping_add(1);
+            let carbon_id = registered.next_id;
+            let mut hotkey_ref = ptr::null_mut();
+            // Fails when another application registered the shortcut first; retried on
+            // the next update
+            let status = unsafe {
+                RegisterEventHotKey(
+                    key_code,
+                    modifiers(shortcut),
+                    EventHotKeyID {
+                        signature: SIGNATURE,
+                        id: carbon_id,
+                    },
+                    GetApplicationEventTarget(),
+                    0,
+                    &mut hotkey_ref,
+                )
+            };
+            if status == NO_ERR {
+                registered.hotkeys.insert(carbon_id, (id, hotkey_ref));
+            }
+        }
+    });
+}
+
+/// Installs the handler for hotkey presses on the application event target.
+fn install_handler() {
+    let spec = EventTypeSpec {
+        event_class: K_EVENT_CLASS_KEYBOARD,
+        event_kind: K_EVENT_HOT_KEY_PRESSED,
+    };
+    unsafe {
+        InstallEventHandler(
+            GetApplicationEventTarget(),
+            hotkey_pressed,
+            1,
+            &spec,
+            ptr::null_mut(),
+            ptr::null_mut(),
+        );
+    }
+}
+
+extern "C" fn hotkey_pressed(
+    _call: EventHandlerCallRef,
+    event: EventRef,
+    _user_data: *mut c_void,
+) -> OSStatus {
+    let mut carbon_id = EventHotKeyID::default();
+    let status = unsafe {
+        GetEventParameter(
+            event,
+            K_EVENT_PARAM_DIRECT_OBJECT,
+            TYPE_EVENT_HOT_KEY_ID,
+            ptr::null_mut(),
+            size_of::<EventHotKeyID>(),
+            ptr::null_mut(),
+            (&raw mut carbon_id).cast(),
+        )
+    };
+    if status != NO_ERR || carbon_id.signature != SIGNATURE {
+        return EVENT_NOT_HANDLED_ERR;
+    }
+
+    // Looked up first, so a callback may change the registrations
+    let id = REGISTERED.with_borrow(|registered| {
+        registered
+            .as_ref()
+            .and_then(|registered| registered.hotkeys.get(&carbon_id.id))
+            .map(|&(id, _)| id)
+    });
+    if let Some(id) = id {
+        hotkey::fire(id);
+    }
+    NO_ERR
+}
+
+/// Converts the modifiers of a shortcut to Carbon flags.
+fn modifiers(shortcut: Shortcut) -> u32 {
+    [
+        (Modifiers::CONTROL, CONTROL_KEY),
+        (Modifiers::SHIFT, SHIFT_KEY),
+        (Modifiers::ALT, OPTION_KEY),
+        (Modifiers::META, CMD_KEY),
+    ]
+    .into_iter()
+    .filter(|&(modifier, _)| shortcut.modifiers.contains(modifier))
+    .fold(0, |acc, (_, flag)| acc | flag)
+}
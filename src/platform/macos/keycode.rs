@@ -1,18 +1,20 @@
-use crate::key::{Key, KeyCode};
+use crate::key::{Key, KeyCode, RawKey};
 
 macro_rules! keymap {
     ($($key:ident => $code:expr),+ $(,)?) => {
         pub fn key_to_code(key: Key) -> Option<KeyCode> {
             match key {
                 $(Key::$key => Some($code),)+
+                Key::Raw(RawKey(code)) => Some(code),
                 _ => None,
             }
         }
 
+        #[cfg_attr(not(feature = "listen"), allow(dead_code))]
         pub fn code_to_key(scancode: KeyCode) -> Key {
             match scancode {
                 $($code => Key::$key,)+
-                _ => Key::Unidentified,
+                _ => Key::Raw(RawKey(scancode)),
             }
         }
     };
@@ -171,8 +173,9 @@ mod tests {
     }
 
     #[test]
-    fn test_code_to_key_unknown_returns_default() {
-        assert_eq!(code_to_key(0xFF), Key::Unidentified);
+    fn test_code_to_key_unknown_returns_raw() {
+        assert_eq!(code_to_key(0xFF), Key::Raw(RawKey(0xFF)));
+        assert_eq!(key_to_code(Key::Raw(RawKey(0xFF))), Some(0xFF));
     }
 
     #[test]
@@ -0,0 +1,142 @@
+use std::ffi::c_void;
+
+use core_foundation::{
+    base::{CFRelease, CFTypeRef},
+    data::{CFDataGetBytePtr, CFDataRef},
+    string::CFStringRef,
+};
+
+use crate::{
+    Key, Modifiers,
+    platform::{
+        KeyboardImpl, PlatformKeyboard,
+        macos::keycode::{code_to_key, key_to_code},
+    },
+};
+
+#[link(name = "Carbon", kind = "framework")]
+unsafe extern "C" {
+    static kTISPropertyUnicodeKeyLayoutData: CFStringRef;
+    fn TISCopyCurrentKeyboardLayoutInputSource() -> CFTypeRef;
+    fn TISGetInputSourceProperty(source: CFTypeRef, key: CFStringRef) -> CFTypeRef;
+    fn LMGetKbdType() -> u8;
+    fn UCKeyTranslate(
+        layout: *const c_void,
+        virtual_key_code: u16,
+        key_action: u16,
+        modifier_key_state: u32,
+        keyboard_type: u32,
+        key_translate_options: u32,
+        dead_key_state: *mut u32,
+        max_string_length: usize,
+        actual_string_length: *mut usize,
+        unicode_string: *mut u16,
+    ) -> i32;
+}
+
+const K_UC_KEY_ACTION_DOWN: u16 = 0;
+/// Reports the character of a dead key instead of starting a composition.
+const K_UC_KEY_TRANSLATE_NO_DEAD_KEYS_MASK: u32 = 1;
+
+// Carbon modifier flags, shifted right by 8 as `UCKeyTranslate` expects them
+const CMD_KEY: u32 = 0x01;
+const SHIFT_KEY: u32 = 0x02;
+const OPTION_KEY: u32 = 0x08;
+const CONTROL_KEY: u32 = 0x10;
+
+/// The key codes of the regular keys, which `UCKeyTranslate` is asked about.
+const KEY_CODES: std::ops::Range<u16> = 0..0x80;
+
+/// The current keyboard layout's translation table.
+struct Layout {
+    source: CFTypeRef,
+    data: *const c_void,
+}
+
+impl Layout {
+    fn current() -> Option<Layout> {
+        unsafe {
+            let source = TISCopyCurrentKeyboardLayoutInputSource();
+            if source.is_null() {
+                return None;
+            }
+            let data = TISGetInputSourceProperty(source, kTISPropertyUnicodeKeyLayoutData);
+            if data.is_null() {
+                CFRelease(source);
+                return None;
+            }
+            // The data belongs to the input source, which stays alive with the layout
+            let data = CFDataGetBytePtr(data as CFDataRef) as *const c_void;
+            Some(Layout { source, data })
+        }
+    }
+
+    /// Returns the character `code` types with the given Carbon modifiers.
+    fn translate(&self, code: u16, modifiers: u32) -> Option<char> {
+        let mut dead_key_state = 0;
+        let mut buf = [0u16; 4];
+        let mut len = 0;
+        let status = unsafe {
+            UCKeyTranslate(
+                self.data,
+                code,
+                K_UC_KEY_ACTION_DOWN,
+                modifiers,
+                u32::from(LMGetKbdType()),
+                K_UC_KEY_TRANSLATE_NO_DEAD_KEYS_MASK,
+                &mut dead_key_state,
+                buf.len(),
+                &mut len,
+                buf.as_mut_ptr(),
+            )
+        };
+        if status != 0 || len == 0 {
+            return None;
+        }
+        char::decode_utf16(buf[..len].iter().copied()).next()?.ok()
+    }
+}
+
+impl Drop for Layout {
+    fn drop(&mut self) {
+        unsafe { CFRelease(self.source) };
+    }
+}
+
+impl KeyboardImpl for PlatformKeyboard {
+    fn key_for_char(ch: char) -> Option<(Key, Modifiers)> {
+        // Return types a carriage return
+        let ch = if ch == '\n' { '\r' } else { ch };
+        let layout = Layout::current()?;
+        let combinations = [
+            (0, Modifiers::NONE),
+            (SHIFT_KEY, Modifiers::SHIFT),
+            (OPTION_KEY, Modifiers::ALT),
+            (SHIFT_KEY | OPTION_KEY, Modifiers::SHIFT | Modifiers::ALT),
+        ];
+        // Prefer the fewest modifiers, and the main keys over the keypad
+        combinations.into_iter().find_map(|(flags, modifiers)| {
+            KEY_CODES
+                .filter(|&code| layout.translate(code, flags) == Some(ch))
+                .map(|code| code_to_key(u32::from(code)))
+                .find(|key| !matches!(key, Key::Raw(_)))
+                .map(|key| (key, modifiers))
+        })
+    }
+
+    fn char_for_key(key: Key, modifiers: Modifiers) -> Option<char> {
+        let code = u16::try_from(key_to_code(key)?).ok()?;
+        let mut flags = 0;
+        for (modifier, flag) in [
+            (Modifiers::SHIFT, SHIFT_KEY),
+            (Modifiers::ALT, OPTION_KEY),
+            (Modifiers::CONTROL, CONTROL_KEY),
+            (Modifiers::META, CMD_KEY),
+        ] {
+            if modifiers.contains(modifier) {
+                flags |= flag;
+            }
+        }
+        Layout::current()?.translate(code, flags)
+    }
+}
@@ -1,17 +1,23 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{
+    Arc,
+    atomic::{AtomicU64, Ordering},
+};
 
 use core_graphics::event::{CGEvent, CGEventField, CGEventType, EventField};
 
 use crate::{
-    dispatcher::{CALLBACKS, NEXT_ID, Status, Subscriber, dispatch, remove_all},
+    ListenFlags,
+    dispatcher::{CALLBACKS, NEXT_ID, Status, Subscriber, dispatch, has_active, refresh, remove_all},
     event::{Event, MouseButton, Point},
+    foreground, held_modifiers, pressed,
     key::KeyCode,
     platform::{
-        PlatformListen, ListenImpl,
+        INJECTION_MARKER, PlatformListen, ListenImpl,
         macos::{
             common::{
-                IS_LISTEN_RUNNING, LISTEN_FLAG, LISTEN_KEYBOARD, LISTEN_MOUSE_BUTTON,
-                LISTEN_MOUSE_MOVE, LISTEN_MOUSE_WHEEL, LISTENS_ALL, update_state,
+                IS_LISTEN_RUNNING, LISTEN_FLAG, LISTEN_IGNORE_INJECTED, LISTEN_KEYBOARD,
+                LISTEN_MOUSE_BUTTON, LISTEN_MOUSE_MOVE, LISTEN_MOUSE_WHEEL, LISTENS_ALL,
+                update_state,
             },
             keycode::code_to_key,
         },
@@ -22,12 +28,13 @@ use crate::{
 static LAST_FLAGS: AtomicU64 = AtomicU64::new(0);
 
 impl ListenImpl for PlatformListen {
-    fn start() {
+    fn start() -> bool {
         if Self::is_run() {
-            return;
+            return false;
         }
 
         LISTEN_FLAG.store(LISTENS_ALL, Ordering::SeqCst);
+        true
     }
 
     fn is_runing() -> bool {
@@ -64,6 +71,26 @@ impl ListenImpl for PlatformListen {
         update_state(&LISTEN_FLAG, LISTEN_KEYBOARD, enable);
     }
 
+    /// Quartz key codes already identify the physical key independent of the layout.
+    fn flags() -> ListenFlags {
+        let state = LISTEN_FLAG.load(Ordering::SeqCst);
+        ListenFlags {
+            running: Self::is_runing(),
+            mouse_move: state & LISTEN_MOUSE_MOVE != 0,
+            mouse_button: state & LISTEN_MOUSE_BUTTON != 0,
+            mouse_wheel: state & LISTEN_MOUSE_WHEEL != 0,
+            keyboard: state & LISTEN_KEYBOARD != 0,
+            scancode_mode: false,
+            ignore_injected: LISTEN_IGNORE_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    fn scancode_mode(_enable: bool) {}
+
+    fn ignore_injected(enable: bool) {
+        LISTEN_IGNORE_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
     fn subscribe<F>(callback: F) -> SubscriptionHandle
     where
         F: Fn(Event) + Send + Sync + 'static,
@@ -73,9 +100,10 @@ impl ListenImpl for PlatformListen {
             id,
             Subscriber {
                 status: Status::Active,
-                callback: Box::new(callback),
+                callback: Arc::new(callback),
             },
         );
+        refresh();
         SubscriptionHandle { id }
     }
 
@@ -95,24 +123,56 @@ impl PlatformListen {
         event.get_integer_value_field(event_field)
     }
 
-    pub(crate) fn handle(event_type: CGEventType, event: &CGEvent) {
-        if !IS_LISTEN_RUNNING.load(Ordering::Relaxed) {
+    /// Dispatches a translated tap event to subscribers if its category is being listened to.
+    /// A `ModifiersChanged` follows the key events that change the modifiers held.
+    pub(crate) fn handle(event: Event, injected: bool) {
+        pressed::track(&event);
+        let modifiers = held_modifiers::update(&event);
+        Self::deliver(event, injected);
+        if let Some(modifiers) = modifiers {
+            Self::deliver(Event::ModifiersChanged { modifiers }, injected);
+        }
+    }
+
+    fn deliver(event: Event, injected: bool) {
+        if !has_active() || !IS_LISTEN_RUNNING.load(Ordering::Relaxed) {
+            return;
+        }
+        if injected && LISTEN_IGNORE_INJECTED.load(Ordering::Relaxed) {
             return;
         }
 
         let state = LISTEN_FLAG.load(Ordering::Relaxed);
-        if state == 0 {
+        let bit = match event {
+            Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. } => LISTEN_MOUSE_MOVE,
+            Event::MouseWheel { .. } => LISTEN_MOUSE_WHEEL,
+            Event::MouseDown { .. } | Event::MouseUp { .. } => LISTEN_MOUSE_BUTTON,
+            Event::KeyDown { .. } | Event::KeyUp { .. } | Event::ModifiersChanged { .. } => {
+                LISTEN_KEYBOARD
+            }
+            Event::FocusChanged { .. } => return dispatch(event),
+        };
+        if state & bit == 0 || !foreground::listen_allowed() {
             return;
         }
 
+        dispatch(event);
+    }
+
+    /// Checks whether a Quartz event was injected by this crate's `Simulate`.
+    pub(crate) fn is_injected(event: &CGEvent) -> bool {
+        event.get_integer_value_field(EventField::EVENT_SOURCE_USER_DATA) == INJECTION_MARKER as i64
+    }
+
+    /// Translates a Quartz event into an [`Event`].
+    ///
+    /// Must be called for every tapped event so modifier transitions are tracked correctly.
+    pub(crate) fn translate(event_type: CGEventType, event: &CGEvent) -> Option<Event> {
         let event = match event_type {
             CGEventType::MouseMoved
             | CGEventType::LeftMouseDragged
             | CGEventType::RightMouseDragged
             | CGEventType::OtherMouseDragged => {
-                if state & LISTEN_MOUSE_MOVE == 0 {
-                    return;
-                }
                 let dx = Self::get_code(event, EventField::MOUSE_EVENT_DELTA_X);
                 let dy = Self::get_code(event, EventField::MOUSE_EVENT_DELTA_Y);
 
@@ -124,16 +184,13 @@ impl PlatformListen {
                         },
                     }
                 } else {
-                    return;
+                    return None;
                 }
             }
             CGEventType::LeftMouseDown
             | CGEventType::LeftMouseUp
             | CGEventType::RightMouseDown
             | CGEventType::RightMouseUp => {
-                if state & LISTEN_MOUSE_BUTTON == 0 {
-                    return;
-                }
                 let match_type = matches!(
                     event_type,
                     CGEventType::LeftMouseDown | CGEventType::LeftMouseUp
@@ -155,15 +212,12 @@ impl PlatformListen {
                 }
             }
             CGEventType::OtherMouseDown | CGEventType::OtherMouseUp => {
-                if state & LISTEN_MOUSE_BUTTON == 0 {
-                    return;
-                }
                 let num = Self::get_code(event, EventField::MOUSE_EVENT_BUTTON_NUMBER);
                 let button = match num {
                     2 => MouseButton::Middle,
                     3 => MouseButton::Back,
                     4 => MouseButton::Forward,
-                    _ => return,
+                    _ => return None,
                 };
 
                 match event_type {
@@ -172,23 +226,39 @@ impl PlatformListen {
                 }
             }
             CGEventType::ScrollWheel => {
-                if state & LISTEN_MOUSE_WHEEL == 0 {
-                    return;
-                }
-                let dy = Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_1);
-                let dx = Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_2);
+                // Trackpads and the Magic Mouse scroll continuously; their fixed-point delta
+                // keeps the fractional lines that the integer delta rounds away
+                let continuous =
+                    Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_IS_CONTINUOUS) != 0;
+                let (dx, dy) = if continuous {
+                    (
+                        event.get_double_value_field(
+                            EventField::SCROLL_WHEEL_EVENT_FIXED_POINT_DELTA_AXIS_2,
+                        ),
+                        event.get_double_value_field(
+                            EventField::SCROLL_WHEEL_EVENT_FIXED_POINT_DELTA_AXIS_1,
+                        ),
+                    )
+                } else {
+                    (
+                        Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_2) as f64,
+                        Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_DELTA_AXIS_1) as f64,
+                    )
+                };
+
+                let raw = Point {
+                    x: Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_2)
+                        as f64,
+                    y: Self::get_code(event, EventField::SCROLL_WHEEL_EVENT_POINT_DELTA_AXIS_1)
+                        as f64,
+                };
 
                 Event::MouseWheel {
-                    delta: Point {
-                        x: dx as f64,
-                        y: dy as f64,
-                    },
+                    delta: Point { x: dx, y: dy },
+                    raw: Some(raw),
                 }
             }
             CGEventType::KeyDown | CGEventType::KeyUp => {
-                if state & LISTEN_KEYBOARD == 0 {
-                    return;
-                }
                 let code = Self::get_code(event, EventField::KEYBOARD_EVENT_KEYCODE) as KeyCode;
                 let key = code_to_key(code);
                 let code = Some(code);
@@ -199,15 +269,12 @@ impl PlatformListen {
                 }
             }
             CGEventType::FlagsChanged => {
-                if state & LISTEN_KEYBOARD == 0 {
-                    return;
-                }
 
                 let new_flags = event.get_flags().bits();
                 let old_flags = LAST_FLAGS.swap(new_flags, Ordering::SeqCst);
                 let changed_bit = new_flags ^ old_flags;
                 if changed_bit == 0 {
-                    return;
+                    return None;
                 }
 
                 let code = Self::get_code(event, EventField::KEYBOARD_EVENT_KEYCODE) as KeyCode;
@@ -220,9 +287,9 @@ impl PlatformListen {
                     Event::KeyUp { key, code }
                 }
             }
-            _ => return,
+            _ => return None,
         };
 
-        dispatch(event);
+        Some(event)
     }
 }
@@ -1,7 +1,26 @@
+#[cfg(feature = "listen")]
 mod common;
+#[cfg(feature = "listen")]
 pub(crate) mod core;
+#[cfg(feature = "listen")]
+pub(crate) mod device;
 pub(crate) mod display;
+#[cfg(feature = "listen")]
+pub(crate) mod foreground;
+#[cfg(feature = "listen")]
 pub(crate) mod grab;
+#[cfg(feature = "listen")]
+pub(crate) mod hid;
+#[cfg(feature = "listen")]
+pub(crate) mod hotkey;
+#[cfg(any(feature = "listen", feature = "simulate"))]
 pub(crate) mod keycode;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) mod layout;
+#[cfg(feature = "listen")]
 pub(crate) mod listen;
+pub(crate) mod mouse;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) mod poll;
+#[cfg(any(feature = "listen", feature = "simulate"))]
 pub(crate) mod simulate;
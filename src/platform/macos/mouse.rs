@@ -0,0 +1,100 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use core_foundation::{
+    base::TCFType,
+    string::{CFString, CFStringRef},
+};
+
+use crate::{
+    OsError, Point,
+    platform::{MouseImpl, PlatformMouse},
+};
+
+/// The mouse scaling key of the HID system, the value behind "Tracking speed".
+const ACCELERATION_KEY: &str = "HIDMouseAcceleration";
+/// The scaling of the fastest tracking speed; -1 disables acceleration.
+const MAX_SCALING: f64 = 3.0;
+const LINEAR: f64 = -1.0;
+/// Reported when the HID system cannot be reached.
+const KERN_FAILURE: i32 = 5;
+
+/// The scaling restored when acceleration is enabled again, as `f64` bits.
+static SAVED_SCALING: AtomicU64 = AtomicU64::new(1.0f64.to_bits());
+
+/// Speed and acceleration share one setting: disabling acceleration moves the pointer
+/// linearly, with no speed of its own. Changes last until logout.
+impl MouseImpl for PlatformMouse {
+    fn get_speed() -> Option<f64> {
+        let scaling = get_scaling()?;
+        (scaling >= 0.0).then(|| (scaling / MAX_SCALING).min(1.0))
+    }
+
+    fn set_speed(speed: f64) -> Result<(), OsError> {
+        set_scaling(speed.clamp(0.0, 1.0) * MAX_SCALING)
+    }
+
+    fn get_acceleration() -> Option<bool> {
+        get_scaling().map(|scaling| scaling >= 0.0)
+    }
+
+    fn set_acceleration(enable: bool) -> Result<(), OsError> {
+        let scaling = get_scaling();
+        match (enable, scaling) {
+            (true, Some(scaling)) if scaling >= 0.0 => Ok(()),
+            (true, _) => set_scaling(f64::from_bits(SAVED_SCALING.load(Ordering::SeqCst))),
+            (false, Some(scaling)) if scaling < 0.0 => Ok(()),
+            (false, scaling) => {
+                if let Some(scaling) = scaling {
+                    SAVED_SCALING.store(scaling.to_bits(), Ordering::SeqCst);
+                }
+                set_scaling(LINEAR)
+            }
+        }
+    }
+
+    /// macOS has no lines per notch; the "Scrolling speed" preference already
+    /// accelerates the wheel deltas.
+    fn get_scroll_lines() -> Option<Point> {
+        None
+    }
+}
+
+fn get_scaling() -> Option<f64> {
+    let key = CFString::from_static_string(ACCELERATION_KEY);
+    let mut scaling = 0.0;
+    let result = with_event_status(|handle| unsafe {
+        IOHIDGetAccelerationWithKey(handle, key.as_concrete_TypeRef(), &mut scaling)
+    })?;
+    (result == 0).then_some(scaling)
+}
+
+fn set_scaling(scaling: f64) -> Result<(), OsError> {
+    let key = CFString::from_static_string(ACCELERATION_KEY);
+    let result = with_event_status(|handle| unsafe {
+        IOHIDSetAccelerationWithKey(handle, key.as_concrete_TypeRef(), scaling)
+    })
+    .unwrap_or(KERN_FAILURE);
+    match result {
+        0 => Ok(()),
+        code => Err(OsError { code }),
+    }
+}
+
+/// Runs `f` with a connection to the HID system, `None` if it cannot be opened.
+fn with_event_status(f: impl FnOnce(u32) -> i32) -> Option<i32> {
+    let handle = unsafe { NXOpenEventStatus() };
+    if handle == 0 {
+        return None;
+    }
+    let result = f(handle);
+    unsafe { NXCloseEventStatus(handle) };
+    Some(result)
+}
+
+#[link(name = "IOKit", kind = "framework")]
+unsafe extern "C" {
+    fn NXOpenEventStatus() -> u32;
+    fn NXCloseEventStatus(handle: u32);
+    fn IOHIDGetAccelerationWithKey(handle: u32, key: CFStringRef, acceleration: *mut f64) -> i32;
+    fn IOHIDSetAccelerationWithKey(handle: u32, key: CFStringRef, acceleration: f64) -> i32;
+}
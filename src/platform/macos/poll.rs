@@ -0,0 +1,46 @@
+#[cfg(feature = "listen")]
+use crate::MouseButton;
+use crate::{
+    Key, KeyCode,
+    platform::{PlatformPoll, PollImpl, macos::keycode::code_to_key},
+};
+
+/// `kCGEventSourceStateHIDSystemState`: the state of the hardware, not of injected events.
+const HID_SYSTEM_STATE: i32 = 1;
+
+/// The key codes of the regular keys.
+const KEY_CODES: std::ops::Range<u16> = 0..0x80;
+
+#[cfg(feature = "listen")]
+const BUTTONS: [(u32, MouseButton); 5] = [
+    (0, MouseButton::Left),
+    (1, MouseButton::Right),
+    (2, MouseButton::Middle),
+    (3, MouseButton::Back),
+    (4, MouseButton::Forward),
+];
+
+#[link(name = "CoreGraphics", kind = "framework")]
+unsafe extern "C" {
+    fn CGEventSourceKeyState(state_id: i32, key: u16) -> bool;
+    #[cfg(feature = "listen")]
+    fn CGEventSourceButtonState(state_id: i32, button: u32) -> bool;
+}
+
+impl PollImpl for PlatformPoll {
+    fn pressed_keys() -> Vec<(Key, KeyCode)> {
+        KEY_CODES
+            .filter(|&code| unsafe { CGEventSourceKeyState(HID_SYSTEM_STATE, code) })
+            .map(|code| (code_to_key(KeyCode::from(code)), KeyCode::from(code)))
+            .collect()
+    }
+
+    #[cfg(feature = "listen")]
+    fn pressed_buttons() -> Vec<MouseButton> {
+        BUTTONS
+            .iter()
+            .filter(|&&(number, _)| unsafe { CGEventSourceButtonState(HID_SYSTEM_STATE, number) })
+            .map(|&(_, button)| button)
+            .collect()
+    }
+}
@@ -1,18 +1,24 @@
 use crate::platform::macos::keycode::key_to_code;
-use crate::platform::{PlatformSimulate, SimulateImpl};
+use crate::platform::{INJECTION_MARKER, PlatformSimulate, SimulateImpl};
 use crate::{Event, Key, MouseButton};
-use core_graphics::event::{CGEvent, CGEventType, CGKeyCode, CGMouseButton, ScrollEventUnit};
+use core_graphics::event::{
+    CGEvent, CGEventTapLocation, CGEventType, CGKeyCode, CGMouseButton, EventField, ScrollEventUnit,
+};
 use core_graphics::event_source::{CGEventSource, CGEventSourceStateID};
 
 impl SimulateImpl for PlatformSimulate {
     fn simulate(event: Event) {
         match event {
             Event::MouseMove { delta, .. } => Self::mouse_move(delta.x, delta.y),
+            Event::MouseMoveAbsolute { position } => Self::mouse_move_to(position.x, position.y),
+            // Both are posted as pixels, the raw delta being pixels already
+            Event::MouseWheel { raw: Some(raw), .. } => Self::mouse_wheel(raw.x, raw.y),
             Event::MouseWheel { delta, .. } => Self::mouse_wheel(delta.x, delta.y),
             Event::MouseDown { button, .. } => Self::mouse_button(button, true),
             Event::MouseUp { button, .. } => Self::mouse_button(button, false),
             Event::KeyDown { key, .. } => Self::keyboard(key, true),
             Event::KeyUp { key, .. } => Self::keyboard(key, false),
+            Event::FocusChanged { .. } | Event::ModifiersChanged { .. } => {}
         }
     }
 
@@ -36,7 +42,7 @@ impl SimulateImpl for PlatformSimulate {
             pos,
             CGMouseButton::Left, // This parameter is ignored when moving
         ) {
-            event.post(core_graphics::event::CGEventTapLocation::HID);
+            post(&event);
         }
     }
 
@@ -45,7 +51,7 @@ impl SimulateImpl for PlatformSimulate {
         if let Ok(event) =
             CGEvent::new_scroll_event(source, ScrollEventUnit::PIXEL, 2, dy as i32, dx as i32, 0)
         {
-            event.post(core_graphics::event::CGEventTapLocation::HID);
+            post(&event);
         }
     }
 
@@ -78,7 +84,7 @@ impl SimulateImpl for PlatformSimulate {
                     btn_num,
                 );
             }
-            event.post(core_graphics::event::CGEventTapLocation::HID);
+            post(&event);
         }
     }
 
@@ -91,7 +97,81 @@ impl SimulateImpl for PlatformSimulate {
         };
 
         if let Ok(event) = CGEvent::new_keyboard_event(source, key_code, down) {
-            event.post(core_graphics::event::CGEventTapLocation::HID);
+            post(&event);
         }
     }
+
+    /// macOS key codes are physical positions with no layout-independent meaning, so
+    /// this has no effect.
+    #[cfg(feature = "simulate")]
+    fn key_semantics(_semantics: crate::KeySemantics) {}
+
+    fn text(text: &str) {
+        let source = CGEventSource::new(CGEventSourceStateID::CombinedSessionState).unwrap();
+
+        // The string of a keyboard event replaces the character its key would type, so
+        // dead keys and the layout don't matter
+        for chunk in utf16_chunks(text) {
+            for down in [true, false] {
+                if let Ok(event) = CGEvent::new_keyboard_event(source.clone(), 0, down) {
+                    event.set_string_from_utf16_unchecked(&chunk);
+                    post(&event);
+                }
+            }
+        }
+    }
+
+    fn unicode(text: &str) {
+        // Text is never typed through key codes here
+        Self::text(text);
+    }
+}
+
+/// The most UTF-16 units `CGEventKeyboardSetUnicodeString` passes on per event.
+const MAX_STRING_UNITS: usize = 20;
+
+/// Splits `text` into strings short enough for one keyboard event each, never between
+/// the halves of a surrogate pair.
+fn utf16_chunks(text: &str) -> Vec<Vec<u16>> {
+    let mut chunks = Vec::new();
+    let mut chunk = Vec::with_capacity(MAX_STRING_UNITS);
+    let mut buf = [0u16; 2];
+    for ch in text.chars() {
+        let units = ch.encode_utf16(&mut buf);
+        if chunk.len() + units.len() > MAX_STRING_UNITS {
+            chunks.push(std::mem::take(&mut chunk));
+        }
+        chunk.extend_from_slice(units);
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+/// Tags the event with the crate's injection marker and posts it at the HID level.
+fn post(event: &CGEvent) {
+    event.set_integer_value_field(EventField::EVENT_SOURCE_USER_DATA, INJECTION_MARKER as i64);
+    event.post(CGEventTapLocation::HID);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utf16_chunks() {
+        assert!(utf16_chunks("").is_empty());
+        assert_eq!(utf16_chunks("abc"), vec![vec![0x61, 0x62, 0x63]]);
+
+        let long = "a".repeat(45);
+        let lengths: Vec<usize> = utf16_chunks(&long).iter().map(Vec::len).collect();
+        assert_eq!(lengths, vec![20, 20, 5]);
+
+        // The emoji doesn't fit into the first chunk and moves on whole
+        let text = format!("{}👋", "a".repeat(19));
+        let chunks = utf16_chunks(&text);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(String::from_utf16(&chunks[1]).unwrap(), "👋");
+    }
 }
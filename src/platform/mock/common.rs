@@ -0,0 +1,243 @@
+use std::sync::{
+    Mutex, MutexGuard, PoisonError,
+    atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    CursorImage, DeviceInfo, ForegroundApp, HidUsage, Key, KeyCode, Modifiers, MonitorInfo,
+    MouseButton, Shortcut,
+    event::{Event, Point},
+    grab_release, hotkey, inject, monitors,
+    platform::{
+        DisplayImpl, KeyboardImpl, PlatformDisplay, PlatformKeyboard, PlatformSimulate,
+        SimulateImpl,
+    },
+};
+
+// --- Global Runtime States ---
+
+/// Indicates if the core engine is currently active.
+pub static IS_CORE_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Stands in for the installed hooks, cleared by `Core::stop`.
+pub static IS_INSTALLED: AtomicBool = AtomicBool::new(false);
+/// Indicates if the input listener is active.
+pub static IS_LISTEN_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Indicates if the input grabber is active.
+pub static IS_GRAB_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Lets events for windows of the current process through the grabber. The virtual
+/// environment has no windows, so nothing is excluded.
+pub static GRAB_EXCLUDE_OWN_WINDOWS: AtomicBool = AtomicBool::new(false);
+/// Lets injected events through the grabber.
+pub static GRAB_PASS_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Withholds injected events from subscribers.
+pub static LISTEN_IGNORE_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Reported by `ListenFlags`; keys are delivered the same way either way.
+pub static SCANCODE_MODE: AtomicBool = AtomicBool::new(false);
+/// Whether the process holds the input permissions.
+pub static HAS_PERMISSION: AtomicBool = AtomicBool::new(true);
+/// Set while `Grab::block_input` blocks all input that was not injected.
+pub static IS_INPUT_BLOCKED: AtomicBool = AtomicBool::new(false);
+
+// --- Listen Flags: Define which events to monitor ---
+
+pub static LISTEN_FLAG: AtomicU32 = AtomicU32::new(0);
+pub const LISTEN_MOUSE_MOVE: u32 = 1 << 0;
+pub const LISTEN_MOUSE_BUTTON: u32 = 1 << 1;
+pub const LISTEN_MOUSE_WHEEL: u32 = 1 << 2;
+pub const LISTEN_KEYBOARD: u32 = 1 << 3;
+pub const LISTENS_ALL: u32 =
+    LISTEN_MOUSE_MOVE | LISTEN_MOUSE_BUTTON | LISTEN_MOUSE_WHEEL | LISTEN_KEYBOARD;
+
+// --- Grab Flags: Define which events to intercept/block ---
+
+pub static GRAB_FLAG: AtomicU32 = AtomicU32::new(0);
+pub const GRAB_MOUSE_MOVE: u32 = 1 << 0;
+pub const GRAB_MOUSE_BUTTON: u32 = 1 << 1;
+pub const GRAB_MOUSE_WHEEL: u32 = 1 << 2;
+pub const GRAB_KEYBOARD: u32 = 1 << 3;
+pub const GRAB_ALL: u32 = GRAB_MOUSE_MOVE | GRAB_MOUSE_BUTTON | GRAB_MOUSE_WHEEL | GRAB_KEYBOARD;
+
+/// Sets or clears `bit` in `atomic`.
+pub fn update_state(atomic: &AtomicU32, bit: u32, enable: bool) {
+    if enable {
+        atomic.fetch_or(bit, Ordering::SeqCst);
+    } else {
+        atomic.fetch_and(!bit, Ordering::SeqCst);
+    }
+}
+
+/// The virtual machine the backend runs against.
+pub struct Environment {
+    /// Events that got past the hooks, in the order the OS would have seen them.
+    pub output: Vec<Event>,
+    /// Text typed into the focused application.
+    pub text: String,
+    /// Modifiers held according to `output`.
+    pub modifiers: Modifiers,
+    /// Keys held according to `output`, with their key codes.
+    pub pressed_keys: Vec<(Key, KeyCode)>,
+    /// Mouse buttons held according to `output`.
+    pub pressed_buttons: Vec<MouseButton>,
+    /// Cursor position in physical pixels.
+    pub cursor: Point,
+    /// The cursor image, `None` while the cursor is hidden.
+    pub cursor_image: Option<CursorImage>,
+    pub monitors: Vec<MonitorInfo>,
+    pub devices: Vec<DeviceInfo>,
+    /// HID usages registered by `Hid` subscriptions.
+    pub hid_usages: Vec<HidUsage>,
+    /// Hotkeys registered with the OS hotkey service while the hooks are not installed,
+    /// by id.
+    pub hotkeys: Vec<(u64, Shortcut)>,
+    pub foreground: Option<ForegroundApp>,
+    /// Whether a password field has the focus, see `mock::set_secure_input`.
+    pub secure_input: bool,
+    /// Pointer speed from 0.0 to 1.0, see `Mouse::set_speed`.
+    pub pointer_speed: f64,
+    pub pointer_acceleration: bool,
+    /// Characters and lines scrolled per wheel notch, see `Mouse::get_scroll_lines`.
+    pub scroll_lines: Point,
+}
+
+impl Default for Environment {
+    /// A single 1920x1080 monitor with the cursor at its centre.
+    fn default() -> Self {
+        Self {
+            output: Vec::new(),
+            text: String::new(),
+            modifiers: Modifiers::NONE,
+            pressed_keys: Vec::new(),
+            pressed_buttons: Vec::new(),
+            cursor: Point { x: 960.0, y: 540.0 },
+            cursor_image: None,
+            monitors: vec![MonitorInfo {
+                name: "Mock".to_string(),
+                is_primary: true,
+                offset: (0.0, 0.0),
+                size: (1920.0, 1080.0),
+                scale_factor: 1.0,
+            }],
+            devices: Vec::new(),
+            hid_usages: Vec::new(),
+            hotkeys: Vec::new(),
+            foreground: None,
+            secure_input: false,
+            pointer_speed: 0.5,
+            pointer_acceleration: true,
+            scroll_lines: Point { x: 3.0, y: 3.0 },
+        }
+    }
+}
+
+static ENVIRONMENT: Lazy<Mutex<Environment>> = Lazy::new(Mutex::default);
+
+/// Locks the environment. Never held while subscribers or middleware run.
+pub fn environment() -> MutexGuard<'static, Environment> {
+    ENVIRONMENT.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Plays the role of the OS hooks for one event. Returns `true` if the event got past
+/// them and reached the virtual environment.
+pub fn hook(event: Event, injected: bool) -> bool {
+    // Like `BlockInput`, the block applies before the hooks
+    if !injected && IS_INPUT_BLOCKED.load(Ordering::Relaxed) {
+        return false;
+    }
+    if IS_INSTALLED.load(Ordering::Relaxed)
+        && IS_CORE_RUNNING.load(Ordering::Relaxed)
+        && !inject::process(event, injected, PlatformSimulate::simulate)
+    {
+        return false;
+    }
+    // Like `RegisterHotKey`, an OS hotkey consumes the key press that fires it
+    if let Some(id) = native_hotkey(&event) {
+        hotkey::fire(id);
+        return false;
+    }
+
+    deliver(event);
+    true
+}
+
+/// Returns the OS hotkey a key press fires, given the modifiers held.
+fn native_hotkey(event: &Event) -> Option<u64> {
+    let Event::KeyDown { key, .. } = *event else {
+        return None;
+    };
+    let env = environment();
+    env.hotkeys
+        .iter()
+        .find(|(_, shortcut)| shortcut.key == key && shortcut.modifiers == env.modifiers)
+        .map(|&(id, _)| id)
+}
+
+/// Applies an event to the environment: moves the cursor, tracks the keys and buttons
+/// held and types the characters keys produce.
+fn deliver(event: Event) {
+    let bounds = PlatformDisplay::get_virtual_screen_bounds();
+    let mut env = environment();
+    match event {
+        Event::MouseMove { delta } => {
+            let (x, y, width, height) = bounds;
+            env.cursor = Point {
+                x: (env.cursor.x + delta.x).clamp(x, x + width - 1.0),
+                y: (env.cursor.y + delta.y).clamp(y, y + height - 1.0),
+            };
+        }
+        Event::MouseMoveAbsolute { position } => {
+            let (x, y, width, height) = bounds;
+            env.cursor = Point {
+                x: position.x.clamp(x, x + width - 1.0),
+                y: position.y.clamp(y, y + height - 1.0),
+            };
+        }
+        Event::KeyDown { key, code } => {
+            if !env.pressed_keys.iter().any(|&(held, _)| held == key) {
+                env.pressed_keys.push((key, code.unwrap_or_default()));
+            }
+            match Modifiers::from_key(key) {
+                Some(modifier) => env.modifiers.insert(modifier),
+                None => {
+                    if let Some(ch) = PlatformKeyboard::char_for_key(key, env.modifiers) {
+                        env.text.push(ch);
+                    }
+                }
+            }
+        }
+        Event::KeyUp { key, .. } => {
+            env.pressed_keys.retain(|&(held, _)| held != key);
+            if let Some(modifier) = Modifiers::from_key(key) {
+                env.modifiers.remove(modifier);
+            }
+        }
+        Event::MouseDown { button } if !env.pressed_buttons.contains(&button) => {
+            env.pressed_buttons.push(button);
+        }
+        Event::MouseUp { button } => env.pressed_buttons.retain(|&held| held != button),
+        _ => {}
+    }
+    env.output.push(event);
+}
+
+/// Types text without key events, like `VK_PACKET` input.
+pub fn type_text(text: &str) {
+    environment().text.push_str(text);
+}
+
+/// Restores the default environment and permissions. The core, `Listen`, `Grab` and
+/// the HID registrations keep their state.
+pub fn reset() {
+    let mut env = environment();
+    let hid_usages = std::mem::take(&mut env.hid_usages);
+    let hotkeys = std::mem::take(&mut env.hotkeys);
+    *env = Environment {
+        hid_usages,
+        hotkeys,
+        ..Environment::default()
+    };
+    monitors::invalidate();
+    grab_release::reset();
+    HAS_PERMISSION.store(true, Ordering::SeqCst);
+}
@@ -0,0 +1,108 @@
+use std::{
+    sync::{Condvar, Mutex, PoisonError, atomic::Ordering},
+    time::Duration,
+};
+
+use crate::{
+    CoreError, TapLocation, ThreadPriority, foreground,
+    platform::{
+        CoreImpl, HotkeyImpl, PlatformCore, PlatformHotkeys,
+        mock::common::{HAS_PERMISSION, IS_CORE_RUNNING, IS_INSTALLED},
+    },
+};
+
+/// Guards [`IS_INSTALLED`] for [`STOPPED`].
+static LOOP: Mutex<()> = Mutex::new(());
+/// Wakes `start` once the core is stopped.
+static STOPPED: Condvar = Condvar::new();
+
+impl CoreImpl for PlatformCore {
+    /// Installs the virtual hooks and blocks until [`Core::stop`](crate::Core::stop).
+    fn start() -> Result<(), CoreError> {
+        Self::attach()?;
+
+        let mut guard = LOOP.lock().unwrap_or_else(PoisonError::into_inner);
+        while IS_INSTALLED.load(Ordering::SeqCst) {
+            guard = STOPPED.wait(guard).unwrap_or_else(PoisonError::into_inner);
+        }
+        Ok(())
+    }
+
+    /// Installs the virtual hooks and returns. Events are handled on the thread that
+    /// feeds them, so there is no loop to drive.
+    fn attach() -> Result<(), CoreError> {
+        // Ensure only one instance is running
+        if IS_CORE_RUNNING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Err(CoreError::AlreadyRunning);
+        }
+        if !Self::has_permission(false) {
+            Self::pause();
+            return Err(CoreError::PermissionDenied);
+        }
+
+        let guard = LOOP.lock().unwrap_or_else(PoisonError::into_inner);
+        IS_INSTALLED.store(true, Ordering::SeqCst);
+        foreground::set_watching(true);
+        drop(guard);
+        // The hooks match hotkeys from now on
+        PlatformHotkeys::update();
+        Ok(())
+    }
+
+    /// Attaches on first use; nothing is ever pending.
+    fn poll(_timeout: Duration) -> Result<(), CoreError> {
+        if !IS_INSTALLED.load(Ordering::SeqCst) {
+            Self::attach()?;
+        }
+        Ok(())
+    }
+
+    fn is_runing() -> bool {
+        IS_CORE_RUNNING.load(Ordering::SeqCst)
+    }
+
+    fn pause() {
+        IS_CORE_RUNNING.store(false, Ordering::SeqCst);
+    }
+
+    fn resume() {
+        IS_CORE_RUNNING.store(true, Ordering::SeqCst);
+    }
+
+    fn stop() {
+        Self::pause();
+
+        let guard = LOOP.lock().unwrap_or_else(PoisonError::into_inner);
+        if IS_INSTALLED.swap(false, Ordering::SeqCst) {
+            foreground::set_watching(false);
+        }
+        STOPPED.notify_all();
+        drop(guard);
+        PlatformHotkeys::update();
+    }
+
+    /// The virtual hooks are always active, so this has no effect.
+    fn listen_only(_enable: bool) {}
+
+    /// The virtual environment has no event taps, so this has no effect.
+    fn tap_location(_location: TapLocation) {}
+
+    /// `start` runs no loop, so this has no effect.
+    fn thread_priority(_priority: ThreadPriority) {}
+
+    fn hooks_installed() -> bool {
+        IS_INSTALLED.load(Ordering::SeqCst)
+    }
+
+    fn tap_enabled() -> Option<bool> {
+        None
+    }
+
+    /// Granted unless revoked with [`mock::set_permission`](crate::mock::set_permission).
+    fn has_permission(_grab: bool) -> bool {
+        HAS_PERMISSION.load(Ordering::SeqCst)
+    }
+}
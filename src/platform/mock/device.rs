@@ -0,0 +1,11 @@
+use crate::{
+    device::DeviceInfo,
+    platform::{DevicesImpl, PlatformDevices, mock::common::environment},
+};
+
+impl DevicesImpl for PlatformDevices {
+    /// The devices set with [`mock::set_devices`](crate::mock::set_devices).
+    fn list() -> Vec<DeviceInfo> {
+        environment().devices.clone()
+    }
+}
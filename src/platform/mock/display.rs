@@ -0,0 +1,81 @@
+use crate::{
+    Point, monitors,
+    platform::{
+        CursorImage, CursorPosition, DisplayImpl, MonitorInfo, PlatformDisplay,
+        mock::common::environment,
+    },
+};
+
+/// Coordinates are physical, with logical ones derived from the primary monitor's scale
+/// factor as on Windows.
+impl DisplayImpl for PlatformDisplay {
+    fn get_scale_factor() -> f64 {
+        Self::get_primary_monitor().map_or(1.0, |m| m.scale_factor)
+    }
+
+    fn get_cursor_position() -> Option<(f64, f64)> {
+        let cursor = environment().cursor;
+        Some((cursor.x, cursor.y))
+    }
+
+    /// Moves the virtual cursor without generating a `MouseMove` event.
+    fn set_cursor_position(x: f64, y: f64) {
+        environment().cursor = Point { x, y };
+    }
+
+    fn get_cursor() -> Option<CursorPosition> {
+        let (x, y) = Self::get_cursor_position()?;
+        let scale_factor = Self::get_scale_factor();
+        Some(CursorPosition {
+            physical: Point { x, y },
+            logical: Point {
+                x: x / scale_factor,
+                y: y / scale_factor,
+            },
+            monitor: Self::get_monitor_from_point(x, y),
+        })
+    }
+
+    fn get_cursor_image() -> Option<CursorImage> {
+        environment().cursor_image.clone()
+    }
+
+    fn get_primary_screen_size() -> (f64, f64) {
+        Self::get_primary_monitor().map_or((0.0, 0.0), |m| m.size)
+    }
+
+    fn get_virtual_screen_size() -> (f64, f64) {
+        let (_, _, w, h) = Self::get_virtual_screen_bounds();
+        (w, h)
+    }
+
+    /// The bounding box of all monitors.
+    fn get_virtual_screen_bounds() -> (f64, f64, f64, f64) {
+        let bounds = Self::get_available_monitors()
+            .iter()
+            .map(MonitorInfo::bounds)
+            .reduce(|bounds, monitor| bounds.union(&monitor))
+            .unwrap_or_default();
+        (bounds.x, bounds.y, bounds.width, bounds.height)
+    }
+
+    fn get_available_monitors() -> Vec<MonitorInfo> {
+        monitors::cached(|| environment().monitors.clone())
+    }
+
+    fn get_primary_monitor() -> Option<MonitorInfo> {
+        Self::get_available_monitors()
+            .into_iter()
+            .find(|m| m.is_primary)
+    }
+
+    fn get_current_monitor() -> Option<MonitorInfo> {
+        Self::get_cursor_position().and_then(|(x, y)| Self::get_monitor_from_point(x, y))
+    }
+
+    fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo> {
+        Self::get_available_monitors()
+            .into_iter()
+            .find(|m| m.contains(Point { x, y }))
+    }
+}
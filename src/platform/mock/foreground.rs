@@ -0,0 +1,16 @@
+use crate::{
+    ForegroundApp,
+    platform::{ForegroundImpl, PlatformForeground, mock::common::environment},
+};
+
+impl ForegroundImpl for PlatformForeground {
+    /// The application focused with [`mock::focus`](crate::mock::focus).
+    fn query() -> Option<ForegroundApp> {
+        environment().foreground.clone()
+    }
+
+    /// Set with [`mock::set_secure_input`](crate::mock::set_secure_input).
+    fn is_secure_input() -> bool {
+        environment().secure_input
+    }
+}
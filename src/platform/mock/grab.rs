@@ -0,0 +1,87 @@
+use std::sync::atomic::Ordering;
+
+use crate::{
+    GrabFlags, StateError,
+    platform::{
+        GrabImpl, PlatformGrab,
+        mock::common::{
+            GRAB_ALL, GRAB_EXCLUDE_OWN_WINDOWS, GRAB_FLAG, GRAB_KEYBOARD, GRAB_MOUSE_BUTTON,
+            GRAB_MOUSE_MOVE, GRAB_MOUSE_WHEEL, GRAB_PASS_INJECTED, IS_GRAB_RUNNING,
+            IS_INPUT_BLOCKED, update_state,
+        },
+    },
+};
+
+impl GrabImpl for PlatformGrab {
+    fn start() -> bool {
+        if IS_GRAB_RUNNING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return false;
+        }
+
+        GRAB_FLAG.fetch_or(GRAB_ALL, Ordering::SeqCst);
+        true
+    }
+
+    fn is_runing() -> bool {
+        IS_GRAB_RUNNING.load(Ordering::SeqCst)
+    }
+
+    fn pause() {
+        IS_GRAB_RUNNING.store(false, Ordering::SeqCst);
+    }
+
+    fn resume() {
+        IS_GRAB_RUNNING.store(true, Ordering::SeqCst);
+    }
+
+    fn stop() {
+        Self::pause();
+        GRAB_FLAG.store(0, Ordering::SeqCst);
+    }
+
+    fn mouse_move(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_MOUSE_MOVE, enable);
+    }
+
+    fn mouse_wheel(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_MOUSE_WHEEL, enable);
+    }
+
+    fn mouse_button(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_MOUSE_BUTTON, enable);
+    }
+
+    fn keyboard(enable: bool) {
+        update_state(&GRAB_FLAG, GRAB_KEYBOARD, enable);
+    }
+
+    /// The virtual environment has no windows, so this only changes the reported flags.
+    fn exclude_own_windows(enable: bool) {
+        GRAB_EXCLUDE_OWN_WINDOWS.store(enable, Ordering::SeqCst);
+    }
+
+    fn pass_injected(enable: bool) {
+        GRAB_PASS_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
+    fn flags() -> GrabFlags {
+        let state = GRAB_FLAG.load(Ordering::SeqCst);
+        GrabFlags {
+            running: Self::is_runing(),
+            mouse_move: state & GRAB_MOUSE_MOVE != 0,
+            mouse_button: state & GRAB_MOUSE_BUTTON != 0,
+            mouse_wheel: state & GRAB_MOUSE_WHEEL != 0,
+            keyboard: state & GRAB_KEYBOARD != 0,
+            exclude_own_windows: GRAB_EXCLUDE_OWN_WINDOWS.load(Ordering::SeqCst),
+            pass_injected: GRAB_PASS_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    fn block_input(block: bool) -> Result<(), StateError> {
+        IS_INPUT_BLOCKED.store(block, Ordering::SeqCst);
+        Ok(())
+    }
+}
@@ -0,0 +1,11 @@
+use crate::{
+    hid,
+    platform::{HidImpl, PlatformHid, mock::common::environment},
+};
+
+impl HidImpl for PlatformHid {
+    /// Registers the subscribed usages with the virtual environment.
+    fn update() {
+        environment().hid_usages = hid::usages();
+    }
+}
@@ -0,0 +1,13 @@
+use crate::{
+    hotkey,
+    platform::{HotkeyImpl, PlatformHotkeys, mock::common::environment},
+};
+
+impl HotkeyImpl for PlatformHotkeys {
+    /// Registers the hotkeys with the virtual environment, which fires them for key
+    /// presses while the hooks are not installed.
+    fn update() {
+        let hotkeys = hotkey::native();
+        environment().hotkeys = hotkeys;
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{
+    Key, Modifiers,
+    platform::{KeyboardImpl, PlatformKeyboard},
+};
+
+/// A US QWERTY layout: each key with the character it types without and with Shift.
+#[rustfmt::skip]
+const US_LAYOUT: &[(Key, char, char)] = &[
+    (Key::KeyA, 'a', 'A'), (Key::KeyB, 'b', 'B'), (Key::KeyC, 'c', 'C'), (Key::KeyD, 'd', 'D'),
+    (Key::KeyE, 'e', 'E'), (Key::KeyF, 'f', 'F'), (Key::KeyG, 'g', 'G'), (Key::KeyH, 'h', 'H'),
+    (Key::KeyI, 'i', 'I'), (Key::KeyJ, 'j', 'J'), (Key::KeyK, 'k', 'K'), (Key::KeyL, 'l', 'L'),
+    (Key::KeyM, 'm', 'M'), (Key::KeyN, 'n', 'N'), (Key::KeyO, 'o', 'O'), (Key::KeyP, 'p', 'P'),
+    (Key::KeyQ, 'q', 'Q'), (Key::KeyR, 'r', 'R'), (Key::KeyS, 's', 'S'), (Key::KeyT, 't', 'T'),
+    (Key::KeyU, 'u', 'U'), (Key::KeyV, 'v', 'V'), (Key::KeyW, 'w', 'W'), (Key::KeyX, 'x', 'X'),
+    (Key::KeyY, 'y', 'Y'), (Key::KeyZ, 'z', 'Z'),
+    (Key::Digit1, '1', '!'), (Key::Digit2, '2', '@'), (Key::Digit3, '3', '#'),
+    (Key::Digit4, '4', '$'), (Key::Digit5, '5', '%'), (Key::Digit6, '6', '^'),
+    (Key::Digit7, '7', '&'), (Key::Digit8, '8', '*'), (Key::Digit9, '9', '('),
+    (Key::Digit0, '0', ')'),
+    (Key::Backquote, '`', '~'), (Key::Minus, '-', '_'), (Key::Equal, '=', '+'),
+    (Key::BracketLeft, '[', '{'), (Key::BracketRight, ']', '}'), (Key::Backslash, '\\', '|'),
+    (Key::Semicolon, ';', ':'), (Key::Quote, '\'', '"'), (Key::Comma, ',', '<'),
+    (Key::Period, '.', '>'), (Key::Slash, '/', '?'),
+    (Key::Space, ' ', ' '), (Key::Enter, '\n', '\n'), (Key::Tab, '\t', '\t'),
+];
+
+impl KeyboardImpl for PlatformKeyboard {
+    fn key_for_char(ch: char) -> Option<(Key, Modifiers)> {
+        US_LAYOUT.iter().find_map(|&(key, plain, shifted)| {
+            if ch == plain {
+                Some((key, Modifiers::NONE))
+            } else if ch == shifted {
+                Some((key, Modifiers::SHIFT))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Ctrl, Alt and Meta shortcuts type nothing.
+    fn char_for_key(key: Key, modifiers: Modifiers) -> Option<char> {
+        if modifiers.contains(Modifiers::CONTROL)
+            || modifiers.contains(Modifiers::ALT)
+            || modifiers.contains(Modifiers::META)
+        {
+            return None;
+        }
+        let &(_, plain, shifted) = US_LAYOUT.iter().find(|(k, ..)| *k == key)?;
+        Some(if modifiers.contains(Modifiers::SHIFT) {
+            shifted
+        } else {
+            plain
+        })
+    }
+}
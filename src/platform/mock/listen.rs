@@ -0,0 +1,146 @@
+use std::sync::{Arc, atomic::Ordering};
+
+use crate::{
+    ListenFlags,
+    dispatcher::{
+        CALLBACKS, NEXT_ID, Status, Subscriber, dispatch, has_active, refresh, remove_all,
+    },
+    event::Event,
+    foreground, held_modifiers, pressed,
+    platform::{
+        ListenImpl, PlatformListen,
+        mock::common::{
+            IS_LISTEN_RUNNING, LISTEN_FLAG, LISTEN_IGNORE_INJECTED, LISTEN_KEYBOARD,
+            LISTEN_MOUSE_BUTTON, LISTEN_MOUSE_MOVE, LISTEN_MOUSE_WHEEL, LISTENS_ALL, SCANCODE_MODE,
+            update_state,
+        },
+    },
+    subscription::SubscriptionHandle,
+};
+
+impl ListenImpl for PlatformListen {
+    fn start() -> bool {
+        if IS_LISTEN_RUNNING
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return false;
+        }
+
+        LISTEN_FLAG.store(LISTENS_ALL, Ordering::SeqCst);
+        true
+    }
+
+    fn is_runing() -> bool {
+        IS_LISTEN_RUNNING.load(Ordering::SeqCst)
+    }
+
+    fn pause() {
+        IS_LISTEN_RUNNING.store(false, Ordering::SeqCst);
+    }
+
+    fn resume() {
+        IS_LISTEN_RUNNING.store(true, Ordering::SeqCst);
+    }
+
+    fn stop() {
+        LISTEN_FLAG.store(0, Ordering::SeqCst);
+        Self::pause();
+        Self::unsubscribe_all();
+    }
+
+    fn mouse_move(enable: bool) {
+        update_state(&LISTEN_FLAG, LISTEN_MOUSE_MOVE, enable);
+    }
+
+    fn mouse_wheel(enable: bool) {
+        update_state(&LISTEN_FLAG, LISTEN_MOUSE_WHEEL, enable);
+    }
+
+    fn mouse_button(enable: bool) {
+        update_state(&LISTEN_FLAG, LISTEN_MOUSE_BUTTON, enable);
+    }
+
+    fn keyboard(enable: bool) {
+        update_state(&LISTEN_FLAG, LISTEN_KEYBOARD, enable);
+    }
+
+    /// Virtual keys carry no scan codes, so this only changes the reported flags.
+    fn scancode_mode(enable: bool) {
+        SCANCODE_MODE.store(enable, Ordering::SeqCst);
+    }
+
+    fn ignore_injected(enable: bool) {
+        LISTEN_IGNORE_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
+    fn flags() -> ListenFlags {
+        let state = LISTEN_FLAG.load(Ordering::SeqCst);
+        ListenFlags {
+            running: Self::is_runing(),
+            mouse_move: state & LISTEN_MOUSE_MOVE != 0,
+            mouse_button: state & LISTEN_MOUSE_BUTTON != 0,
+            mouse_wheel: state & LISTEN_MOUSE_WHEEL != 0,
+            keyboard: state & LISTEN_KEYBOARD != 0,
+            scancode_mode: SCANCODE_MODE.load(Ordering::SeqCst),
+            ignore_injected: LISTEN_IGNORE_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    fn subscribe<F>(callback: F) -> SubscriptionHandle
+    where
+        F: Fn(Event) + Send + Sync + 'static,
+    {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        CALLBACKS.insert(
+            id,
+            Subscriber {
+                status: Status::Active,
+                callback: Arc::new(callback),
+            },
+        );
+        refresh();
+        SubscriptionHandle { id }
+    }
+
+    fn unsubscribe_all() {
+        remove_all();
+    }
+}
+
+impl PlatformListen {
+    /// Dispatches an event to subscribers if its category is being listened to.
+    /// A `ModifiersChanged` follows the key events that change the modifiers held.
+    pub(crate) fn handle(event: Event, injected: bool) {
+        pressed::track(&event);
+        let modifiers = held_modifiers::update(&event);
+        Self::deliver(event, injected);
+        if let Some(modifiers) = modifiers {
+            Self::deliver(Event::ModifiersChanged { modifiers }, injected);
+        }
+    }
+
+    fn deliver(event: Event, injected: bool) {
+        if !has_active() || !IS_LISTEN_RUNNING.load(Ordering::Relaxed) {
+            return;
+        }
+        if injected && LISTEN_IGNORE_INJECTED.load(Ordering::Relaxed) {
+            return;
+        }
+
+        let bit = match event {
+            Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. } => LISTEN_MOUSE_MOVE,
+            Event::MouseWheel { .. } => LISTEN_MOUSE_WHEEL,
+            Event::MouseDown { .. } | Event::MouseUp { .. } => LISTEN_MOUSE_BUTTON,
+            Event::KeyDown { .. } | Event::KeyUp { .. } | Event::ModifiersChanged { .. } => {
+                LISTEN_KEYBOARD
+            }
+            Event::FocusChanged { .. } => return dispatch(event),
+        };
+        if LISTEN_FLAG.load(Ordering::Relaxed) & bit == 0 || !foreground::listen_allowed() {
+            return;
+        }
+
+        dispatch(event);
+    }
+}
@@ -0,0 +1,14 @@
+//! In-memory backend selected by the `mock` feature, driven through [`crate::mock`].
+pub(crate) mod common;
+mod core;
+mod device;
+mod display;
+mod foreground;
+mod grab;
+mod hid;
+mod hotkey;
+mod layout;
+mod listen;
+mod mouse;
+mod poll;
+mod simulate;
@@ -0,0 +1,28 @@
+use crate::{
+    OsError, Point,
+    platform::{MouseImpl, PlatformMouse, mock::common::environment},
+};
+
+impl MouseImpl for PlatformMouse {
+    fn get_speed() -> Option<f64> {
+        Some(environment().pointer_speed)
+    }
+
+    fn set_speed(speed: f64) -> Result<(), OsError> {
+        environment().pointer_speed = speed.clamp(0.0, 1.0);
+        Ok(())
+    }
+
+    fn get_acceleration() -> Option<bool> {
+        Some(environment().pointer_acceleration)
+    }
+
+    fn set_acceleration(enable: bool) -> Result<(), OsError> {
+        environment().pointer_acceleration = enable;
+        Ok(())
+    }
+
+    fn get_scroll_lines() -> Option<Point> {
+        Some(environment().scroll_lines)
+    }
+}
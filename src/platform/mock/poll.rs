@@ -0,0 +1,14 @@
+use crate::{
+    Key, KeyCode, MouseButton,
+    platform::{PlatformPoll, PollImpl, mock::common::environment},
+};
+
+impl PollImpl for PlatformPoll {
+    fn pressed_keys() -> Vec<(Key, KeyCode)> {
+        environment().pressed_keys.clone()
+    }
+
+    fn pressed_buttons() -> Vec<MouseButton> {
+        environment().pressed_buttons.clone()
+    }
+}
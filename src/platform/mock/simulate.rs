@@ -0,0 +1,86 @@
+use crate::{
+    Key, KeySemantics, Modifiers, MouseButton,
+    event::{Event, Point},
+    platform::{
+        DisplayImpl, KeyboardImpl, PlatformDisplay, PlatformKeyboard, PlatformSimulate,
+        SimulateImpl,
+        mock::common::{hook, type_text},
+    },
+};
+
+/// Injected events go through the virtual hooks like real input, marked as injected.
+impl SimulateImpl for PlatformSimulate {
+    fn simulate(event: Event) {
+        hook(event, true);
+    }
+
+    fn mouse_move(delta_x: f64, delta_y: f64) {
+        Self::simulate(Event::MouseMove {
+            delta: Point {
+                x: delta_x,
+                y: delta_y,
+            },
+        });
+    }
+
+    /// Moves by the distance to the target, in logical units like on Windows.
+    fn mouse_move_to(x: f64, y: f64) {
+        let scale_factor = PlatformDisplay::get_scale_factor();
+        let (cx, cy) = PlatformDisplay::get_cursor_position().unwrap_or_default();
+        Self::mouse_move(x * scale_factor - cx, y * scale_factor - cy);
+    }
+
+    fn mouse_wheel(delta_x: f64, delta_y: f64) {
+        Self::simulate(Event::MouseWheel {
+            delta: Point {
+                x: delta_x,
+                y: delta_y,
+            },
+            raw: None,
+        });
+    }
+
+    fn mouse_button(button: MouseButton, down: bool) {
+        Self::simulate(if down {
+            Event::MouseDown { button }
+        } else {
+            Event::MouseUp { button }
+        });
+    }
+
+    fn keyboard(key: Key, down: bool) {
+        Self::simulate(if down {
+            Event::KeyDown { key, code: None }
+        } else {
+            Event::KeyUp { key, code: None }
+        });
+    }
+
+    /// Presses the keys of the layout for the characters it has, holding Shift where
+    /// needed. Other characters are typed without key events, like `unicode`.
+    fn text(text: &str) {
+        for ch in text.chars() {
+            let Some((key, modifiers)) = PlatformKeyboard::key_for_char(ch) else {
+                type_text(ch.encode_utf8(&mut [0; 4]));
+                continue;
+            };
+            let shift = modifiers.contains(Modifiers::SHIFT);
+            if shift {
+                Self::keyboard(Key::ShiftLeft, true);
+            }
+            Self::keyboard(key, true);
+            Self::keyboard(key, false);
+            if shift {
+                Self::keyboard(Key::ShiftLeft, false);
+            }
+        }
+    }
+
+    /// Types the text without key events, so the hooks never see it.
+    fn unicode(text: &str) {
+        type_text(text);
+    }
+
+    /// Virtual keys map to themselves, so this has no effect.
+    fn key_semantics(_semantics: KeySemantics) {}
+}
@@ -1,22 +1,66 @@
 // #[cfg(target_os = "linux")]
 // mod linux;
-#[cfg(target_os = "macos")]
+#[cfg(all(target_os = "android", not(feature = "mock")))]
+mod android;
+#[cfg(all(target_os = "macos", not(feature = "mock")))]
 mod macos;
-#[cfg(target_os = "windows")]
+#[cfg(feature = "mock")]
+pub(crate) mod mock;
+#[cfg(all(target_os = "windows", not(feature = "mock")))]
 mod windows;
 
-use crate::{CoreError, Event, MonitorInfo, SubscriptionHandle};
-
+#[cfg(feature = "listen")]
+use std::time::Duration;
+
+#[cfg(any(feature = "listen", feature = "simulate"))]
+use crate::Event;
+#[cfg(feature = "listen")]
+use crate::{
+    CoreError, DeviceInfo, ForegroundApp, GrabFlags, ListenFlags, StateError, SubscriptionHandle,
+    TapLocation, ThreadPriority,
+};
+use crate::{CursorImage, CursorPosition, MonitorInfo, OsError, Point};
+
+/// Tag attached to every event injected by `Simulate`, so the hooks can recognise
+/// the crate's own output when it comes back around.
+#[cfg(any(feature = "listen", feature = "simulate"))]
+#[cfg_attr(any(feature = "mock", target_os = "android"), allow(dead_code))]
+pub(crate) const INJECTION_MARKER: usize = 0x5249_4E50; // "RINP"
+
+#[cfg(feature = "listen")]
 pub(crate) struct PlatformCore;
+#[cfg(feature = "listen")]
 pub(crate) struct PlatformListen;
+#[cfg(feature = "listen")]
 pub(crate) struct PlatformGrab;
+#[cfg(feature = "listen")]
+pub(crate) struct PlatformHid;
+#[cfg(feature = "listen")]
+pub(crate) struct PlatformDevices;
+#[cfg(feature = "listen")]
+pub(crate) struct PlatformForeground;
+#[cfg(feature = "listen")]
+pub(crate) struct PlatformHotkeys;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) struct PlatformPoll;
+#[cfg(any(feature = "listen", feature = "simulate"))]
 pub(crate) struct PlatformSimulate;
 pub(crate) struct PlatformDisplay;
+pub(crate) struct PlatformMouse;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) struct PlatformKeyboard;
 
+#[cfg(feature = "listen")]
 pub(crate) trait CoreImpl {
     /// Starts the core engine.
     fn start() -> Result<(), CoreError>;
 
+    /// Starts the core engine on the event loop of the calling thread, without running it.
+    fn attach() -> Result<(), CoreError>;
+
+    /// Processes pending events on the calling thread, waiting at most `timeout`.
+    fn poll(timeout: Duration) -> Result<(), CoreError>;
+
     /// Checks if the core engine is runing.
     fn is_runing() -> bool;
 
@@ -28,11 +72,30 @@ pub(crate) trait CoreImpl {
 
     /// Stops the core engine.
     fn stop();
+
+    /// Prefers a passive event tap while `Grab` is not started.
+    fn listen_only(enable: bool);
+
+    /// Chooses where the event tap is installed.
+    fn tap_location(location: TapLocation);
+
+    /// Sets the priority `start` gives the core thread.
+    fn thread_priority(priority: ThreadPriority);
+
+    /// Checks if the hooks or the event tap are installed.
+    fn hooks_installed() -> bool;
+
+    /// Checks if the event tap is enabled, `None` without one.
+    fn tap_enabled() -> Option<bool>;
+
+    /// Checks if the process may observe input, or block it with `grab`.
+    fn has_permission(grab: bool) -> bool;
 }
 
+#[cfg(feature = "listen")]
 pub(crate) trait ListenImpl {
-    /// Starts listening for events.
-    fn start();
+    /// Starts listening for events. Returns `false` if already listening.
+    fn start() -> bool;
 
     /// Checks if listening for events is active.
     fn is_runing() -> bool;
@@ -58,6 +121,15 @@ pub(crate) trait ListenImpl {
     /// Keyboard listening
     fn keyboard(enable: bool);
 
+    /// Scan code keyboard delivery
+    fn scancode_mode(enable: bool);
+
+    /// Never deliver events injected by `Simulate`
+    fn ignore_injected(enable: bool);
+
+    /// Current configuration
+    fn flags() -> ListenFlags;
+
     /// Subscribe to input events
     fn subscribe<F>(callback: F) -> SubscriptionHandle
     where
@@ -67,9 +139,10 @@ pub(crate) trait ListenImpl {
     fn unsubscribe_all();
 }
 
+#[cfg(feature = "listen")]
 pub(crate) trait GrabImpl {
-    /// Starts grabbing (blocking) events.
-    fn start();
+    /// Starts grabbing (blocking) events. Returns `false` if already grabbing.
+    fn start() -> bool;
 
     /// Checks if grabbing is active.
     fn is_runing() -> bool;
@@ -94,8 +167,61 @@ pub(crate) trait GrabImpl {
 
     /// Keyboard grab
     fn keyboard(enable: bool);
+
+    /// Never block events for windows of the current process
+    fn exclude_own_windows(enable: bool);
+
+    /// Never block events injected by `Simulate`
+    fn pass_injected(enable: bool);
+
+    /// Current configuration
+    fn flags() -> GrabFlags;
+
+    /// Blocks or unblocks all physical input. Called on the thread holding the block.
+    fn block_input(block: bool) -> Result<(), StateError>;
 }
 
+#[cfg(feature = "listen")]
+pub(crate) trait HidImpl {
+    /// Applies the usages returned by `hid::usages` to the backend. Called whenever the
+    /// subscriptions change.
+    fn update();
+}
+
+#[cfg(feature = "listen")]
+pub(crate) trait HotkeyImpl {
+    /// Applies the hotkeys returned by `hotkey::native` to the OS hotkey service. Called
+    /// whenever the registrations change and when the hooks are installed or removed.
+    fn update();
+}
+
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) trait PollImpl {
+    /// Reads the keys held down right now, with their platform key codes.
+    fn pressed_keys() -> Vec<(crate::Key, crate::KeyCode)>;
+
+    /// Reads the mouse buttons held down right now.
+    #[cfg(feature = "listen")]
+    fn pressed_buttons() -> Vec<crate::MouseButton>;
+}
+
+#[cfg(feature = "listen")]
+pub(crate) trait DevicesImpl {
+    /// Lists the connected input devices.
+    fn list() -> Vec<DeviceInfo>;
+}
+
+#[cfg(feature = "listen")]
+pub(crate) trait ForegroundImpl {
+    /// Queries the application owning the foreground window.
+    fn query() -> Option<ForegroundApp>;
+
+    /// Checks whether keyboard input currently goes to a password field.
+    fn is_secure_input() -> bool;
+}
+
+#[cfg(any(feature = "listen", feature = "simulate"))]
+#[cfg_attr(not(feature = "simulate"), allow(dead_code))]
 pub(crate) trait SimulateImpl {
     /// Simulates an input event.
     fn simulate(event: Event);
@@ -114,6 +240,25 @@ pub(crate) trait SimulateImpl {
 
     /// Simulates key up or down.
     fn keyboard(key: crate::Key, down: bool);
+
+    /// Types text into the focused application.
+    fn text(text: &str);
+
+    /// Types text as Unicode input, without going through the keyboard layout.
+    fn unicode(text: &str);
+
+    /// Chooses how simulated keys are resolved.
+    #[cfg(feature = "simulate")]
+    fn key_semantics(semantics: crate::KeySemantics);
+}
+
+#[cfg(any(feature = "listen", feature = "simulate"))]
+pub(crate) trait KeyboardImpl {
+    /// Finds the key and modifiers that type `ch` on the active layout.
+    fn key_for_char(ch: char) -> Option<(crate::Key, crate::Modifiers)>;
+
+    /// Returns the character `key` types with `modifiers` on the active layout.
+    fn char_for_key(key: crate::Key, modifiers: crate::Modifiers) -> Option<char>;
 }
 
 pub(crate) trait DisplayImpl {
@@ -123,6 +268,15 @@ pub(crate) trait DisplayImpl {
     /// Returns the current cursor position on the screen.
     fn get_cursor_position() -> Option<(f64, f64)>;
 
+    /// Warps the cursor to the given position without generating an input event.
+    fn set_cursor_position(x: f64, y: f64);
+
+    /// Returns the current cursor position in both coordinate spaces, with its monitor.
+    fn get_cursor() -> Option<CursorPosition>;
+
+    /// Returns the image of the cursor, or `None` while it is hidden.
+    fn get_cursor_image() -> Option<CursorImage>;
+
     /// Returns the size of the primary monitor.
     fn get_primary_screen_size() -> (f64, f64);
 
@@ -144,3 +298,20 @@ pub(crate) trait DisplayImpl {
     /// Returns information about the monitor at the given point (x, y).
     fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo>;
 }
+
+pub(crate) trait MouseImpl {
+    /// Returns the pointer speed, from 0.0 (slowest) to 1.0 (fastest).
+    fn get_speed() -> Option<f64>;
+
+    /// Sets the pointer speed, from 0.0 (slowest) to 1.0 (fastest).
+    fn set_speed(speed: f64) -> Result<(), OsError>;
+
+    /// Checks if pointer acceleration is enabled.
+    fn get_acceleration() -> Option<bool>;
+
+    /// Enables or disables pointer acceleration.
+    fn set_acceleration(enable: bool) -> Result<(), OsError>;
+
+    /// Returns the characters (`x`) and lines (`y`) scrolled per wheel notch.
+    fn get_scroll_lines() -> Option<Point>;
+}
@@ -12,6 +12,15 @@ pub static IS_CORE_RUNNING: AtomicBool = AtomicBool::new(false);
 pub static IS_LISTEN_RUNNING: AtomicBool = AtomicBool::new(false);
 /// Indicates if the input grabber (interceptor) is active.
 pub static IS_GRAB_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Lets events for windows of the current process through the grabber.
+pub static GRAB_EXCLUDE_OWN_WINDOWS: AtomicBool = AtomicBool::new(false);
+/// Lets events carrying the crate's injection marker through the grabber.
+pub static GRAB_PASS_INJECTED: AtomicBool = AtomicBool::new(false);
+/// Withholds events carrying the crate's injection marker from subscribers.
+pub static LISTEN_IGNORE_INJECTED: AtomicBool = AtomicBool::new(false);
+
+/// Delivers keyboard events from Raw Input scan codes instead of the low-level hook.
+pub static SCANCODE_MODE: AtomicBool = AtomicBool::new(false);
 
 /// Stores the global window handle (HWND) for reference across threads.
 pub static GLOBAL_HWND: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
@@ -102,7 +111,6 @@ pub mod utils {
     }
 
     /// Reconstructs the full scan code, including extended key prefixes (0xE0).
-    #[allow(dead_code)]
     pub(crate) fn get_scan_code(kb: &KBDLLHOOKSTRUCT) -> u32 {
         // The right-hand SHIFT, NumLock, and some other keys are handled specifically.
         // Reference: https://learn.microsoft.com/en-us/windows/win32/inputdev/about-keyboard-input#:~:text=The%20right%2Dhand%20SHIFT%20key%20is%20not%20considered%20an%20extended%2Dkey%2C%20it%20has%20a%20separate%20scan%20code%20instead.
@@ -2,37 +2,68 @@ use std::{
     ffi::c_void,
     mem::size_of,
     ptr::null_mut,
-    sync::atomic::{AtomicPtr, AtomicU32, Ordering},
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicU8, AtomicU32, Ordering},
+    time::Duration,
 };
 
 use windows::{
     Win32::{
         Foundation::{HINSTANCE, HWND, LPARAM, LRESULT, WPARAM},
-        System::{LibraryLoader::GetModuleHandleW, Threading::GetCurrentThreadId},
+        System::{
+            LibraryLoader::GetModuleHandleW,
+            Threading::{
+                GetCurrentThread, GetCurrentThreadId, SetThreadPriority, THREAD_PRIORITY_HIGHEST,
+                THREAD_PRIORITY_TIME_CRITICAL,
+            },
+        },
         UI::{
             Input::{RAWINPUTDEVICE, RIDEV_INPUTSINK, RegisterRawInputDevices},
             WindowsAndMessaging::{
-                CallNextHookEx, CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW,
-                HC_ACTION, HHOOK, HWND_MESSAGE, MSG, PostMessageW, PostThreadMessageW,
+                CallNextHookEx, CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW,
+                GetMessageW, HC_ACTION, HHOOK, MSG, MsgWaitForMultipleObjects, PM_NOREMOVE,
+                PM_REMOVE, PeekMessageW, PostMessageW, PostThreadMessageW, QS_ALLINPUT,
                 RegisterClassW, SetWindowsHookExW, UnhookWindowsHookEx, WH_KEYBOARD_LL,
-                WH_MOUSE_LL, WINDOWS_HOOK_ID, WM_INPUT, WM_QUIT, WNDCLASSW,
+                WH_MOUSE_LL, WINDOWS_HOOK_ID, WM_APP, WM_DISPLAYCHANGE, WM_DPICHANGED, WM_INPUT,
+                WM_MOUSEHWHEEL, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_QUIT, WM_SETTINGCHANGE, WNDCLASSW,
+                WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
             },
         },
     },
     core::w,
 };
 
-use crate::platform::{
-    CoreError, CoreImpl, PlatformCore, PlatformGrab, PlatformListen,
-    windows::{
-        common::{GLOBAL_HWND, IS_CORE_RUNNING, IS_GRAB_RUNNING},
-        grab::{KEYBOARD_HOOK, MOUSE_HOOK},
+use crate::{
+    Event, OsError, TapLocation, ThreadPriority, dispatcher,
+    grab_release::{self, Blocker},
+    monitors, pipeline,
+    platform::{
+        CoreError, CoreImpl, HotkeyImpl, PlatformCore, PlatformForeground, PlatformGrab,
+        PlatformHid, PlatformHotkeys, PlatformListen, PlatformSimulate, SimulateImpl,
+        windows::{
+            common::{GLOBAL_HWND, IS_CORE_RUNNING, IS_GRAB_RUNNING},
+            grab::{KEYBOARD_HOOK, MOUSE_HOOK},
+            hid::WM_UPDATE_HID,
+            os_error,
+        },
     },
+    raw_event,
+    sequence::{self, EventSource},
+    state,
 };
 
 /// Stores the ID of the thread running the message loop to allow remote shutdown.
 static CORE_THREAD_ID: AtomicU32 = AtomicU32::new(0);
 
+/// Set while the core runs on a message loop owned by the application.
+static IS_ATTACHED: AtomicBool = AtomicBool::new(false);
+
+/// The [`ThreadPriority`] `start` gives the core thread, as its discriminant.
+static THREAD_PRIORITY: AtomicU8 = AtomicU8::new(0);
+
+/// Posted to the raw input window to tear the core down on the thread that owns it,
+/// without quitting the application's message loop.
+const WM_DETACH_CORE: u32 = WM_APP + 2;
+
 impl CoreImpl for PlatformCore {
     /// Starts the core engine and blocks the current thread with a Windows message loop.
     ///
@@ -53,19 +84,15 @@ impl CoreImpl for PlatformCore {
     fn start() -> Result<(), CoreError> {
         // Ensure only one instance is running
         if Self::is_run() {
-            return Ok(());
+            return Err(CoreError::AlreadyRunning);
         }
 
-        // Initialize a hidden window to receive Raw Input messages (WM_INPUT)
-        if GLOBAL_HWND.load(Ordering::SeqCst).is_null() {
-            let hwnd: HWND = Self::setup_raw_input_window()?;
-            GLOBAL_HWND.store(hwnd.0, Ordering::SeqCst);
+        Self::apply_thread_priority();
+        if let Err(err) = Self::install() {
+            Self::pause();
+            return Err(err);
         }
 
-        // Set up low-level system hooks for mouse and keyboard
-        Self::handle_hook(WH_MOUSE_LL)?;
-        Self::handle_hook(WH_KEYBOARD_LL)?;
-
         unsafe {
             // Save current thread ID so stop() can send WM_QUIT to this thread
             CORE_THREAD_ID.store(GetCurrentThreadId(), Ordering::SeqCst);
@@ -78,12 +105,48 @@ impl CoreImpl for PlatformCore {
                 DispatchMessageW(&msg);
             }
         }
+        PlatformForeground::unwatch();
 
         // Perform cleanup after the message loop exits
         Self::stop();
         Ok(())
     }
 
+    /// Installs the hooks and the raw input window on the calling thread and returns,
+    /// leaving the message loop to the application.
+    fn attach() -> Result<(), CoreError> {
+        // Ensure only one instance is running
+        if Self::is_run() {
+            return Err(CoreError::AlreadyRunning);
+        }
+
+        if let Err(err) = Self::install() {
+            Self::pause();
+            return Err(err);
+        }
+        IS_ATTACHED.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn poll(timeout: Duration) -> Result<(), CoreError> {
+        // Finish a pending detach before installing again
+        Self::dispatch_pending();
+        if !IS_ATTACHED.load(Ordering::SeqCst) {
+            Self::attach()?;
+        }
+
+        let mut msg = MSG::default();
+        unsafe {
+            if !PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE).as_bool() {
+                // Stays below INFINITE, which would never time out
+                let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX - 1);
+                MsgWaitForMultipleObjects(None, false, millis, QS_ALLINPUT);
+            }
+        }
+        Self::dispatch_pending();
+        Ok(())
+    }
+
     fn is_runing() -> bool {
         IS_CORE_RUNNING.load(Ordering::SeqCst)
     }
@@ -96,6 +159,30 @@ impl CoreImpl for PlatformCore {
         IS_CORE_RUNNING.store(true, Ordering::SeqCst);
     }
 
+    /// Low-level hooks cannot be passive, so this has no effect on Windows.
+    fn listen_only(_enable: bool) {}
+
+    /// Windows has no event taps, so this has no effect.
+    fn tap_location(_location: TapLocation) {}
+
+    fn thread_priority(priority: ThreadPriority) {
+        THREAD_PRIORITY.store(priority as u8, Ordering::SeqCst);
+    }
+
+    fn hooks_installed() -> bool {
+        !MOUSE_HOOK.load(Ordering::SeqCst).is_null()
+            && !KEYBOARD_HOOK.load(Ordering::SeqCst).is_null()
+    }
+
+    fn tap_enabled() -> Option<bool> {
+        None
+    }
+
+    /// Low-level hooks need no permission.
+    fn has_permission(_grab: bool) -> bool {
+        true
+    }
+
     /// Stops the core engine, unhooks all listeners, and terminates the message loop.
     fn stop() {
         Self::pause();
@@ -103,6 +190,18 @@ impl CoreImpl for PlatformCore {
         // Remove Windows Hooks
         Self::unhook(&MOUSE_HOOK);
         Self::unhook(&KEYBOARD_HOOK);
+        PlatformHotkeys::update();
+
+        // The message loop belongs to the application, only the window goes away
+        if IS_ATTACHED.swap(false, Ordering::SeqCst) {
+            let hwnd = GLOBAL_HWND.swap(null_mut(), Ordering::SeqCst);
+            if !hwnd.is_null() {
+                unsafe {
+                    let _ = PostMessageW(Some(HWND(hwnd)), WM_DETACH_CORE, WPARAM(0), LPARAM(0));
+                }
+            }
+            return;
+        }
 
         // Notify the core thread to exit the GetMessage loop
         let thread_id = CORE_THREAD_ID.swap(0, Ordering::SeqCst);
@@ -131,6 +230,56 @@ impl PlatformCore {
             .is_err()
     }
 
+    /// Raises the priority of the calling thread as configured. Failures are ignored,
+    /// the core works at any priority.
+    fn apply_thread_priority() {
+        let priority = match THREAD_PRIORITY.load(Ordering::SeqCst) {
+            1 => THREAD_PRIORITY_HIGHEST,
+            2 => THREAD_PRIORITY_TIME_CRITICAL,
+            _ => return,
+        };
+        unsafe {
+            let _ = SetThreadPriority(GetCurrentThread(), priority);
+        }
+    }
+
+    /// Sets up the raw input window, the hooks and focus tracking on the calling thread,
+    /// whose message loop then drives them.
+    fn install() -> Result<(), CoreError> {
+        // Initialize a hidden window to receive Raw Input messages (WM_INPUT)
+        if GLOBAL_HWND.load(Ordering::SeqCst).is_null() {
+            let hwnd: HWND = Self::setup_raw_input_window()?;
+            GLOBAL_HWND.store(hwnd.0, Ordering::SeqCst);
+            PlatformHid::attach(hwnd);
+        }
+
+        // Set up low-level system hooks for mouse and keyboard, both or neither
+        let hooks = Self::handle_hook(WH_MOUSE_LL)
+            .and_then(|()| Self::handle_hook(WH_KEYBOARD_LL));
+        if let Err(err) = hooks {
+            Self::unhook(&MOUSE_HOOK);
+            Self::unhook(&KEYBOARD_HOOK);
+            return Err(err);
+        }
+
+        // Track the foreground window, so its application is known without a query per event
+        PlatformForeground::watch();
+
+        // The hooks match hotkeys from now on
+        PlatformHotkeys::update();
+        Ok(())
+    }
+
+    /// Dispatches the messages queued for the calling thread, without waiting.
+    fn dispatch_pending() {
+        let mut msg = MSG::default();
+        unsafe {
+            while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+
     /// Internal helper to manage hook registration and error mapping.
     fn handle_hook(hook_id: WINDOWS_HOOK_ID) -> Result<(), CoreError> {
         let target_static = match hook_id {
@@ -162,21 +311,13 @@ impl PlatformCore {
     fn set_hook(
         hook_id: WINDOWS_HOOK_ID,
         callback: extern "system" fn(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT,
-    ) -> Result<HHOOK, String> {
+    ) -> Result<HHOOK, OsError> {
         unsafe {
-            let instance = GetModuleHandleW(None);
-            let instance = match instance {
-                Ok(i) => i,
-                Err(err) => return Err(format!("Failed to get module handle: {:?}", err)),
-            };
+            let instance = GetModuleHandleW(None).map_err(os_error)?;
             let h_instance = HINSTANCE(instance.0);
 
             // Register a low-level hook. 0 as the thread ID means global hook.
-            let hook = SetWindowsHookExW(hook_id, Some(callback), Some(h_instance), 0);
-            match hook {
-                Ok(h) => Ok(h),
-                Err(err) => Err(format!("Failed to set hook: {:?}", err)),
-            }
+            SetWindowsHookExW(hook_id, Some(callback), Some(h_instance), 0).map_err(os_error)
         }
     }
 
@@ -190,8 +331,11 @@ impl PlatformCore {
         }
     }
 
-    /// Creates a hidden "message-only" window to subscribe to Raw Input events.
+    /// Creates a hidden window to subscribe to Raw Input events.
     /// This allows receiving high-definition mouse delta data without a visible UI.
+    ///
+    /// The window is top-level rather than message-only, since only top-level windows
+    /// receive the display change broadcasts that invalidate the monitor cache.
     fn setup_raw_input_window() -> Result<HWND, CoreError> {
         unsafe {
             let instance = GetModuleHandleW(None).unwrap_or_default();
@@ -205,9 +349,9 @@ impl PlatformCore {
             };
             RegisterClassW(&wc);
 
-            // HWND_MESSAGE makes this a "Message-Only" window, invisible to the user
+            // Never shown, and kept out of the taskbar and Alt+Tab
             let hwnd = CreateWindowExW(
-                Default::default(),
+                WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
                 class_name,
                 w!("RawInputMouseDeltaWindow"),
                 Default::default(),
@@ -215,16 +359,15 @@ impl PlatformCore {
                 0,
                 0,
                 0,
-                Some(HWND_MESSAGE),
+                None,
                 None,
                 Some(instance.into()),
                 None,
             )
-            .map_err(|e| {
-                CoreError::WindowsRegisterRawInputError(format!("CreateWindowExW failed: {:?}", e))
-            })?;
+            .map_err(|e| CoreError::WindowsRawInputWindowError(os_error(e)))?;
 
-            // Register Mouse (Usage: 0x02) for Raw Input.
+            // Register Mouse (Usage: 0x02) for Raw Input. The keyboard is registered by
+            // `PlatformHid::apply`, only while scan code mode or a subscription needs it.
             // RIDEV_INPUTSINK allows receiving input even when the window is not focused.
             let devices = [RAWINPUTDEVICE {
                 usUsagePage: 0x01,
@@ -233,9 +376,8 @@ impl PlatformCore {
                 hwndTarget: hwnd,
             }];
 
-            RegisterRawInputDevices(&devices, size_of::<RAWINPUTDEVICE>() as u32).map_err(|e| {
-                CoreError::WindowsRegisterRawInputError(format!("Registration failed: {:?}", e))
-            })?;
+            RegisterRawInputDevices(&devices, size_of::<RAWINPUTDEVICE>() as u32)
+                .map_err(|e| CoreError::WindowsRegisterRawInputError(os_error(e)))?;
 
             Ok(hwnd)
         }
@@ -246,16 +388,22 @@ impl PlatformCore {
 extern "system" fn hook_event_callback(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     // HC_ACTION means the hook is processing an actual input event
     if code == HC_ACTION as i32 {
-        // Dispatch the event to the Listen module for monitoring
-        PlatformListen::handle(wparam, lparam);
-
-        // If the 'Grab' (interception) feature is active, check if we should block this event
-        if !IS_GRAB_RUNNING.load(Ordering::Relaxed) {
-            return unsafe { CallNextHookEx(None, code, wparam, lparam) };
-        }
+        state::observed();
 
+        // With no subscribers and an idle pipeline only presses and releases are
+        // translated, for `grab_release`; moves and wheel turns are never even read
         let msg = wparam.0 as u32;
-        if PlatformGrab::should_block(msg) {
+        let processed = dispatcher::has_active() || !pipeline::is_idle();
+        let wanted = processed || !matches!(msg, WM_MOUSEMOVE | WM_MOUSEWHEEL | WM_MOUSEHWHEEL);
+        let translated = wanted
+            .then(|| PlatformListen::translate(wparam, lparam))
+            .flatten();
+        let blocker = hook_blocks(translated.filter(|_| processed), wparam, lparam);
+        // Remember the presses the applications saw, and who blocks their releases
+        if let Some(event) = translated {
+            grab_release::track(&event, blocker);
+        }
+        if blocker.is_some() {
             // Returning LRESULT(1) consumes the event and prevents it from reaching other apps
             return LRESULT(1);
         }
@@ -265,6 +413,53 @@ extern "system" fn hook_event_callback(code: i32, wparam: WPARAM, lparam: LPARAM
     unsafe { CallNextHookEx(None, code, wparam, lparam) }
 }
 
+/// Runs a hook event through the subscribers, the middleware pipeline and the grab
+/// filter. Returns what blocks it if it must not reach other applications.
+fn hook_blocks(translated: Option<Event>, wparam: WPARAM, lparam: LPARAM) -> Option<Blocker> {
+    if let Some(event) = translated {
+        // Dispatch the event to the Listen module for monitoring
+        let injected = PlatformListen::is_injected(wparam, lparam);
+        let age = PlatformListen::hook_event_age(wparam, lparam);
+        let raw = PlatformListen::raw_event(wparam, lparam);
+        raw_event::with_raw(raw, || {
+            sequence::with_origin(EventSource::Hook, age, || {
+                PlatformListen::handle(event, injected)
+            })
+        });
+
+        // Run the middleware pipeline (remapping, scroll transforms, hotkeys, ...)
+        let outcome = pipeline::run(event, injected);
+        for emitted in outcome.emitted {
+            PlatformSimulate::simulate(emitted);
+        }
+        if let Some(stage) = outcome.block {
+            return Some(stage);
+        }
+
+        // Low-level hooks cannot modify events, so replace the original with a new one
+        if let Some((replaced, stage)) = outcome.replaced {
+            PlatformSimulate::simulate(replaced);
+            return Some(stage);
+        }
+
+        // A grab scope's decision overrides the 'Grab' flags
+        if let Some(block) = outcome.grab {
+            return block;
+        }
+    }
+
+    // If the 'Grab' (interception) feature is active, check if we should block this event
+    if !IS_GRAB_RUNNING.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    let msg = wparam.0 as u32;
+    let block = PlatformGrab::should_block(msg)
+        && !PlatformGrab::targets_own_window(msg, lparam)
+        && !PlatformGrab::passes_injected(wparam, lparam);
+    block.then_some(Blocker::Grab)
+}
+
 /// Window Procedure for the hidden window to handle WM_INPUT messages.
 extern "system" fn listen_mouse_move_event_callback(
     hwnd: HWND,
@@ -272,9 +467,30 @@ extern "system" fn listen_mouse_move_event_callback(
     wparam: WPARAM,
     lparam: LPARAM,
 ) -> LRESULT {
+    if msg == WM_UPDATE_HID {
+        PlatformHid::apply(hwnd);
+        return LRESULT(0);
+    }
+
+    if msg == WM_DETACH_CORE {
+        PlatformForeground::unwatch();
+        unsafe {
+            let _ = DestroyWindow(hwnd);
+        }
+        return LRESULT(0);
+    }
+
+    if msg == WM_DISPLAYCHANGE || msg == WM_DPICHANGED || msg == WM_SETTINGCHANGE {
+        // Monitors were added, removed, rearranged or rescaled
+        monitors::invalidate();
+    }
+
     if msg == WM_INPUT {
-        // Raw Input provides relative mouse movement (deltas)
-        let is_handle = PlatformListen::handle_mouse_move(lparam);
+        // Raw Input provides relative mouse movement (deltas) and scan codes
+        let age = PlatformListen::message_age();
+        let is_handle = sequence::with_origin(EventSource::RawInput, age, || {
+            PlatformListen::handle_raw_input(lparam)
+        });
         if is_handle {
             return LRESULT(0);
         }
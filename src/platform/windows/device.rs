@@ -0,0 +1,194 @@
+use std::{
+    ffi::c_void,
+    mem::{size_of, size_of_val},
+};
+
+use windows::{
+    Win32::{
+        Devices::HumanInterfaceDevice::{
+            HIDD_ATTRIBUTES, HidD_GetAttributes, HidD_GetManufacturerString, HidD_GetProductString,
+        },
+        Foundation::{CloseHandle, HANDLE},
+        Storage::FileSystem::{
+            CreateFileW, FILE_FLAGS_AND_ATTRIBUTES, FILE_SHARE_READ, FILE_SHARE_WRITE,
+            OPEN_EXISTING,
+        },
+        UI::Input::{
+            GetRawInputDeviceInfoW, GetRawInputDeviceList, RAWINPUTDEVICELIST, RID_DEVICE_INFO,
+            RIDI_DEVICEINFO, RIDI_DEVICENAME, RIM_TYPEHID, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE,
+        },
+    },
+    core::PCWSTR,
+};
+
+use crate::{
+    device::{DeviceId, DeviceInfo, DeviceKind, KeyboardInfo, MouseInfo},
+    hid::HidUsage,
+    platform::{DevicesImpl, PlatformDevices},
+};
+
+impl DevicesImpl for PlatformDevices {
+    fn list() -> Vec<DeviceInfo> {
+        let mut count = 0;
+        let entry_size = size_of::<RAWINPUTDEVICELIST>() as u32;
+        if unsafe { GetRawInputDeviceList(None, &mut count, entry_size) } == u32::MAX {
+            return Vec::new();
+        }
+        let mut entries = vec![RAWINPUTDEVICELIST::default(); count as usize];
+        // Devices may attach in between, in which case the call fails with a larger count
+        let written =
+            unsafe { GetRawInputDeviceList(Some(entries.as_mut_ptr()), &mut count, entry_size) };
+        if written == u32::MAX {
+            return Vec::new();
+        }
+        entries.truncate(written as usize);
+
+        entries
+            .iter()
+            .filter_map(|entry| {
+                let info = device_info(entry.hDevice)?;
+                let (kind, mouse, keyboard) = match info.dwType {
+                    RIM_TYPEMOUSE => {
+                        let mouse = unsafe { info.Anonymous.mouse };
+                        let mouse = MouseInfo {
+                            // Windows has no notion of mouse resolution
+                            dpi: None,
+                            // Only PS/2 mice report a sample rate
+                            polling_rate: (mouse.dwSampleRate != 0).then_some(mouse.dwSampleRate),
+                            buttons: Some(mouse.dwNumberOfButtons),
+                            has_horizontal_wheel: Some(mouse.fHasHorizontalWheel.as_bool()),
+                        };
+                        (DeviceKind::Mouse, Some(mouse), None)
+                    }
+                    RIM_TYPEKEYBOARD => {
+                        let keyboard = unsafe { info.Anonymous.keyboard };
+                        let keyboard = KeyboardInfo {
+                            keyboard_type: Some(keyboard.dwType),
+                            subtype: Some(keyboard.dwSubType),
+                            function_keys: Some(keyboard.dwNumberOfFunctionKeys),
+                            indicators: Some(keyboard.dwNumberOfIndicators),
+                            keys: Some(keyboard.dwNumberOfKeysTotal),
+                        };
+                        (DeviceKind::Keyboard, None, Some(keyboard))
+                    }
+                    RIM_TYPEHID => {
+                        let hid = unsafe { info.Anonymous.hid };
+                        let usage = HidUsage::new(hid.usUsagePage, hid.usUsage);
+                        (DeviceKind::Hid(usage), None, None)
+                    }
+                    _ => return None,
+                };
+
+                let mut device = DeviceInfo {
+                    id: DeviceId(entry.hDevice.0 as u64),
+                    kind,
+                    vendor_id: None,
+                    product_id: None,
+                    manufacturer: None,
+                    product: None,
+                    path: device_name(entry.hDevice),
+                    mouse,
+                    keyboard,
+                };
+                if let Some(path) = device.path.clone() {
+                    describe(&mut device, &path);
+                }
+                Some(device)
+            })
+            .collect()
+    }
+}
+
+/// Reads the device interface path, which doubles as the path for `CreateFileW`.
+fn device_name(device: HANDLE) -> Option<String> {
+    let mut size = 0;
+    unsafe { GetRawInputDeviceInfoW(Some(device), RIDI_DEVICENAME, None, &mut size) };
+    if size == 0 {
+        return None;
+    }
+    // The size is in characters, including the terminating null
+    let mut name = vec![0u16; size as usize];
+    let result = unsafe {
+        GetRawInputDeviceInfoW(
+            Some(device),
+            RIDI_DEVICENAME,
+            Some(name.as_mut_ptr().cast()),
+            &mut size,
+        )
+    };
+    if result == u32::MAX {
+        return None;
+    }
+    name.truncate(result as usize);
+    Some(from_wide(&name))
+}
+
+/// Fills in the vendor and product from the HID descriptors, for devices that have them.
+fn describe(device: &mut DeviceInfo, path: &str) {
+    let path: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+    // No access rights are needed to query attributes, so this works for devices the
+    // system has opened exclusively, like keyboards and mice
+    let Ok(handle) = (unsafe {
+        CreateFileW(
+            PCWSTR(path.as_ptr()),
+            0,
+            FILE_SHARE_READ | FILE_SHARE_WRITE,
+            None,
+            OPEN_EXISTING,
+            FILE_FLAGS_AND_ATTRIBUTES(0),
+            None,
+        )
+    }) else {
+        return;
+    };
+
+    let mut attributes = HIDD_ATTRIBUTES {
+        Size: size_of::<HIDD_ATTRIBUTES>() as u32,
+        ..Default::default()
+    };
+    if unsafe { HidD_GetAttributes(handle, &mut attributes) } {
+        device.vendor_id = Some(attributes.VendorID);
+        device.product_id = Some(attributes.ProductID);
+    }
+    let string = |get: unsafe fn(HANDLE, *mut c_void, u32) -> bool| {
+        // HID strings are limited to 126 characters plus the terminating null
+        let mut buffer = [0u16; 127];
+        let ok = unsafe {
+            get(
+                handle,
+                buffer.as_mut_ptr().cast(),
+                size_of_val(&buffer) as u32,
+            )
+        };
+        Some(from_wide(&buffer)).filter(|string| ok && !string.is_empty())
+    };
+    device.manufacturer = string(HidD_GetManufacturerString);
+    device.product = string(HidD_GetProductString);
+
+    unsafe {
+        let _ = CloseHandle(handle);
+    }
+}
+
+fn from_wide(wide: &[u16]) -> String {
+    let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// Reads the `RIDI_DEVICEINFO` of a raw input device.
+pub(crate) fn device_info(device: HANDLE) -> Option<RID_DEVICE_INFO> {
+    let mut info = RID_DEVICE_INFO {
+        cbSize: size_of::<RID_DEVICE_INFO>() as u32,
+        ..Default::default()
+    };
+    let mut size = info.cbSize;
+    let result = unsafe {
+        GetRawInputDeviceInfoW(
+            Some(device),
+            RIDI_DEVICEINFO,
+            Some(&mut info as *mut _ as *mut _),
+            &mut size,
+        )
+    };
+    (result != u32::MAX).then_some(info)
+}
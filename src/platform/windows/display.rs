@@ -1,11 +1,12 @@
-use std::{mem::size_of, sync::Once};
+use std::{ffi::c_void, mem::size_of, sync::Once};
 
 use windows::{
     Win32::{
         Foundation::{LPARAM, POINT, RECT},
         Graphics::Gdi::{
-            EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITOR_DEFAULTTONEAREST,
-            MONITORINFOEXW, MonitorFromPoint,
+            BI_RGB, BITMAP, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, DeleteObject,
+            EnumDisplayMonitors, GetDC, GetDIBits, GetMonitorInfoW, GetObjectW, HBITMAP, HDC,
+            HMONITOR, MONITORINFOEXW, ReleaseDC,
         },
         UI::{
             HiDpi::{
@@ -13,16 +14,20 @@ use windows::{
                 SetProcessDpiAwarenessContext,
             },
             WindowsAndMessaging::{
-                GetCursorPos, GetSystemMetrics, MONITORINFOF_PRIMARY, SM_CXSCREEN,
-                SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN, SM_XVIRTUALSCREEN,
-                SM_YVIRTUALSCREEN, USER_DEFAULT_SCREEN_DPI,
+                CURSOR_SHOWING, CURSORINFO, CopyIcon, DestroyIcon, GetCursorInfo, GetCursorPos,
+                GetIconInfo, GetSystemMetrics, HICON, ICONINFO, MONITORINFOF_PRIMARY,
+                SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN,
+                SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SetCursorPos, USER_DEFAULT_SCREEN_DPI,
             },
         },
     },
     core::BOOL,
 };
 
-use crate::platform::{DisplayImpl, MonitorInfo, PlatformDisplay};
+use crate::{
+    Point, monitors,
+    platform::{CursorImage, CursorPosition, DisplayImpl, MonitorInfo, PlatformDisplay},
+};
 
 /// Initializes DPI awareness for the process to ensure coordinates are handled correctly
 /// on high-resolution displays. This is called only once.
@@ -30,14 +35,9 @@ static DPI_INIT: Once = Once::new();
 
 // public functions
 impl DisplayImpl for PlatformDisplay {
-    /// Returns the UI scale factor of the primary monitor.
-    /// This is a convenience method that references the primary monitor's DPI settings.
+    /// Returns the UI scale factor of the primary monitor, from the monitor cache.
     fn get_scale_factor() -> f64 {
-        Self::ensure_dpi_awareness();
-        unsafe {
-            let h_monitor = MonitorFromPoint(POINT::default(), MONITOR_DEFAULTTONEAREST);
-            Self::get_scale_for_hmonitor(h_monitor)
-        }
+        Self::get_primary_monitor().map_or(1.0, |m| m.scale_factor)
     }
 
     /// Retrieves the current cursor position in global physical coordinates.
@@ -56,6 +56,102 @@ impl DisplayImpl for PlatformDisplay {
         }
     }
 
+    /// Moves the cursor with `SetCursorPos`, which bypasses the low-level hooks
+    /// and Raw Input, so no `MouseMove` event is generated.
+    fn set_cursor_position(x: f64, y: f64) {
+        Self::ensure_dpi_awareness();
+        unsafe {
+            let _ = SetCursorPos(x.round() as i32, y.round() as i32);
+        }
+    }
+
+    /// The native cursor position is physical; logical coordinates use the primary
+    /// monitor's scale factor, matching what `Simulate::mouse_move_to` expects.
+    fn get_cursor() -> Option<CursorPosition> {
+        let (x, y) = Self::get_cursor_position()?;
+        let scale_factor = Self::get_scale_factor();
+        Some(CursorPosition {
+            physical: Point { x, y },
+            logical: Point {
+                x: x / scale_factor,
+                y: y / scale_factor,
+            },
+            monitor: Self::get_monitor_from_point(x, y),
+        })
+    }
+
+    /// Copies the cursor with `CopyIcon` and reads its bitmaps. Monochrome cursors have
+    /// no color bitmap but a mask twice as high, the AND mask above the XOR mask; pixels
+    /// set in both invert the screen and come out black.
+    fn get_cursor_image() -> Option<CursorImage> {
+        let mut cursor = CURSORINFO {
+            cbSize: size_of::<CURSORINFO>() as u32,
+            ..Default::default()
+        };
+        unsafe { GetCursorInfo(&mut cursor) }.ok()?;
+        if cursor.flags.0 & CURSOR_SHOWING.0 == 0 {
+            return None;
+        }
+
+        let mut info = ICONINFO::default();
+        unsafe {
+            let icon = CopyIcon(HICON(cursor.hCursor.0)).ok()?;
+            let result = GetIconInfo(icon, &mut info);
+            let _ = DestroyIcon(icon);
+            result.ok()?;
+        }
+        let mask = Self::read_bitmap(info.hbmMask);
+        let color = (!info.hbmColor.is_invalid()).then(|| Self::read_bitmap(info.hbmColor));
+        unsafe {
+            let _ = DeleteObject(info.hbmMask.into());
+            if !info.hbmColor.is_invalid() {
+                let _ = DeleteObject(info.hbmColor.into());
+            }
+        }
+
+        // Both are read as 32-bit BGRA; the mask's pixels are black or white
+        let (mask, width, mask_height) = mask?;
+        let (pixels, height) = match color {
+            Some(color) => {
+                let (color, _, height) = color?;
+                // Cursors without an alpha channel take their transparency from the mask
+                let has_alpha = color.chunks_exact(4).any(|bgra| bgra[3] != 0);
+                let pixels = color
+                    .chunks_exact(4)
+                    .zip(mask.chunks_exact(4))
+                    .flat_map(|(bgra, and)| {
+                        let alpha = match (has_alpha, and[0]) {
+                            (true, _) => bgra[3],
+                            (false, 0) => 0xFF,
+                            (false, _) => 0,
+                        };
+                        [bgra[2], bgra[1], bgra[0], alpha]
+                    })
+                    .collect();
+                (pixels, height)
+            }
+            None => {
+                let (and, xor) = mask.split_at(mask.len() / 2);
+                let pixels = and
+                    .chunks_exact(4)
+                    .zip(xor.chunks_exact(4))
+                    .flat_map(|(and, xor)| match (and[0] != 0, xor[0] != 0) {
+                        (false, false) | (true, true) => [0x00, 0x00, 0x00, 0xFF],
+                        (false, true) => [0xFF, 0xFF, 0xFF, 0xFF],
+                        (true, false) => [0x00, 0x00, 0x00, 0x00],
+                    })
+                    .collect();
+                (pixels, mask_height / 2)
+            }
+        };
+        Some(CursorImage {
+            width,
+            height,
+            hotspot: (info.xHotspot, info.yHotspot),
+            pixels,
+        })
+    }
+
     /// Gets the physical resolution (width, height) of the primary screen.
     fn get_primary_screen_size() -> (f64, f64) {
         Self::ensure_dpi_awareness();
@@ -85,19 +181,10 @@ impl DisplayImpl for PlatformDisplay {
         }
     }
 
-    /// Enumerates all connected monitors and retrieves their physical properties.
+    /// Enumerates all connected monitors and retrieves their physical properties. The
+    /// list is cached until the display configuration changes.
     fn get_available_monitors() -> Vec<MonitorInfo> {
-        Self::ensure_dpi_awareness();
-        let mut monitors = Vec::new();
-        unsafe {
-            let _ = EnumDisplayMonitors(
-                None,
-                None,
-                Some(monitor_enum_proc),
-                LPARAM(&mut monitors as *mut _ as isize),
-            );
-        }
-        monitors
+        monitors::cached(Self::enumerate_monitors)
     }
 
     /// Identifies and returns the primary monitor info if available.
@@ -116,17 +203,75 @@ impl DisplayImpl for PlatformDisplay {
 
     /// Determines which monitor contains the specified global physical point.
     fn get_monitor_from_point(x: f64, y: f64) -> Option<MonitorInfo> {
-        Self::get_available_monitors().into_iter().find(|m| {
-            x >= m.offset.0
-                && x < m.offset.0 + m.size.0
-                && y >= m.offset.1
-                && y < m.offset.1 + m.size.1
-        })
+        Self::get_available_monitors()
+            .into_iter()
+            .find(|m| m.contains(Point { x, y }))
     }
 }
 
 // private functions
 impl PlatformDisplay {
+    fn enumerate_monitors() -> Vec<MonitorInfo> {
+        Self::ensure_dpi_awareness();
+        let mut monitors = Vec::new();
+        unsafe {
+            let _ = EnumDisplayMonitors(
+                None,
+                None,
+                Some(monitor_enum_proc),
+                LPARAM(&mut monitors as *mut _ as isize),
+            );
+        }
+        monitors
+    }
+
+    /// Reads a bitmap as 32-bit BGRA rows from the top. Returns the pixels, the width and
+    /// the height.
+    fn read_bitmap(bitmap: HBITMAP) -> Option<(Vec<u8>, u32, u32)> {
+        let mut header = BITMAP::default();
+        let written = unsafe {
+            GetObjectW(
+                bitmap.into(),
+                size_of::<BITMAP>() as i32,
+                Some(&mut header as *mut _ as *mut c_void),
+            )
+        };
+        if written == 0 {
+            return None;
+        }
+
+        let (width, height) = (header.bmWidth, header.bmHeight);
+        let mut info = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative for rows from the top
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB.0,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let lines = unsafe {
+            let hdc = GetDC(None);
+            let lines = GetDIBits(
+                hdc,
+                bitmap,
+                0,
+                height as u32,
+                Some(pixels.as_mut_ptr().cast()),
+                &mut info,
+                DIB_RGB_COLORS,
+            );
+            ReleaseDC(None, hdc);
+            lines
+        };
+        (lines == height).then_some((pixels, width as u32, height as u32))
+    }
+
     fn ensure_dpi_awareness() {
         DPI_INIT.call_once(|| unsafe {
             // Set awareness to Per-Monitor V2 for modern Windows 10/11 behavior
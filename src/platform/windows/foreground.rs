@@ -0,0 +1,233 @@
+use std::{
+    ffi::c_void,
+    path::Path,
+    ptr::null_mut,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, AtomicPtr, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread,
+};
+
+use windows::{
+    Win32::{
+        Foundation::{CloseHandle, HWND},
+        System::{
+            Com::{CLSCTX_INPROC_SERVER, COINIT_MULTITHREADED, CoCreateInstance, CoInitializeEx},
+            Threading::{
+                OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION,
+                QueryFullProcessImageNameW,
+            },
+        },
+        UI::{
+            Accessibility::{
+                CUIAutomation, HWINEVENTHOOK, IUIAutomation, SetWinEventHook, UnhookWinEvent,
+            },
+            WindowsAndMessaging::{
+                EVENT_OBJECT_FOCUS, EVENT_SYSTEM_FOREGROUND, GetForegroundWindow,
+                GetWindowTextLengthW, GetWindowTextW, GetWindowThreadProcessId,
+                WINEVENT_OUTOFCONTEXT,
+            },
+        },
+    },
+    core::PWSTR,
+};
+
+use crate::{
+    Event,
+    foreground::{self, ForegroundApp},
+    platform::{ForegroundImpl, PlatformForeground, PlatformListen},
+};
+
+/// The `EVENT_SYSTEM_FOREGROUND` hook, installed on the core thread while it runs.
+static FOREGROUND_HOOK: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+
+/// The `EVENT_OBJECT_FOCUS` hook, installed alongside [`FOREGROUND_HOOK`].
+static FOCUS_HOOK: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
+
+/// Whether the focused element is a password field, as last reported by UI Automation.
+static SECURE: AtomicBool = AtomicBool::new(false);
+
+/// Wakes the thread that asks UI Automation about the focused element. Started on the
+/// first focus change while the secure input guard is enabled.
+static FOCUS_QUERIES: OnceLock<Sender<()>> = OnceLock::new();
+
+impl ForegroundImpl for PlatformForeground {
+    fn query() -> Option<ForegroundApp> {
+        Self::app(unsafe { GetForegroundWindow() })
+    }
+
+    /// Updated shortly after each focus change while `Core` runs.
+    fn is_secure_input() -> bool {
+        SECURE.load(Ordering::Relaxed)
+    }
+}
+
+impl PlatformForeground {
+    /// Starts tracking foreground changes. Must run on a thread with a message loop.
+    pub(crate) fn watch() {
+        let hook = unsafe {
+            SetWinEventHook(
+                EVENT_SYSTEM_FOREGROUND,
+                EVENT_SYSTEM_FOREGROUND,
+                None,
+                Some(foreground_event_callback),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            )
+        };
+        if !hook.is_invalid() {
+            FOREGROUND_HOOK.store(hook.0, Ordering::SeqCst);
+            foreground::set_watching(true);
+        }
+
+        let hook = unsafe {
+            SetWinEventHook(
+                EVENT_OBJECT_FOCUS,
+                EVENT_OBJECT_FOCUS,
+                None,
+                Some(focus_event_callback),
+                0,
+                0,
+                WINEVENT_OUTOFCONTEXT,
+            )
+        };
+        if !hook.is_invalid() {
+            FOCUS_HOOK.store(hook.0, Ordering::SeqCst);
+        }
+    }
+
+    /// Stops tracking foreground changes. Must run on the thread that called `watch`.
+    pub(crate) fn unwatch() {
+        let hook = FOREGROUND_HOOK.swap(null_mut(), Ordering::SeqCst);
+        if !hook.is_null() {
+            foreground::set_watching(false);
+            unsafe {
+                let _ = UnhookWinEvent(HWINEVENTHOOK(hook));
+            }
+        }
+
+        let hook = FOCUS_HOOK.swap(null_mut(), Ordering::SeqCst);
+        if !hook.is_null() {
+            SECURE.store(false, Ordering::SeqCst);
+            unsafe {
+                let _ = UnhookWinEvent(HWINEVENTHOOK(hook));
+            }
+        }
+    }
+
+    /// Describes the application owning a window.
+    fn app(hwnd: HWND) -> Option<ForegroundApp> {
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let mut pid = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+        if pid == 0 {
+            return None;
+        }
+        Some(ForegroundApp {
+            pid,
+            exe: Self::exe(pid),
+            bundle_id: None,
+            title: Self::title(hwnd),
+        })
+    }
+
+    fn exe(pid: u32) -> Option<String> {
+        // Limited information access works for elevated processes too
+        let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }.ok()?;
+        let mut path = [0u16; 1024];
+        let mut len = path.len() as u32;
+        let result = unsafe {
+            QueryFullProcessImageNameW(
+                process,
+                PROCESS_NAME_WIN32,
+                PWSTR(path.as_mut_ptr()),
+                &mut len,
+            )
+        };
+        unsafe {
+            let _ = CloseHandle(process);
+        }
+        result.ok()?;
+
+        let path = String::from_utf16_lossy(&path[..len as usize]);
+        Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+
+    fn title(hwnd: HWND) -> Option<String> {
+        let len = unsafe { GetWindowTextLengthW(hwnd) };
+        if len <= 0 {
+            return None;
+        }
+        let mut title = vec![0u16; len as usize + 1];
+        let len = unsafe { GetWindowTextW(hwnd, &mut title) };
+        (len > 0).then(|| String::from_utf16_lossy(&title[..len as usize]))
+    }
+}
+
+/// Invoked by Windows on the core thread whenever another window comes to the foreground.
+unsafe extern "system" fn foreground_event_callback(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    let app = PlatformForeground::app(hwnd);
+    let pid = app.as_ref().map(|app| app.pid);
+    foreground::changed(app);
+    if let Some(pid) = pid {
+        PlatformListen::handle(Event::FocusChanged { pid }, false);
+    }
+}
+
+/// Invoked by Windows on the core thread whenever the keyboard focus moves to another
+/// element.
+unsafe extern "system" fn focus_event_callback(
+    _hook: HWINEVENTHOOK,
+    _event: u32,
+    _hwnd: HWND,
+    _id_object: i32,
+    _id_child: i32,
+    _event_thread: u32,
+    _event_time: u32,
+) {
+    if foreground::secure_input_guard() {
+        // UI Automation calls into the focused process, so they must not block the hooks
+        let _ = FOCUS_QUERIES.get_or_init(spawn_focus_thread).send(());
+    }
+}
+
+/// Starts the thread that records whether the focused element is a password field.
+fn spawn_focus_thread() -> Sender<()> {
+    let (sender, receiver) = mpsc::channel::<()>();
+    thread::Builder::new()
+        .name("raw-input-uia".to_string())
+        .spawn(move || {
+            let automation: Option<IUIAutomation> = unsafe {
+                let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+                CoCreateInstance(&CUIAutomation, None, CLSCTX_INPROC_SERVER).ok()
+            };
+            while receiver.recv().is_ok() {
+                // Only the latest focus change matters
+                while receiver.try_recv().is_ok() {}
+                let secure = automation.as_ref().is_some_and(|automation| unsafe {
+                    automation
+                        .GetFocusedElement()
+                        .and_then(|element| element.CurrentIsPassword())
+                        .is_ok_and(|is_password| is_password.as_bool())
+                });
+                SECURE.store(secure, Ordering::SeqCst);
+            }
+        })
+        .expect("failed to spawn thread");
+    sender
+}
@@ -4,30 +4,39 @@ use std::{
     sync::atomic::{AtomicPtr, Ordering},
 };
 
-use windows::Win32::UI::WindowsAndMessaging::{
-    WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP,
-    WM_MOUSEHWHEEL, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN,
-    WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
+use windows::Win32::{
+    Foundation::{LPARAM, WPARAM},
+    UI::{
+        Input::KeyboardAndMouse::BlockInput,
+        WindowsAndMessaging::{
+            GetForegroundWindow, GetWindowThreadProcessId, MSLLHOOKSTRUCT, WM_KEYDOWN, WM_KEYUP,
+            WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL,
+            WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
+            WM_XBUTTONDOWN, WM_XBUTTONUP, WindowFromPoint,
+        },
+    },
 };
 
 use crate::platform::{
-    GrabImpl, PlatformGrab,
+    GrabImpl, PlatformGrab, PlatformListen,
     windows::common::{
-        GRAB_ALL, GRAB_FLAG, GRAB_KEYBOARD, GRAB_MOUSE_BUTTON, GRAB_MOUSE_MOVE, GRAB_MOUSE_WHEEL,
-        IS_GRAB_RUNNING, update_state,
+        GRAB_ALL, GRAB_EXCLUDE_OWN_WINDOWS, GRAB_FLAG, GRAB_KEYBOARD, GRAB_MOUSE_BUTTON,
+        GRAB_MOUSE_MOVE, GRAB_MOUSE_WHEEL, GRAB_PASS_INJECTED, IS_GRAB_RUNNING, update_state,
     },
 };
+use crate::{GrabFlags, StateError, foreground};
 
 pub static MOUSE_HOOK: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
 pub static KEYBOARD_HOOK: AtomicPtr<c_void> = AtomicPtr::new(null_mut());
 
 impl GrabImpl for PlatformGrab {
-    fn start() {
+    fn start() -> bool {
         if Self::is_run() {
-            return;
+            return false;
         }
 
         GRAB_FLAG.fetch_or(GRAB_ALL, Ordering::SeqCst);
+        true
     }
 
     fn is_runing() -> bool {
@@ -62,6 +71,33 @@ impl GrabImpl for PlatformGrab {
     fn keyboard(enable: bool) {
         update_state(&GRAB_FLAG, GRAB_KEYBOARD, enable);
     }
+
+    fn exclude_own_windows(enable: bool) {
+        GRAB_EXCLUDE_OWN_WINDOWS.store(enable, Ordering::SeqCst);
+    }
+
+    fn pass_injected(enable: bool) {
+        GRAB_PASS_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
+    fn flags() -> GrabFlags {
+        let state = GRAB_FLAG.load(Ordering::SeqCst);
+        GrabFlags {
+            running: Self::is_runing(),
+            mouse_move: state & GRAB_MOUSE_MOVE != 0,
+            mouse_button: state & GRAB_MOUSE_BUTTON != 0,
+            mouse_wheel: state & GRAB_MOUSE_WHEEL != 0,
+            keyboard: state & GRAB_KEYBOARD != 0,
+            exclude_own_windows: GRAB_EXCLUDE_OWN_WINDOWS.load(Ordering::SeqCst),
+            pass_injected: GRAB_PASS_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    /// `BlockInput` fails unless the process runs at a high enough integrity level to
+    /// own the input desktop, e.g. elevated.
+    fn block_input(block: bool) -> Result<(), StateError> {
+        unsafe { BlockInput(block) }.map_err(|_| StateError::PermissionMissing)
+    }
 }
 
 impl PlatformGrab {
@@ -75,7 +111,7 @@ impl PlatformGrab {
     #[inline]
     pub(crate) fn should_block(msg: u32) -> bool {
         let state = GRAB_FLAG.load(Ordering::Relaxed);
-        if state == 0 {
+        if state == 0 || !foreground::grab_allowed() {
             return false;
         }
 
@@ -95,4 +131,35 @@ impl PlatformGrab {
             _ => false,
         }
     }
+
+    /// Returns `true` if injected events are let through and the event was injected by
+    /// this crate's `Simulate`.
+    #[inline]
+    pub(crate) fn passes_injected(wparam: WPARAM, lparam: LPARAM) -> bool {
+        GRAB_PASS_INJECTED.load(Ordering::Relaxed) && PlatformListen::is_injected(wparam, lparam)
+    }
+
+    /// Returns `true` if own windows are excluded and the event is headed for one: the
+    /// window under the cursor for mouse events, the foreground window for keys.
+    #[inline]
+    pub(crate) fn targets_own_window(msg: u32, lparam: LPARAM) -> bool {
+        if !GRAB_EXCLUDE_OWN_WINDOWS.load(Ordering::Relaxed) {
+            return false;
+        }
+
+        let hwnd = match msg {
+            WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => unsafe { GetForegroundWindow() },
+            _ => {
+                let info = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
+                unsafe { WindowFromPoint(info.pt) }
+            }
+        };
+        if hwnd.is_invalid() {
+            return false;
+        }
+
+        let mut pid = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, Some(&mut pid)) };
+        pid == std::process::id()
+    }
 }
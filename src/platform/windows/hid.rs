@@ -0,0 +1,138 @@
+use std::{
+    collections::HashMap,
+    mem::size_of,
+    sync::{Mutex, MutexGuard, PoisonError, atomic::Ordering},
+};
+
+use once_cell::sync::Lazy;
+use windows::Win32::{
+    Foundation::{HANDLE, HWND, LPARAM, WPARAM},
+    UI::{
+        Input::{
+            RAWINPUT, RAWINPUTDEVICE, RAWINPUTDEVICE_FLAGS, RIDEV_INPUTSINK, RIDEV_REMOVE,
+            RIM_TYPEHID, RegisterRawInputDevices,
+        },
+        WindowsAndMessaging::{PostMessageW, WM_APP},
+    },
+};
+
+use crate::{
+    device::DeviceId,
+    hid::{self, HidReport, HidUsage},
+    platform::{
+        HidImpl, PlatformHid,
+        windows::{
+            common::{GLOBAL_HWND, SCANCODE_MODE},
+            device::device_info,
+        },
+    },
+};
+
+/// Posted to the raw input window so registrations change on the thread that owns it.
+pub const WM_UPDATE_HID: u32 = WM_APP + 1;
+
+/// Usages currently registered with the raw input window.
+static REGISTERED: Mutex<Vec<HidUsage>> = Mutex::new(Vec::new());
+
+/// Top-level collection usage of each device that sent reports, keyed by device handle.
+static DEVICE_USAGES: Lazy<Mutex<HashMap<isize, Option<HidUsage>>>> = Lazy::new(Mutex::default);
+
+impl HidImpl for PlatformHid {
+    fn update() {
+        let hwnd = GLOBAL_HWND.load(Ordering::SeqCst);
+        if !hwnd.is_null() {
+            unsafe {
+                let _ = PostMessageW(Some(HWND(hwnd)), WM_UPDATE_HID, WPARAM(0), LPARAM(0));
+            }
+        }
+    }
+}
+
+impl PlatformHid {
+    /// Registers the subscribed usages with a freshly created raw input window.
+    pub(crate) fn attach(hwnd: HWND) {
+        registered().clear();
+        DEVICE_USAGES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+        Self::apply(hwnd);
+    }
+
+    /// Brings the raw input registrations in line with the subscriptions and scan code
+    /// mode. Runs on the core thread.
+    pub(crate) fn apply(hwnd: HWND) {
+        let mut wanted = hid::usages();
+        // Keyboards only while something reads their raw input, such as scan code mode
+        if SCANCODE_MODE.load(Ordering::SeqCst) || hid::is_subscribed(HidUsage::KEYBOARD) {
+            wanted.push(HidUsage::KEYBOARD);
+        }
+        let mut registered = registered();
+
+        let device = |usage: &HidUsage, flags: RAWINPUTDEVICE_FLAGS, target: HWND| RAWINPUTDEVICE {
+            usUsagePage: usage.page,
+            usUsage: usage.usage,
+            dwFlags: flags,
+            hwndTarget: target,
+        };
+        let mut changes: Vec<RAWINPUTDEVICE> = wanted
+            .iter()
+            .filter(|usage| !registered.contains(usage))
+            .map(|usage| device(usage, RIDEV_INPUTSINK, hwnd))
+            .collect();
+        // Removal requires a null target window
+        changes.extend(
+            registered
+                .iter()
+                .filter(|usage| !wanted.contains(usage))
+                .map(|usage| device(usage, RIDEV_REMOVE, HWND::default())),
+        );
+        if changes.is_empty() {
+            return;
+        }
+
+        let result =
+            unsafe { RegisterRawInputDevices(&changes, size_of::<RAWINPUTDEVICE>() as u32) };
+        if result.is_ok() {
+            *registered = wanted;
+        }
+    }
+
+    /// Delivers the reports of a raw HID packet to the subscribers.
+    pub(crate) fn process(raw: &RAWINPUT) {
+        let Some(usage) = Self::device_usage(raw.header.hDevice) else {
+            return;
+        };
+
+        let hid = unsafe { &raw.data.hid };
+        let report_size = hid.dwSizeHid as usize;
+        if report_size == 0 {
+            return;
+        }
+        // The reports follow the header back to back, past the declared one-byte array
+        let data = unsafe {
+            std::slice::from_raw_parts(hid.bRawData.as_ptr(), report_size * hid.dwCount as usize)
+        };
+        for report in data.chunks_exact(report_size) {
+            hid::dispatch(&HidReport {
+                usage,
+                device: DeviceId(raw.header.hDevice.0 as u64),
+                data: report,
+            });
+        }
+    }
+
+    /// Looks up the top-level collection usage of a device, caching the result.
+    fn device_usage(device: HANDLE) -> Option<HidUsage> {
+        let mut cache = DEVICE_USAGES.lock().unwrap_or_else(PoisonError::into_inner);
+        *cache.entry(device.0 as isize).or_insert_with(|| {
+            let info = device_info(device).filter(|info| info.dwType == RIM_TYPEHID)?;
+            let hid = unsafe { info.Anonymous.hid };
+            Some(HidUsage::new(hid.usUsagePage, hid.usUsage))
+        })
+    }
+}
+
+fn registered() -> MutexGuard<'static, Vec<HidUsage>> {
+    REGISTERED.lock().unwrap_or_else(PoisonError::into_inner)
+}
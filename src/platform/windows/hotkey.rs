@@ -0,0 +1,128 @@
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, mpsc},
+    thread,
+};
+
+use windows::Win32::{
+    Foundation::{LPARAM, WPARAM},
+    System::Threading::GetCurrentThreadId,
+    UI::{
+        Input::KeyboardAndMouse::{
+            HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN,
+            RegisterHotKey, UnregisterHotKey,
+        },
+        WindowsAndMessaging::{
+            GetMessageW, MSG, PM_NOREMOVE, PeekMessageW, PostThreadMessageW, WM_APP, WM_HOTKEY,
+        },
+    },
+};
+
+use crate::{
+    hotkey,
+    key::Modifiers,
+    platform::{HotkeyImpl, PlatformHotkeys, windows::keycode::key_to_code},
+    shortcut::Shortcut,
+};
+
+/// Posted to the hotkey thread so registrations change on the thread that owns them.
+const WM_UPDATE_HOTKEYS: u32 = WM_APP + 3;
+
+/// Ids `RegisterHotKey` accepts from applications.
+const MAX_HOTKEY_ID: i32 = 0xBFFF;
+
+/// The thread owning the registered hotkeys, started with the first one.
+static THREAD_ID: OnceLock<u32> = OnceLock::new();
+
+impl HotkeyImpl for PlatformHotkeys {
+    fn update() {
+        let thread_id = match THREAD_ID.get() {
+            Some(&thread_id) => thread_id,
+            None if hotkey::native().is_empty() => return,
+            None => *THREAD_ID.get_or_init(spawn),
+        };
+        unsafe {
+            let _ = PostThreadMessageW(thread_id, WM_UPDATE_HOTKEYS, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+/// Starts the hotkey thread and returns its id once it can receive messages.
+fn spawn() -> u32 {
+    let (sender, receiver) = mpsc::channel();
+    thread::Builder::new()
+        .name("raw-input-hotkey".to_string())
+        .spawn(move || {
+            let mut msg = MSG::default();
+            unsafe {
+                // Creates the message queue before the id is handed out
+                let _ = PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE);
+                let _ = sender.send(GetCurrentThreadId());
+            }
+
+            // Hotkey ids by `RegisterHotKey` id
+            let mut registered: HashMap<i32, u64> = HashMap::new();
+            apply(&mut registered);
+            while unsafe { GetMessageW(&mut msg, None, 0, 0) }.as_bool() {
+                match msg.message {
+                    WM_UPDATE_HOTKEYS => apply(&mut registered),
+                    WM_HOTKEY => {
+                        if let Some(&id) = registered.get(&(msg.wParam.0 as i32)) {
+                            hotkey::fire(id);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        })
+        .expect("failed to spawn thread");
+    receiver.recv().expect("hotkey thread failed to start")
+}
+
+/// Brings the registrations of the calling thread in line with `hotkey::native`.
+fn apply(registered: &mut HashMap<i32, u64>) {
+    let wanted = hotkey::native();
+    registered.retain(|&native_id, id| {
+        let keep = wanted.iter().any(|(wanted_id, _)| wanted_id == id);
+        if !keep {
+            unsafe {
+                let _ = UnregisterHotKey(None, native_id);
+            }
+        }
+        keep
+    });
+
+    for (id, shortcut) in wanted {
+        if registered
+            .values()
+            .any(|&registered_id| registered_id == id)
+        {
+            continue;
+        }
+        let Some(vk) = key_to_code(shortcut.key) else {
+            continue;
+        };
+        let Some(native_id) = (1..=MAX_HOTKEY_ID).find(|n| !registered.contains_key(n)) else {
+            return;
+        };
+        // Fails when another application registered the shortcut first; retried on the
+        // next update
+        let result = unsafe { RegisterHotKey(None, native_id, modifiers(shortcut), vk) };
+        if result.is_ok() {
+            registered.insert(native_id, id);
+        }
+    }
+}
+
+/// Converts the modifiers of a shortcut, without auto-repeat as the hooks do.
+fn modifiers(shortcut: Shortcut) -> HOT_KEY_MODIFIERS {
+    [
+        (Modifiers::CONTROL, MOD_CONTROL),
+        (Modifiers::SHIFT, MOD_SHIFT),
+        (Modifiers::ALT, MOD_ALT),
+        (Modifiers::META, MOD_WIN),
+    ]
+    .into_iter()
+    .filter(|&(modifier, _)| shortcut.modifiers.contains(modifier))
+    .fold(MOD_NOREPEAT, |acc, (_, flag)| acc | flag)
+}
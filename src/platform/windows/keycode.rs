@@ -1,4 +1,4 @@
-use crate::key::{Key, KeyCode};
+use crate::key::{Key, KeyCode, RawKey};
 
 // from: https://github.com/rustdesk-org/rdev/blob/a90dbe1172f8832f54c97c62e823c5a34af5fdfe/src/keycodes/windows.rs
 macro_rules! keymap {
@@ -6,10 +6,12 @@ macro_rules! keymap {
         pub fn key_to_code(key: Key) -> Option<KeyCode> {
             match key {
                 $(Key::$key => Some($code),)+
+                Key::Raw(RawKey(code)) => Some(code),
                 _=>None,
             }
         }
 
+        #[cfg_attr(not(feature = "listen"), allow(dead_code))]
         pub fn code_to_key(code: KeyCode) -> Key {
             #[allow(unreachable_patterns)]
             match code {
@@ -24,7 +26,7 @@ macro_rules! keymap {
                 _=>None,
             }
         }
-        #[allow(dead_code)]
+        #[cfg_attr(not(feature = "listen"), allow(dead_code))]
         pub fn scancode_to_key(scancode: KeyCode) -> Key{
             match scancode {
                 0 => Key::default(),
@@ -32,15 +34,16 @@ macro_rules! keymap {
                 _ => Key::default(),
             }
         }
-        #[allow(dead_code)]
+        #[cfg_attr(not(feature = "listen"), allow(dead_code))]
         pub fn get_win_key(keycode: KeyCode, scancode: KeyCode) -> Key{
             let key = code_to_key(keycode);
             let scancode_key = scancode_to_key(scancode);
 
-            if key == Key::AltRight || key == Key::NumpadDivide || key == Key::ControlRight {
+            if key == Key::AltRight || key == Key::NumpadDivide || key == Key::ControlRight || key == Key::Pause {
                 // note: Alt and AltRight have same scancode.
                 // slash and divide.
                 // left control and right control .
+                // pause and numlock.
                 key
             } else if scancode_key != Key::default() {
                 // note: numpad should use scancode directly,
@@ -51,6 +54,10 @@ macro_rules! keymap {
         }
 
         pub fn get_win_codes(key: Key) -> Option<(KeyCode, KeyCode)>{
+            // Raw keys carry a virtual-key code only; they are injected in virtual key mode
+            if let Key::Raw(RawKey(code)) = key {
+                return Some((code, 0));
+            }
             let keycode = key_to_code(key)?;
             let key = if key == Key::default() {
                 code_to_key(keycode)
@@ -264,4 +271,22 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_get_win_key_uses_physical_position() {
+        // AZERTY reports VK_A for the key in the QWERTY `Q` position
+        assert_eq!(get_win_key(65, 0x10), Key::KeyQ);
+        assert_eq!(get_win_key(13, 0xE01C), Key::NumpadEnter);
+        assert_eq!(get_win_key(19, 0x45), Key::Pause);
+        assert_eq!(get_win_key(144, 0x45), Key::NumLock);
+        // Injected input without a scan code falls back to the virtual key
+        assert_eq!(get_win_key(65, 0), Key::KeyA);
+    }
+
+    #[test]
+    fn test_raw_key_codes() {
+        let key = Key::Raw(RawKey(0xFF));
+        assert_eq!(key_to_code(key), Some(0xFF));
+        assert_eq!(get_win_codes(key), Some((0xFF, 0)));
+    }
 }
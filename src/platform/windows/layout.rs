@@ -0,0 +1,192 @@
+use windows::Win32::UI::{
+    Input::KeyboardAndMouse::{
+        GetKeyState, GetKeyboardLayout, HKL, MAPVK_VK_TO_CHAR, MAPVK_VK_TO_VSC_EX,
+        MAPVK_VSC_TO_VK_EX, MapVirtualKeyExW, ToUnicodeEx, VK_CAPITAL, VK_CONTROL, VK_MENU,
+        VK_SHIFT, VkKeyScanExW,
+    },
+    WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId},
+};
+
+use crate::{
+    Key, Modifiers,
+    platform::{
+        KeyboardImpl, PlatformKeyboard,
+        windows::keycode::{key_to_scancode, scancode_to_key},
+    },
+};
+
+/// A key, and the modifiers that must be held for it to type a character.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct Keystroke {
+    pub vk: u16,
+    pub shift: bool,
+    /// AltGr, which Windows reports as Ctrl+Alt.
+    pub alt_gr: bool,
+}
+
+impl Keystroke {
+    /// Finds the key that types `ch` on `layout`, dead keys included.
+    fn for_char(ch: char, layout: HKL) -> Option<Keystroke> {
+        let unit = u16::try_from(u32::from(ch)).ok()?;
+        Keystroke::from_scan(unsafe { VkKeyScanExW(unit, layout) })
+    }
+
+    /// Decodes the result of `VkKeyScanExW`: the virtual key in the low byte, and the
+    /// Shift (1), Ctrl (2) and Alt (4) state in the high byte.
+    fn from_scan(scan: i16) -> Option<Keystroke> {
+        if scan == -1 {
+            return None;
+        }
+        let vk = (scan & 0xFF) as u16;
+        let state = (scan >> 8) & 0xFF;
+        let alt_gr = state & 6 == 6;
+        // Ctrl or Alt alone type control characters, not text
+        if state & !1 != 0 && (!alt_gr || state & !7 != 0) {
+            return None;
+        }
+        Some(Keystroke {
+            vk,
+            shift: state & 1 != 0,
+            alt_gr,
+        })
+    }
+
+    /// Returns the character the keystroke types on `layout` with CapsLock on or off.
+    fn typed(self, caps_lock: bool, layout: HKL) -> Option<char> {
+        typed_char(self.vk, self.modifiers(), caps_lock, layout)
+    }
+
+    /// The modifiers to hold, with AltGr as Ctrl+Alt.
+    fn modifiers(self) -> Modifiers {
+        let mut modifiers = Modifiers::NONE;
+        if self.shift {
+            modifiers.insert(Modifiers::SHIFT);
+        }
+        if self.alt_gr {
+            modifiers.insert(Modifiers::CONTROL | Modifiers::ALT);
+        }
+        modifiers
+    }
+}
+
+/// Returns the character `vk` types on `layout` with `modifiers` held, including the
+/// character of a dead key.
+fn typed_char(vk: u16, modifiers: Modifiers, caps_lock: bool, layout: HKL) -> Option<char> {
+    let mut state = [0u8; 256];
+    for (modifier, vk) in [
+        (Modifiers::SHIFT, VK_SHIFT),
+        (Modifiers::CONTROL, VK_CONTROL),
+        (Modifiers::ALT, VK_MENU),
+    ] {
+        if modifiers.contains(modifier) {
+            state[usize::from(vk.0)] = 0x80;
+        }
+    }
+    if caps_lock {
+        state[usize::from(VK_CAPITAL.0)] = 0x01;
+    }
+    let mut buf = [0u16; 4];
+    // Flag 4 leaves the keyboard state, including a pending dead key, untouched. Dead
+    // keys report their character with a negative length.
+    let len = unsafe { ToUnicodeEx(u32::from(vk), 0, &state, &mut buf, 4, Some(layout)) };
+    if len != 1 && len != -1 {
+        return None;
+    }
+    char::from_u32(u32::from(buf[0]))
+}
+
+/// Checks whether CapsLock is toggled on.
+pub(crate) fn caps_lock() -> bool {
+    unsafe { GetKeyState(i32::from(VK_CAPITAL.0)) & 1 != 0 }
+}
+
+/// Returns the keyboard layout of the foreground window's thread, which receives the
+/// injected input.
+pub(crate) fn foreground_layout() -> HKL {
+    unsafe {
+        let thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+        GetKeyboardLayout(thread)
+    }
+}
+
+/// Finds the key that types `ch` on `layout`, with Shift and AltGr as needed, e.g.
+/// AltGr+Q for `@` on a German layout.
+///
+/// With `caps_lock` on, Shift is inverted for the keys CapsLock affects, so `H` is
+/// typed without Shift.
+///
+/// Returns `None` for characters the layout lacks or only produces through a dead key;
+/// pressing a dead key would start an accent composition.
+pub(crate) fn keystroke(ch: char, layout: HKL, caps_lock: bool) -> Option<Keystroke> {
+    // Enter types a carriage return
+    let ch = if ch == '\n' { '\r' } else { ch };
+    let stroke = Keystroke::for_char(ch, layout)?;
+    // Dead keys have the top bit of their character set
+    let mapped = unsafe { MapVirtualKeyExW(u32::from(stroke.vk), MAPVK_VK_TO_CHAR, Some(layout)) };
+    if mapped & 0x8000_0000 != 0 {
+        return None;
+    }
+
+    if caps_lock && stroke.typed(true, layout) != Some(ch) {
+        let flipped = Keystroke {
+            shift: !stroke.shift,
+            ..stroke
+        };
+        return (flipped.typed(true, layout) == Some(ch)).then_some(flipped);
+    }
+    Some(stroke)
+}
+
+impl KeyboardImpl for PlatformKeyboard {
+    fn key_for_char(ch: char) -> Option<(Key, Modifiers)> {
+        // Enter types a carriage return
+        let ch = if ch == '\n' { '\r' } else { ch };
+        let layout = foreground_layout();
+        let stroke = Keystroke::for_char(ch, layout)?;
+        let scancode =
+            unsafe { MapVirtualKeyExW(u32::from(stroke.vk), MAPVK_VK_TO_VSC_EX, Some(layout)) };
+        match scancode_to_key(scancode) {
+            Key::Unidentified => None,
+            key => Some((key, stroke.modifiers())),
+        }
+    }
+
+    fn char_for_key(key: Key, modifiers: Modifiers) -> Option<char> {
+        let layout = foreground_layout();
+        let scancode = key_to_scancode(key)?;
+        let vk = unsafe { MapVirtualKeyExW(scancode, MAPVK_VSC_TO_VK_EX, Some(layout)) };
+        let vk = u16::try_from(vk).ok().filter(|&vk| vk != 0)?;
+        typed_char(vk, modifiers, false, layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystroke_from_scan() {
+        // 'A': Shift+A
+        assert_eq!(
+            Keystroke::from_scan(0x0141),
+            Some(Keystroke {
+                vk: 0x41,
+                shift: true,
+                alt_gr: false
+            })
+        );
+        // '@' on German layouts: AltGr+Q
+        assert_eq!(
+            Keystroke::from_scan(0x0651),
+            Some(Keystroke {
+                vk: 0x51,
+                shift: false,
+                alt_gr: true
+            })
+        );
+        // Control characters and missing characters
+        assert_eq!(Keystroke::from_scan(0x0243), None);
+        assert_eq!(Keystroke::from_scan(0x0443), None);
+        assert_eq!(Keystroke::from_scan(-1), None);
+    }
+}
@@ -1,14 +1,22 @@
-use std::{ffi::c_void, mem::size_of, sync::atomic::Ordering};
+use std::{
+    ffi::c_void,
+    mem::size_of,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
 
 use windows::Win32::{
     Foundation::{LPARAM, WPARAM},
+    System::SystemInformation::GetTickCount,
     UI::{
         Input::{
-            GetRawInputData, HRAWINPUT, MOUSE_MOVE_ABSOLUTE, RAWINPUT, RAWINPUTHEADER, RID_INPUT,
-            RIM_TYPEMOUSE,
+            GetRawInputBuffer, GetRawInputData, HRAWINPUT, KeyboardAndMouse::VK_PACKET,
+            MOUSE_MOVE_ABSOLUTE, MOUSE_VIRTUAL_DESKTOP, RAWINPUT, RAWINPUTHEADER, RAWKEYBOARD,
+            RAWMOUSE, RID_INPUT, RIM_TYPEHID, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE,
         },
         WindowsAndMessaging::{
-            KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, WHEEL_DELTA, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN,
+            GetMessageTime, GetQueueStatus, KBDLLHOOKSTRUCT, MSLLHOOKSTRUCT, QS_RAWINPUT,
+            RI_KEY_BREAK, RI_KEY_E0, RI_KEY_E1, WHEEL_DELTA, WM_KEYDOWN, WM_KEYUP, WM_LBUTTONDOWN,
             WM_LBUTTONUP, WM_MBUTTONDOWN, WM_MBUTTONUP, WM_MOUSEHWHEEL, WM_MOUSEWHEEL,
             WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SYSKEYDOWN, WM_SYSKEYUP, WM_XBUTTONDOWN, WM_XBUTTONUP,
             XBUTTON1, XBUTTON2,
@@ -17,29 +25,44 @@ use windows::Win32::{
 };
 
 use crate::{
-    dispatcher::{CALLBACKS, NEXT_ID, Status, Subscriber, dispatch, remove_all},
+    ListenFlags,
+    dispatcher::{
+        CALLBACKS, NEXT_ID, Status, Subscriber, dispatch, has_active, refresh, remove_all,
+    },
     event::{Event, MouseButton, Point},
-    key::KeyCode,
+    foreground, held_modifiers, hid, pressed,
+    key::{Key, KeyCode, RawKey},
     platform::{
-        ListenImpl, PlatformListen,
+        DisplayImpl, HidImpl, INJECTION_MARKER, ListenImpl, PlatformDisplay, PlatformHid,
+        PlatformListen,
         windows::{
             common::{
-                IS_LISTEN_RUNNING, LISTEN_FLAG, LISTEN_KEYBOARD, LISTEN_MOUSE_BUTTON,
-                LISTEN_MOUSE_MOVE, LISTEN_MOUSE_WHEEL, LISTENS_ALL, update_state, utils,
+                IS_LISTEN_RUNNING, LISTEN_FLAG, LISTEN_IGNORE_INJECTED, LISTEN_KEYBOARD,
+                LISTEN_MOUSE_BUTTON, LISTEN_MOUSE_MOVE, LISTEN_MOUSE_WHEEL, LISTENS_ALL,
+                SCANCODE_MODE, update_state, utils,
             },
-            keycode::code_to_key,
+            keycode::{get_win_key, scancode_to_key},
         },
     },
+    raw_event::{self, RawEvent},
     subscription::SubscriptionHandle,
 };
 
+/// Size of the buffer for a single `WM_INPUT` packet, in `u64`s; larger HID reports are
+/// dropped.
+const RAW_INPUT_MESSAGE_LEN: usize = 128;
+
+/// Size of the `GetRawInputBuffer` batch, in `u64`s; 8 KiB holds about 170 mouse packets.
+const RAW_INPUT_BUFFER_LEN: usize = 1024;
+
 impl ListenImpl for PlatformListen {
-    fn start() {
+    fn start() -> bool {
         if Self::is_run() {
-            return;
+            return false;
         }
 
         LISTEN_FLAG.store(LISTENS_ALL, Ordering::SeqCst);
+        true
     }
 
     fn is_runing() -> bool {
@@ -76,6 +99,31 @@ impl ListenImpl for PlatformListen {
         update_state(&LISTEN_FLAG, LISTEN_KEYBOARD, enable);
     }
 
+    fn flags() -> ListenFlags {
+        let state = LISTEN_FLAG.load(Ordering::SeqCst);
+        ListenFlags {
+            running: Self::is_runing(),
+            mouse_move: state & LISTEN_MOUSE_MOVE != 0,
+            mouse_button: state & LISTEN_MOUSE_BUTTON != 0,
+            mouse_wheel: state & LISTEN_MOUSE_WHEEL != 0,
+            keyboard: state & LISTEN_KEYBOARD != 0,
+            scancode_mode: SCANCODE_MODE.load(Ordering::SeqCst),
+            ignore_injected: LISTEN_IGNORE_INJECTED.load(Ordering::SeqCst),
+        }
+    }
+
+    /// Switches keyboard delivery between the low-level hook and Raw Input, which reports
+    /// the scan codes straight from the keyboard driver.
+    fn scancode_mode(enable: bool) {
+        SCANCODE_MODE.store(enable, Ordering::SeqCst);
+        // Registers or removes the raw keyboard
+        PlatformHid::update();
+    }
+
+    fn ignore_injected(enable: bool) {
+        LISTEN_IGNORE_INJECTED.store(enable, Ordering::SeqCst);
+    }
+
     fn subscribe<F>(callback: F) -> SubscriptionHandle
     where
         F: Fn(Event) + Send + Sync + 'static,
@@ -85,9 +133,10 @@ impl ListenImpl for PlatformListen {
             id,
             Subscriber {
                 status: Status::Active,
-                callback: Box::new(callback),
+                callback: Arc::new(callback),
             },
         );
+        refresh();
         SubscriptionHandle { id }
     }
 
@@ -103,31 +152,58 @@ impl PlatformListen {
             .is_err()
     }
 
-    pub(crate) fn handle(wparam: WPARAM, lparam: LPARAM) {
-        if !IS_LISTEN_RUNNING.load(Ordering::Relaxed) {
+    /// Dispatches a translated hook event to subscribers if its category is being listened to.
+    /// A `ModifiersChanged` follows the key events that change the modifiers held.
+    pub(crate) fn handle(event: Event, injected: bool) {
+        pressed::track(&event);
+        let modifiers = held_modifiers::update(&event);
+        Self::deliver(event, injected);
+        if let Some(modifiers) = modifiers {
+            Self::deliver(Event::ModifiersChanged { modifiers }, injected);
+        }
+    }
+
+    fn deliver(event: Event, injected: bool) {
+        if !has_active() || !IS_LISTEN_RUNNING.load(Ordering::Relaxed) {
+            return;
+        }
+        if injected && LISTEN_IGNORE_INJECTED.load(Ordering::Relaxed) {
             return;
         }
 
         let state = LISTEN_FLAG.load(Ordering::Relaxed);
-        if state == 0 {
+        let bit = match event {
+            Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. } => LISTEN_MOUSE_MOVE,
+            Event::MouseWheel { .. } => LISTEN_MOUSE_WHEEL,
+            Event::MouseDown { .. } | Event::MouseUp { .. } => LISTEN_MOUSE_BUTTON,
+            // In scan code mode Raw Input delivers the keyboard instead
+            Event::KeyDown { .. } | Event::KeyUp { .. }
+                if SCANCODE_MODE.load(Ordering::Relaxed) =>
+            {
+                return;
+            }
+            Event::KeyDown { .. } | Event::KeyUp { .. } | Event::ModifiersChanged { .. } => {
+                LISTEN_KEYBOARD
+            }
+            Event::FocusChanged { .. } => return dispatch(event),
+        };
+        if state & bit == 0 || !foreground::listen_allowed() {
             return;
         }
 
+        dispatch(event);
+    }
+
+    /// Translates a low-level hook message into an [`Event`].
+    ///
+    /// Mouse moves are not translated here; they are delivered through Raw Input instead.
+    pub(crate) fn translate(wparam: WPARAM, lparam: LPARAM) -> Option<Event> {
         let msg = wparam.0 as u32;
 
         let event = match msg {
             // ================= Mouse Buttons & Wheel =================
             WM_LBUTTONDOWN | WM_LBUTTONUP | WM_RBUTTONDOWN | WM_RBUTTONUP | WM_MBUTTONDOWN
             | WM_MBUTTONUP | WM_XBUTTONDOWN | WM_XBUTTONUP | WM_MOUSEWHEEL | WM_MOUSEHWHEEL => {
-                let is_wheel = msg == WM_MOUSEWHEEL || msg == WM_MOUSEHWHEEL;
-                if is_wheel {
-                    if (state & LISTEN_MOUSE_WHEEL) == 0 {
-                        return;
-                    }
-                } else if (state & LISTEN_MOUSE_BUTTON) == 0 {
-                    return;
-                }
-
                 // Cast LPARAM to Low-Level Mouse Hook structure
                 let mouse = unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) };
                 // Extract high-order word for wheel delta or X-button index
@@ -155,16 +231,24 @@ impl PlatformListen {
 
                     WM_MOUSEWHEEL => {
                         // Normalize vertical wheel delta
-                        let y = delta as i16 as f64 / WHEEL_DELTA as f64;
+                        let raw = delta as i16 as f64;
                         Event::MouseWheel {
-                            delta: Point { x: 0.0, y },
+                            delta: Point {
+                                x: 0.0,
+                                y: raw / WHEEL_DELTA as f64,
+                            },
+                            raw: Some(Point { x: 0.0, y: raw }),
                         }
                     }
                     WM_MOUSEHWHEEL => {
                         // Normalize horizontal wheel delta
-                        let x = delta as i16 as f64 / WHEEL_DELTA as f64;
+                        let raw = delta as i16 as f64;
                         Event::MouseWheel {
-                            delta: Point { x, y: 0.0 },
+                            delta: Point {
+                                x: raw / WHEEL_DELTA as f64,
+                                y: 0.0,
+                            },
+                            raw: Some(Point { x: raw, y: 0.0 }),
                         }
                     }
 
@@ -173,7 +257,7 @@ impl PlatformListen {
                         let button = match delta {
                             XBUTTON1 => MouseButton::Back,
                             XBUTTON2 => MouseButton::Forward,
-                            _ => return,
+                            _ => return None,
                         };
                         if msg == WM_XBUTTONDOWN {
                             Event::MouseDown { button }
@@ -181,20 +265,25 @@ impl PlatformListen {
                             Event::MouseUp { button }
                         }
                     }
-                    _ => return,
+                    _ => return None,
                 }
             }
 
             // ================= Keyboard =================
             WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => {
-                if (state & LISTEN_KEYBOARD) == 0 {
-                    return;
-                }
-
                 // Cast LPARAM to Low-Level Keyboard Hook structure
                 let kb = unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) };
                 let code: KeyCode = utils::get_code(kb);
-                let key = code_to_key(code);
+                // Derive the key from the scan code so it reflects the physical position
+                // regardless of the active layout; unicode packets have no physical key
+                let key = if kb.vkCode != VK_PACKET.0 as u32 {
+                    match get_win_key(kb.vkCode, utils::get_scan_code(kb)) {
+                        Key::Unidentified => Key::Raw(RawKey(kb.vkCode)),
+                        key => key,
+                    }
+                } else {
+                    Key::Unidentified
+                };
                 let code = Some(code);
 
                 if msg == WM_KEYDOWN || msg == WM_SYSKEYDOWN {
@@ -203,32 +292,77 @@ impl PlatformListen {
                     Event::KeyUp { key, code }
                 }
             }
-            _ => return,
+            _ => return None,
         };
 
-        dispatch(event);
+        Some(event)
     }
 
-    pub(crate) fn handle_mouse_move(lparam: LPARAM) -> bool {
-        if !IS_LISTEN_RUNNING.load(Ordering::Relaxed) {
-            return false;
+    /// Wraps a low-level hook message for [`Listen::subscribe_raw`](crate::Listen::subscribe_raw).
+    pub(crate) fn raw_event(wparam: WPARAM, lparam: LPARAM) -> RawEvent {
+        let message = wparam.0 as u32;
+        let data = lparam.0 as *const c_void;
+        match message {
+            WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => {
+                RawEvent::KeyboardHook { message, data }
+            }
+            _ => RawEvent::MouseHook { message, data },
         }
+    }
 
-        let state = LISTEN_FLAG.load(Ordering::Relaxed);
-        if state & LISTEN_MOUSE_MOVE == 0 {
+    /// Checks whether a low-level hook message was injected by this crate's `Simulate`.
+    pub(crate) fn is_injected(wparam: WPARAM, lparam: LPARAM) -> bool {
+        let extra_info = match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => {
+                unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) }.dwExtraInfo
+            }
+            _ => unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) }.dwExtraInfo,
+        };
+        extra_info == INJECTION_MARKER
+    }
+
+    /// Returns how long ago the OS recorded a low-level hook event.
+    pub(crate) fn hook_event_age(wparam: WPARAM, lparam: LPARAM) -> Duration {
+        let time = match wparam.0 as u32 {
+            WM_KEYDOWN | WM_SYSKEYDOWN | WM_KEYUP | WM_SYSKEYUP => {
+                unsafe { &*(lparam.0 as *const KBDLLHOOKSTRUCT) }.time
+            }
+            _ => unsafe { &*(lparam.0 as *const MSLLHOOKSTRUCT) }.time,
+        };
+        Self::age(time)
+    }
+
+    /// Returns how long ago the OS posted the message being handled, such as `WM_INPUT`.
+    pub(crate) fn message_age() -> Duration {
+        Self::age(unsafe { GetMessageTime() } as u32)
+    }
+
+    /// Converts a `GetTickCount` timestamp into its age, which wraps every 49.7 days.
+    fn age(time: u32) -> Duration {
+        let age = unsafe { GetTickCount() }.wrapping_sub(time) as i32;
+        Duration::from_millis(age.max(0) as u64)
+    }
+
+    /// Handles a `WM_INPUT` message: relative mouse motion, and the keyboard in scan code
+    /// mode. Returns `false` if the message was not consumed.
+    pub(crate) fn handle_raw_input(lparam: LPARAM) -> bool {
+        // Checked before `GetRawInputData`, so idle applications never read the input
+        if !Self::wants_raw_mouse() && !Self::wants_raw_keyboard() && !hid::is_active() {
             return false;
         }
 
         let h_raw_input = HRAWINPUT(lparam.0 as *mut c_void);
-        let mut raw = RAWINPUT::default();
-        let mut size = std::mem::size_of::<RAWINPUT>() as u32;
+        // Room for HID reports as well as mouse and keyboard packets; `u64` elements give
+        // the buffer the alignment of `RAWINPUT`
+        let mut buffer = [0u64; RAW_INPUT_MESSAGE_LEN];
+        let mut size = size_of_val(&buffer) as u32;
 
         // Retrieve Raw Input data from the message LPARAM
         let raw_size = unsafe {
             GetRawInputData(
                 h_raw_input,
                 RID_INPUT,
-                Some(&mut raw as *mut _ as *mut _),
+                Some(buffer.as_mut_ptr().cast()),
                 &mut size,
                 size_of::<RAWINPUTHEADER>() as u32,
             )
@@ -238,15 +372,74 @@ impl PlatformListen {
             return false;
         }
 
+        let raw = unsafe { &*buffer.as_ptr().cast::<RAWINPUT>() };
+        let handled = Self::process_raw_input(raw);
+
+        // Under pressure, drain the queued packets in batches instead of one message each.
+        // 32-bit processes on 64-bit Windows get a misaligned buffer layout, so they stay
+        // on the per-message path.
+        if handled && cfg!(target_pointer_width = "64") && Self::has_queued_raw_input() {
+            Self::drain_raw_input_buffer();
+        }
+
+        handled
+    }
+
+    /// Checks whether raw mouse motion has a listener. Raw Input bypasses [`Self::deliver`],
+    /// so the application rule of `Listen::only_when_app` is applied here.
+    fn wants_raw_mouse() -> bool {
+        has_active()
+            && IS_LISTEN_RUNNING.load(Ordering::Relaxed)
+            && LISTEN_FLAG.load(Ordering::Relaxed) & LISTEN_MOUSE_MOVE != 0
+            && foreground::listen_allowed()
+    }
+
+    /// Checks whether raw keyboard input has a listener, which requires scan code mode.
+    fn wants_raw_keyboard() -> bool {
+        SCANCODE_MODE.load(Ordering::Relaxed)
+            && has_active()
+            && IS_LISTEN_RUNNING.load(Ordering::Relaxed)
+            && LISTEN_FLAG.load(Ordering::Relaxed) & LISTEN_KEYBOARD != 0
+            && foreground::listen_allowed()
+    }
+
+    /// Dispatches a raw input packet. Returns `false` if it is not mouse, keyboard or HID
+    /// input.
+    fn process_raw_input(raw: &RAWINPUT) -> bool {
+        let data = (raw as *const RAWINPUT).cast();
+        raw_event::with_raw(RawEvent::RawInput(data), || Self::dispatch_raw_input(raw))
+    }
+
+    fn dispatch_raw_input(raw: &RAWINPUT) -> bool {
+        if raw.header.dwType == RIM_TYPEHID.0 {
+            PlatformHid::process(raw);
+            return true;
+        }
+
+        if raw.header.dwType == RIM_TYPEKEYBOARD.0 {
+            Self::process_raw_keyboard(unsafe { &raw.data.keyboard });
+            return true;
+        }
+
         // Ensure the input type is mouse
         if raw.header.dwType != RIM_TYPEMOUSE.0 {
             return false;
         }
 
+        if !Self::wants_raw_mouse() {
+            return true;
+        }
+
         let mouse = unsafe { &raw.data.mouse };
+        if Self::ignores_raw_input(mouse.ulExtraInformation) {
+            return true;
+        }
 
-        // Filter out absolute movement events to keep only relative deltas
+        // Tablets, touch screens and VM or remote desktop mice report positions
         if mouse.usFlags.0 & MOUSE_MOVE_ABSOLUTE.0 != 0 {
+            if let Some(position) = Self::absolute_position(mouse) {
+                dispatch(Event::MouseMoveAbsolute { position });
+            }
             return true;
         }
 
@@ -261,4 +454,166 @@ impl PlatformListen {
 
         true
     }
+
+    /// Maps the normalized coordinates (0 to 65535) of an absolute raw mouse packet to
+    /// global physical pixels, over the virtual desktop or the primary monitor.
+    fn absolute_position(mouse: &RAWMOUSE) -> Option<Point> {
+        let (x, y, width, height) = if mouse.usFlags.0 & MOUSE_VIRTUAL_DESKTOP.0 != 0 {
+            PlatformDisplay::get_virtual_screen_bounds()
+        } else {
+            let bounds = PlatformDisplay::get_primary_monitor()?.bounds();
+            (bounds.x, bounds.y, bounds.width, bounds.height)
+        };
+        if width <= 0.0 || height <= 0.0 {
+            return None;
+        }
+        Some(Point {
+            x: x + mouse.lLastX as f64 * width / 65535.0,
+            y: y + mouse.lLastY as f64 * height / 65535.0,
+        })
+    }
+
+    /// Dispatches a raw keyboard packet in scan code mode, before any layout or virtual-key
+    /// translation. The event's `code` is the scan code, with `0xE0` or `0xE1` in the high
+    /// byte for extended keys.
+    fn process_raw_keyboard(keyboard: &RAWKEYBOARD) {
+        if !Self::wants_raw_keyboard() {
+            return;
+        }
+        // Fake keys that are part of an escaped sequence, such as the second half of Pause
+        if keyboard.VKey == 0xFF || Self::ignores_raw_input(keyboard.ExtraInformation) {
+            return;
+        }
+
+        let flags = keyboard.Flags as u32;
+        let make_code = keyboard.MakeCode as KeyCode;
+        let scancode = if flags & RI_KEY_E1 != 0 {
+            // Pause is the only key with an E1 prefix; its make code is reported as 0x1D
+            0xE145
+        } else if flags & RI_KEY_E0 != 0 {
+            0xE000 | make_code
+        } else {
+            make_code
+        };
+
+        let key = scancode_to_key(scancode);
+        let code = Some(scancode);
+        dispatch(match flags & RI_KEY_BREAK {
+            0 => Event::KeyDown { key, code },
+            _ => Event::KeyUp { key, code },
+        });
+    }
+
+    /// Checks whether a raw input packet must be withheld because it was injected by this
+    /// crate's `Simulate`. Raw Input keeps only the low 32 bits of the extra information.
+    fn ignores_raw_input(extra_info: u32) -> bool {
+        LISTEN_IGNORE_INJECTED.load(Ordering::Relaxed) && extra_info as usize == INJECTION_MARKER
+    }
+
+    /// Checks whether more raw input is waiting in this thread's message queue.
+    fn has_queued_raw_input() -> bool {
+        // The high word holds the kinds of messages currently in the queue
+        let status = unsafe { GetQueueStatus(QS_RAWINPUT) };
+        (status >> 16) & QS_RAWINPUT.0 != 0
+    }
+
+    /// Reads every queued raw input packet with `GetRawInputBuffer`, many per call.
+    fn drain_raw_input_buffer() {
+        // `u64` elements give the buffer the 8-byte alignment of `RAWINPUT` blocks
+        let mut buffer = [0u64; RAW_INPUT_BUFFER_LEN];
+        loop {
+            let mut size = size_of_val(&buffer) as u32;
+            let count = unsafe {
+                GetRawInputBuffer(
+                    Some(buffer.as_mut_ptr().cast()),
+                    &mut size,
+                    size_of::<RAWINPUTHEADER>() as u32,
+                )
+            };
+            if count == 0 || count == u32::MAX {
+                break;
+            }
+
+            let mut block = buffer.as_ptr().cast::<u8>();
+            for _ in 0..count {
+                let raw = unsafe { &*block.cast::<RAWINPUT>() };
+                Self::process_raw_input(raw);
+                // NEXTRAWINPUTBLOCK: blocks start at 8-byte boundaries
+                let next = (raw.header.dwSize as usize).next_multiple_of(8);
+                block = unsafe { block.add(next) };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicUsize;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{AppMatcher, ForegroundApp};
+
+    fn raw_mouse_move() -> RAWINPUT {
+        let mut mouse: RAWMOUSE = unsafe { std::mem::zeroed() };
+        mouse.lLastX = 5;
+        let mut raw: RAWINPUT = unsafe { std::mem::zeroed() };
+        raw.header.dwType = RIM_TYPEMOUSE.0;
+        raw.data.mouse = mouse;
+        raw
+    }
+
+    fn raw_key_down() -> RAWINPUT {
+        let mut keyboard: RAWKEYBOARD = unsafe { std::mem::zeroed() };
+        keyboard.MakeCode = 0x1E;
+        keyboard.VKey = 0x41;
+        let mut raw: RAWINPUT = unsafe { std::mem::zeroed() };
+        raw.header.dwType = RIM_TYPEKEYBOARD.0;
+        raw.data.keyboard = keyboard;
+        raw
+    }
+
+    fn focus(exe: &str) {
+        foreground::changed(Some(ForegroundApp {
+            pid: 1,
+            exe: Some(exe.to_string()),
+            bundle_id: None,
+            title: None,
+        }));
+    }
+
+    #[serial]
+    #[test]
+    fn test_raw_input_follows_listen_rule() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let counter = count.clone();
+        let handle = PlatformListen::subscribe(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        });
+        let flags = LISTEN_FLAG.load(Ordering::SeqCst);
+        update_state(&LISTEN_FLAG, LISTEN_MOUSE_MOVE | LISTEN_KEYBOARD, true);
+        IS_LISTEN_RUNNING.store(true, Ordering::SeqCst);
+        SCANCODE_MODE.store(true, Ordering::SeqCst);
+        foreground::set_watching(true);
+        foreground::set_listen_rule(Some(AppMatcher::exe("app.exe")));
+
+        let feed = || {
+            PlatformListen::dispatch_raw_input(&raw_mouse_move());
+            PlatformListen::dispatch_raw_input(&raw_key_down());
+        };
+        focus("other.exe");
+        feed();
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+        focus("app.exe");
+        feed();
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+
+        foreground::set_listen_rule(None);
+        foreground::set_watching(false);
+        SCANCODE_MODE.store(false, Ordering::SeqCst);
+        IS_LISTEN_RUNNING.store(false, Ordering::SeqCst);
+        LISTEN_FLAG.store(flags, Ordering::SeqCst);
+        handle.unsubscribe();
+    }
 }
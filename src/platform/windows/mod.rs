@@ -1,7 +1,40 @@
+#[cfg(feature = "listen")]
 mod common;
+#[cfg(feature = "listen")]
 mod core;
+#[cfg(feature = "listen")]
+mod device;
 mod display;
+#[cfg(feature = "listen")]
+mod foreground;
+#[cfg(feature = "listen")]
 mod grab;
+#[cfg(feature = "listen")]
+mod hid;
+#[cfg(feature = "listen")]
+mod hotkey;
+#[cfg(any(feature = "listen", feature = "simulate"))]
 mod keycode;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+mod layout;
+#[cfg(feature = "listen")]
 mod listen;
+mod mouse;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+mod poll;
+#[cfg(any(feature = "listen", feature = "simulate"))]
 mod simulate;
+
+use crate::OsError;
+
+/// Extracts the Win32 error code from an API error, which wraps it in an `HRESULT`.
+pub(crate) fn os_error(err: windows::core::Error) -> OsError {
+    let code = err.code().0;
+    // HRESULT_FROM_WIN32 puts the code in the low word, with FACILITY_WIN32
+    let code = if (code as u32) & 0xFFFF_0000 == 0x8007_0000 {
+        code & 0xFFFF
+    } else {
+        code
+    };
+    OsError { code }
+}
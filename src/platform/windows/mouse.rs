@@ -0,0 +1,109 @@
+use std::ffi::c_void;
+
+use windows::Win32::UI::WindowsAndMessaging::{
+    SPI_GETMOUSE, SPI_GETMOUSESPEED, SPI_GETWHEELSCROLLCHARS, SPI_GETWHEELSCROLLLINES,
+    SPI_SETMOUSE, SPI_SETMOUSESPEED, SPIF_SENDCHANGE, SYSTEM_PARAMETERS_INFO_ACTION,
+    SystemParametersInfoW,
+};
+
+use crate::{
+    OsError, Point,
+    platform::{MouseImpl, PlatformMouse, windows::os_error},
+};
+
+/// The range of `SPI_SETMOUSESPEED`, 10 being the default.
+const MIN_SPEED: f64 = 1.0;
+const MAX_SPEED: f64 = 20.0;
+
+/// The thresholds and acceleration `SPI_SETMOUSE` uses for "Enhance pointer
+/// precision", as set by the Control Panel.
+const ACCELERATION_ON: [i32; 3] = [6, 10, 1];
+const ACCELERATION_OFF: [i32; 3] = [0, 0, 0];
+
+/// The scroll lines setting for scrolling one page per notch.
+const WHEEL_PAGESCROLL: u32 = u32::MAX;
+
+/// Changes are broadcast but not written to the user profile, so they last until logoff
+/// like on macOS.
+impl MouseImpl for PlatformMouse {
+    fn get_speed() -> Option<f64> {
+        let mut speed = 0u32;
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETMOUSESPEED,
+                0,
+                Some(&mut speed as *mut u32 as *mut c_void),
+                Default::default(),
+            )
+            .ok()?;
+        }
+        Some((speed as f64 - MIN_SPEED) / (MAX_SPEED - MIN_SPEED))
+    }
+
+    fn set_speed(speed: f64) -> Result<(), OsError> {
+        let speed = (MIN_SPEED + speed.clamp(0.0, 1.0) * (MAX_SPEED - MIN_SPEED)).round();
+        // The speed is passed as the pointer itself
+        unsafe {
+            SystemParametersInfoW(
+                SPI_SETMOUSESPEED,
+                0,
+                Some(speed as usize as *mut c_void),
+                SPIF_SENDCHANGE,
+            )
+            .map_err(os_error)
+        }
+    }
+
+    fn get_acceleration() -> Option<bool> {
+        let mut params = [0i32; 3];
+        unsafe {
+            SystemParametersInfoW(
+                SPI_GETMOUSE,
+                0,
+                Some(params.as_mut_ptr() as *mut c_void),
+                Default::default(),
+            )
+            .ok()?;
+        }
+        Some(params[2] != 0)
+    }
+
+    fn set_acceleration(enable: bool) -> Result<(), OsError> {
+        let mut params = if enable {
+            ACCELERATION_ON
+        } else {
+            ACCELERATION_OFF
+        };
+        unsafe {
+            SystemParametersInfoW(
+                SPI_SETMOUSE,
+                0,
+                Some(params.as_mut_ptr() as *mut c_void),
+                SPIF_SENDCHANGE,
+            )
+            .map_err(os_error)
+        }
+    }
+
+    fn get_scroll_lines() -> Option<Point> {
+        Some(Point {
+            x: get_u32(SPI_GETWHEELSCROLLCHARS)? as f64,
+            y: get_u32(SPI_GETWHEELSCROLLLINES)? as f64,
+        })
+    }
+}
+
+/// Reads a `u32` setting, `None` when it is unset or scrolls a whole page per notch.
+fn get_u32(action: SYSTEM_PARAMETERS_INFO_ACTION) -> Option<u32> {
+    let mut value = 0u32;
+    unsafe {
+        SystemParametersInfoW(
+            action,
+            0,
+            Some(&mut value as *mut u32 as *mut c_void),
+            Default::default(),
+        )
+        .ok()?;
+    }
+    (value != WHEEL_PAGESCROLL).then_some(value)
+}
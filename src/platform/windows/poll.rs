@@ -0,0 +1,53 @@
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    GetAsyncKeyState, MAPVK_VK_TO_VSC_EX, MapVirtualKeyW, VIRTUAL_KEY, VK_CONTROL, VK_LBUTTON,
+    VK_MBUTTON, VK_MENU, VK_RBUTTON, VK_SHIFT, VK_XBUTTON1, VK_XBUTTON2,
+};
+
+use crate::{
+    Key, KeyCode, MouseButton, RawKey,
+    platform::{PlatformPoll, PollImpl, windows::keycode::get_win_key},
+};
+
+const BUTTONS: [(VIRTUAL_KEY, MouseButton); 5] = [
+    (VK_LBUTTON, MouseButton::Left),
+    (VK_RBUTTON, MouseButton::Right),
+    (VK_MBUTTON, MouseButton::Middle),
+    (VK_XBUTTON1, MouseButton::Back),
+    (VK_XBUTTON2, MouseButton::Forward),
+];
+
+impl PollImpl for PlatformPoll {
+    fn pressed_keys() -> Vec<(Key, KeyCode)> {
+        (0x08..=0xFE)
+            // The mouse buttons, and the modifiers that have left and right codes too
+            .filter(|&vk| {
+                !BUTTONS.iter().any(|&(button, _)| button.0 == vk)
+                    && ![VK_SHIFT, VK_CONTROL, VK_MENU].iter().any(|m| m.0 == vk)
+            })
+            .filter(|&vk| is_down(VIRTUAL_KEY(vk)))
+            .map(|vk| {
+                let code = KeyCode::from(vk);
+                let scancode = unsafe { MapVirtualKeyW(code, MAPVK_VK_TO_VSC_EX) };
+                let key = match get_win_key(code, scancode) {
+                    Key::Unidentified => Key::Raw(RawKey(code)),
+                    key => key,
+                };
+                (key, code)
+            })
+            .collect()
+    }
+
+    /// Reports the physical buttons, before swapping the primary and secondary button.
+    #[cfg(feature = "listen")]
+    fn pressed_buttons() -> Vec<MouseButton> {
+        BUTTONS
+            .iter()
+            .filter(|&&(vk, _)| is_down(vk))
+            .map(|&(_, button)| button)
+            .collect()
+    }
+}
+
+fn is_down(vk: VIRTUAL_KEY) -> bool {
+    unsafe { GetAsyncKeyState(i32::from(vk.0)) as u16 & 0x8000 != 0 }
+}
@@ -1,22 +1,32 @@
-use std::mem::size_of;
+use std::{
+    mem::size_of,
+    sync::atomic::{AtomicBool, Ordering},
+};
 
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBD_EVENT_FLAGS, KEYBDINPUT,
-    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE, MOUSEEVENTF_ABSOLUTE,
-    MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN,
-    MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP,
-    MOUSEEVENTF_VIRTUALDESK, MOUSEEVENTF_WHEEL, MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT,
-    SendInput, VIRTUAL_KEY,
+    HKL, INPUT, INPUT_0, INPUT_KEYBOARD, INPUT_MOUSE, KEYBD_EVENT_FLAGS, KEYBDINPUT,
+    KEYEVENTF_EXTENDEDKEY, KEYEVENTF_KEYUP, KEYEVENTF_SCANCODE, KEYEVENTF_UNICODE,
+    MAPVK_VK_TO_VSC_EX, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_HWHEEL, MOUSEEVENTF_LEFTDOWN,
+    MOUSEEVENTF_LEFTUP, MOUSEEVENTF_MIDDLEDOWN, MOUSEEVENTF_MIDDLEUP, MOUSEEVENTF_MOVE,
+    MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_RIGHTUP, MOUSEEVENTF_VIRTUALDESK, MOUSEEVENTF_WHEEL,
+    MOUSEEVENTF_XDOWN, MOUSEEVENTF_XUP, MOUSEINPUT, MapVirtualKeyExW, SendInput, VIRTUAL_KEY,
+    VK_LCONTROL, VK_RMENU, VK_SHIFT,
 };
 
 use crate::{
     Event, Key, MouseButton,
     platform::{
-        DisplayImpl, PlatformDisplay, PlatformSimulate, SimulateImpl,
-        windows::keycode::get_win_codes,
+        DisplayImpl, INJECTION_MARKER, PlatformDisplay, PlatformSimulate, SimulateImpl,
+        windows::{
+            keycode::get_win_codes,
+            layout::{Keystroke, caps_lock, foreground_layout, keystroke},
+        },
     },
 };
 
+/// Resolves simulated keys through the active layout, see [`crate::KeySemantics`].
+static CHARACTER_SEMANTICS: AtomicBool = AtomicBool::new(false);
+
 impl SimulateImpl for PlatformSimulate {
     fn simulate(event: Event) {
         InputBuilder::new().add_event(event).send();
@@ -41,7 +51,33 @@ impl SimulateImpl for PlatformSimulate {
     fn keyboard(key: Key, down: bool) {
         InputBuilder::new().add_keyboard(key, down).send();
     }
+
+    #[cfg(feature = "simulate")]
+    fn key_semantics(semantics: crate::KeySemantics) {
+        let character = semantics == crate::KeySemantics::Character;
+        CHARACTER_SEMANTICS.store(character, Ordering::SeqCst);
+    }
+
+    fn text(text: &str) {
+        let layout = foreground_layout();
+        let caps_lock = caps_lock();
+        let mut builder = InputBuilder::new();
+        for ch in text.chars() {
+            builder = match keystroke(ch, layout, caps_lock) {
+                Some(stroke) => builder.add_keystroke(stroke, layout),
+                None => builder.add_unicode(ch),
+            };
+        }
+        builder.send();
+    }
+
+    fn unicode(text: &str) {
+        text.chars()
+            .fold(InputBuilder::new(), InputBuilder::add_unicode)
+            .send();
+    }
 }
+
 struct InputBuilder {
     inputs: Vec<INPUT>,
 }
@@ -53,11 +89,16 @@ impl InputBuilder {
     fn add_event(self, event: Event) -> Self {
         match event {
             Event::MouseMove { delta, .. } => self.add_mouse_move(delta.x, delta.y),
+            Event::MouseMoveAbsolute { position } => {
+                self.add_mouse_move_to_physical(position.x, position.y)
+            }
+            Event::MouseWheel { raw: Some(raw), .. } => self.add_mouse_wheel_raw(raw.x, raw.y),
             Event::MouseWheel { delta, .. } => self.add_mouse_wheel(delta.x, delta.y),
             Event::MouseDown { button, .. } => self.add_mouse_button(button, true),
             Event::MouseUp { button, .. } => self.add_mouse_button(button, false),
             Event::KeyDown { key, .. } => self.add_keyboard(key, true),
             Event::KeyUp { key, .. } => self.add_keyboard(key, false),
+            Event::FocusChanged { .. } | Event::ModifiersChanged { .. } => self,
         }
     }
 
@@ -72,7 +113,14 @@ impl InputBuilder {
     }
 
     /// Adds absolute mouse movement.
-    fn add_mouse_move_to(mut self, x: f64, y: f64) -> Self {
+    #[cfg_attr(not(feature = "simulate"), allow(dead_code))]
+    fn add_mouse_move_to(self, x: f64, y: f64) -> Self {
+        let scale_factor = PlatformDisplay::get_scale_factor();
+        self.add_mouse_move_to_physical(x * scale_factor, y * scale_factor)
+    }
+
+    /// Adds absolute mouse movement to a position in global physical coordinates.
+    fn add_mouse_move_to_physical(mut self, phys_x: f64, phys_y: f64) -> Self {
         // Get the boundary of the entire virtual desktop (multi-monitor support).
         let (vx, vy, vw, vh) = PlatformDisplay::get_virtual_screen_bounds();
 
@@ -80,11 +128,6 @@ impl InputBuilder {
             return self;
         }
 
-        let scale_factor = PlatformDisplay::get_scale_factor();
-
-        let phys_x = x * scale_factor;
-        let phys_y = y * scale_factor;
-
         // Normalized mapping logic:
         // Coordinate mapping formula for SendInput: (physical coordinates - start offset) * 65535 / (total size - 1)
         // Use f64 calculations to prevent overflow or loss of precision in intermediate steps.
@@ -123,17 +166,23 @@ impl InputBuilder {
         self
     }
 
-    fn add_mouse_wheel(mut self, dx: f64, dy: f64) -> Self {
-        if dy.abs() > f64::EPSILON {
+    fn add_mouse_wheel(self, dx: f64, dy: f64) -> Self {
+        self.add_mouse_wheel_raw(dx * 120.0, dy * 120.0)
+    }
+
+    /// Adds wheel events in `WHEEL_DELTA` units, 120 per notch.
+    fn add_mouse_wheel_raw(mut self, dx: f64, dy: f64) -> Self {
+        let (dx, dy) = (dx as i32, dy as i32);
+        if dy != 0 {
             self.push_mouse(MOUSEINPUT {
-                mouseData: (dy * 120.0) as i32 as u32,
+                mouseData: dy as u32,
                 dwFlags: MOUSEEVENTF_WHEEL,
                 ..Default::default()
             });
         }
-        if dx.abs() > f64::EPSILON {
+        if dx != 0 {
             self.push_mouse(MOUSEINPUT {
-                mouseData: (dx * 120.0) as i32 as u32,
+                mouseData: dx as u32,
                 dwFlags: MOUSEEVENTF_HWHEEL,
                 ..Default::default()
             });
@@ -144,11 +193,20 @@ impl InputBuilder {
     /// Adds a keyboard event to the input queue.
     fn add_keyboard(mut self, key: Key, down: bool) -> Self {
         // 1. Get Windows-specific Virtual Key and Scan Code from cross-platform Key enum.
-        let (vk, scancode) = match get_win_codes(key) {
+        let (vk, mut scancode) = match get_win_codes(key) {
             Some(codes) => codes,
             None => return self,
         };
 
+        // The key's position on the active layout, e.g. where A is on AZERTY
+        if CHARACTER_SEMANTICS.load(Ordering::Relaxed) && scancode != 0 {
+            let mapped =
+                unsafe { MapVirtualKeyExW(vk, MAPVK_VK_TO_VSC_EX, Some(foreground_layout())) };
+            if mapped != 0 {
+                scancode = mapped;
+            }
+        }
+
         // 2. Determine whether to use Scan Code or Virtual Key mode.
         // Referencing logic: scancode mode is preferred for better compatibility with physical layouts.
         let (w_vk, w_scan, mut flags) = if scancode != 0 {
@@ -179,14 +237,72 @@ impl InputBuilder {
         self
     }
 
-    fn push_mouse(&mut self, mi: MOUSEINPUT) {
+    /// Adds a press and release of the key typing a character, holding its modifiers.
+    fn add_keystroke(mut self, stroke: Keystroke, layout: HKL) -> Self {
+        // AltGr is pressed as left Ctrl and right Alt, which layouts without AltGr
+        // also understand
+        let mut modifiers = Vec::new();
+        if stroke.alt_gr {
+            modifiers.extend([VK_LCONTROL.0, VK_RMENU.0]);
+        }
+        if stroke.shift {
+            modifiers.push(VK_SHIFT.0);
+        }
+
+        for &vk in &modifiers {
+            self.push_vk(vk, true, layout);
+        }
+        self.push_vk(stroke.vk, true, layout);
+        self.push_vk(stroke.vk, false, layout);
+        for &vk in modifiers.iter().rev() {
+            self.push_vk(vk, false, layout);
+        }
+        self
+    }
+
+    /// Adds a character as Unicode input (`VK_PACKET`), independent of the layout.
+    fn add_unicode(mut self, ch: char) -> Self {
+        let mut buf = [0u16; 2];
+        for &unit in ch.encode_utf16(&mut buf).iter() {
+            for flags in [KEYEVENTF_UNICODE, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP] {
+                self.push_keyboard(KEYBDINPUT {
+                    wScan: unit,
+                    dwFlags: flags,
+                    ..Default::default()
+                });
+            }
+        }
+        self
+    }
+
+    /// Adds a virtual key event, with the scan code the key has on `layout`.
+    fn push_vk(&mut self, vk: u16, down: bool, layout: HKL) {
+        let scan = unsafe { MapVirtualKeyExW(u32::from(vk), MAPVK_VK_TO_VSC_EX, Some(layout)) };
+        let mut flags = 0;
+        if (scan >> 8) == 0xE0 || (scan >> 8) == 0xE1 {
+            flags |= KEYEVENTF_EXTENDEDKEY.0;
+        }
+        if !down {
+            flags |= KEYEVENTF_KEYUP.0;
+        }
+        self.push_keyboard(KEYBDINPUT {
+            wVk: VIRTUAL_KEY(vk),
+            wScan: scan as u16,
+            dwFlags: KEYBD_EVENT_FLAGS(flags),
+            ..Default::default()
+        });
+    }
+
+    fn push_mouse(&mut self, mut mi: MOUSEINPUT) {
+        mi.dwExtraInfo = INJECTION_MARKER;
         self.inputs.push(INPUT {
             r#type: INPUT_MOUSE,
             Anonymous: INPUT_0 { mi },
         });
     }
 
-    fn push_keyboard(&mut self, ki: KEYBDINPUT) {
+    fn push_keyboard(&mut self, mut ki: KEYBDINPUT) {
+        ki.dwExtraInfo = INJECTION_MARKER;
         self.inputs.push(INPUT {
             r#type: INPUT_KEYBOARD,
             Anonymous: INPUT_0 { ki },
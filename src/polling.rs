@@ -0,0 +1,291 @@
+use std::{
+    sync::atomic::{AtomicU8, AtomicU64, Ordering},
+    thread,
+    time::Duration,
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    CoreError, Event, Key, KeyCode, MouseButton, Point, pipeline,
+    platform::{
+        CoreImpl, DisplayImpl, HotkeyImpl, PlatformCore, PlatformDisplay, PlatformHotkeys,
+        PlatformListen, PlatformPoll, PollImpl,
+    },
+    sequence::{self, EventSource},
+    state,
+};
+
+const RUNNING: u8 = 0;
+const PAUSED: u8 = 1;
+const STOPPED: u8 = 2;
+
+static STATE: AtomicU8 = AtomicU8::new(STOPPED);
+
+/// The sampling interval of the running poll, in microseconds.
+static INTERVAL: AtomicU64 = AtomicU64::new(0);
+
+/// How the core captures input, see [`CoreState::capture`](crate::CoreState::capture).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum CaptureMode {
+    /// The low-level hooks on Windows and the event tap on macOS. The default.
+    #[default]
+    Hooks,
+    /// The polling fallback of [`Core::start_polling`](crate::Core::start_polling),
+    /// sampling every `interval`.
+    Polling { interval: Duration },
+}
+
+impl CaptureMode {
+    /// Returns what input captured this way lacks.
+    pub fn limitations(&self) -> Limitations {
+        match self {
+            CaptureMode::Hooks => Limitations::default(),
+            CaptureMode::Polling { .. } => Limitations {
+                no_blocking: true,
+                no_wheel: true,
+                coarse_timing: true,
+                no_injected_detection: true,
+                cursor_deltas: true,
+            },
+        }
+    }
+}
+
+/// What a [`CaptureMode`] cannot do. All `false` for the hooks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Limitations {
+    /// Events cannot be blocked or changed: `Grab`, remapping and the blocking of
+    /// hotkeys have no effect. Hotkeys still fire.
+    pub no_blocking: bool,
+    /// Mouse wheel events are not captured.
+    pub no_wheel: bool,
+    /// Events are only as precise as the sampling interval, and a key pressed and
+    /// released within one interval is missed.
+    pub coarse_timing: bool,
+    /// Events injected by `Simulate` cannot be told apart from physical input.
+    pub no_injected_detection: bool,
+    /// Mouse moves are derived from the cursor position: pixels after pointer
+    /// acceleration, which stop at the screen edges.
+    pub cursor_deltas: bool,
+}
+
+/// Returns `true` while the poll runs and is not paused.
+pub(crate) fn is_running() -> bool {
+    STATE.load(Ordering::SeqCst) == RUNNING
+}
+
+/// Returns `true` from the start of the poll until it stops, paused or not.
+pub(crate) fn is_active() -> bool {
+    STATE.load(Ordering::SeqCst) != STOPPED
+}
+
+/// Returns how the core captures input.
+pub(crate) fn capture_mode() -> CaptureMode {
+    if is_active() {
+        CaptureMode::Polling {
+            interval: Duration::from_micros(INTERVAL.load(Ordering::SeqCst)),
+        }
+    } else {
+        CaptureMode::Hooks
+    }
+}
+
+pub(crate) fn pause() {
+    let _ = STATE.compare_exchange(RUNNING, PAUSED, Ordering::SeqCst, Ordering::SeqCst);
+}
+
+pub(crate) fn resume() {
+    let _ = STATE.compare_exchange(PAUSED, RUNNING, Ordering::SeqCst, Ordering::SeqCst);
+}
+
+pub(crate) fn stop() {
+    STATE.store(STOPPED, Ordering::SeqCst);
+}
+
+/// Samples the input state every `interval` until [`stop`], see
+/// [`Core::start_polling`](crate::Core::start_polling).
+pub(crate) fn run(interval: Duration) -> Result<(), CoreError> {
+    if PlatformCore::is_runing() || PlatformCore::hooks_installed() {
+        return Err(CoreError::AlreadyRunning);
+    }
+    INTERVAL.store(interval.as_micros() as u64, Ordering::SeqCst);
+    if STATE
+        .compare_exchange(STOPPED, RUNNING, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return Err(CoreError::AlreadyRunning);
+    }
+    // Hotkeys are matched from the samples instead of the OS hotkey service
+    PlatformHotkeys::update();
+
+    let mut sampler = Sampler::default();
+    loop {
+        match STATE.load(Ordering::SeqCst) {
+            STOPPED => break,
+            // Moves are not reported across a pause
+            PAUSED => sampler.forget_cursor(),
+            _ => {
+                let events = sampler.sample(Snapshot {
+                    keys: PlatformPoll::pressed_keys(),
+                    buttons: PlatformPoll::pressed_buttons(),
+                    cursor: PlatformDisplay::get_cursor_position().map(|(x, y)| Point { x, y }),
+                });
+                for event in events {
+                    dispatch(event);
+                }
+            }
+        }
+        thread::sleep(interval);
+    }
+
+    PlatformHotkeys::update();
+    Ok(())
+}
+
+/// Delivers a sampled event like the hooks do, except that nothing can be blocked.
+fn dispatch(event: Event) {
+    state::observed();
+    sequence::with_origin(EventSource::Polling, Duration::ZERO, || {
+        PlatformListen::handle(event, false)
+    });
+    // Hotkeys and other stages still see the event, but their verdicts cannot apply
+    let _ = pipeline::run(event, false);
+}
+
+/// The input state at one point in time.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Snapshot {
+    /// The keys held down, with their platform key codes.
+    keys: Vec<(Key, KeyCode)>,
+    buttons: Vec<MouseButton>,
+    cursor: Option<Point>,
+}
+
+/// Turns successive snapshots into the events between them.
+#[derive(Debug, Default)]
+pub(crate) struct Sampler {
+    last: Option<Snapshot>,
+}
+
+impl Sampler {
+    /// Returns the events leading from the previous snapshot to `now`: the move first,
+    /// then releases, then presses. The first snapshot only sets the baseline, so input
+    /// held before the poll started is not reported.
+    pub(crate) fn sample(&mut self, now: Snapshot) -> Vec<Event> {
+        let Some(last) = self.last.replace(now.clone()) else {
+            return Vec::new();
+        };
+        let mut events = Vec::new();
+
+        if let (Some(from), Some(to)) = (last.cursor, now.cursor)
+            && from != to
+        {
+            events.push(Event::MouseMove {
+                delta: Point {
+                    x: to.x - from.x,
+                    y: to.y - from.y,
+                },
+            });
+        }
+
+        for button in &last.buttons {
+            if !now.buttons.contains(button) {
+                events.push(Event::MouseUp { button: *button });
+            }
+        }
+        for &(key, code) in &last.keys {
+            if !now.keys.iter().any(|&(held, _)| held == key) {
+                events.push(Event::KeyUp {
+                    key,
+                    code: Some(code),
+                });
+            }
+        }
+        for button in &now.buttons {
+            if !last.buttons.contains(button) {
+                events.push(Event::MouseDown { button: *button });
+            }
+        }
+        for &(key, code) in &now.keys {
+            if !last.keys.iter().any(|&(held, _)| held == key) {
+                events.push(Event::KeyDown {
+                    key,
+                    code: Some(code),
+                });
+            }
+        }
+        events
+    }
+
+    /// Makes the next snapshot set the cursor baseline instead of reporting a move.
+    pub(crate) fn forget_cursor(&mut self) {
+        if let Some(last) = &mut self.last {
+            last.cursor = None;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(keys: &[Key], buttons: &[MouseButton], cursor: (f64, f64)) -> Snapshot {
+        Snapshot {
+            keys: keys.iter().map(|&key| (key, 0)).collect(),
+            buttons: buttons.to_vec(),
+            cursor: Some(Point {
+                x: cursor.0,
+                y: cursor.1,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_sampler_reports_changes() {
+        let mut sampler = Sampler::default();
+        // Input held when the poll starts is not reported
+        assert!(
+            sampler
+                .sample(snapshot(&[Key::KeyA], &[], (0.0, 0.0)))
+                .is_empty()
+        );
+
+        let events = sampler.sample(snapshot(&[Key::KeyB], &[MouseButton::Left], (3.0, -1.0)));
+        assert_eq!(
+            events,
+            [
+                Event::MouseMove {
+                    delta: Point { x: 3.0, y: -1.0 }
+                },
+                Event::KeyUp {
+                    key: Key::KeyA,
+                    code: Some(0)
+                },
+                Event::MouseDown {
+                    button: MouseButton::Left
+                },
+                Event::KeyDown {
+                    key: Key::KeyB,
+                    code: Some(0)
+                },
+            ]
+        );
+        assert!(
+            sampler
+                .sample(snapshot(&[Key::KeyB], &[MouseButton::Left], (3.0, -1.0)))
+                .is_empty()
+        );
+
+        sampler.forget_cursor();
+        assert_eq!(
+            sampler.sample(snapshot(&[Key::KeyB], &[], (9.0, 9.0))),
+            [Event::MouseUp {
+                button: MouseButton::Left
+            }]
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use std::sync::{Mutex, PoisonError};
+
+use crate::event::{Event, MouseButton};
+use crate::key::Key;
+
+/// The keys and buttons believed to be down, see [`track`].
+struct Pressed {
+    keys: Vec<Key>,
+    buttons: Vec<MouseButton>,
+}
+
+static PRESSED: Mutex<Pressed> = Mutex::new(Pressed {
+    keys: Vec::new(),
+    buttons: Vec::new(),
+});
+
+/// Records a key or button transition, observed by the hooks or injected by `Simulate`.
+pub(crate) fn track(event: &Event) {
+    let mut pressed = PRESSED.lock().unwrap_or_else(PoisonError::into_inner);
+    match *event {
+        Event::KeyDown { key, .. } if !pressed.keys.contains(&key) => pressed.keys.push(key),
+        Event::KeyUp { key, .. } => pressed.keys.retain(|&held| held != key),
+        Event::MouseDown { button } if !pressed.buttons.contains(&button) => {
+            pressed.buttons.push(button)
+        }
+        Event::MouseUp { button } => pressed.buttons.retain(|&held| held != button),
+        _ => {}
+    }
+}
+
+/// Returns the keys and buttons believed to be down, in the order they went down, and
+/// forgets them.
+#[cfg_attr(not(feature = "simulate"), allow(dead_code))]
+pub(crate) fn take() -> (Vec<Key>, Vec<MouseButton>) {
+    let mut pressed = PRESSED.lock().unwrap_or_else(PoisonError::into_inner);
+    (
+        std::mem::take(&mut pressed.keys),
+        std::mem::take(&mut pressed.buttons),
+    )
+}
@@ -0,0 +1,218 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, MutexGuard, PoisonError,
+        atomic::{AtomicU64, Ordering},
+    },
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    Grab, Listen,
+    key::Key,
+    remap::{Remap, RemapHandle},
+    state::{GrabFlags, ListenFlags},
+};
+
+type Callback = Arc<dyn Fn(ProfileEvent) + Send + Sync + 'static>;
+
+/// A named set of listen, grab and remap settings, see [`Profiles`].
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Profile {
+    /// The listener flags to set, or `None` to leave them as they are. `running` is
+    /// ignored, starting and stopping is left to the application.
+    pub listen: Option<ListenFlags>,
+    /// The grabber flags to set, or `None` to leave them as they are. `running` is
+    /// ignored.
+    pub grab: Option<GrabFlags>,
+    /// `(from, to)` key remaps, registered while the profile is active.
+    pub remaps: Vec<(Key, Key)>,
+}
+
+/// Emitted when the active profile changes, see [`Profiles::subscribe`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileEvent {
+    /// The profile that was active before, if any.
+    pub previous: Option<String>,
+    /// The profile that is active now, if any.
+    pub current: Option<String>,
+}
+
+#[derive(Default)]
+struct State {
+    profiles: HashMap<String, Profile>,
+    /// The active profile with the remaps it registered.
+    active: Option<(String, Vec<RemapHandle>)>,
+}
+
+impl State {
+    /// Removes the remaps of the active profile and returns its name.
+    fn take_active(&mut self) -> Option<String> {
+        let (name, remaps) = self.active.take()?;
+        remaps.into_iter().for_each(RemapHandle::remove);
+        Some(name)
+    }
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(Mutex::default);
+static CALLBACKS: Lazy<Mutex<Vec<(u64, Callback)>>> = Lazy::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+fn lock() -> MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn callbacks() -> MutexGuard<'static, Vec<(u64, Callback)>> {
+    CALLBACKS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Calls the subscribers if the active profile changed.
+fn emit(previous: Option<String>, current: Option<String>) {
+    if previous == current {
+        return;
+    }
+    // Cloned so a callback can subscribe or switch profiles itself
+    let subscribers: Vec<Callback> = callbacks().iter().map(|(_, cb)| cb.clone()).collect();
+    let event = ProfileEvent { previous, current };
+    for callback in subscribers {
+        callback(event.clone());
+    }
+}
+
+fn apply_listen(flags: &ListenFlags) {
+    Listen::mouse_move(flags.mouse_move);
+    Listen::mouse_button(flags.mouse_button);
+    Listen::mouse_wheel(flags.mouse_wheel);
+    Listen::keyboard(flags.keyboard);
+    Listen::scancode_mode(flags.scancode_mode);
+    Listen::ignore_injected(flags.ignore_injected);
+}
+
+fn apply_grab(flags: &GrabFlags) {
+    Grab::mouse_move(flags.mouse_move);
+    Grab::mouse_button(flags.mouse_button);
+    Grab::mouse_wheel(flags.mouse_wheel);
+    Grab::keyboard(flags.keyboard);
+    Grab::exclude_own_windows(flags.exclude_own_windows);
+    Grab::pass_injected(flags.pass_injected);
+}
+
+/// Named profiles of listen, grab and remap settings, switched at runtime.
+///
+/// At most one profile is active. Activating a profile sets its flags and replaces the
+/// remaps of the previous profile with its own; flags a profile leaves as `None` keep
+/// the values the previous one set. Subscribers are told about every change.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{GrabFlags, Key, Profile, Profiles};
+///
+/// Profiles::define("typing", Profile {
+///     remaps: vec![(Key::CapsLock, Key::Escape)],
+///     ..Default::default()
+/// });
+/// Profiles::define("gaming", Profile {
+///     grab: Some(GrabFlags { keyboard: true, ..Default::default() }),
+///     ..Default::default()
+/// });
+/// let handle = Profiles::subscribe(|event| println!("Profile: {:?}", event.current));
+///
+/// Profiles::activate("gaming");
+///
+/// // Later
+/// handle.unsubscribe();
+/// ```
+pub struct Profiles;
+
+impl Profiles {
+    /// Defines the profile `name`, replacing a previous definition.
+    ///
+    /// Redefining the active profile takes effect the next time it is activated.
+    pub fn define(name: impl Into<String>, profile: Profile) {
+        lock().profiles.insert(name.into(), profile);
+    }
+
+    /// Removes the profile `name` and returns it. The profile is deactivated first if it
+    /// is active.
+    pub fn remove(name: &str) -> Option<Profile> {
+        let mut state = lock();
+        let is_active = state
+            .active
+            .as_ref()
+            .is_some_and(|(active, _)| active == name);
+        let previous = if is_active { state.take_active() } else { None };
+        let profile = state.profiles.remove(name);
+        drop(state);
+        emit(previous, None);
+        profile
+    }
+
+    /// Activates the profile `name`. Returns `false` if no such profile is defined.
+    ///
+    /// Activating the active profile again reapplies it without emitting an event.
+    pub fn activate(name: &str) -> bool {
+        let mut state = lock();
+        let Some(profile) = state.profiles.get(name).cloned() else {
+            return false;
+        };
+        let previous = state.take_active();
+        if let Some(flags) = &profile.listen {
+            apply_listen(flags);
+        }
+        if let Some(flags) = &profile.grab {
+            apply_grab(flags);
+        }
+        let remaps = profile
+            .remaps
+            .iter()
+            .map(|&(from, to)| Remap::add(from, to))
+            .collect();
+        state.active = Some((name.to_string(), remaps));
+        drop(state);
+        emit(previous, Some(name.to_string()));
+        true
+    }
+
+    /// Deactivates the active profile, removing its remaps. Its flags are kept.
+    pub fn deactivate() {
+        let previous = lock().take_active();
+        emit(previous, None);
+    }
+
+    /// Returns the name of the active profile.
+    pub fn active() -> Option<String> {
+        lock().active.as_ref().map(|(name, _)| name.clone())
+    }
+
+    /// Returns the names of the defined profiles, sorted.
+    pub fn names() -> Vec<String> {
+        let mut names: Vec<String> = lock().profiles.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Calls `callback` whenever the active profile changes.
+    ///
+    /// The callback runs on the thread that changed the profile.
+    pub fn subscribe<F>(callback: F) -> ProfileHandle
+    where
+        F: Fn(ProfileEvent) + Send + Sync + 'static,
+    {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        callbacks().push((id, Arc::new(callback)));
+        ProfileHandle { id }
+    }
+}
+
+/// A handle to a [`Profiles`] subscriber.
+pub struct ProfileHandle {
+    id: u64,
+}
+
+impl ProfileHandle {
+    /// Stops receiving profile changes.
+    pub fn unsubscribe(self) {
+        callbacks().retain(|(id, _)| *id != self.id);
+    }
+}
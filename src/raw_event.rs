@@ -0,0 +1,86 @@
+use std::{cell::Cell, ffi::c_void};
+
+use crate::{Listen, event::Event, subscription::SubscriptionHandle};
+
+/// The OS data an event was translated from, see
+/// [`Listen::subscribe_raw`](crate::Listen::subscribe_raw).
+///
+/// The pointers are only valid until the callback returns, and the data must not be
+/// written through them.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RawEvent {
+    /// The low-level keyboard hook on Windows: the hook message, such as `WM_KEYDOWN`,
+    /// and a `*const KBDLLHOOKSTRUCT`.
+    KeyboardHook { message: u32, data: *const c_void },
+    /// The low-level mouse hook on Windows: the hook message, such as `WM_MOUSEMOVE`,
+    /// and a `*const MSLLHOOKSTRUCT`.
+    MouseHook { message: u32, data: *const c_void },
+    /// Raw Input on Windows: a `*const RAWINPUT`.
+    RawInput(*const c_void),
+    /// The event tap on macOS: the `CGEventType` and the `CGEventRef`.
+    CGEvent {
+        event_type: u32,
+        event: *const c_void,
+    },
+}
+
+thread_local! {
+    /// The OS data of the input the backend is handling on this thread.
+    static CURRENT: Cell<Option<RawEvent>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, attributing the events it dispatches to `raw`. Called by the platform
+/// backends.
+#[cfg_attr(
+    any(feature = "mock", not(any(target_os = "windows", target_os = "macos"))),
+    allow(dead_code)
+)]
+pub(crate) fn with_raw<R>(raw: RawEvent, f: impl FnOnce() -> R) -> R {
+    // Restored afterwards, since a subscriber may cause a nested dispatch
+    let outer = CURRENT.replace(Some(raw));
+    let result = f();
+    CURRENT.set(outer);
+    result
+}
+
+/// Returns the OS data of the event being dispatched on this thread.
+fn current() -> Option<RawEvent> {
+    CURRENT.get()
+}
+
+/// Subscribes `callback` to events with their OS data, see
+/// [`Listen::subscribe_raw`](crate::Listen::subscribe_raw).
+pub(crate) fn subscribe<F>(callback: F) -> SubscriptionHandle
+where
+    F: Fn(Event, RawEvent) + Send + Sync + 'static,
+{
+    Listen::subscribe(move |event| {
+        if let Some(raw) = current() {
+            callback(event, raw);
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+
+    #[test]
+    fn test_raw_event_is_scoped() {
+        let outer = RawEvent::RawInput(ptr::null());
+        let inner = RawEvent::KeyboardHook {
+            message: 0x0100,
+            data: ptr::null(),
+        };
+        assert_eq!(current(), None);
+        with_raw(outer, || {
+            assert_eq!(current(), Some(outer));
+            with_raw(inner, || assert_eq!(current(), Some(inner)));
+            assert_eq!(current(), Some(outer));
+        });
+        assert_eq!(current(), None);
+    }
+}
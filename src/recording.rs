@@ -0,0 +1,482 @@
+#[cfg(feature = "listen")]
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(any(feature = "listen", feature = "simulate"))]
+use std::{sync::Arc, time::Instant};
+#[cfg(feature = "simulate")]
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread::{self, JoinHandle},
+};
+
+#[cfg(feature = "serialize")]
+use std::{fs, io, path::Path};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "simulate")]
+use crate::Simulate;
+use crate::event::Event;
+#[cfg(feature = "serialize")]
+use crate::wire;
+#[cfg(feature = "listen")]
+use crate::{Listen, subscription::SubscriptionHandle};
+
+/// Longest single sleep during playback, so cancellation is noticed promptly.
+#[cfg(feature = "simulate")]
+const CANCEL_GRANULARITY: Duration = Duration::from_millis(10);
+
+/// Current version of the recording file format, shared by the JSON and binary variants.
+///
+/// Version 2 switched binary recordings to the length-prefixed [`wire`] encoding; version
+/// 1 files are still read.
+#[cfg(feature = "serialize")]
+pub const RECORDING_VERSION: u16 = 2;
+
+/// Leading bytes of a binary recording file.
+#[cfg(feature = "serialize")]
+const BINARY_MAGIC: &[u8; 4] = b"RIRC";
+
+/// On-disk representation used by [`Recording::save`].
+///
+/// Both formats start with a version number. Readers reject files with a newer
+/// version than they understand instead of guessing.
+#[cfg(feature = "serialize")]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecordingFormat {
+    /// Human-readable JSON, convenient for editing recordings by hand.
+    ///
+    /// `{ "version": 2, "events": [{ "time_us": 0, "event": { ... } }] }`
+    Json,
+    /// Compact binary: the `RIRC` magic, a little-endian `u16` version, a `u32` event
+    /// count, then for each event a `u64` microsecond timestamp and the event in the
+    /// [`wire`] encoding. Events of kinds unknown to the reader are skipped.
+    Binary,
+}
+
+#[cfg(feature = "serialize")]
+#[derive(Serialize, Deserialize)]
+struct JsonRecording {
+    version: u16,
+    events: Vec<JsonEvent>,
+}
+
+#[cfg(feature = "serialize")]
+#[derive(Serialize, Deserialize)]
+struct JsonEvent {
+    time_us: u64,
+    event: Event,
+}
+
+/// A single event captured in a [`Recording`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RecordedEvent {
+    /// Time elapsed since the start of the recording.
+    pub time: Duration,
+    /// The captured event.
+    pub event: Event,
+}
+
+/// An ordered list of timestamped events that can be replayed through [`Simulate`].
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use raw_input::{PlayOptions, Recording};
+///
+/// let recorder = Recording::record();
+/// std::thread::sleep(Duration::from_secs(5));
+/// let recording = recorder.stop();
+///
+/// let playback = recording.play(PlayOptions { speed: 2.0, loops: 3 });
+/// playback.join();
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Recording {
+    pub events: Vec<RecordedEvent>,
+}
+
+/// Options controlling [`Recording::play`].
+#[cfg(feature = "simulate")]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PlayOptions {
+    /// Playback speed multiplier. `2.0` plays twice as fast, `0.5` at half speed.
+    pub speed: f64,
+    /// Number of times to play the recording. `0` repeats until cancelled.
+    pub loops: u32,
+}
+
+#[cfg(feature = "simulate")]
+impl Default for PlayOptions {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            loops: 1,
+        }
+    }
+}
+
+impl Recording {
+    /// Creates an empty recording.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts capturing events from [`Listen`] into a new recording.
+    ///
+    /// Requires `Core` and `Listen` to be running.
+    #[cfg(feature = "listen")]
+    pub fn record() -> Recorder {
+        let start = Instant::now();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let sink = events.clone();
+
+        let handle = Listen::subscribe(move |event| {
+            let time = start.elapsed();
+            if let Ok(mut events) = sink.lock() {
+                events.push(RecordedEvent { time, event });
+            }
+        });
+
+        Recorder { handle, events }
+    }
+
+    /// Appends an event at the given offset from the start of the recording.
+    pub fn push(&mut self, time: Duration, event: Event) {
+        self.events.push(RecordedEvent { time, event });
+    }
+
+    /// Returns the total duration of the recording.
+    pub fn duration(&self) -> Duration {
+        self.events.last().map_or(Duration::ZERO, |e| e.time)
+    }
+
+    /// Replays the recording on a background thread, honoring the original timing.
+    ///
+    /// Events are injected with [`Simulate::simulate`]. The returned handle can be used
+    /// to cancel playback or wait for it to finish.
+    #[cfg(feature = "simulate")]
+    pub fn play(&self, options: PlayOptions) -> PlaybackHandle {
+        let events = self.events.clone();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let flag = cancelled.clone();
+
+        let thread = thread::Builder::new()
+            .name("raw-input-playback".to_string())
+            .spawn(move || {
+                let mut iteration = 0;
+                while options.loops == 0 || iteration < options.loops {
+                    let mut previous = Duration::ZERO;
+                    for recorded in &events {
+                        let delay =
+                            scale_delay(recorded.time.saturating_sub(previous), options.speed);
+                        previous = recorded.time;
+
+                        if !sleep_unless_cancelled(delay, &flag) {
+                            return;
+                        }
+                        Simulate::simulate(recorded.event);
+                    }
+
+                    iteration += 1;
+                    if events.is_empty() {
+                        break;
+                    }
+                }
+            })
+            .expect("failed to spawn thread");
+
+        PlaybackHandle { cancelled, thread }
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl Recording {
+    /// Writes the recording to `path` in the given format.
+    pub fn save(&self, path: impl AsRef<Path>, format: RecordingFormat) -> io::Result<()> {
+        fs::write(path, self.to_bytes(format)?)
+    }
+
+    /// Reads a recording from `path`, detecting the format automatically.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Recording> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    /// Encodes the recording in the given format.
+    pub fn to_bytes(&self, format: RecordingFormat) -> io::Result<Vec<u8>> {
+        match format {
+            RecordingFormat::Json => {
+                let file = JsonRecording {
+                    version: RECORDING_VERSION,
+                    events: self
+                        .events
+                        .iter()
+                        .map(|e| JsonEvent {
+                            time_us: e.time.as_micros() as u64,
+                            event: e.event,
+                        })
+                        .collect(),
+                };
+                serde_json::to_vec_pretty(&file).map_err(io::Error::other)
+            }
+            RecordingFormat::Binary => {
+                let mut out = Vec::with_capacity(10 + self.events.len() * 14);
+                out.extend_from_slice(BINARY_MAGIC);
+                out.extend_from_slice(&RECORDING_VERSION.to_le_bytes());
+                out.extend_from_slice(&(self.events.len() as u32).to_le_bytes());
+                for e in &self.events {
+                    out.extend_from_slice(&(e.time.as_micros() as u64).to_le_bytes());
+                    wire::encode(&e.event, &mut out);
+                }
+                Ok(out)
+            }
+        }
+    }
+
+    /// Decodes a recording produced by [`Recording::to_bytes`] in either format.
+    pub fn from_bytes(bytes: &[u8]) -> io::Result<Recording> {
+        if let Some(rest) = bytes.strip_prefix(BINARY_MAGIC) {
+            return Self::from_binary(rest)
+                .ok_or_else(|| invalid_data("malformed binary recording"));
+        }
+
+        let file: JsonRecording = serde_json::from_slice(bytes).map_err(io::Error::other)?;
+        check_version(file.version)?;
+        let events = file
+            .events
+            .into_iter()
+            .map(|e| RecordedEvent {
+                time: Duration::from_micros(e.time_us),
+                event: e.event,
+            })
+            .collect();
+        Ok(Recording { events })
+    }
+
+    fn from_binary(mut input: &[u8]) -> Option<Recording> {
+        let version = u16::from_le_bytes(wire::take(&mut input)?);
+        check_version(version).ok()?;
+
+        let count = u32::from_le_bytes(wire::take(&mut input)?) as usize;
+        let mut events = Vec::with_capacity(count.min(input.len()));
+        for _ in 0..count {
+            let time = Duration::from_micros(u64::from_le_bytes(wire::take(&mut input)?));
+            let event = match version {
+                1 => Some(wire::decode_v1(&mut input)?),
+                _ => wire::decode(&mut input).ok()?,
+            };
+            if let Some(event) = event {
+                events.push(RecordedEvent { time, event });
+            }
+        }
+        Some(Recording { events })
+    }
+}
+
+#[cfg(feature = "serialize")]
+fn check_version(version: u16) -> io::Result<()> {
+    if version == 0 || version > RECORDING_VERSION {
+        return Err(invalid_data("unsupported recording version"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "serialize")]
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// An active capture started by [`Recording::record`].
+#[cfg(feature = "listen")]
+pub struct Recorder {
+    handle: SubscriptionHandle,
+    events: Arc<Mutex<Vec<RecordedEvent>>>,
+}
+
+#[cfg(feature = "listen")]
+impl Recorder {
+    /// Stops capturing and returns the recorded events.
+    pub fn stop(self) -> Recording {
+        self.handle.unsubscribe();
+        let events = match self.events.lock() {
+            Ok(mut events) => std::mem::take(&mut *events),
+            Err(_) => Vec::new(),
+        };
+        Recording { events }
+    }
+}
+
+/// A handle to a running playback started by [`Recording::play`].
+#[cfg(feature = "simulate")]
+pub struct PlaybackHandle {
+    cancelled: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+#[cfg(feature = "simulate")]
+impl PlaybackHandle {
+    /// Stops playback before the next event is injected.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns `true` once playback has finished or was cancelled.
+    pub fn is_finished(&self) -> bool {
+        self.thread.is_finished()
+    }
+
+    /// Blocks until playback has finished.
+    pub fn join(self) {
+        let _ = self.thread.join();
+    }
+}
+
+/// Divides `delay` by `speed`, treating non-positive or non-finite speeds as instant playback.
+#[cfg(feature = "simulate")]
+fn scale_delay(delay: Duration, speed: f64) -> Duration {
+    if speed.is_finite() && speed > 0.0 {
+        delay.div_f64(speed)
+    } else {
+        Duration::ZERO
+    }
+}
+
+/// Sleeps for `duration` in small slices. Returns `false` if cancelled in the meantime.
+#[cfg(feature = "simulate")]
+fn sleep_unless_cancelled(duration: Duration, cancelled: &AtomicBool) -> bool {
+    let deadline = Instant::now() + duration;
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return false;
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return true;
+        }
+        thread::sleep(remaining.min(CANCEL_GRANULARITY));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::key::Key;
+
+    #[cfg(feature = "simulate")]
+    #[test]
+    fn test_scale_delay() {
+        let delay = Duration::from_millis(100);
+        assert_eq!(scale_delay(delay, 1.0), delay);
+        assert_eq!(scale_delay(delay, 2.0), Duration::from_millis(50));
+        assert_eq!(scale_delay(delay, 0.5), Duration::from_millis(200));
+        assert_eq!(scale_delay(delay, 0.0), Duration::ZERO);
+        assert_eq!(scale_delay(delay, f64::NAN), Duration::ZERO);
+    }
+
+    #[cfg(feature = "simulate")]
+    #[test]
+    fn test_sleep_unless_cancelled() {
+        let cancelled = AtomicBool::new(false);
+        assert!(sleep_unless_cancelled(Duration::from_millis(1), &cancelled));
+
+        cancelled.store(true, Ordering::SeqCst);
+        let start = Instant::now();
+        assert!(!sleep_unless_cancelled(Duration::from_secs(10), &cancelled));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_recording_duration() {
+        let mut recording = Recording::new();
+        assert_eq!(recording.duration(), Duration::ZERO);
+
+        let event = Event::KeyDown {
+            key: Key::KeyA,
+            code: None,
+        };
+        recording.push(Duration::from_millis(10), event);
+        recording.push(Duration::from_millis(250), event);
+        assert_eq!(recording.duration(), Duration::from_millis(250));
+    }
+
+    #[cfg(feature = "serialize")]
+    fn sample() -> Recording {
+        let mut recording = Recording::new();
+        recording.push(
+            Duration::ZERO,
+            Event::KeyDown {
+                key: Key::KeyA,
+                code: Some(65),
+            },
+        );
+        recording.push(
+            Duration::from_micros(1500),
+            Event::MouseMove {
+                delta: crate::event::Point { x: 4.0, y: -2.0 },
+            },
+        );
+        recording
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_recording_roundtrip_json() {
+        let recording = sample();
+        let bytes = recording.to_bytes(RecordingFormat::Json).unwrap();
+        assert_eq!(Recording::from_bytes(&bytes).unwrap(), recording);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_recording_roundtrip_binary() {
+        let recording = sample();
+        let bytes = recording.to_bytes(RecordingFormat::Binary).unwrap();
+        assert!(bytes.starts_with(BINARY_MAGIC));
+        assert_eq!(Recording::from_bytes(&bytes).unwrap(), recording);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_recording_rejects_newer_version() {
+        let json = br#"{ "version": 999, "events": [] }"#;
+        assert!(Recording::from_bytes(json).is_err());
+
+        let mut bytes = sample().to_bytes(RecordingFormat::Binary).unwrap();
+        bytes[4..6].copy_from_slice(&999u16.to_le_bytes());
+        assert!(Recording::from_bytes(&bytes).is_err());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_recording_rejects_truncated_binary() {
+        let mut bytes = sample().to_bytes(RecordingFormat::Binary).unwrap();
+        bytes.truncate(bytes.len() - 3);
+        assert!(Recording::from_bytes(&bytes).is_err());
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_recording_reads_version_1_binary() {
+        let mut bytes = BINARY_MAGIC.to_vec();
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&1u32.to_le_bytes());
+        bytes.extend_from_slice(&1500u64.to_le_bytes());
+        bytes.push(0);
+        bytes.extend_from_slice(&4.0f64.to_le_bytes());
+        bytes.extend_from_slice(&(-2.0f64).to_le_bytes());
+
+        let recording = Recording::from_bytes(&bytes).unwrap();
+        assert_eq!(recording.events, sample().events[1..]);
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_recording_skips_unknown_events() {
+        let mut bytes = sample().to_bytes(RecordingFormat::Binary).unwrap();
+        bytes[6..10].copy_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&2000u64.to_le_bytes());
+        bytes.extend_from_slice(&[200, 1, 0]);
+        assert_eq!(Recording::from_bytes(&bytes).unwrap(), sample());
+    }
+}
@@ -0,0 +1,117 @@
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::Point;
+
+/// An axis-aligned rectangle, such as the bounds of a monitor.
+///
+/// The left and top edges are inside the rectangle, the right and bottom ones are not,
+/// so adjacent rectangles never share a point.
+///
+/// # Example
+/// ```
+/// use raw_input::{Point, Rect};
+///
+/// let left = Rect::new(0.0, 0.0, 1920.0, 1080.0);
+/// let right = Rect::new(1920.0, 0.0, 1280.0, 1024.0);
+///
+/// assert!(left.contains(Point { x: 1919.0, y: 0.0 }));
+/// assert!(!left.contains(Point { x: 1920.0, y: 0.0 }));
+/// assert_eq!(left.intersection(&right), None);
+/// assert_eq!(left.union(&right), Rect::new(0.0, 0.0, 3200.0, 1080.0));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct Rect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Rect {
+    pub const fn new(x: f64, y: f64, width: f64, height: f64) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the x coordinate just past the right edge.
+    pub fn right(&self) -> f64 {
+        self.x + self.width
+    }
+
+    /// Returns the y coordinate just past the bottom edge.
+    pub fn bottom(&self) -> f64 {
+        self.y + self.height
+    }
+
+    /// Returns `true` if the rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0.0 || self.height <= 0.0
+    }
+
+    /// Returns `true` if `point` lies inside the rectangle.
+    pub fn contains(&self, point: Point) -> bool {
+        point.x >= self.x && point.x < self.right() && point.y >= self.y && point.y < self.bottom()
+    }
+
+    /// Returns the centre of the rectangle.
+    pub fn center(&self) -> Point {
+        Point {
+            x: self.x + self.width / 2.0,
+            y: self.y + self.height / 2.0,
+        }
+    }
+
+    /// Returns the area both rectangles cover, or `None` if they do not overlap.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let rect = Rect::new(
+            x,
+            y,
+            self.right().min(other.right()) - x,
+            self.bottom().min(other.bottom()) - y,
+        );
+        (!rect.is_empty()).then_some(rect)
+    }
+
+    /// Returns `true` if the rectangles overlap.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the smallest rectangle containing both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(
+            x,
+            y,
+            self.right().max(other.right()) - x,
+            self.bottom().max(other.bottom()) - y,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intersection() {
+        let a = Rect::new(0.0, 0.0, 100.0, 100.0);
+        let b = Rect::new(50.0, -20.0, 100.0, 100.0);
+
+        assert_eq!(a.intersection(&b), Some(Rect::new(50.0, 0.0, 50.0, 80.0)));
+        assert_eq!(a.union(&b), Rect::new(0.0, -20.0, 150.0, 120.0));
+        assert!(a.intersects(&b));
+        // Touching edges do not overlap
+        assert!(!a.intersects(&Rect::new(100.0, 0.0, 10.0, 10.0)));
+        assert_eq!(a.center(), Point { x: 50.0, y: 50.0 });
+    }
+}
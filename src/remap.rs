@@ -0,0 +1,331 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{event::Event, key::Key};
+
+type Condition = Arc<dyn Fn() -> bool + Send + Sync + 'static>;
+
+/// A single key-to-key mapping, optionally guarded by a condition.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Key, Remap, RemapRule};
+///
+/// fn game_is_focused() -> bool {
+///     // Query the foreground application or active layout here
+///     true
+/// }
+///
+/// let handle = Remap::add_rule(RemapRule::new(Key::KeyW, Key::ArrowUp).when(game_is_focused));
+///
+/// // Later
+/// handle.remove();
+/// ```
+#[derive(Clone)]
+pub struct RemapRule {
+    from: Key,
+    to: Key,
+    condition: Option<Condition>,
+}
+
+impl RemapRule {
+    /// Creates a rule that replaces `from` with `to`.
+    pub fn new(from: Key, to: Key) -> Self {
+        Self {
+            from,
+            to,
+            condition: None,
+        }
+    }
+
+    /// Only applies the rule while `condition` returns `true`.
+    ///
+    /// The condition is evaluated when the key is pressed, on the core thread, so it must
+    /// be cheap. It is typically used to restrict a mapping to a keyboard layout or to a
+    /// foreground application. The release always goes to the key chosen at press time.
+    pub fn when<F>(mut self, condition: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.condition = Some(Arc::new(condition));
+        self
+    }
+}
+
+#[derive(Default)]
+struct State {
+    rules: Vec<(u64, RemapRule)>,
+    /// Keys whose press was remapped, with the key that was injected in their place.
+    active: HashMap<Key, Key>,
+}
+
+impl State {
+    fn update_enabled(&self) {
+        let enabled = !self.rules.is_empty() || !self.active.is_empty();
+        ENABLED.store(enabled, Ordering::SeqCst);
+    }
+}
+
+static STATE: Lazy<Mutex<State>> = Lazy::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Set while rules are registered or remapped keys are held, so the hook can skip remapping.
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` while the built-in remapping stage has work to do.
+pub(crate) fn is_active() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Key remapping at the hook level.
+///
+/// A remapped key press is blocked before it reaches any application, and the target key
+/// is injected in its place. Injected events carry the crate's injection marker and are
+/// never remapped again, so mappings such as `A -> B` and `B -> A` can coexist without
+/// feedback loops. Requires `Core` to be running.
+///
+/// When several rules match the same key, the first one registered wins.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::{Key, Remap};
+///
+/// // Swap CapsLock and Escape
+/// let caps = Remap::add(Key::CapsLock, Key::Escape);
+/// let esc = Remap::add(Key::Escape, Key::CapsLock);
+///
+/// // Later
+/// caps.remove();
+/// esc.remove();
+/// ```
+pub struct Remap;
+
+impl Remap {
+    /// Replaces `from` with `to`.
+    pub fn add(from: Key, to: Key) -> RemapHandle {
+        Self::add_rule(RemapRule::new(from, to))
+    }
+
+    /// Registers a remapping rule.
+    pub fn add_rule(rule: RemapRule) -> RemapHandle {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let mut state = lock();
+        state.rules.push((id, rule));
+        state.update_enabled();
+        RemapHandle { id }
+    }
+
+    /// Removes every rule.
+    pub fn clear() {
+        let mut state = lock();
+        state.rules.clear();
+        state.update_enabled();
+    }
+}
+
+/// A handle to a registered [`RemapRule`].
+pub struct RemapHandle {
+    id: u64,
+}
+
+impl RemapHandle {
+    /// Removes the rule. Keys currently held down are still released as their target.
+    pub fn remove(self) {
+        let mut state = lock();
+        state.rules.retain(|(id, _)| *id != self.id);
+        state.update_enabled();
+    }
+}
+
+fn lock() -> std::sync::MutexGuard<'static, State> {
+    STATE.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Feeds a translated, non-injected event to the remapper.
+///
+/// Called by the built-in pipeline stage. When the event is remapped, the replacement is passed
+/// to `inject` and `true` is returned so the original can be blocked.
+pub(crate) fn process(event: &Event, mut inject: impl FnMut(Key, bool)) -> bool {
+    let (key, down) = match *event {
+        Event::KeyDown { key, .. } => (key, true),
+        Event::KeyUp { key, .. } => (key, false),
+        _ => return false,
+    };
+
+    if !ENABLED.load(Ordering::Relaxed) {
+        return false;
+    }
+
+    // Releases of keys remapped at press time are handled even after their rule is gone
+    if !down {
+        let target = {
+            let mut state = lock();
+            let target = state.active.remove(&key);
+            state.update_enabled();
+            target
+        };
+        return match target {
+            Some(target) => {
+                inject(target, false);
+                true
+            }
+            None => false,
+        };
+    }
+
+    // Auto-repeat keeps the target chosen by the first press
+    let (repeat, candidates) = {
+        let state = lock();
+        let candidates: Vec<RemapRule> = state
+            .rules
+            .iter()
+            .filter(|(_, rule)| rule.from == key)
+            .map(|(_, rule)| rule.clone())
+            .collect();
+        (state.active.get(&key).copied(), candidates)
+    };
+    if let Some(target) = repeat {
+        inject(target, true);
+        return true;
+    }
+
+    // Conditions run outside the lock so they may register or remove rules
+    let Some(rule) = candidates
+        .into_iter()
+        .find(|rule| rule.condition.as_ref().is_none_or(|condition| condition()))
+    else {
+        return false;
+    };
+
+    {
+        let mut state = lock();
+        state.active.insert(key, rule.to);
+        state.update_enabled();
+    }
+    inject(rule.to, true);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    fn reset() {
+        *lock() = State::default();
+        ENABLED.store(false, Ordering::SeqCst);
+    }
+
+    fn run(event: Event) -> (bool, Vec<(Key, bool)>) {
+        let mut injected = Vec::new();
+        let blocked = process(&event, |key, down| injected.push((key, down)));
+        (blocked, injected)
+    }
+
+    fn down(key: Key) -> (bool, Vec<(Key, bool)>) {
+        run(Event::KeyDown { key, code: None })
+    }
+
+    fn up(key: Key) -> (bool, Vec<(Key, bool)>) {
+        run(Event::KeyUp { key, code: None })
+    }
+
+    #[serial]
+    #[test]
+    fn test_remap_replaces_press_repeat_and_release() {
+        reset();
+        let handle = Remap::add(Key::CapsLock, Key::Escape);
+
+        assert_eq!(down(Key::CapsLock), (true, vec![(Key::Escape, true)]));
+        assert_eq!(down(Key::CapsLock), (true, vec![(Key::Escape, true)]));
+        assert_eq!(up(Key::CapsLock), (true, vec![(Key::Escape, false)]));
+
+        // Unmapped keys pass through
+        assert_eq!(down(Key::KeyA), (false, vec![]));
+        assert_eq!(up(Key::KeyA), (false, vec![]));
+
+        handle.remove();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_remap_condition_is_evaluated_on_press() {
+        reset();
+        let enabled = Arc::new(AtomicBool::new(false));
+        let flag = enabled.clone();
+        let handle = Remap::add_rule(
+            RemapRule::new(Key::KeyW, Key::ArrowUp).when(move || flag.load(Ordering::SeqCst)),
+        );
+
+        assert_eq!(down(Key::KeyW), (false, vec![]));
+        assert_eq!(up(Key::KeyW), (false, vec![]));
+
+        enabled.store(true, Ordering::SeqCst);
+        assert_eq!(down(Key::KeyW), (true, vec![(Key::ArrowUp, true)]));
+
+        // The release follows the press even if the condition changed meanwhile
+        enabled.store(false, Ordering::SeqCst);
+        assert_eq!(up(Key::KeyW), (true, vec![(Key::ArrowUp, false)]));
+
+        handle.remove();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_first_matching_rule_wins() {
+        reset();
+        let first = Remap::add_rule(RemapRule::new(Key::KeyA, Key::KeyB).when(|| false));
+        let second = Remap::add(Key::KeyA, Key::KeyC);
+        let third = Remap::add(Key::KeyA, Key::KeyD);
+
+        assert_eq!(down(Key::KeyA), (true, vec![(Key::KeyC, true)]));
+        assert_eq!(up(Key::KeyA), (true, vec![(Key::KeyC, false)]));
+
+        first.remove();
+        second.remove();
+        third.remove();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_removed_rule_still_releases_held_key() {
+        reset();
+        let handle = Remap::add(Key::KeyA, Key::KeyB);
+
+        assert_eq!(down(Key::KeyA), (true, vec![(Key::KeyB, true)]));
+        handle.remove();
+        assert!(
+            ENABLED.load(Ordering::SeqCst),
+            "a remapped key is still held"
+        );
+        assert_eq!(up(Key::KeyA), (true, vec![(Key::KeyB, false)]));
+        assert!(!ENABLED.load(Ordering::SeqCst));
+        assert_eq!(down(Key::KeyA), (false, vec![]));
+
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_clear_removes_all_rules() {
+        reset();
+        Remap::add(Key::KeyA, Key::KeyB);
+        Remap::add(Key::KeyC, Key::KeyD);
+        Remap::clear();
+
+        assert_eq!(down(Key::KeyA), (false, vec![]));
+        assert_eq!(down(Key::KeyC), (false, vec![]));
+        reset();
+    }
+}
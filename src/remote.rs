@@ -0,0 +1,543 @@
+//! Remote input over the network.
+//!
+//! A [`Session`] is an established, handshaken connection between two peers. One side
+//! forwards its captured input with [`forward`], the other replays it with [`replay`].
+//! This is the core of a software KVM.
+//!
+//! # Protocol
+//!
+//! Every message is a frame: a one byte kind followed by its payload. Transports are
+//! responsible for delimiting frames ([`TcpTransport`] length-prefixes them,
+//! [`UdpTransport`] sends one datagram per frame).
+//!
+//! * `Hello` (kind 0): protocol version (`u16`), capabilities (`u32`). Both peers send
+//!   one when the session starts; the session uses the intersection of both capability
+//!   sets and fails if the versions differ.
+//! * `Event` (kind 1): sender timestamp in microseconds since its session start (`u64`),
+//!   followed by the event in the [`wire`](crate::wire) encoding. Events the receiver
+//!   does not understand are skipped.
+//!
+//! All integers are little-endian.
+
+use std::{
+    io::{self, Read, Write},
+    net::{TcpStream, UdpSocket},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Listen, Simulate, event::Event, subscription::SubscriptionHandle, wire};
+
+/// Version of the remote protocol. Peers with different versions refuse to connect.
+pub const PROTOCOL_VERSION: u16 = 2;
+
+const KIND_HELLO: u8 = 0;
+const KIND_EVENT: u8 = 1;
+
+/// Largest frame accepted by the built-in transports.
+const MAX_FRAME_LEN: usize = 64 * 1024;
+
+/// A message-oriented channel between two peers.
+///
+/// Implement this to carry sessions over something other than TCP or UDP, such as a
+/// TLS stream or a websocket.
+pub trait Transport: Send {
+    /// Sends one frame.
+    fn send(&mut self, frame: &[u8]) -> io::Result<()>;
+
+    /// Blocks until one whole frame has been received.
+    fn recv(&mut self) -> io::Result<Vec<u8>>;
+}
+
+/// A [`Transport`] over a TCP stream, with frames prefixed by their `u32` length.
+pub struct TcpTransport {
+    stream: TcpStream,
+}
+
+impl TcpTransport {
+    /// Wraps a connected stream. Disables Nagle's algorithm to keep input latency low.
+    pub fn new(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self { stream })
+    }
+}
+
+impl Transport for TcpTransport {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        let mut buf = Vec::with_capacity(4 + frame.len());
+        buf.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+        buf.extend_from_slice(frame);
+        self.stream.write_all(&buf)
+    }
+
+    fn recv(&mut self) -> io::Result<Vec<u8>> {
+        let mut len = [0u8; 4];
+        self.stream.read_exact(&mut len)?;
+        let len = u32::from_le_bytes(len) as usize;
+        if len > MAX_FRAME_LEN {
+            return Err(invalid_data("frame too large"));
+        }
+        let mut frame = vec![0u8; len];
+        self.stream.read_exact(&mut frame)?;
+        Ok(frame)
+    }
+}
+
+/// A [`Transport`] over a connected UDP socket, one datagram per frame.
+///
+/// UDP trades reliability for latency: lost mouse moves are harmless, but a lost key
+/// release leaves the key stuck on the peer. Prefer TCP unless latency is critical.
+pub struct UdpTransport {
+    socket: UdpSocket,
+}
+
+impl UdpTransport {
+    /// Wraps a socket that has been [`connect`](UdpSocket::connect)ed to the peer.
+    pub fn new(socket: UdpSocket) -> Self {
+        Self { socket }
+    }
+}
+
+impl Transport for UdpTransport {
+    fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+        self.socket.send(frame).map(|_| ())
+    }
+
+    fn recv(&mut self) -> io::Result<Vec<u8>> {
+        let mut buf = vec![0u8; MAX_FRAME_LEN];
+        let len = self.socket.recv(&mut buf)?;
+        buf.truncate(len);
+        Ok(buf)
+    }
+}
+
+/// The categories of events a peer is able to send or replay.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Capabilities(u32);
+
+impl Capabilities {
+    pub const NONE: Capabilities = Capabilities(0);
+    pub const MOUSE_MOVE: Capabilities = Capabilities(1 << 0);
+    pub const MOUSE_BUTTON: Capabilities = Capabilities(1 << 1);
+    pub const MOUSE_WHEEL: Capabilities = Capabilities(1 << 2);
+    pub const KEYBOARD: Capabilities = Capabilities(1 << 3);
+    pub const ALL: Capabilities = Capabilities(0b1111);
+
+    /// Returns `true` if every capability in `other` is also in `self`.
+    pub const fn contains(self, other: Capabilities) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the capabilities present in both sets.
+    pub const fn intersection(self, other: Capabilities) -> Capabilities {
+        Capabilities(self.0 & other.0)
+    }
+
+    /// Returns `true` if the capabilities allow sending `event`.
+    pub fn allows(self, event: &Event) -> bool {
+        let required = match event {
+            Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. } => Self::MOUSE_MOVE,
+            Event::MouseWheel { .. } => Self::MOUSE_WHEEL,
+            Event::MouseDown { .. } | Event::MouseUp { .. } => Self::MOUSE_BUTTON,
+            Event::KeyDown { .. } | Event::KeyUp { .. } => Self::KEYBOARD,
+            // Focus changes describe the local desktop and cannot be replayed; modifier
+            // changes follow from the key events, which are sent themselves
+            Event::FocusChanged { .. } | Event::ModifiersChanged { .. } => return false,
+        };
+        self.contains(required)
+    }
+}
+
+impl std::ops::BitOr for Capabilities {
+    type Output = Capabilities;
+
+    fn bitor(self, rhs: Capabilities) -> Capabilities {
+        Capabilities(self.0 | rhs.0)
+    }
+}
+
+/// An event received from the peer, with the peer's timestamp.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct RemoteEvent {
+    /// Time since the peer's session start at which the event was captured.
+    pub time: Duration,
+    pub event: Event,
+}
+
+/// A handshaken connection to a peer.
+pub struct Session<T: Transport> {
+    transport: T,
+    capabilities: Capabilities,
+    started: Instant,
+}
+
+impl<T: Transport> Session<T> {
+    /// Performs the handshake over `transport`, offering `capabilities`.
+    ///
+    /// Both peers call this; it returns once the peer's `Hello` has been received.
+    pub fn handshake(mut transport: T, capabilities: Capabilities) -> io::Result<Self> {
+        let mut hello = vec![KIND_HELLO];
+        hello.extend_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        hello.extend_from_slice(&capabilities.0.to_le_bytes());
+        transport.send(&hello)?;
+
+        let frame = transport.recv()?;
+        let mut input = frame.as_slice();
+        if wire::take::<1>(&mut input) != Some([KIND_HELLO]) {
+            return Err(invalid_data("expected hello"));
+        }
+        let version = wire::take(&mut input)
+            .map(u16::from_le_bytes)
+            .ok_or_else(|| invalid_data("truncated hello"))?;
+        if version != PROTOCOL_VERSION {
+            return Err(invalid_data(&format!(
+                "unsupported protocol version {} (expected {})",
+                version, PROTOCOL_VERSION
+            )));
+        }
+        let peer = wire::take(&mut input)
+            .map(u32::from_le_bytes)
+            .ok_or_else(|| invalid_data("truncated hello"))?;
+
+        Ok(Self {
+            transport,
+            capabilities: capabilities.intersection(Capabilities(peer)),
+            started: Instant::now(),
+        })
+    }
+
+    /// Returns the negotiated capabilities, i.e. those offered by both peers.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Sends an event, timestamped now. Events outside the negotiated capabilities are
+    /// silently dropped.
+    pub fn send(&mut self, event: &Event) -> io::Result<()> {
+        if !self.capabilities.allows(event) {
+            return Ok(());
+        }
+        let time = self.started.elapsed().as_micros() as u64;
+        let mut frame = vec![KIND_EVENT];
+        frame.extend_from_slice(&time.to_le_bytes());
+        wire::encode(event, &mut frame);
+        self.transport.send(&frame)
+    }
+
+    /// Blocks until the next event from the peer arrives.
+    ///
+    /// Frames of unknown kinds, and events this version of the crate does not understand,
+    /// are skipped, so newer peers may add message and event kinds without breaking older
+    /// ones.
+    pub fn recv(&mut self) -> io::Result<RemoteEvent> {
+        loop {
+            let frame = self.transport.recv()?;
+            let mut input = frame.as_slice();
+            match wire::take::<1>(&mut input) {
+                Some([KIND_EVENT]) => {}
+                Some(_) => continue,
+                None => return Err(invalid_data("empty frame")),
+            }
+            let time = wire::take(&mut input)
+                .map(u64::from_le_bytes)
+                .ok_or_else(|| invalid_data("truncated event"))?;
+            let Some(event) = wire::decode(&mut input).map_err(io::Error::other)? else {
+                continue;
+            };
+            return Ok(RemoteEvent {
+                time: Duration::from_micros(time),
+                event,
+            });
+        }
+    }
+}
+
+/// Options for [`replay`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ReplayOptions {
+    /// Extra delay added to every event to absorb network jitter. With a non-zero buffer,
+    /// events are replayed with the spacing they were captured with.
+    pub jitter_buffer: Duration,
+    /// Mouse moves arriving later than this behind schedule are dropped instead of
+    /// replayed, so the pointer catches up after a stall. Other events are never dropped.
+    pub max_lag: Duration,
+}
+
+impl Default for ReplayOptions {
+    fn default() -> Self {
+        Self {
+            jitter_buffer: Duration::ZERO,
+            max_lag: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Maps peer timestamps to local replay times.
+///
+/// The clock delta is the smallest observed `local arrival - peer timestamp`, which
+/// corresponds to the fastest trip through the network (it may be negative, since the
+/// peers start their session clocks independently). Each event is scheduled at its peer
+/// timestamp plus that delta plus the jitter buffer.
+#[derive(Debug)]
+pub(crate) struct Clock {
+    delta: Option<i128>,
+}
+
+impl Clock {
+    pub(crate) fn new() -> Self {
+        Self { delta: None }
+    }
+
+    /// Records an arrival and returns the local time, relative to the session start, at
+    /// which the event should be replayed.
+    pub(crate) fn schedule(
+        &mut self,
+        remote: Duration,
+        arrival: Duration,
+        buffer: Duration,
+    ) -> Duration {
+        let observed = arrival.as_micros() as i128 - remote.as_micros() as i128;
+        let delta = self.delta.map_or(observed, |delta| delta.min(observed));
+        self.delta = Some(delta);
+        let due = remote.as_micros() as i128 + delta + buffer.as_micros() as i128;
+        Duration::from_micros(due.max(0) as u64)
+    }
+}
+
+/// Replays the peer's events with [`Simulate`] until the connection fails.
+///
+/// Blocks the current thread; returns the error that ended the session.
+pub fn replay<T: Transport>(mut session: Session<T>, options: ReplayOptions) -> io::Error {
+    let mut clock = Clock::new();
+    loop {
+        let remote = match session.recv() {
+            Ok(remote) => remote,
+            Err(err) => return err,
+        };
+
+        let arrival = session.started.elapsed();
+        let due = clock.schedule(remote.time, arrival, options.jitter_buffer);
+        if due > arrival {
+            thread::sleep(due - arrival);
+        } else if arrival - due > options.max_lag
+            && matches!(
+                remote.event,
+                Event::MouseMove { .. } | Event::MouseMoveAbsolute { .. }
+            )
+        {
+            continue;
+        }
+
+        Simulate::simulate(remote.event);
+    }
+}
+
+/// Forwards locally captured input to the peer on a background thread.
+///
+/// Requires `Core` and `Listen` to be running. The returned handle stops forwarding.
+pub fn forward<T: Transport + 'static>(mut session: Session<T>) -> ForwardHandle {
+    let running = Arc::new(AtomicBool::new(true));
+    let (sender, receiver) = mpsc::channel();
+    let subscription = Listen::subscribe(move |event| {
+        let _ = sender.send(event);
+    });
+
+    let flag = running.clone();
+    thread::Builder::new()
+        .name("raw-input-forward".to_string())
+        .spawn(move || {
+            while flag.load(Ordering::Relaxed) {
+                let Ok(event) = receiver.recv_timeout(Duration::from_millis(100)) else {
+                    continue;
+                };
+                if session.send(&event).is_err() {
+                    flag.store(false, Ordering::SeqCst);
+                }
+            }
+        })
+        .expect("failed to spawn thread");
+
+    ForwardHandle {
+        subscription,
+        running,
+    }
+}
+
+/// A handle to a running [`forward`] session.
+pub struct ForwardHandle {
+    subscription: SubscriptionHandle,
+    running: Arc<AtomicBool>,
+}
+
+impl ForwardHandle {
+    /// Returns `false` once the connection has failed or forwarding was stopped.
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Stops forwarding and closes the session.
+    pub fn stop(self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.subscription.unsubscribe();
+    }
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::{
+        event::{MouseButton, Point},
+        key::Key,
+    };
+
+    /// One end of an in-memory frame channel.
+    struct Pipe {
+        tx: mpsc::Sender<Vec<u8>>,
+        rx: mpsc::Receiver<Vec<u8>>,
+    }
+
+    impl Transport for Pipe {
+        fn send(&mut self, frame: &[u8]) -> io::Result<()> {
+            self.tx
+                .send(frame.to_vec())
+                .map_err(|_| io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn recv(&mut self) -> io::Result<Vec<u8>> {
+            self.rx
+                .recv()
+                .map_err(|_| io::Error::from(io::ErrorKind::UnexpectedEof))
+        }
+    }
+
+    fn pipe() -> (Pipe, Pipe) {
+        let (a_tx, b_rx) = mpsc::channel();
+        let (b_tx, a_rx) = mpsc::channel();
+        (Pipe { tx: a_tx, rx: a_rx }, Pipe { tx: b_tx, rx: b_rx })
+    }
+
+    fn connect(a: Capabilities, b: Capabilities) -> (Session<Pipe>, Session<Pipe>) {
+        let (left, right) = pipe();
+        let peer = thread::spawn(move || Session::handshake(right, b).unwrap());
+        let session = Session::handshake(left, a).unwrap();
+        (session, peer.join().unwrap())
+    }
+
+    #[test]
+    fn test_handshake_negotiates_common_capabilities() {
+        let (a, b) = connect(
+            Capabilities::KEYBOARD | Capabilities::MOUSE_MOVE,
+            Capabilities::KEYBOARD | Capabilities::MOUSE_BUTTON,
+        );
+        assert_eq!(a.capabilities(), Capabilities::KEYBOARD);
+        assert_eq!(b.capabilities(), Capabilities::KEYBOARD);
+    }
+
+    #[test]
+    fn test_handshake_rejects_other_versions() {
+        let (left, mut right) = pipe();
+        let mut hello = vec![KIND_HELLO];
+        hello.extend_from_slice(&(PROTOCOL_VERSION + 1).to_le_bytes());
+        hello.extend_from_slice(&Capabilities::ALL.0.to_le_bytes());
+        right.send(&hello).unwrap();
+
+        let err = Session::handshake(left, Capabilities::ALL).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_events_roundtrip_and_respect_capabilities() {
+        let (mut a, mut b) = connect(
+            Capabilities::ALL,
+            Capabilities::KEYBOARD | Capabilities::MOUSE_BUTTON,
+        );
+
+        let key = Event::KeyDown {
+            key: Key::KeyA,
+            code: Some(30),
+        };
+        let click = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        let moved = Event::MouseMove {
+            delta: Point { x: 1.0, y: 2.0 },
+        };
+
+        a.send(&moved).unwrap(); // not negotiated, dropped
+        a.send(&key).unwrap();
+        a.send(&click).unwrap();
+
+        assert_eq!(b.recv().unwrap().event, key);
+        assert_eq!(b.recv().unwrap().event, click);
+    }
+
+    #[test]
+    fn test_recv_skips_unknown_frames() {
+        let (mut a, mut b) = connect(Capabilities::ALL, Capabilities::ALL);
+        a.transport.send(&[0xff, 1, 2, 3]).unwrap();
+        let event = Event::KeyUp {
+            key: Key::Escape,
+            code: None,
+        };
+        a.send(&event).unwrap();
+        assert_eq!(b.recv().unwrap().event, event);
+    }
+
+    #[test]
+    fn test_tcp_transport_frames() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut session =
+                Session::handshake(TcpTransport::new(stream).unwrap(), Capabilities::ALL).unwrap();
+            session.recv().unwrap().event
+        });
+
+        let stream = TcpStream::connect(addr).unwrap();
+        let mut session =
+            Session::handshake(TcpTransport::new(stream).unwrap(), Capabilities::ALL).unwrap();
+        let event = Event::MouseWheel {
+            delta: Point { x: 0.0, y: -1.0 },
+            raw: Some(Point { x: 0.0, y: -120.0 }),
+        };
+        session.send(&event).unwrap();
+        assert_eq!(server.join().unwrap(), event);
+    }
+
+    #[test]
+    fn test_clock_uses_fastest_trip() {
+        let ms = Duration::from_millis;
+        let mut clock = Clock::new();
+
+        // The first event defines the delta
+        assert_eq!(clock.schedule(ms(100), ms(130), ms(0)), ms(130));
+        // A slower trip keeps the delta, so the event is already due
+        assert_eq!(clock.schedule(ms(110), ms(160), ms(0)), ms(140));
+        // A faster trip lowers the delta
+        assert_eq!(clock.schedule(ms(120), ms(140), ms(0)), ms(140));
+        // The jitter buffer shifts the schedule
+        assert_eq!(clock.schedule(ms(130), ms(155), ms(50)), ms(200));
+    }
+
+    #[test]
+    fn test_clock_handles_peer_ahead() {
+        let ms = Duration::from_millis;
+        let mut clock = Clock::new();
+
+        // The peer started its session clock earlier than we did
+        assert_eq!(clock.schedule(ms(500), ms(20), ms(0)), ms(20));
+        assert_eq!(clock.schedule(ms(510), ms(40), ms(0)), ms(30));
+    }
+}
@@ -0,0 +1,165 @@
+use std::{
+    cell::RefCell,
+    sync::{
+        Arc, PoisonError, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::event::{Event, Point};
+
+/// Input that [`Simulate`](crate::Simulate) would have injected, reported instead of
+/// being injected while a sandbox is active, see
+/// [`Simulate::sandbox`](crate::Simulate::sandbox) and
+/// [`Simulate::dry_run`](crate::Simulate::dry_run).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub enum Injection {
+    /// An event, from [`Simulate::simulate`](crate::Simulate::simulate) or one of the
+    /// shorthands such as [`Simulate::keyboard`](crate::Simulate::keyboard).
+    Event(Event),
+    /// An absolute pointer move, from
+    /// [`Simulate::mouse_move_to`](crate::Simulate::mouse_move_to).
+    MoveTo(Point),
+    /// Text from [`Simulate::text`](crate::Simulate::text).
+    Text(String),
+    /// Text from [`Simulate::unicode`](crate::Simulate::unicode).
+    Unicode(String),
+}
+
+type Sink = Arc<dyn Fn(Injection) + Send + Sync>;
+
+/// The process-wide sandbox, see [`set`].
+static SANDBOX: RwLock<Option<Sink>> = RwLock::new(None);
+/// Set while [`SANDBOX`] holds a sink, so injection skips the lock otherwise.
+static HAS_SANDBOX: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// Injections collected by a [`record`] running on this thread.
+    static RECORDING: RefCell<Option<Vec<Injection>>> = const { RefCell::new(None) };
+}
+
+/// Routes all injection to `sink`, or back to the OS with `None`.
+pub(crate) fn set(sink: Option<Sink>) {
+    let mut sandbox = SANDBOX.write().unwrap_or_else(PoisonError::into_inner);
+    HAS_SANDBOX.store(sink.is_some(), Ordering::SeqCst);
+    *sandbox = sink;
+}
+
+/// Runs `f`, collecting what it injects on this thread instead of injecting it.
+pub(crate) fn record<F: FnOnce()>(f: F) -> Vec<Injection> {
+    /// Restores the enclosing recording, also when `f` panics.
+    struct Restore(Option<Vec<Injection>>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let outer = self.0.take();
+            RECORDING.with(|recording| *recording.borrow_mut() = outer);
+        }
+    }
+
+    let outer = RECORDING.with(|recording| recording.borrow_mut().replace(Vec::new()));
+    let restore = Restore(outer);
+    f();
+    let recorded = RECORDING.with(|recording| recording.borrow_mut().take());
+    drop(restore);
+    recorded.unwrap_or_default()
+}
+
+/// Hands an injection to the active sandbox. Returns `true` if there is one, in which
+/// case the caller must not inject anything.
+pub(crate) fn intercept(injection: impl FnOnce() -> Injection) -> bool {
+    let recorded = RECORDING.with(|recording| match recording.borrow_mut().as_mut() {
+        Some(recorded) => {
+            recorded.push(injection());
+            None
+        }
+        None => Some(injection),
+    });
+    let Some(injection) = recorded else {
+        return true;
+    };
+
+    if !HAS_SANDBOX.load(Ordering::Relaxed) {
+        return false;
+    }
+    // Cloned out of the lock, so the sink may change the sandbox
+    let sink = SANDBOX
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    match sink {
+        Some(sink) => {
+            sink(injection());
+            true
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use serial_test::serial;
+
+    use super::*;
+    use crate::{Key, MouseButton, Simulate};
+
+    #[serial]
+    #[test]
+    fn test_dry_run_records_injections() {
+        let recorded = Simulate::dry_run(|| {
+            Simulate::keyboard(Key::KeyA, true);
+            Simulate::mouse_button(MouseButton::Left, false);
+            Simulate::mouse_move_to(10.0, 20.0);
+            Simulate::text("hi");
+
+            // Nested runs collect their own injections
+            let inner = Simulate::dry_run(|| Simulate::unicode("👋"));
+            assert_eq!(inner, [Injection::Unicode("👋".to_string())]);
+        });
+
+        assert_eq!(
+            recorded,
+            [
+                Injection::Event(Event::KeyDown {
+                    key: Key::KeyA,
+                    code: None
+                }),
+                Injection::Event(Event::MouseUp {
+                    button: MouseButton::Left
+                }),
+                Injection::MoveTo(Point { x: 10.0, y: 20.0 }),
+                Injection::Text("hi".to_string()),
+            ]
+        );
+        assert!(!intercept(|| unreachable!()));
+    }
+
+    #[serial]
+    #[test]
+    fn test_global_sandbox() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        Simulate::sandbox(move |injection| sink.lock().unwrap().push(injection));
+
+        Simulate::mouse_wheel(0.0, 1.0);
+        // A dry run on this thread takes precedence
+        let recorded = Simulate::dry_run(|| Simulate::mouse_move(1.0, 2.0));
+        Simulate::unsandbox();
+
+        assert_eq!(recorded.len(), 1);
+        assert_eq!(
+            *seen.lock().unwrap(),
+            [Injection::Event(Event::MouseWheel {
+                delta: Point { x: 0.0, y: 1.0 },
+                raw: None,
+            })]
+        );
+        assert!(!HAS_SANDBOX.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,183 @@
+//! The stable serialized representation of the crate's data types.
+
+/// Version of the serde representation produced by the `serialize` feature.
+///
+/// The representation is part of the crate's public API, so persisted recordings and
+/// cross-process messages keep working across crate versions. Shown as JSON:
+///
+/// * [`Event`](crate::Event) is externally tagged by variant name:
+///   `{"MouseMove":{"delta":{"x":1.0,"y":2.0}}}`, `{"MouseDown":{"button":"Left"}}`,
+///   `{"KeyDown":{"key":"KeyA","code":65}}`. A missing `code` or wheel `raw` reads as
+///   `None`.
+/// * [`Key`](crate::Key) is its W3C `code` name, e.g. `"ArrowUp"`; raw keys are
+///   `{"Raw":255}`. [`MouseButton`](crate::MouseButton) is its variant name.
+/// * [`Point`](crate::Point) is `{"x":..,"y":..}`.
+/// * [`MonitorInfo`](crate::MonitorInfo) is
+///   `{"name":..,"is_primary":..,"offset":[x,y],"size":[w,h],"scale_factor":..}`.
+/// * [`Shortcut`](crate::Shortcut) is its string form, e.g. `"Ctrl+Shift+K"`.
+/// * [`EventFilter`](crate::EventFilter) is
+///   `{"categories":["Keyboard"],"keys":["KeyK"],"modifiers":1,"app":{"Exe":"code.exe"}}`,
+///   with the [`Modifiers`](crate::Modifiers) bits as a number. Missing fields read as
+///   empty.
+///
+/// Within a version, variant and field names are never renamed or removed. New variants
+/// and new optional fields may be added; data written by an older crate version always
+/// deserializes in a newer one. Readers of data from a newer version must be prepared to
+/// reject variants they do not know. Any other change bumps this version, and the
+/// [`RECORDING_VERSION`](crate::RECORDING_VERSION) of JSON recordings with it.
+pub const SCHEMA_VERSION: u16 = 1;
+
+#[cfg(test)]
+mod tests {
+    use serde::{Serialize, de::DeserializeOwned};
+    use serde_json::{Value, json};
+
+    use crate::{Event, Key, MonitorInfo, MouseButton, Point, RawKey, Shortcut, key::Modifiers};
+
+    /// Asserts that `value` serializes to exactly `expected` and deserializes back.
+    fn pinned<T>(value: T, expected: Value)
+    where
+        T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(serde_json::to_value(&value).unwrap(), expected);
+        assert_eq!(serde_json::from_value::<T>(expected).unwrap(), value);
+    }
+
+    #[test]
+    fn test_event_schema() {
+        let delta = Point { x: 1.0, y: -2.5 };
+        pinned(
+            Event::MouseMove { delta },
+            json!({"MouseMove": {"delta": {"x": 1.0, "y": -2.5}}}),
+        );
+        pinned(
+            Event::MouseMoveAbsolute { position: delta },
+            json!({"MouseMoveAbsolute": {"position": {"x": 1.0, "y": -2.5}}}),
+        );
+        pinned(
+            Event::MouseWheel { delta, raw: None },
+            json!({"MouseWheel": {"delta": {"x": 1.0, "y": -2.5}, "raw": null}}),
+        );
+        pinned(
+            Event::MouseWheel {
+                delta,
+                raw: Some(Point {
+                    x: 120.0,
+                    y: -300.0,
+                }),
+            },
+            json!({"MouseWheel": {
+                "delta": {"x": 1.0, "y": -2.5},
+                "raw": {"x": 120.0, "y": -300.0},
+            }}),
+        );
+        pinned(
+            Event::MouseDown {
+                button: MouseButton::Left,
+            },
+            json!({"MouseDown": {"button": "Left"}}),
+        );
+        pinned(
+            Event::MouseUp {
+                button: MouseButton::Forward,
+            },
+            json!({"MouseUp": {"button": "Forward"}}),
+        );
+        pinned(
+            Event::KeyDown {
+                key: Key::KeyA,
+                code: Some(65),
+            },
+            json!({"KeyDown": {"key": "KeyA", "code": 65}}),
+        );
+        pinned(
+            Event::KeyUp {
+                key: Key::Raw(RawKey(255)),
+                code: None,
+            },
+            json!({"KeyUp": {"key": {"Raw": 255}, "code": null}}),
+        );
+    }
+
+    #[test]
+    fn test_missing_optional_fields() {
+        let event: Event = serde_json::from_value(json!({"KeyUp": {"key": "Enter"}})).unwrap();
+        assert_eq!(
+            event,
+            Event::KeyUp {
+                key: Key::Enter,
+                code: None,
+            }
+        );
+        let event: Event =
+            serde_json::from_value(json!({"MouseWheel": {"delta": {"x": 0.0, "y": 1.0}}})).unwrap();
+        assert_eq!(
+            event,
+            Event::MouseWheel {
+                delta: Point { x: 0.0, y: 1.0 },
+                raw: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_key_schema() {
+        for &key in Key::ALL {
+            pinned(key, Value::String(key.to_string()));
+        }
+        pinned(Key::Raw(RawKey(7)), json!({"Raw": 7}));
+    }
+
+    #[test]
+    fn test_monitor_and_shortcut_schema() {
+        pinned(
+            MonitorInfo {
+                name: "DISPLAY1".to_string(),
+                is_primary: true,
+                offset: (0.0, -1080.0),
+                size: (1920.0, 1080.0),
+                scale_factor: 1.5,
+            },
+            json!({
+                "name": "DISPLAY1",
+                "is_primary": true,
+                "offset": [0.0, -1080.0],
+                "size": [1920.0, 1080.0],
+                "scale_factor": 1.5,
+            }),
+        );
+        pinned(
+            Shortcut::new(Modifiers::CONTROL | Modifiers::SHIFT, Key::KeyK),
+            json!("Ctrl+Shift+K"),
+        );
+    }
+
+    #[cfg(feature = "listen")]
+    #[test]
+    fn test_filter_schema() {
+        use crate::{AppMatcher, EventCategory, EventFilter};
+
+        pinned(
+            EventFilter {
+                categories: vec![EventCategory::Keyboard, EventCategory::MouseWheel],
+                keys: vec![Key::KeyK],
+                modifiers: Modifiers::CONTROL,
+                app: Some(AppMatcher::exe("code.exe")),
+            },
+            json!({
+                "categories": ["Keyboard", "MouseWheel"],
+                "keys": ["KeyK"],
+                "modifiers": 1,
+                "app": {"Exe": "code.exe"},
+            }),
+        );
+        let filter: EventFilter = serde_json::from_value(json!({"keys": ["Escape"]})).unwrap();
+        assert_eq!(
+            filter,
+            EventFilter {
+                keys: vec![Key::Escape],
+                ..Default::default()
+            }
+        );
+    }
+}
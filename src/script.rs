@@ -0,0 +1,423 @@
+//! Automation scripts in [Rhai](https://rhai.rs), so end users of an application can
+//! write macros without recompiling it.
+//!
+//! [`compile`] or [`from_file`] turns a script into a [`Script`], and [`Script::run`]
+//! runs its top level on the calling thread. Hotkeys and key callbacks the script
+//! registers run afterwards, one at a time, on a thread of their own, so a macro that
+//! sleeps never holds up the hooks. They stay registered until [`ScriptHandle::stop`].
+//!
+//! ```rhai
+//! // Runs once
+//! text("Hello from a script!");
+//!
+//! hotkey("Ctrl+Alt+D", || {
+//!     tap("Ctrl+A");
+//!     tap("Ctrl+C");
+//!     sleep(50);
+//!     move_to(100, 100);
+//!     click("Left");
+//! });
+//!
+//! on_key("F13", |down| if down { tap("Meta+Tab") });
+//! ```
+//!
+//! Scripts only reach the input functions below; Rhai itself gives them no access to
+//! files, the network or other processes, and `import` and `eval` are disabled. Keys and
+//! shortcuts use the names [`Shortcut`] parses, such as `"A"`, `"Enter"` or
+//! `"Ctrl+Shift+K"`; buttons are `"Left"`, `"Right"`, `"Middle"`, `"Back"` and
+//! `"Forward"`. Coordinates and deltas may be integers or decimals.
+//!
+//! | Function                    | Effect                                                  |
+//! |-----------------------------|---------------------------------------------------------|
+//! | `press(key)`, `release(key)` | [`Simulate::keyboard`]                                 |
+//! | `tap(shortcut)`             | Presses the modifiers and the key, then releases them   |
+//! | `text(text)`                | [`Simulate::text`]                                      |
+//! | `move_to(x, y)`             | [`Simulate::mouse_move_to`]                             |
+//! | `move_by(dx, dy)`           | [`Simulate::mouse_move`]                                |
+//! | `mouse_down(button)`, `mouse_up(button)` | [`Simulate::mouse_button`]                 |
+//! | `click(button)`             | Presses and releases a button                           |
+//! | `scroll(dx, dy)`            | [`Simulate::mouse_wheel`]                               |
+//! | `release_all()`             | [`Simulate::release_all`]                               |
+//! | `sleep(ms)`                 | Waits                                                   |
+//! | `cursor()`                  | The cursor position as `#{ x, y }`, or `()`             |
+//! | `hotkey(shortcut, fn)`      | Calls `fn()` when the shortcut is pressed, consuming it |
+//! | `on_key(key, fn)`           | Calls `fn(down)` for every press and release of the key |
+//!
+//! `hotkey` and `on_key` may only be called from the top level. `on_key` needs
+//! [`Listen`] to be started by the application.
+//!
+//! # Example
+//! ```no_run
+//! use raw_input::{Core, Listen, script};
+//!
+//! std::thread::spawn(|| Core::start());
+//! Listen::start().ok();
+//!
+//! let script = script::from_file("macros.rhai").expect("invalid script");
+//! let handle = script
+//!     .on_error(|err| eprintln!("Macro failed: {}", err))
+//!     .run()
+//!     .expect("script failed");
+//!
+//! // Later
+//! handle.stop();
+//! ```
+
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::{Arc, Mutex, PoisonError, mpsc},
+    thread,
+    time::Duration,
+};
+
+use rhai::{
+    AST, Dynamic, Engine, EvalAltResult, FnPtr, Map, module_resolvers::DummyModuleResolver,
+};
+
+use crate::{
+    Display, Event, Hotkey, HotkeyHandle, Key, Listen, Modifiers, MouseButton, Shortcut, Simulate,
+    SubscriptionHandle, shortcut::parse_key,
+};
+
+/// An error produced by [`compile`], [`from_file`] or a running [`Script`].
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The script is not valid Rhai. The message includes the position.
+    Syntax(String),
+    /// The script failed while running, e.g. on an unknown key or when it exceeded
+    /// [`Script::max_operations`]. The message includes the position.
+    Runtime(String),
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read the script: {err}"),
+            Self::Syntax(message) => write!(f, "syntax error: {message}"),
+            Self::Runtime(message) => write!(f, "runtime error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            Self::Syntax(_) | Self::Runtime(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for ScriptError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl From<Box<EvalAltResult>> for ScriptError {
+    fn from(err: Box<EvalAltResult>) -> Self {
+        Self::Runtime(err.to_string())
+    }
+}
+
+type ErrorSink = Arc<dyn Fn(ScriptError) + Send + Sync>;
+
+/// A callback of the script, run on the script's thread.
+type Job = Box<dyn FnOnce(&Engine, &AST) -> Result<Dynamic, Box<EvalAltResult>> + Send>;
+
+/// A hotkey or key callback registered by the top level of a script.
+enum Binding {
+    Hotkey(Shortcut, FnPtr),
+    Key(Key, FnPtr),
+}
+
+/// A compiled script, see the [module documentation](self).
+pub struct Script {
+    ast: AST,
+    max_operations: u64,
+    on_error: Option<ErrorSink>,
+}
+
+impl Script {
+    /// Limits the work of the top level and of every callback, so a runaway loop fails
+    /// with [`ScriptError::Runtime`] instead of running forever. Sleeping is not counted.
+    /// `0`, the default, sets no limit.
+    pub fn max_operations(mut self, operations: u64) -> Self {
+        self.max_operations = operations;
+        self
+    }
+
+    /// Reports the errors of hotkeys and key callbacks, which happen after
+    /// [`Script::run`] returned. Without it they are dropped.
+    pub fn on_error<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(ScriptError) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Arc::new(callback));
+        self
+    }
+
+    /// Runs the top level of the script on the calling thread, then registers the
+    /// hotkeys and key callbacks it set up. Nothing is registered if it fails.
+    pub fn run(&self) -> Result<ScriptHandle, ScriptError> {
+        let pending = Arc::new(Mutex::new(Some(Vec::new())));
+        let engine = engine(self.max_operations, Some(pending.clone()));
+        engine.run_ast(&self.ast)?;
+
+        let bindings = pending
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .unwrap_or_default();
+        let mut handle = ScriptHandle {
+            hotkeys: Vec::new(),
+            subscriptions: Vec::new(),
+        };
+        if bindings.is_empty() {
+            return Ok(handle);
+        }
+
+        let (sender, receiver) = mpsc::channel::<Job>();
+        let ast = self.ast.clone();
+        let on_error = self.on_error.clone();
+        thread::Builder::new()
+            .name("raw-input-script".to_string())
+            .spawn(move || {
+                // Ends once the bindings are unregistered, which drops every sender
+                for job in receiver {
+                    if let Err(err) = job(&engine, &ast)
+                        && let Some(on_error) = &on_error
+                    {
+                        on_error(err.into());
+                    }
+                }
+            })
+            .expect("failed to spawn thread");
+
+        for binding in bindings {
+            let sender = sender.clone();
+            match binding {
+                Binding::Hotkey(shortcut, callback) => {
+                    let hotkey =
+                        Hotkey::from_shortcut(shortcut)
+                            .suppress(true)
+                            .register(move || {
+                                let callback = callback.clone();
+                                let _ = sender.send(Box::new(move |engine, ast| {
+                                    callback.call::<Dynamic>(engine, ast, ())
+                                }));
+                            });
+                    handle.hotkeys.push(hotkey);
+                }
+                Binding::Key(key, callback) => {
+                    let subscription = Listen::subscribe(move |event| {
+                        let down = match event {
+                            Event::KeyDown { key: pressed, .. } if pressed == key => true,
+                            Event::KeyUp { key: released, .. } if released == key => false,
+                            _ => return,
+                        };
+                        let callback = callback.clone();
+                        let _ = sender.send(Box::new(move |engine, ast| {
+                            callback.call::<Dynamic>(engine, ast, (down,))
+                        }));
+                    });
+                    handle.subscriptions.push(subscription);
+                }
+            }
+        }
+        Ok(handle)
+    }
+}
+
+/// The hotkeys and key callbacks registered by [`Script::run`].
+pub struct ScriptHandle {
+    hotkeys: Vec<HotkeyHandle>,
+    subscriptions: Vec<SubscriptionHandle>,
+}
+
+impl ScriptHandle {
+    /// Unregisters the hotkeys and key callbacks. A callback already running finishes,
+    /// and the script's thread exits after it.
+    pub fn stop(self) {
+        self.hotkeys.into_iter().for_each(HotkeyHandle::unregister);
+        self.subscriptions
+            .into_iter()
+            .for_each(SubscriptionHandle::unsubscribe);
+    }
+}
+
+/// Compiles a script.
+pub fn compile(source: &str) -> Result<Script, ScriptError> {
+    let ast = engine(0, None)
+        .compile(source)
+        .map_err(|err| ScriptError::Syntax(err.to_string()))?;
+    Ok(Script {
+        ast,
+        max_operations: 0,
+        on_error: None,
+    })
+}
+
+/// Reads and compiles a script file.
+pub fn from_file(path: impl AsRef<Path>) -> Result<Script, ScriptError> {
+    compile(&fs::read_to_string(path)?)
+}
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// Builds an engine with the functions of the [module documentation](self). `pending`
+/// collects the bindings while the top level runs; without it, or once it is taken,
+/// `hotkey` and `on_key` fail.
+fn engine(max_operations: u64, pending: Option<Arc<Mutex<Option<Vec<Binding>>>>>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(max_operations);
+    engine.disable_symbol("eval");
+
+    engine
+        .register_fn("press", |key: &str| -> ScriptResult<()> {
+            Simulate::keyboard(key_arg(key)?, true);
+            Ok(())
+        })
+        .register_fn("release", |key: &str| -> ScriptResult<()> {
+            Simulate::keyboard(key_arg(key)?, false);
+            Ok(())
+        })
+        .register_fn("tap", |shortcut: &str| -> ScriptResult<()> {
+            tap(shortcut_arg(shortcut)?);
+            Ok(())
+        })
+        .register_fn("text", |text: &str| Simulate::text(text))
+        .register_fn("move_to", |x: Dynamic, y: Dynamic| -> ScriptResult<()> {
+            Simulate::mouse_move_to(number(x)?, number(y)?);
+            Ok(())
+        })
+        .register_fn("move_by", |dx: Dynamic, dy: Dynamic| -> ScriptResult<()> {
+            Simulate::mouse_move(number(dx)?, number(dy)?);
+            Ok(())
+        })
+        .register_fn("mouse_down", |button: &str| -> ScriptResult<()> {
+            Simulate::mouse_button(button_arg(button)?, true);
+            Ok(())
+        })
+        .register_fn("mouse_up", |button: &str| -> ScriptResult<()> {
+            Simulate::mouse_button(button_arg(button)?, false);
+            Ok(())
+        })
+        .register_fn("click", |button: &str| -> ScriptResult<()> {
+            let button = button_arg(button)?;
+            Simulate::mouse_button(button, true);
+            Simulate::mouse_button(button, false);
+            Ok(())
+        })
+        .register_fn("scroll", |dx: Dynamic, dy: Dynamic| -> ScriptResult<()> {
+            Simulate::mouse_wheel(number(dx)?, number(dy)?);
+            Ok(())
+        })
+        .register_fn("release_all", Simulate::release_all)
+        .register_fn("sleep", |ms: rhai::INT| {
+            thread::sleep(Duration::from_millis(ms.max(0) as u64));
+        })
+        .register_fn("cursor", || -> Dynamic {
+            let Some((x, y)) = Display::get_cursor_position() else {
+                return Dynamic::UNIT;
+            };
+            let mut position = Map::new();
+            position.insert("x".into(), Dynamic::from_float(x));
+            position.insert("y".into(), Dynamic::from_float(y));
+            position.into()
+        });
+
+    let bind = move |binding: Binding| -> ScriptResult<()> {
+        let mut pending = match &pending {
+            Some(pending) => pending.lock().unwrap_or_else(PoisonError::into_inner),
+            None => return Err(TOP_LEVEL_ONLY.into()),
+        };
+        match pending.as_mut() {
+            Some(bindings) => {
+                bindings.push(binding);
+                Ok(())
+            }
+            None => Err(TOP_LEVEL_ONLY.into()),
+        }
+    };
+    let bind_key = bind.clone();
+    engine
+        .register_fn(
+            "hotkey",
+            move |shortcut: &str, callback: FnPtr| -> ScriptResult<()> {
+                bind(Binding::Hotkey(shortcut_arg(shortcut)?, callback))
+            },
+        )
+        .register_fn(
+            "on_key",
+            move |key: &str, callback: FnPtr| -> ScriptResult<()> {
+                bind_key(Binding::Key(key_arg(key)?, callback))
+            },
+        );
+    engine
+}
+
+const TOP_LEVEL_ONLY: &str = "hotkeys and key callbacks can only be registered at the top level";
+
+/// The modifier keys `tap` holds for a shortcut.
+const MODIFIER_KEYS: [(Modifiers, Key); 4] = [
+    (Modifiers::CONTROL, Key::ControlLeft),
+    (Modifiers::SHIFT, Key::ShiftLeft),
+    (Modifiers::ALT, Key::AltLeft),
+    (Modifiers::META, Key::MetaLeft),
+];
+
+/// Presses the modifiers and the key of `shortcut`, then releases them in reverse.
+fn tap(shortcut: Shortcut) {
+    let modifiers: Vec<Key> = MODIFIER_KEYS
+        .iter()
+        .filter(|&&(modifier, _)| shortcut.modifiers.contains(modifier))
+        .map(|&(_, key)| key)
+        .collect();
+    for &key in &modifiers {
+        Simulate::keyboard(key, true);
+    }
+    Simulate::keyboard(shortcut.key, true);
+    Simulate::keyboard(shortcut.key, false);
+    for &key in modifiers.iter().rev() {
+        Simulate::keyboard(key, false);
+    }
+}
+
+fn key_arg(name: &str) -> ScriptResult<Key> {
+    parse_key(name.trim()).map_err(|err| err.to_string().into())
+}
+
+fn shortcut_arg(shortcut: &str) -> ScriptResult<Shortcut> {
+    shortcut
+        .parse()
+        .map_err(|err| format!("invalid shortcut {shortcut:?}: {err}").into())
+}
+
+fn button_arg(name: &str) -> ScriptResult<MouseButton> {
+    let button = match name.trim().to_ascii_lowercase().as_str() {
+        "left" => MouseButton::Left,
+        "right" => MouseButton::Right,
+        "middle" => MouseButton::Middle,
+        "back" => MouseButton::Back,
+        "forward" => MouseButton::Forward,
+        _ => return Err(format!("unknown mouse button {name:?}").into()),
+    };
+    Ok(button)
+}
+
+/// Accepts integers and decimals alike.
+fn number(value: Dynamic) -> ScriptResult<f64> {
+    if let Ok(int) = value.as_int() {
+        return Ok(int as f64);
+    }
+    value
+        .as_float()
+        .map_err(|type_name| format!("expected a number, got {type_name}").into())
+}
@@ -0,0 +1,220 @@
+use std::sync::{
+    Arc, Mutex, PoisonError,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::event::Point;
+
+type Condition = Arc<dyn Fn() -> bool + Send + Sync + 'static>;
+
+/// Inverts and/or scales mouse wheel events before they reach any application.
+///
+/// Transforms are applied inside the platform hook. On Windows the original wheel event
+/// is blocked and the transformed one is injected in its place; on macOS the event is
+/// rewritten in place, so line, pixel and continuous (trackpad) deltas stay consistent.
+/// Requires `Core` to be running. Listeners still observe the original deltas.
+///
+/// Several transforms can be active at once; their factors are multiplied together.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::ScrollTransform;
+///
+/// // Natural scrolling at double speed
+/// let handle = ScrollTransform::new().invert(true).scale(2.0).apply();
+///
+/// // Later
+/// handle.remove();
+/// ```
+#[derive(Clone)]
+pub struct ScrollTransform {
+    invert: bool,
+    scale: (f64, f64),
+    condition: Option<Condition>,
+}
+
+impl ScrollTransform {
+    /// Creates a transform that leaves scrolling unchanged.
+    pub fn new() -> Self {
+        Self {
+            invert: false,
+            scale: (1.0, 1.0),
+            condition: None,
+        }
+    }
+
+    /// Reverses the scroll direction on both axes.
+    pub fn invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Multiplies the scroll distance on both axes.
+    pub fn scale(self, scale: f64) -> Self {
+        self.scale_axes(scale, scale)
+    }
+
+    /// Multiplies the horizontal and vertical scroll distance separately.
+    pub fn scale_axes(mut self, x: f64, y: f64) -> Self {
+        self.scale = (x, y);
+        self
+    }
+
+    /// Only applies the transform while `condition` returns `true`.
+    ///
+    /// The condition is evaluated for every wheel event on the core thread, so it must be
+    /// cheap. It is typically used for per-application scroll speeds.
+    pub fn when<F>(mut self, condition: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        self.condition = Some(Arc::new(condition));
+        self
+    }
+
+    /// Activates the transform.
+    pub fn apply(self) -> ScrollTransformHandle {
+        let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+        let mut transforms = lock();
+        transforms.push((id, self));
+        HAS_TRANSFORMS.store(true, Ordering::SeqCst);
+        ScrollTransformHandle { id }
+    }
+
+    fn factors(&self) -> (f64, f64) {
+        let sign = if self.invert { -1.0 } else { 1.0 };
+        (self.scale.0 * sign, self.scale.1 * sign)
+    }
+}
+
+impl Default for ScrollTransform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A handle to an active [`ScrollTransform`].
+pub struct ScrollTransformHandle {
+    id: u64,
+}
+
+impl ScrollTransformHandle {
+    /// Deactivates the transform.
+    pub fn remove(self) {
+        let mut transforms = lock();
+        transforms.retain(|(id, _)| *id != self.id);
+        HAS_TRANSFORMS.store(!transforms.is_empty(), Ordering::SeqCst);
+    }
+}
+
+static TRANSFORMS: Lazy<Mutex<Vec<(u64, ScrollTransform)>>> = Lazy::new(Mutex::default);
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Set while at least one transform is active, so the hook can skip wheel processing.
+static HAS_TRANSFORMS: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` while the built-in scroll stage has work to do.
+pub(crate) fn is_active() -> bool {
+    HAS_TRANSFORMS.load(Ordering::Relaxed)
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Vec<(u64, ScrollTransform)>> {
+    TRANSFORMS.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Returns the combined per-axis factors of the transforms that currently apply, or
+/// `None` when the wheel event should pass through untouched.
+///
+/// Called by the built-in pipeline stage for every non-injected wheel event.
+pub(crate) fn factors() -> Option<Point> {
+    if !HAS_TRANSFORMS.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    // Conditions run outside the lock so they may add or remove transforms
+    let transforms: Vec<ScrollTransform> = lock().iter().map(|(_, t)| t.clone()).collect();
+    let mut applied = false;
+    let mut factors = Point { x: 1.0, y: 1.0 };
+    for transform in transforms {
+        if transform
+            .condition
+            .as_ref()
+            .is_none_or(|condition| condition())
+        {
+            let (x, y) = transform.factors();
+            factors.x *= x;
+            factors.y *= y;
+            applied = true;
+        }
+    }
+    applied.then_some(factors)
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+
+    fn reset() {
+        lock().clear();
+        HAS_TRANSFORMS.store(false, Ordering::SeqCst);
+    }
+
+    #[serial]
+    #[test]
+    fn test_no_transform_passes_through() {
+        reset();
+        assert_eq!(factors(), None);
+    }
+
+    #[serial]
+    #[test]
+    fn test_invert_and_scale() {
+        reset();
+        let handle = ScrollTransform::new().invert(true).scale(2.0).apply();
+        assert_eq!(factors(), Some(Point { x: -2.0, y: -2.0 }));
+        handle.remove();
+
+        let handle = ScrollTransform::new().scale_axes(1.0, 3.0).apply();
+        assert_eq!(factors(), Some(Point { x: 1.0, y: 3.0 }));
+        handle.remove();
+
+        assert_eq!(factors(), None);
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_transforms_compose() {
+        reset();
+        let first = ScrollTransform::new().invert(true).apply();
+        let second = ScrollTransform::new().scale(0.5).apply();
+        assert_eq!(factors(), Some(Point { x: -0.5, y: -0.5 }));
+
+        first.remove();
+        second.remove();
+        reset();
+    }
+
+    #[serial]
+    #[test]
+    fn test_condition_is_evaluated_per_event() {
+        reset();
+        let enabled = Arc::new(AtomicBool::new(false));
+        let flag = enabled.clone();
+        let handle = ScrollTransform::new()
+            .invert(true)
+            .when(move || flag.load(Ordering::SeqCst))
+            .apply();
+
+        assert_eq!(factors(), None);
+        enabled.store(true, Ordering::SeqCst);
+        assert_eq!(factors(), Some(Point { x: -1.0, y: -1.0 }));
+
+        handle.remove();
+        reset();
+    }
+}
@@ -0,0 +1,337 @@
+use std::{
+    cell::Cell,
+    cmp::Reverse,
+    collections::BinaryHeap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Listen, event::Event, subscription::SubscriptionHandle};
+
+/// Where the OS reported an event.
+///
+/// Each source delivers its own events in order, but sources are not ordered against
+/// each other: on Windows, Raw Input mouse motion is queued as `WM_INPUT` messages while
+/// the low-level hooks run immediately, so a move can reach subscribers after a click
+/// that happened later.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum EventSource {
+    /// The low-level hooks on Windows and the event tap on macOS.
+    Hook,
+    /// Raw Input on Windows: mouse motion, and the keyboard in scan code mode.
+    RawInput,
+    /// Notifications that are not input, such as [`Event::FocusChanged`].
+    System,
+    /// The polling fallback, see [`Core::start_polling`](crate::Core::start_polling).
+    Polling,
+}
+
+impl EventSource {
+    const COUNT: usize = 4;
+
+    fn index(self) -> usize {
+        match self {
+            EventSource::Hook => 0,
+            EventSource::RawInput => 1,
+            EventSource::System => 2,
+            EventSource::Polling => 3,
+        }
+    }
+}
+
+/// An event with the metadata needed to restore the order the input happened in, see
+/// [`Listen::subscribe_sequenced`](crate::Listen::subscribe_sequenced).
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SequencedEvent {
+    pub event: Event,
+    pub source: EventSource,
+    /// Position of the event among the events of its source. Consecutive events of a
+    /// source have consecutive numbers.
+    pub sequence: u64,
+    /// When the OS recorded the input, which may be before the event was dispatched.
+    /// Only as precise as the OS timestamp, a millisecond on Windows.
+    pub time: Instant,
+}
+
+static SEQUENCES: [AtomicU64; EventSource::COUNT] =
+    [const { AtomicU64::new(0) }; EventSource::COUNT];
+
+thread_local! {
+    /// Source and age of the input the backend is handling on this thread.
+    static ORIGIN: Cell<Option<(EventSource, Duration)>> = const { Cell::new(None) };
+    /// The event being delivered on this thread.
+    static CURRENT: Cell<Option<SequencedEvent>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, attributing the events it dispatches to `source`. `age` is how long ago the
+/// OS recorded the input. Called by the platform backends.
+pub(crate) fn with_origin<R>(source: EventSource, age: Duration, f: impl FnOnce() -> R) -> R {
+    let outer = ORIGIN.replace(Some((source, age)));
+    let result = f();
+    ORIGIN.set(outer);
+    result
+}
+
+/// Stamps an event about to be delivered and runs `deliver` while
+/// [`current`] returns it.
+pub(crate) fn delivering(event: Event, deliver: impl FnOnce()) {
+    let now = Instant::now();
+    let (source, age) = ORIGIN
+        .get()
+        .unwrap_or((EventSource::System, Duration::ZERO));
+    let sequenced = SequencedEvent {
+        event,
+        source,
+        sequence: SEQUENCES[source.index()].fetch_add(1, Ordering::Relaxed),
+        time: now.checked_sub(age).unwrap_or(now),
+    };
+    // Restored afterwards, since a subscriber may cause a nested dispatch
+    let outer = CURRENT.replace(Some(sequenced));
+    deliver();
+    CURRENT.set(outer);
+}
+
+/// Returns the event being delivered on this thread.
+fn current() -> Option<SequencedEvent> {
+    CURRENT.get()
+}
+
+/// Subscribes `callback` to sequenced events, see
+/// [`Listen::subscribe_sequenced`](crate::Listen::subscribe_sequenced).
+pub(crate) fn subscribe<F>(callback: F) -> SubscriptionHandle
+where
+    F: Fn(SequencedEvent) + Send + Sync + 'static,
+{
+    Listen::subscribe(move |_| {
+        if let Some(sequenced) = current() {
+            callback(sequenced);
+        }
+    })
+}
+
+/// Subscribes `callback` to events merged in the order they happened, see
+/// [`Listen::subscribe_ordered`](crate::Listen::subscribe_ordered).
+pub(crate) fn subscribe_ordered<F>(window: Duration, mut callback: F) -> SubscriptionHandle
+where
+    F: FnMut(SequencedEvent) + Send + 'static,
+{
+    let (sender, receiver) = mpsc::channel::<SequencedEvent>();
+
+    // The worker ends once the subscription, and with it the sender, is dropped
+    thread::Builder::new()
+        .name("raw-input-order".to_string())
+        .spawn(move || {
+            let mut merge = Merge::default();
+            loop {
+                let received = match merge.next_due() {
+                    Some(due) => {
+                        receiver.recv_timeout(due.saturating_duration_since(Instant::now()))
+                    }
+                    None => receiver.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match received {
+                    Ok(sequenced) => merge.push(sequenced, window),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+                while let Some(sequenced) = merge.pop_due(Instant::now()) {
+                    callback(sequenced);
+                }
+            }
+            while let Some(sequenced) = merge.pop() {
+                callback(sequenced);
+            }
+        })
+        .expect("failed to spawn thread");
+
+    subscribe(move |sequenced| {
+        let _ = sender.send(sequenced);
+    })
+}
+
+/// Holds events back until `window` after they happened, and releases them in the order
+/// they happened. Events of one source keep their order; events that arrive later than
+/// the window are released as soon as possible.
+#[derive(Default)]
+struct Merge {
+    pending: BinaryHeap<Reverse<Held>>,
+    arrivals: u64,
+    /// The time of the last released event, which later events are clamped to.
+    released: Option<Instant>,
+}
+
+/// An event in a [`Merge`], ordered by time and then by arrival.
+struct Held {
+    time: Instant,
+    arrival: u64,
+    due: Instant,
+    sequenced: SequencedEvent,
+}
+
+impl Held {
+    fn key(&self) -> (Instant, u64) {
+        (self.time, self.arrival)
+    }
+}
+
+impl PartialEq for Held {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Held {}
+
+impl PartialOrd for Held {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Held {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+impl Merge {
+    fn push(&mut self, sequenced: SequencedEvent, window: Duration) {
+        // Never overtakes an event that was already released
+        let time = self
+            .released
+            .map_or(sequenced.time, |t| t.max(sequenced.time));
+        self.pending.push(Reverse(Held {
+            time,
+            arrival: self.arrivals,
+            due: sequenced.time + window,
+            sequenced,
+        }));
+        self.arrivals += 1;
+    }
+
+    /// Returns when the earliest held event is due.
+    fn next_due(&self) -> Option<Instant> {
+        self.pending.peek().map(|Reverse(held)| held.due)
+    }
+
+    /// Releases the earliest held event if it is due at `now`.
+    fn pop_due(&mut self, now: Instant) -> Option<SequencedEvent> {
+        if self.next_due()? > now {
+            return None;
+        }
+        self.pop()
+    }
+
+    /// Releases the earliest held event.
+    fn pop(&mut self) -> Option<SequencedEvent> {
+        let Reverse(held) = self.pending.pop()?;
+        self.released = Some(held.time);
+        Some(held.sequenced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{event::Point, key::Key};
+
+    fn sequenced(
+        source: EventSource,
+        sequence: u64,
+        time: Instant,
+        event: Event,
+    ) -> SequencedEvent {
+        SequencedEvent {
+            event,
+            source,
+            sequence,
+            time,
+        }
+    }
+
+    #[test]
+    fn test_merge_restores_real_order() {
+        let start = Instant::now();
+        let window = Duration::from_millis(10);
+        let click = sequenced(
+            EventSource::Hook,
+            0,
+            start + Duration::from_millis(5),
+            Event::KeyDown {
+                key: Key::KeyA,
+                code: None,
+            },
+        );
+        let movement = sequenced(
+            EventSource::RawInput,
+            0,
+            start + Duration::from_millis(2),
+            Event::MouseMove {
+                delta: Point { x: 1.0, y: 0.0 },
+            },
+        );
+
+        let mut merge = Merge::default();
+        merge.push(click, window);
+        merge.push(movement, window);
+        assert_eq!(merge.pop_due(start + Duration::from_millis(11)), None);
+        assert_eq!(
+            merge.pop_due(start + Duration::from_millis(12)),
+            Some(movement)
+        );
+        assert_eq!(merge.pop_due(start + Duration::from_millis(12)), None);
+        assert_eq!(
+            merge.pop_due(start + Duration::from_millis(15)),
+            Some(click)
+        );
+    }
+
+    #[test]
+    fn test_late_events_do_not_overtake_released_ones() {
+        let start = Instant::now();
+        let event = Event::FocusChanged { pid: 1 };
+        let mut merge = Merge::default();
+        merge.push(
+            sequenced(
+                EventSource::Hook,
+                0,
+                start + Duration::from_millis(5),
+                event,
+            ),
+            Duration::ZERO,
+        );
+        assert!(merge.pop().is_some());
+
+        let late = sequenced(EventSource::RawInput, 0, start, event);
+        let next = sequenced(
+            EventSource::Hook,
+            1,
+            start + Duration::from_millis(6),
+            event,
+        );
+        merge.push(late, Duration::ZERO);
+        merge.push(next, Duration::ZERO);
+        assert_eq!(merge.pop(), Some(late));
+        assert_eq!(merge.pop(), Some(next));
+    }
+
+    #[test]
+    fn test_sequences_are_per_source() {
+        let mut seen = Vec::new();
+        for source in [EventSource::Hook, EventSource::RawInput, EventSource::Hook] {
+            with_origin(source, Duration::ZERO, || {
+                delivering(Event::FocusChanged { pid: 1 }, || {
+                    seen.push(current().unwrap())
+                });
+            });
+        }
+        assert_eq!(seen[0].source, EventSource::Hook);
+        assert!(seen[2].sequence > seen[0].sequence);
+        assert_eq!(seen[1].source, EventSource::RawInput);
+        assert!(current().is_none());
+    }
+}
@@ -0,0 +1,239 @@
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Display, Listen,
+    event::{Event, Point},
+    subscription::SubscriptionHandle,
+};
+
+/// Emitted when the mouse is shaken, see [`Shake`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct ShakeEvent {
+    /// The cursor position when the shake was recognized, if it could be determined.
+    pub position: Option<Point>,
+}
+
+/// Counts direction reversals of horizontal mouse movement.
+#[derive(Debug)]
+pub(crate) struct Tracker {
+    reversals: usize,
+    window: Duration,
+    min_swing: f64,
+    /// The sign of the current swing, `0.0` before any movement.
+    direction: f64,
+    /// Distance covered by the current swing.
+    swing: f64,
+    /// When recent swings reversed, oldest first.
+    history: Vec<Instant>,
+    /// Set once a shake was reported, until the shaking stops.
+    fired: bool,
+}
+
+impl Tracker {
+    pub(crate) fn new(reversals: usize, window: Duration, min_swing: f64) -> Self {
+        Self {
+            reversals,
+            window,
+            min_swing,
+            direction: 0.0,
+            swing: 0.0,
+            history: Vec::new(),
+            fired: false,
+        }
+    }
+
+    /// Handles a movement. Returns `true` when it completes a shake.
+    pub(crate) fn movement(&mut self, delta: Point, now: Instant) -> bool {
+        self.history
+            .retain(|&at| now.duration_since(at) <= self.window);
+        if self.history.is_empty() {
+            self.fired = false;
+        }
+        if delta.x == 0.0 {
+            return false;
+        }
+
+        let direction = delta.x.signum();
+        if direction == self.direction {
+            self.swing += delta.x.abs();
+            return false;
+        }
+
+        // Short swings are jitter rather than shaking
+        if self.direction != 0.0 && self.swing >= self.min_swing {
+            self.history.push(now);
+        }
+        self.direction = direction;
+        self.swing = delta.x.abs();
+
+        if !self.fired && self.history.len() >= self.reversals {
+            self.fired = true;
+            return true;
+        }
+        false
+    }
+}
+
+/// Detects shaking the mouse back and forth, like shake-to-locate on macOS, so overlays
+/// can enlarge or highlight the pointer.
+///
+/// A shake is a number of horizontal direction reversals within a time window, each
+/// after a minimum swing. It is reported once; continued shaking is not reported again
+/// until the mouse has calmed down for the length of the window.
+///
+/// # Example
+/// ```no_run
+/// use raw_input::Shake;
+///
+/// let handle = Shake::new().subscribe(|shake| {
+///     println!("Found the cursor at {:?}", shake.position);
+/// });
+///
+/// // Later
+/// handle.unsubscribe();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Shake {
+    reversals: usize,
+    window: Duration,
+    min_swing: f64,
+}
+
+impl Shake {
+    /// Creates a detector requiring 4 reversals within 500 ms, with 40 unit swings.
+    pub fn new() -> Self {
+        Self {
+            reversals: 4,
+            window: Duration::from_millis(500),
+            min_swing: 40.0,
+        }
+    }
+
+    /// Sets how many reversals make a shake. Fewer is more sensitive.
+    pub fn reversals(mut self, count: usize) -> Self {
+        self.reversals = count.max(1);
+        self
+    }
+
+    /// Sets the time window the reversals must occur in. Longer is more sensitive.
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Sets how far the mouse must move between reversals, in the units of
+    /// [`Event::MouseMove`] deltas. Shorter is more sensitive.
+    pub fn min_swing(mut self, distance: f64) -> Self {
+        self.min_swing = distance;
+        self
+    }
+
+    /// Starts detecting shakes. Requires mouse move listening.
+    ///
+    /// The callback runs on the listener thread.
+    pub fn subscribe<F>(self, callback: F) -> ShakeHandle
+    where
+        F: Fn(ShakeEvent) + Send + Sync + 'static,
+    {
+        let tracker = Tracker::new(self.reversals, self.window, self.min_swing);
+        let tracker = Arc::new(Mutex::new(tracker));
+
+        let handle = Listen::subscribe(move |event| {
+            let Event::MouseMove { delta } = event else {
+                return;
+            };
+            let shaken = tracker
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .movement(delta, Instant::now());
+            if shaken {
+                let position = Display::get_cursor_position().map(|(x, y)| Point { x, y });
+                callback(ShakeEvent { position });
+            }
+        });
+
+        ShakeHandle { handle }
+    }
+}
+
+impl Default for Shake {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A handle to a running [`Shake`] detector.
+pub struct ShakeHandle {
+    handle: SubscriptionHandle,
+}
+
+impl ShakeHandle {
+    /// Stops detecting shakes.
+    pub fn unsubscribe(self) {
+        self.handle.unsubscribe();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dx(x: f64) -> Point {
+        Point { x, y: 0.0 }
+    }
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    /// Swings back and forth every `interval` ms, returning when a shake fired.
+    fn shake(tracker: &mut Tracker, start: Instant, swings: u64, interval: u64) -> Vec<u64> {
+        let mut fired = Vec::new();
+        for i in 0..swings {
+            let direction = if i % 2 == 0 { 1.0 } else { -1.0 };
+            let at = i * interval;
+            for step in 0..5 {
+                if tracker.movement(dx(direction * 10.0), ms(start, at + step)) {
+                    fired.push(at);
+                }
+            }
+        }
+        fired
+    }
+
+    #[test]
+    fn test_fast_reversals_are_a_shake() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(4, Duration::from_millis(500), 40.0);
+        // The fifth swing completes the fourth reversal, and shaking on is not reported
+        assert_eq!(shake(&mut tracker, start, 10, 50), vec![200]);
+    }
+
+    #[test]
+    fn test_slow_or_short_movement_is_not_a_shake() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(4, Duration::from_millis(500), 40.0);
+        assert!(shake(&mut tracker, start, 10, 300).is_empty());
+
+        let mut tracker = Tracker::new(4, Duration::from_millis(500), 100.0);
+        assert!(shake(&mut tracker, start, 10, 50).is_empty());
+    }
+
+    #[test]
+    fn test_fires_again_after_calming_down() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(4, Duration::from_millis(500), 40.0);
+        assert_eq!(shake(&mut tracker, start, 5, 50).len(), 1);
+
+        let later = ms(start, 2000);
+        assert!(!tracker.movement(dx(1.0), later));
+        assert_eq!(shake(&mut tracker, later, 6, 50).len(), 1);
+    }
+}
@@ -0,0 +1,314 @@
+use std::{fmt, str::FromStr};
+
+use crate::key::{Key, Modifiers, ParseKeyError};
+
+/// A keyboard shortcut: a key pressed while a set of modifiers is held.
+///
+/// Shortcuts are written as `+`-separated modifiers followed by a key, for example
+/// `"Ctrl+Alt+Delete"` or `"Cmd+Shift+4"`. Parsing is case-insensitive and accepts
+/// common aliases (`Control`, `Option`, `Cmd`, `Win`, `Super`, `Esc`, `Up`, ...).
+/// Formatting always produces the canonical form with `Ctrl`, `Alt`, `Shift` and `Meta`
+/// in that order, so the output is stable across platforms.
+///
+/// With the `serialize` feature, shortcuts serialize as their string form.
+///
+/// # Example
+/// ```
+/// use raw_input::{Key, Modifiers, Shortcut};
+///
+/// let shortcut: Shortcut = "cmd+shift+4".parse().unwrap();
+/// assert_eq!(shortcut.modifiers, Modifiers::META | Modifiers::SHIFT);
+/// assert_eq!(shortcut.key, Key::Digit4);
+/// assert_eq!(shortcut.to_string(), "Shift+Meta+4");
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Shortcut {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl Shortcut {
+    /// Creates a shortcut for `key` pressed with `modifiers` held.
+    pub const fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self { modifiers, key }
+    }
+}
+
+/// Errors that can occur when parsing a [`Shortcut`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseShortcutError {
+    /// The input was empty or contained an empty segment (e.g. `"Ctrl++"`).
+    Empty,
+    /// The input only contained modifiers.
+    MissingKey,
+    /// The input contained more than one non-modifier key.
+    MultipleKeys,
+    /// A segment is neither a modifier nor a known key.
+    UnknownKey(String),
+}
+
+impl fmt::Display for ParseShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty shortcut segment"),
+            Self::MissingKey => write!(f, "shortcut has no key"),
+            Self::MultipleKeys => write!(f, "shortcut has more than one key"),
+            Self::UnknownKey(name) => write!(f, "unknown key name: {:?}", name),
+        }
+    }
+}
+
+impl std::error::Error for ParseShortcutError {}
+
+impl From<ParseKeyError> for ParseShortcutError {
+    fn from(err: ParseKeyError) -> Self {
+        Self::UnknownKey(err.0)
+    }
+}
+
+impl FromStr for Shortcut {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = Modifiers::NONE;
+        let mut key = None;
+
+        for segment in s.split('+').map(str::trim) {
+            if segment.is_empty() {
+                return Err(ParseShortcutError::Empty);
+            }
+            if let Some(modifier) = parse_modifier(segment) {
+                modifiers |= modifier;
+                continue;
+            }
+            if key.replace(parse_key(segment)?).is_some() {
+                return Err(ParseShortcutError::MultipleKeys);
+            }
+        }
+
+        match key {
+            Some(key) => Ok(Shortcut { modifiers, key }),
+            None => Err(ParseShortcutError::MissingKey),
+        }
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [
+            (Modifiers::CONTROL, "Ctrl"),
+            (Modifiers::ALT, "Alt"),
+            (Modifiers::SHIFT, "Shift"),
+            (Modifiers::META, "Meta"),
+        ];
+        for (modifier, name) in names {
+            if self.modifiers.contains(modifier) {
+                write!(f, "{}+", name)?;
+            }
+        }
+        f.write_str(&key_name(self.key))
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl serde::Serialize for Shortcut {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serialize")]
+impl<'de> serde::Deserialize<'de> for Shortcut {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+fn parse_modifier(name: &str) -> Option<Modifiers> {
+    let modifier = match name.to_ascii_lowercase().as_str() {
+        "ctrl" | "control" => Modifiers::CONTROL,
+        "shift" => Modifiers::SHIFT,
+        "alt" | "option" | "opt" => Modifiers::ALT,
+        "meta" | "cmd" | "command" | "win" | "windows" | "super" => Modifiers::META,
+        _ => return None,
+    };
+    Some(modifier)
+}
+
+/// Resolves a key segment: single characters, friendly aliases, then W3C code names.
+pub(crate) fn parse_key(name: &str) -> Result<Key, ParseKeyError> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next())
+        && let Some(key) = char_key(c.to_ascii_uppercase())
+    {
+        return Ok(key);
+    }
+
+    let key = match name.to_ascii_lowercase().as_str() {
+        "esc" => Key::Escape,
+        "return" => Key::Enter,
+        "del" => Key::Delete,
+        "ins" => Key::Insert,
+        "pgup" => Key::PageUp,
+        "pgdn" => Key::PageDown,
+        "up" => Key::ArrowUp,
+        "down" => Key::ArrowDown,
+        "left" => Key::ArrowLeft,
+        "right" => Key::ArrowRight,
+        "capslock" | "caps" => Key::CapsLock,
+        "menu" | "apps" => Key::ContextMenu,
+        _ => return name.parse(),
+    };
+    Ok(key)
+}
+
+/// Formats a key the way users write it: `A`, `4`, `-`, otherwise its W3C code name.
+fn key_name(key: Key) -> String {
+    let c = match key {
+        Key::Backquote => '`',
+        Key::Backslash => '\\',
+        Key::BracketLeft => '[',
+        Key::BracketRight => ']',
+        Key::Comma => ',',
+        Key::Equal => '=',
+        Key::Minus => '-',
+        Key::Period => '.',
+        Key::Quote => '\'',
+        Key::Semicolon => ';',
+        Key::Slash => '/',
+        _ => {
+            let name = key.to_string();
+            return match name
+                .strip_prefix("Key")
+                .or_else(|| name.strip_prefix("Digit"))
+            {
+                Some(rest) if rest.len() == 1 => rest.to_string(),
+                _ => name,
+            };
+        }
+    };
+    c.to_string()
+}
+
+/// Maps an (uppercase) character to the key that produces it on a US layout, without Shift.
+fn char_key(c: char) -> Option<Key> {
+    #[rustfmt::skip]
+    const LETTERS: [Key; 26] = [
+        Key::KeyA, Key::KeyB, Key::KeyC, Key::KeyD, Key::KeyE, Key::KeyF, Key::KeyG,
+        Key::KeyH, Key::KeyI, Key::KeyJ, Key::KeyK, Key::KeyL, Key::KeyM, Key::KeyN,
+        Key::KeyO, Key::KeyP, Key::KeyQ, Key::KeyR, Key::KeyS, Key::KeyT, Key::KeyU,
+        Key::KeyV, Key::KeyW, Key::KeyX, Key::KeyY, Key::KeyZ,
+    ];
+    #[rustfmt::skip]
+    const DIGITS: [Key; 10] = [
+        Key::Digit0, Key::Digit1, Key::Digit2, Key::Digit3, Key::Digit4,
+        Key::Digit5, Key::Digit6, Key::Digit7, Key::Digit8, Key::Digit9,
+    ];
+
+    let key = match c {
+        'A'..='Z' => LETTERS[(c as u8 - b'A') as usize],
+        '0'..='9' => DIGITS[(c as u8 - b'0') as usize],
+        '`' => Key::Backquote,
+        '\\' => Key::Backslash,
+        '[' => Key::BracketLeft,
+        ']' => Key::BracketRight,
+        ',' => Key::Comma,
+        '=' => Key::Equal,
+        '-' => Key::Minus,
+        '.' => Key::Period,
+        '\'' => Key::Quote,
+        ';' => Key::Semicolon,
+        '/' => Key::Slash,
+        _ => return None,
+    };
+    Some(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Shortcut {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_basic() {
+        assert_eq!(
+            parse("Ctrl+Alt+Delete"),
+            Shortcut::new(Modifiers::CONTROL | Modifiers::ALT, Key::Delete)
+        );
+        assert_eq!(
+            parse("Cmd+Shift+4"),
+            Shortcut::new(Modifiers::META | Modifiers::SHIFT, Key::Digit4)
+        );
+        assert_eq!(parse("F5"), Shortcut::new(Modifiers::NONE, Key::F5));
+    }
+
+    #[test]
+    fn test_parse_is_case_insensitive_and_trims() {
+        assert_eq!(
+            parse(" ctrl + shift + k "),
+            Shortcut::new(Modifiers::CONTROL | Modifiers::SHIFT, Key::KeyK)
+        );
+        assert_eq!(parse("CONTROL+a"), parse("Ctrl+A"));
+    }
+
+    #[test]
+    fn test_parse_aliases() {
+        assert_eq!(parse("Option+Esc").modifiers, Modifiers::ALT);
+        assert_eq!(parse("Option+Esc").key, Key::Escape);
+        assert_eq!(parse("Win+Up"), parse("Super+ArrowUp"));
+        assert_eq!(parse("Ctrl+/").key, Key::Slash);
+        assert_eq!(parse("Ctrl+Numpad7").key, Key::Numpad7);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!("".parse::<Shortcut>(), Err(ParseShortcutError::Empty));
+        assert_eq!("Ctrl++".parse::<Shortcut>(), Err(ParseShortcutError::Empty));
+        assert_eq!(
+            "Ctrl+Shift".parse::<Shortcut>(),
+            Err(ParseShortcutError::MissingKey)
+        );
+        assert_eq!(
+            "Ctrl+A+B".parse::<Shortcut>(),
+            Err(ParseShortcutError::MultipleKeys)
+        );
+        assert_eq!(
+            "Ctrl+Banana".parse::<Shortcut>(),
+            Err(ParseShortcutError::UnknownKey("Banana".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_display_is_canonical() {
+        assert_eq!(
+            parse("shift+cmd+ctrl+alt+k").to_string(),
+            "Ctrl+Alt+Shift+Meta+K"
+        );
+        assert_eq!(parse("Ctrl+Alt+Del").to_string(), "Ctrl+Alt+Delete");
+        assert_eq!(parse("Ctrl+-").to_string(), "Ctrl+-");
+        assert_eq!(parse("Up").to_string(), "ArrowUp");
+    }
+
+    #[test]
+    fn test_display_parse_roundtrip_all_keys() {
+        for &key in Key::ALL {
+            let shortcut = Shortcut::new(Modifiers::CONTROL | Modifiers::SHIFT, key);
+            assert_eq!(parse(&shortcut.to_string()), shortcut, "{}", shortcut);
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_serde_uses_string_form() {
+        let shortcut = parse("Ctrl+Shift+K");
+        let json = serde_json::to_string(&shortcut).unwrap();
+        assert_eq!(json, "\"Ctrl+Shift+K\"");
+        assert_eq!(serde_json::from_str::<Shortcut>(&json).unwrap(), shortcut);
+        assert!(serde_json::from_str::<Shortcut>("\"Ctrl+\"").is_err());
+    }
+}
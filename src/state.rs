@@ -0,0 +1,214 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+#[cfg(feature = "serialize")]
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    StateError,
+    dispatcher::{CALLBACKS, Status},
+    platform::{CoreImpl, PlatformCore},
+    polling::{self, CaptureMode, Limitations},
+};
+
+/// The listener configuration, see [`Listen::flags`](crate::Listen::flags).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct ListenFlags {
+    /// Whether events are delivered to subscribers.
+    pub running: bool,
+    pub mouse_move: bool,
+    pub mouse_button: bool,
+    pub mouse_wheel: bool,
+    pub keyboard: bool,
+    /// Whether keyboard events are read in scan code mode. Always `false` on macOS.
+    pub scancode_mode: bool,
+    /// Whether events injected by `Simulate` are withheld from subscribers.
+    pub ignore_injected: bool,
+}
+
+/// The grabber configuration, see [`Grab::flags`](crate::Grab::flags).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct GrabFlags {
+    /// Whether events are blocked.
+    pub running: bool,
+    pub mouse_move: bool,
+    pub mouse_button: bool,
+    pub mouse_wheel: bool,
+    pub keyboard: bool,
+    /// Whether events for windows of the current process are let through.
+    pub exclude_own_windows: bool,
+    /// Whether events injected by `Simulate` are let through.
+    pub pass_injected: bool,
+}
+
+/// A snapshot of the core engine, see [`Core::state`](crate::Core::state).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serialize", derive(Serialize, Deserialize))]
+pub struct CoreState {
+    /// Whether the core runs and is not paused, polling or not.
+    pub running: bool,
+    /// How input is captured.
+    pub capture: CaptureMode,
+    /// What the capture mode cannot do, see [`CaptureMode::limitations`].
+    pub limitations: Limitations,
+    /// Whether the platform hooks (Windows) or the event tap (macOS) are installed.
+    pub hooks_installed: bool,
+    /// Whether the macOS event tap is enabled. The system disables taps that respond too
+    /// slowly. `None` without a tap and on Windows.
+    pub tap_enabled: Option<bool>,
+    /// Number of subscriptions, including paused ones.
+    pub subscribers: usize,
+    /// Number of subscriptions that receive events.
+    pub active_subscribers: usize,
+}
+
+impl CoreState {
+    pub(crate) fn current() -> Self {
+        let (subscribers, active_subscribers) =
+            CALLBACKS.iter().fold((0, 0), |(all, active), subscriber| {
+                match subscriber.status {
+                    Status::Active => (all + 1, active + 1),
+                    Status::Paused | Status::GroupPaused => (all + 1, active),
+                }
+            });
+        let capture = polling::capture_mode();
+        CoreState {
+            running: PlatformCore::is_runing() || polling::is_running(),
+            capture,
+            limitations: capture.limitations(),
+            hooks_installed: PlatformCore::hooks_installed(),
+            tap_enabled: PlatformCore::tap_enabled(),
+            subscribers,
+            active_subscribers,
+        }
+    }
+}
+
+/// The result of a self-check, see [`Core::health`](crate::Core::health).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Health {
+    /// Whether the platform hooks (Windows) or the event tap (macOS) are installed.
+    pub hooks_installed: bool,
+    /// Whether the macOS event tap is enabled, `None` without a tap and on Windows.
+    pub tap_enabled: Option<bool>,
+    /// Time since the hooks last saw input, `None` if they never did.
+    pub since_last_event: Option<Duration>,
+    /// The longest time the subscribers took to handle a single event since the previous
+    /// check. Subscribers run on the hook thread, so slow ones hold up all input.
+    pub slowest_dispatch: Duration,
+}
+
+impl Health {
+    /// Dispatch time from which events are considered backing up. Windows removes hooks
+    /// that take longer than about a second, and macOS disables slow taps.
+    pub const SLOW_DISPATCH: Duration = Duration::from_millis(100);
+
+    /// Returns `true` if the hooks are installed and enabled, saw input within `max_idle`
+    /// and dispatch keeps up.
+    pub fn is_healthy(&self, max_idle: Duration) -> bool {
+        self.hooks_installed
+            && self.tap_enabled != Some(false)
+            && self.since_last_event.is_some_and(|idle| idle <= max_idle)
+            && self.slowest_dispatch < Self::SLOW_DISPATCH
+    }
+
+    pub(crate) fn check() -> Self {
+        let last_event = LAST_EVENT.load(Ordering::Relaxed);
+        Health {
+            hooks_installed: PlatformCore::hooks_installed(),
+            tap_enabled: PlatformCore::tap_enabled(),
+            since_last_event: (last_event != 0).then(|| {
+                EPOCH
+                    .elapsed()
+                    .saturating_sub(Duration::from_micros(last_event))
+            }),
+            slowest_dispatch: Duration::from_micros(SLOWEST_DISPATCH.swap(0, Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Reference point for the timestamps below, which fit an atomic as microseconds.
+static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
+
+/// When the hooks last saw input, in microseconds since [`EPOCH`]; `0` for never.
+static LAST_EVENT: AtomicU64 = AtomicU64::new(0);
+
+/// The longest dispatch since the last [`Health::check`], in microseconds.
+static SLOWEST_DISPATCH: AtomicU64 = AtomicU64::new(0);
+
+/// Records that the hooks saw an input event. Called by the platform hooks.
+#[inline]
+pub(crate) fn observed() {
+    let now = EPOCH.elapsed().as_micros() as u64;
+    LAST_EVENT.store(now.max(1), Ordering::Relaxed);
+}
+
+/// Records how long the subscribers took to handle one event.
+#[inline]
+pub(crate) fn dispatched(took: Duration) {
+    SLOWEST_DISPATCH.fetch_max(took.as_micros() as u64, Ordering::Relaxed);
+}
+
+/// Reports why a started listener or grabber would not see any events.
+pub(crate) fn check_core(grab: bool) -> Result<(), StateError> {
+    // The polling fallback needs no permission, but cannot grab
+    if !grab && polling::is_running() {
+        Ok(())
+    } else if !PlatformCore::has_permission(grab) {
+        Err(StateError::PermissionMissing)
+    } else if !PlatformCore::is_runing() {
+        Err(StateError::NotStarted)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serial_test::serial;
+
+    use super::*;
+    use crate::Listen;
+
+    #[serial]
+    #[test]
+    fn test_subscriber_counts() {
+        let before = CoreState::current();
+        let first = Listen::subscribe(|_| {});
+        let second = Listen::subscribe(|_| {});
+        second.pause();
+
+        let state = CoreState::current();
+        assert_eq!(state.subscribers, before.subscribers + 2);
+        assert_eq!(state.active_subscribers, before.active_subscribers + 1);
+
+        first.unsubscribe();
+        second.unsubscribe();
+        assert_eq!(CoreState::current().subscribers, before.subscribers);
+    }
+
+    #[serial]
+    #[test]
+    fn test_health_tracks_events_and_dispatch_time() {
+        observed();
+        dispatched(Duration::from_millis(150));
+        dispatched(Duration::from_millis(5));
+
+        let health = Health::check();
+        assert!(
+            health
+                .since_last_event
+                .is_some_and(|idle| idle < Duration::from_secs(1))
+        );
+        assert_eq!(health.slowest_dispatch, Duration::from_millis(150));
+        assert!(!health.is_healthy(Duration::from_secs(1)));
+
+        // The slowest dispatch is measured per check
+        assert_eq!(Health::check().slowest_dispatch, Duration::ZERO);
+    }
+}
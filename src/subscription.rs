@@ -1,8 +1,15 @@
-use crate::dispatcher::{CALLBACKS, Status};
+use std::ops::Deref;
+use std::sync::{Arc, OnceLock};
+
+use crate::Listen;
+use crate::dispatcher::{CALLBACKS, GROUPS, Status, is_group_paused, refresh, set_group};
+use crate::event::Event;
 
 /// A handle that allows control over an active event subscription.
 ///
-/// It can be used to pause, resume, or permanently remove a callback.
+/// It can be used to pause, resume, or permanently remove a callback. Dropping the handle
+/// keeps the subscription for the rest of the program; use
+/// [`auto_unsubscribe`](Self::auto_unsubscribe) to tie the subscription to the handle.
 pub struct SubscriptionHandle {
     pub(crate) id: u64,
 }
@@ -18,18 +25,43 @@ impl SubscriptionHandle {
         if let Some(mut subscriber) = CALLBACKS.get_mut(&self.id) {
             subscriber.status = Status::Paused;
         }
+        refresh();
     }
 
     /// Resumes a previously paused subscription.
     ///
+    /// While its group is paused, the subscription resumes together with the group.
+    ///
     /// # Example
     /// ```no_run
     /// handle.resume();
     /// ```
     pub fn resume(&self) {
+        let status = match is_group_paused(self.id) {
+            true => Status::GroupPaused,
+            false => Status::Active,
+        };
         if let Some(mut subscriber) = CALLBACKS.get_mut(&self.id) {
-            subscriber.status = Status::Active;
+            subscriber.status = status;
         }
+        refresh();
+    }
+
+    /// Puts the subscription in `group`, replacing its previous group, so it can be paused
+    /// and resumed along with the rest of the group by
+    /// [`Listen::pause_group`](crate::Listen::pause_group) and
+    /// [`Listen::resume_group`](crate::Listen::resume_group).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Listen;
+    ///
+    /// let handle = Listen::subscribe(|event| println!("{:?}", event)).in_group("overlay");
+    /// Listen::pause_group("overlay");
+    /// ```
+    pub fn in_group(self, group: impl Into<String>) -> Self {
+        set_group(self.id, group.into());
+        self
     }
 
     /// Removes the subscription from the dispatcher.
@@ -40,16 +72,97 @@ impl SubscriptionHandle {
     /// handle.unsubscribe();
     /// ```
     pub fn unsubscribe(self) {
-        CALLBACKS.remove(&self.id);
+        remove(self.id);
+    }
+
+    /// Keeps the subscription for the rest of the program and drops the handle.
+    ///
+    /// This is what dropping the handle does too; calling it states the intent.
+    pub fn detach(self) {}
+
+    /// Returns a guard that unsubscribes when it is dropped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raw_input::Listen;
+    ///
+    /// {
+    ///     let _guard = Listen::subscribe(|event| println!("{:?}", event)).auto_unsubscribe();
+    ///     // ...
+    /// } // Unsubscribed here
+    /// ```
+    pub fn auto_unsubscribe(self) -> AutoUnsubscribe {
+        AutoUnsubscribe { handle: self }
+    }
+}
+
+/// A [`SubscriptionHandle`] that unsubscribes when it is dropped, see
+/// [`SubscriptionHandle::auto_unsubscribe`].
+///
+/// It dereferences to the handle, so the subscription can still be paused and resumed.
+#[must_use = "the subscription is removed when the guard is dropped"]
+pub struct AutoUnsubscribe {
+    handle: SubscriptionHandle,
+}
+
+impl AutoUnsubscribe {
+    /// Returns the plain handle, so dropping it no longer unsubscribes.
+    pub fn detach(self) -> SubscriptionHandle {
+        let handle = SubscriptionHandle { id: self.handle.id };
+        std::mem::forget(self);
+        handle
+    }
+}
+
+impl Deref for AutoUnsubscribe {
+    type Target = SubscriptionHandle;
+
+    fn deref(&self) -> &SubscriptionHandle {
+        &self.handle
+    }
+}
+
+impl Drop for AutoUnsubscribe {
+    fn drop(&mut self) {
+        remove(self.handle.id);
     }
 }
 
+/// Subscribes `callback` for as long as `owner` lives, see
+/// [`Listen::subscribe_weak`](crate::Listen::subscribe_weak).
+pub(crate) fn subscribe_weak<T, F>(owner: &Arc<T>, callback: F) -> SubscriptionHandle
+where
+    T: Send + Sync + 'static,
+    F: Fn(&T, Event) + Send + Sync + 'static,
+{
+    let owner = Arc::downgrade(owner);
+    let id = Arc::new(OnceLock::new());
+    let own_id = id.clone();
+    let handle = Listen::subscribe(move |event| match owner.upgrade() {
+        Some(owner) => callback(&owner, event),
+        None => {
+            if let Some(&id) = own_id.get() {
+                remove(id);
+            }
+        }
+    });
+    let _ = id.set(handle.id);
+    handle
+}
+
+/// Removes subscription `id` from the dispatcher.
+fn remove(id: u64) {
+    CALLBACKS.remove(&id);
+    GROUPS.remove(&id);
+    refresh();
+}
+
 #[cfg(test)]
 mod tests {
     use serial_test::serial;
 
     use super::*;
-    use crate::dispatcher::{NEXT_ID, Subscriber, dispatch, remove_all};
+    use crate::dispatcher::{NEXT_ID, Subscriber, dispatch, has_active, remove_all, set_group_paused};
     use crate::event::Event;
     use crate::key::Key;
 
@@ -61,7 +174,7 @@ mod tests {
         let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         CALLBACKS.insert(id, Subscriber {
             status: Status::Active,
-            callback: Box::new(|_| {}),
+            callback: Arc::new(|_| {}),
         });
         SubscriptionHandle { id }
     }
@@ -109,7 +222,7 @@ mod tests {
         let id = NEXT_ID.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         CALLBACKS.insert(id, Subscriber {
             status: Status::Active,
-            callback: Box::new(move |_| { called_clone.store(true, std::sync::atomic::Ordering::SeqCst); }),
+            callback: Arc::new(move |_| { called_clone.store(true, std::sync::atomic::Ordering::SeqCst); }),
         });
         let handle = SubscriptionHandle { id };
 
@@ -148,4 +261,65 @@ mod tests {
 
         remove_all();
     }
+
+    #[serial]
+    #[test]
+    fn test_has_active_tracks_subscriber_status() {
+        remove_all();
+        assert!(!has_active());
+        let handle = insert_callback();
+        refresh();
+        assert!(has_active());
+        handle.pause();
+        assert!(!has_active(), "paused subscribers should not count as active");
+        handle.resume();
+        assert!(has_active());
+        handle.unsubscribe();
+        assert!(!has_active());
+        remove_all();
+    }
+
+    #[serial]
+    #[test]
+    fn test_subscription_groups() {
+        remove_all();
+        let overlay = insert_callback().in_group("overlay");
+        let paused = insert_callback().in_group("overlay");
+        let other = insert_callback();
+        paused.pause();
+
+        set_group_paused("overlay", true);
+        assert_eq!(CALLBACKS.get(&overlay.id).unwrap().status, Status::GroupPaused);
+        assert_eq!(CALLBACKS.get(&paused.id).unwrap().status, Status::Paused);
+        assert_eq!(CALLBACKS.get(&other.id).unwrap().status, Status::Active);
+
+        // Joining or resuming inside a paused group waits for the group
+        let late = insert_callback().in_group("overlay");
+        assert_eq!(CALLBACKS.get(&late.id).unwrap().status, Status::GroupPaused);
+        paused.resume();
+        assert_eq!(CALLBACKS.get(&paused.id).unwrap().status, Status::GroupPaused);
+        other.unsubscribe();
+        assert!(!has_active(), "group-paused subscribers should not count as active");
+
+        set_group_paused("overlay", false);
+        assert_eq!(CALLBACKS.get(&overlay.id).unwrap().status, Status::Active);
+        assert_eq!(CALLBACKS.get(&paused.id).unwrap().status, Status::Active);
+        assert_eq!(CALLBACKS.get(&late.id).unwrap().status, Status::Active);
+        remove_all();
+    }
+
+    #[serial]
+    #[test]
+    fn test_auto_unsubscribe_on_drop() {
+        remove_all();
+        let guard = insert_callback().auto_unsubscribe();
+        let id = guard.id;
+        guard.pause();
+        drop(guard);
+        assert!(CALLBACKS.get(&id).is_none(), "dropping the guard should unsubscribe");
+
+        let handle = insert_callback().auto_unsubscribe().detach();
+        assert!(CALLBACKS.get(&handle.id).is_some(), "a detached guard should keep the subscription");
+        remove_all();
+    }
 }
@@ -0,0 +1,338 @@
+use std::{
+    sync::{
+        Arc, Mutex, PoisonError,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{Listen, event::Event, key::Key, subscription::SubscriptionHandle};
+
+type Callback = Arc<dyn Fn() + Send + Sync + 'static>;
+
+/// The classification of a key press, as reported by a [`TapHold`] detector.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Action {
+    Tap,
+    Hold,
+    HoldRelease,
+}
+
+/// Tracks a single key and classifies its presses.
+#[derive(Debug)]
+pub(crate) struct Tracker {
+    key: Key,
+    threshold: Duration,
+    pressed_at: Option<Instant>,
+    held: bool,
+    /// Bumped on every press, so stale threshold timers can be recognised.
+    generation: u64,
+}
+
+impl Tracker {
+    pub(crate) fn new(key: Key, threshold: Duration) -> Self {
+        Self {
+            key,
+            threshold,
+            pressed_at: None,
+            held: false,
+            generation: 0,
+        }
+    }
+
+    /// Handles a key press. Returns `Some(generation)` when a new press of the tracked key
+    /// starts, so the caller can arm a threshold timer.
+    pub(crate) fn key_down(&mut self, key: Key, now: Instant) -> (Option<u64>, Option<Action>) {
+        if key == self.key {
+            if self.pressed_at.is_some() {
+                // Auto-repeat
+                return (None, None);
+            }
+            self.pressed_at = Some(now);
+            self.held = false;
+            self.generation += 1;
+            return (Some(self.generation), None);
+        }
+
+        // Another key pressed while the tracked key is down commits it to a hold,
+        // so e.g. CapsLock+J can act as Ctrl+J before the threshold elapses.
+        if self.pressed_at.is_some() && !self.held {
+            self.held = true;
+            return (None, Some(Action::Hold));
+        }
+        (None, None)
+    }
+
+    /// Handles a key release.
+    pub(crate) fn key_up(&mut self, key: Key, now: Instant) -> Vec<Action> {
+        if key != self.key {
+            return Vec::new();
+        }
+        let Some(pressed_at) = self.pressed_at.take() else {
+            return Vec::new();
+        };
+
+        if self.held {
+            vec![Action::HoldRelease]
+        } else if now.duration_since(pressed_at) < self.threshold {
+            vec![Action::Tap]
+        } else {
+            // The threshold timer has not run yet; report the hold before its release
+            vec![Action::Hold, Action::HoldRelease]
+        }
+    }
+
+    /// Called when the threshold timer armed for `generation` expires.
+    pub(crate) fn expire(&mut self, generation: u64) -> Option<Action> {
+        if generation != self.generation || self.pressed_at.is_none() || self.held {
+            return None;
+        }
+        self.held = true;
+        Some(Action::Hold)
+    }
+}
+
+/// Tap versus hold detection for a single key.
+///
+/// A press is a *tap* when the key is released before the threshold. It becomes a *hold*
+/// as soon as the threshold elapses or another key is pressed while it is down, whichever
+/// comes first; the hold callback fires at that moment, and the release callback fires
+/// when the key is let go.
+///
+/// This is the building block for dual-function keys. Combine it with [`Grab`](crate::Grab)
+/// to block the original key and [`Simulate`](crate::Simulate) to emit its replacements.
+///
+/// # Example
+/// ```no_run
+/// use std::time::Duration;
+/// use raw_input::{Key, TapHold};
+///
+/// // CapsLock: Escape on tap, Control while held
+/// let handle = TapHold::new(Key::CapsLock)
+///     .threshold(Duration::from_millis(200))
+///     .on_tap(|| println!("Escape"))
+///     .on_hold(|| println!("Control down"))
+///     .on_hold_release(|| println!("Control up"))
+///     .subscribe();
+///
+/// // Later
+/// handle.unsubscribe();
+/// ```
+#[derive(Clone)]
+pub struct TapHold {
+    key: Key,
+    threshold: Duration,
+    on_tap: Option<Callback>,
+    on_hold: Option<Callback>,
+    on_hold_release: Option<Callback>,
+}
+
+impl TapHold {
+    /// Creates a detector for `key` with a 200 ms threshold.
+    pub fn new(key: Key) -> Self {
+        Self {
+            key,
+            threshold: Duration::from_millis(200),
+            on_tap: None,
+            on_hold: None,
+            on_hold_release: None,
+        }
+    }
+
+    /// Sets how long the key must be held down before a press counts as a hold.
+    pub fn threshold(mut self, threshold: Duration) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the callback for a press released before the threshold.
+    pub fn on_tap<F: Fn() + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_tap = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets the callback for a press that became a hold.
+    pub fn on_hold<F: Fn() + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_hold = Some(Arc::new(callback));
+        self
+    }
+
+    /// Sets the callback for the release that ends a hold.
+    pub fn on_hold_release<F: Fn() + Send + Sync + 'static>(mut self, callback: F) -> Self {
+        self.on_hold_release = Some(Arc::new(callback));
+        self
+    }
+
+    /// Starts classifying presses of the key. Requires keyboard listening.
+    ///
+    /// Callbacks run on the listener thread, except a hold triggered by the threshold,
+    /// which runs on a timer thread.
+    pub fn subscribe(self) -> TapHoldHandle {
+        let active = Arc::new(AtomicBool::new(true));
+        let tracker = Arc::new(Mutex::new(Tracker::new(self.key, self.threshold)));
+        let this = Arc::new(self);
+
+        let flag = active.clone();
+        let handle = Listen::subscribe(move |event| {
+            let now = Instant::now();
+            let actions = {
+                let mut state = lock(&tracker);
+                match event {
+                    Event::KeyDown { key, .. } => {
+                        let (timer, action) = state.key_down(key, now);
+                        if let Some(generation) = timer {
+                            arm(this.clone(), tracker.clone(), flag.clone(), generation);
+                        }
+                        action.into_iter().collect()
+                    }
+                    Event::KeyUp { key, .. } => state.key_up(key, now),
+                    _ => Vec::new(),
+                }
+            };
+            for action in actions {
+                this.emit(action);
+            }
+        });
+
+        TapHoldHandle { handle, active }
+    }
+
+    fn emit(&self, action: Action) {
+        let callback = match action {
+            Action::Tap => &self.on_tap,
+            Action::Hold => &self.on_hold,
+            Action::HoldRelease => &self.on_hold_release,
+        };
+        if let Some(callback) = callback {
+            callback();
+        }
+    }
+}
+
+fn lock(tracker: &Mutex<Tracker>) -> std::sync::MutexGuard<'_, Tracker> {
+    tracker.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Reports a hold once the threshold elapses, unless the press has ended by then.
+fn arm(
+    tap_hold: Arc<TapHold>,
+    tracker: Arc<Mutex<Tracker>>,
+    active: Arc<AtomicBool>,
+    generation: u64,
+) {
+    thread::Builder::new()
+        .name("raw-input-tap-hold".to_string())
+        .spawn(move || {
+            thread::sleep(tap_hold.threshold);
+            if !active.load(Ordering::SeqCst) {
+                return;
+            }
+            let action = lock(&tracker).expire(generation);
+            if let Some(action) = action {
+                tap_hold.emit(action);
+            }
+        })
+        .expect("failed to spawn thread");
+}
+
+/// A handle to a running [`TapHold`] detector.
+pub struct TapHoldHandle {
+    handle: SubscriptionHandle,
+    active: Arc<AtomicBool>,
+}
+
+impl TapHoldHandle {
+    /// Stops the detector. None of its callbacks will be called again.
+    pub fn unsubscribe(self) {
+        self.active.store(false, Ordering::SeqCst);
+        self.handle.unsubscribe();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: Duration = Duration::from_millis(200);
+
+    fn ms(start: Instant, millis: u64) -> Instant {
+        start + Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_quick_release_is_tap() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Key::CapsLock, THRESHOLD);
+
+        let (timer, action) = tracker.key_down(Key::CapsLock, start);
+        assert_eq!(timer, Some(1));
+        assert_eq!(action, None);
+        assert_eq!(
+            tracker.key_up(Key::CapsLock, ms(start, 50)),
+            vec![Action::Tap]
+        );
+
+        // The timer of a finished press is stale
+        assert_eq!(tracker.expire(1), None);
+    }
+
+    #[test]
+    fn test_threshold_expiry_is_hold() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Key::CapsLock, THRESHOLD);
+
+        let (timer, _) = tracker.key_down(Key::CapsLock, start);
+        assert_eq!(tracker.expire(timer.unwrap()), Some(Action::Hold));
+        assert_eq!(
+            tracker.key_up(Key::CapsLock, ms(start, 500)),
+            vec![Action::HoldRelease]
+        );
+    }
+
+    #[test]
+    fn test_late_release_without_timer_reports_hold() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Key::CapsLock, THRESHOLD);
+
+        tracker.key_down(Key::CapsLock, start);
+        assert_eq!(
+            tracker.key_up(Key::CapsLock, ms(start, 300)),
+            vec![Action::Hold, Action::HoldRelease]
+        );
+    }
+
+    #[test]
+    fn test_other_key_commits_hold() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Key::CapsLock, THRESHOLD);
+
+        let (timer, _) = tracker.key_down(Key::CapsLock, start);
+        assert_eq!(
+            tracker.key_down(Key::KeyJ, ms(start, 20)),
+            (None, Some(Action::Hold))
+        );
+        assert!(tracker.key_up(Key::KeyJ, ms(start, 40)).is_empty());
+        assert_eq!(tracker.expire(timer.unwrap()), None);
+        assert_eq!(
+            tracker.key_up(Key::CapsLock, ms(start, 60)),
+            vec![Action::HoldRelease]
+        );
+    }
+
+    #[test]
+    fn test_auto_repeat_and_unrelated_keys_are_ignored() {
+        let start = Instant::now();
+        let mut tracker = Tracker::new(Key::CapsLock, THRESHOLD);
+
+        assert_eq!(tracker.key_down(Key::KeyA, start), (None, None));
+        assert!(tracker.key_up(Key::KeyA, start).is_empty());
+
+        tracker.key_down(Key::CapsLock, start);
+        assert_eq!(tracker.key_down(Key::CapsLock, ms(start, 30)), (None, None));
+        assert_eq!(
+            tracker.key_up(Key::CapsLock, ms(start, 60)),
+            vec![Action::Tap]
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::MonitorInfo;
+
+/// Tolerance used when comparing monitor edges, to absorb rounding in scaled coordinates.
+const EDGE_EPSILON: f64 = 1.0;
+
+/// The monitors directly adjacent to a given monitor, one per side.
+///
+/// A monitor is considered adjacent when its edge touches the corresponding edge
+/// of the reference monitor and the two share a non-empty span along that edge.
+/// If several monitors touch the same side, the one with the longest shared span wins.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Neighbors {
+    /// The monitor touching the left edge.
+    pub left: Option<MonitorInfo>,
+    /// The monitor touching the right edge.
+    pub right: Option<MonitorInfo>,
+    /// The monitor touching the top edge.
+    pub top: Option<MonitorInfo>,
+    /// The monitor touching the bottom edge.
+    pub bottom: Option<MonitorInfo>,
+}
+
+/// Computes the neighbors of `monitor` from a list of monitor rectangles.
+pub(crate) fn neighbors(monitors: &[MonitorInfo], monitor: &MonitorInfo) -> Neighbors {
+    let (x, y) = monitor.offset;
+    let (w, h) = monitor.size;
+
+    let mut result = Neighbors::default();
+    let mut best = [0.0_f64; 4];
+
+    for other in monitors.iter().filter(|m| *m != monitor) {
+        let (ox, oy) = other.offset;
+        let (ow, oh) = other.size;
+
+        // Length of the shared span along each axis
+        let vertical_overlap = overlap(y, y + h, oy, oy + oh);
+        let horizontal_overlap = overlap(x, x + w, ox, ox + ow);
+
+        let candidates = [
+            (touches(ox + ow, x), vertical_overlap),
+            (touches(x + w, ox), vertical_overlap),
+            (touches(oy + oh, y), horizontal_overlap),
+            (touches(y + h, oy), horizontal_overlap),
+        ];
+
+        for (side, (touching, span)) in candidates.into_iter().enumerate() {
+            if touching && span > best[side] {
+                best[side] = span;
+                let slot = match side {
+                    0 => &mut result.left,
+                    1 => &mut result.right,
+                    2 => &mut result.top,
+                    _ => &mut result.bottom,
+                };
+                *slot = Some(other.clone());
+            }
+        }
+    }
+
+    result
+}
+
+#[inline]
+fn touches(a: f64, b: f64) -> bool {
+    (a - b).abs() < EDGE_EPSILON
+}
+
+#[inline]
+fn overlap(a_start: f64, a_end: f64, b_start: f64, b_end: f64) -> f64 {
+    (a_end.min(b_end) - a_start.max(b_start)).max(0.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::monitor;
+
+    #[test]
+    fn test_neighbors_single_monitor() {
+        let main = monitor("main", 0.0, 0.0, 1920.0, 1080.0);
+        let result = neighbors(std::slice::from_ref(&main), &main);
+        assert_eq!(result, Neighbors::default());
+    }
+
+    #[test]
+    fn test_neighbors_side_by_side() {
+        let main = monitor("main", 0.0, 0.0, 1920.0, 1080.0);
+        let right = monitor("right", 1920.0, 0.0, 2560.0, 1440.0);
+        let monitors = vec![main.clone(), right.clone()];
+
+        let result = neighbors(&monitors, &main);
+        assert_eq!(result.right, Some(right.clone()));
+        assert_eq!(result.left, None);
+
+        let result = neighbors(&monitors, &right);
+        assert_eq!(result.left, Some(main));
+        assert_eq!(result.right, None);
+    }
+
+    #[test]
+    fn test_neighbors_stacked_with_negative_offset() {
+        let main = monitor("main", 0.0, 0.0, 1920.0, 1080.0);
+        let top = monitor("top", 0.0, -1080.0, 1920.0, 1080.0);
+        let monitors = vec![main.clone(), top.clone()];
+
+        let result = neighbors(&monitors, &main);
+        assert_eq!(result.top, Some(top));
+        assert_eq!(result.bottom, None);
+        assert_eq!(result.left, None);
+    }
+
+    #[test]
+    fn test_neighbors_corner_touch_is_not_adjacent() {
+        let main = monitor("main", 0.0, 0.0, 1920.0, 1080.0);
+        let diagonal = monitor("diagonal", 1920.0, 1080.0, 1920.0, 1080.0);
+        let monitors = vec![main.clone(), diagonal];
+
+        assert_eq!(neighbors(&monitors, &main), Neighbors::default());
+    }
+
+    #[test]
+    fn test_neighbors_prefers_longest_shared_edge() {
+        let main = monitor("main", 0.0, 0.0, 1920.0, 1080.0);
+        let small = monitor("small", 1920.0, 900.0, 800.0, 600.0);
+        let large = monitor("large", 1920.0, -200.0, 800.0, 1000.0);
+        let monitors = vec![main.clone(), small, large.clone()];
+
+        assert_eq!(neighbors(&monitors, &main).right, Some(large));
+    }
+}
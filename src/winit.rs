@@ -0,0 +1,203 @@
+//! Helpers for applications built on [winit](https://docs.rs/winit).
+//!
+//! [`attach`] runs `Core` on winit's event loop instead of a thread of its own, and
+//! [`forward`] delivers the captured events to the application as user events, so they
+//! arrive in `ApplicationHandler::user_event` next to the window events.
+//!
+//! [`Key`] converts to and from winit's [`PhysicalKey`], which names the same physical
+//! key positions. Keys one side has no name for convert to the other's unidentified key;
+//! [`Key::Raw`] codes are not carried over. [`MouseButton`] converts to winit's
+//! `MouseButton`, and back from every button but `Other`.
+//!
+//! # Example
+//! ```no_run
+//! use raw_input::{Event, Listen};
+//! use winit::{
+//!     application::ApplicationHandler,
+//!     event::WindowEvent,
+//!     event_loop::{ActiveEventLoop, EventLoop},
+//!     window::WindowId,
+//! };
+//!
+//! struct App;
+//!
+//! impl ApplicationHandler<Event> for App {
+//!     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
+//!         raw_input::winit::attach(event_loop).ok();
+//!     }
+//!
+//!     fn window_event(&mut self, _: &ActiveEventLoop, _: WindowId, _: WindowEvent) {}
+//!
+//!     fn user_event(&mut self, _: &ActiveEventLoop, event: Event) {
+//!         println!("{:?}", event);
+//!     }
+//! }
+//!
+//! let event_loop = EventLoop::<Event>::with_user_event().build().unwrap();
+//! let _forwarding = raw_input::winit::forward(event_loop.create_proxy());
+//! Listen::start().expect("failed to start listening");
+//! event_loop.run_app(&mut App).unwrap();
+//! ```
+
+use ::winit::{
+    event::MouseButton as WinitMouseButton,
+    event_loop::{ActiveEventLoop, EventLoopProxy},
+    keyboard::{KeyCode, NativeKeyCode, PhysicalKey},
+};
+
+use crate::{Core, CoreError, Event, Key, Listen, MouseButton, SubscriptionHandle};
+
+/// Starts `Core` on the thread running `event_loop`, see
+/// [`Core::attach_to_current_thread`]. Call it from `ApplicationHandler::resumed`; as
+/// that may run more than once, later calls fail with [`CoreError::AlreadyRunning`].
+pub fn attach(event_loop: &ActiveEventLoop) -> Result<(), CoreError> {
+    // Only a running event loop hands out an `ActiveEventLoop`, on its own thread
+    let _ = event_loop;
+    Core::attach_to_current_thread()
+}
+
+/// Sends every event [`Listen`] captures to the event loop of `proxy`, until the handle
+/// is unsubscribed or the event loop exits.
+pub fn forward<T>(proxy: EventLoopProxy<T>) -> SubscriptionHandle
+where
+    T: From<Event> + Send + 'static,
+{
+    Listen::subscribe(move |event| {
+        let _ = proxy.send_event(event.into());
+    })
+}
+
+/// Generates both directions of the key conversion from the keys named alike.
+macro_rules! key_codes {
+    ($($name:ident),* $(,)?) => {
+        fn to_key_code(key: Key) -> Option<KeyCode> {
+            let code = match key {
+                $(Key::$name => KeyCode::$name,)*
+                Key::MetaLeft => KeyCode::SuperLeft,
+                Key::MetaRight => KeyCode::SuperRight,
+                Key::Unidentified | Key::Raw(_) => return None,
+            };
+            Some(code)
+        }
+
+        fn from_key_code(code: KeyCode) -> Key {
+            match code {
+                $(KeyCode::$name => Key::$name,)*
+                KeyCode::SuperLeft => Key::MetaLeft,
+                KeyCode::SuperRight => Key::MetaRight,
+                _ => Key::Unidentified,
+            }
+        }
+    };
+}
+
+#[rustfmt::skip]
+key_codes!(
+    Backquote, Backslash, BracketLeft, BracketRight, Comma, Digit0, Digit1, Digit2, Digit3,
+    Digit4, Digit5, Digit6, Digit7, Digit8, Digit9, Equal, IntlBackslash, IntlRo, IntlYen,
+    KeyA, KeyB, KeyC, KeyD, KeyE, KeyF, KeyG, KeyH, KeyI, KeyJ, KeyK, KeyL, KeyM, KeyN, KeyO,
+    KeyP, KeyQ, KeyR, KeyS, KeyT, KeyU, KeyV, KeyW, KeyX, KeyY, KeyZ, Minus, Period, Quote,
+    Semicolon, Slash, AltLeft, AltRight, Backspace, CapsLock, ContextMenu, ControlLeft,
+    ControlRight, Enter, ShiftLeft, ShiftRight, Space, Tab, Convert, NonConvert, Delete, End,
+    Help, Home, Insert, PageDown, PageUp, ArrowDown, ArrowLeft, ArrowRight, ArrowUp, NumLock,
+    Numpad0, Numpad1, Numpad2, Numpad3, Numpad4, Numpad5, Numpad6, Numpad7, Numpad8, Numpad9,
+    NumpadAdd, NumpadDecimal, NumpadDivide, NumpadEnter, NumpadMultiply, NumpadSubtract,
+    Escape, F1, F2, F3, F4, F5, F6, F7, F8, F9, F10, F11, F12, F13, F14, F15, F16, F17, F18,
+    F19, F20, F21, F22, F23, F24, PrintScreen, ScrollLock, Pause,
+);
+
+impl From<Key> for PhysicalKey {
+    fn from(key: Key) -> Self {
+        match to_key_code(key) {
+            Some(code) => PhysicalKey::Code(code),
+            None => PhysicalKey::Unidentified(NativeKeyCode::Unidentified),
+        }
+    }
+}
+
+impl From<PhysicalKey> for Key {
+    fn from(key: PhysicalKey) -> Self {
+        match key {
+            PhysicalKey::Code(code) => from_key_code(code),
+            PhysicalKey::Unidentified(_) => Key::Unidentified,
+        }
+    }
+}
+
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Self {
+        from_key_code(code)
+    }
+}
+
+impl From<MouseButton> for WinitMouseButton {
+    fn from(button: MouseButton) -> Self {
+        match button {
+            MouseButton::Left => WinitMouseButton::Left,
+            MouseButton::Right => WinitMouseButton::Right,
+            MouseButton::Middle => WinitMouseButton::Middle,
+            MouseButton::Back => WinitMouseButton::Back,
+            MouseButton::Forward => WinitMouseButton::Forward,
+        }
+    }
+}
+
+/// Fails with the index of an `Other` button.
+impl TryFrom<WinitMouseButton> for MouseButton {
+    type Error = u16;
+
+    fn try_from(button: WinitMouseButton) -> Result<Self, u16> {
+        match button {
+            WinitMouseButton::Left => Ok(MouseButton::Left),
+            WinitMouseButton::Right => Ok(MouseButton::Right),
+            WinitMouseButton::Middle => Ok(MouseButton::Middle),
+            WinitMouseButton::Back => Ok(MouseButton::Back),
+            WinitMouseButton::Forward => Ok(MouseButton::Forward),
+            WinitMouseButton::Other(index) => Err(index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_key_roundtrips() {
+        for &key in Key::ALL {
+            let physical = PhysicalKey::from(key);
+            if key == Key::Unidentified {
+                assert!(matches!(physical, PhysicalKey::Unidentified(_)));
+            } else {
+                assert!(matches!(physical, PhysicalKey::Code(_)), "{key}");
+            }
+            assert_eq!(Key::from(physical), key);
+        }
+        assert_eq!(
+            PhysicalKey::from(Key::MetaLeft),
+            PhysicalKey::Code(KeyCode::SuperLeft)
+        );
+        assert_eq!(Key::from(KeyCode::MediaPlayPause), Key::Unidentified);
+        assert_eq!(
+            Key::from(PhysicalKey::from(Key::Raw(crate::RawKey(0xff)))),
+            Key::Unidentified
+        );
+    }
+
+    #[test]
+    fn test_mouse_buttons_roundtrip() {
+        for button in [
+            MouseButton::Left,
+            MouseButton::Right,
+            MouseButton::Middle,
+            MouseButton::Back,
+            MouseButton::Forward,
+        ] {
+            assert_eq!(
+                MouseButton::try_from(WinitMouseButton::from(button)),
+                Ok(button)
+            );
+        }
+        assert_eq!(MouseButton::try_from(WinitMouseButton::Other(7)), Err(7));
+    }
+}
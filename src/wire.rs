@@ -0,0 +1,804 @@
+//! Versioned, compact binary encoding for [`Event`].
+//!
+//! This is the encoding used by binary recordings and by the `remote` protocol. It is
+//! stable: data written by one version of the crate can be read by any other version
+//! that supports the same [`VERSION`], following the rules below.
+//!
+//! # Format
+//!
+//! Each event is a record: a one byte tag, the payload length as a varint, then the
+//! payload. Varints are unsigned LEB128; signed integers are zigzag-encoded first.
+//!
+//! | Tag | Event               | Payload                                     |
+//! |-----|---------------------|---------------------------------------------|
+//! | 0   | `MouseMove`         | point                                       |
+//! | 1   | `MouseWheel`        | point, optional raw point                   |
+//! | 2   | `MouseDown`         | button id (`u8`)                            |
+//! | 3   | `MouseUp`           | button id (`u8`)                            |
+//! | 4   | `KeyDown`           | key id (varint), optional key code (varint) |
+//! | 5   | `KeyUp`             | key id (varint), optional key code (varint) |
+//! | 6   | `FocusChanged`      | process id (varint)                         |
+//! | 7   | `MouseMoveAbsolute` | point                                       |
+//! | 8   | `ModifiersChanged`  | modifier bits (`u8`)                        |
+//!
+//! A point is a one byte mode followed by both coordinates: two signed varints when both
+//! are whole numbers (mode 0), two little-endian `f32` (mode 1) or two little-endian
+//! `f64` (mode 2). Encoders pick the smallest mode that represents the point exactly, so
+//! a typical mouse move takes five bytes and a key press three or four.
+//!
+//! Named keys have ids below `0x10000`. A [`Key::Raw`] key is encoded as `0x10000` plus
+//! its raw code, so decoders that predate raw keys read it as [`Key::Unidentified`].
+//!
+//! # Compatibility rules
+//!
+//! * Tags, key ids, button ids and point modes are never reused or renumbered; new ones
+//!   are only appended.
+//! * New fields are only appended to the end of a payload. Decoders ignore payload bytes
+//!   after the fields they know, and treat missing trailing optional fields as absent.
+//! * Records with an unknown tag, and records containing an unknown button id or point
+//!   mode, are skipped: [`decode`] consumes them and returns `Ok(None)`.
+//! * Unknown key ids decode as [`Key::Unidentified`], keeping the platform key code.
+//! * Any other change is incompatible and bumps [`VERSION`]. Containers such as
+//!   recordings store the version they were written with.
+
+use std::fmt;
+
+use crate::{
+    event::{Event, MouseButton, Point},
+    key::{Key, KeyCode, Modifiers, RawKey},
+};
+
+/// Version of the encoding described in the [module documentation](self).
+///
+/// Version 1 was a fixed-size encoding without length prefixes; it can still be read
+/// from old recordings but is no longer written.
+pub const VERSION: u16 = 2;
+
+const TAG_MOUSE_MOVE: u8 = 0;
+const TAG_MOUSE_WHEEL: u8 = 1;
+const TAG_MOUSE_DOWN: u8 = 2;
+const TAG_MOUSE_UP: u8 = 3;
+const TAG_KEY_DOWN: u8 = 4;
+const TAG_KEY_UP: u8 = 5;
+const TAG_FOCUS_CHANGED: u8 = 6;
+const TAG_MOUSE_MOVE_ABSOLUTE: u8 = 7;
+const TAG_MODIFIERS_CHANGED: u8 = 8;
+
+const POINT_INT: u8 = 0;
+const POINT_F32: u8 = 1;
+const POINT_F64: u8 = 2;
+
+/// Wire id of [`Key::Raw`] with raw code 0; raw codes are added to it.
+const RAW_KEY_ID: u64 = 0x1_0000;
+
+/// Coordinates at or beyond this magnitude are never encoded as integers.
+const MAX_INT_COORD: f64 = (1u64 << 53) as f64;
+
+/// An error produced by [`decode`].
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input ended in the middle of a record.
+    Truncated,
+    /// A record of a known kind contained invalid data.
+    Malformed,
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "truncated event record"),
+            Self::Malformed => write!(f, "malformed event record"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+macro_rules! key_ids {
+    ($($key:ident => $id:literal),+ $(,)?) => {
+        /// Returns the stable wire id of a named key, or `None` for [`Key::Raw`].
+        pub(crate) fn key_to_id(key: Key) -> Option<u16> {
+            match key {
+                $(Key::$key => Some($id),)+
+                Key::Raw(_) => None,
+            }
+        }
+
+        /// Returns the key for a wire id, or `None` if the id is unknown.
+        pub(crate) fn id_to_key(id: u16) -> Option<Key> {
+            match id {
+                $($id => Some(Key::$key),)+
+                _ => None,
+            }
+        }
+    };
+}
+
+// Ids are part of the encoding: never reuse or renumber them, only append.
+key_ids! {
+    Unidentified => 0,
+    Backquote => 1,
+    Backslash => 2,
+    BracketLeft => 3,
+    BracketRight => 4,
+    Comma => 5,
+    Digit0 => 6,
+    Digit1 => 7,
+    Digit2 => 8,
+    Digit3 => 9,
+    Digit4 => 10,
+    Digit5 => 11,
+    Digit6 => 12,
+    Digit7 => 13,
+    Digit8 => 14,
+    Digit9 => 15,
+    Equal => 16,
+    IntlBackslash => 17,
+    IntlRo => 18,
+    IntlYen => 19,
+    KeyA => 20,
+    KeyB => 21,
+    KeyC => 22,
+    KeyD => 23,
+    KeyE => 24,
+    KeyF => 25,
+    KeyG => 26,
+    KeyH => 27,
+    KeyI => 28,
+    KeyJ => 29,
+    KeyK => 30,
+    KeyL => 31,
+    KeyM => 32,
+    KeyN => 33,
+    KeyO => 34,
+    KeyP => 35,
+    KeyQ => 36,
+    KeyR => 37,
+    KeyS => 38,
+    KeyT => 39,
+    KeyU => 40,
+    KeyV => 41,
+    KeyW => 42,
+    KeyX => 43,
+    KeyY => 44,
+    KeyZ => 45,
+    Minus => 46,
+    Period => 47,
+    Quote => 48,
+    Semicolon => 49,
+    Slash => 50,
+    AltLeft => 51,
+    AltRight => 52,
+    Backspace => 53,
+    CapsLock => 54,
+    ContextMenu => 55,
+    ControlLeft => 56,
+    ControlRight => 57,
+    Enter => 58,
+    MetaLeft => 59,
+    MetaRight => 60,
+    ShiftLeft => 61,
+    ShiftRight => 62,
+    Space => 63,
+    Tab => 64,
+    Convert => 65,
+    NonConvert => 66,
+    Delete => 67,
+    End => 68,
+    Help => 69,
+    Home => 70,
+    Insert => 71,
+    PageDown => 72,
+    PageUp => 73,
+    ArrowDown => 74,
+    ArrowLeft => 75,
+    ArrowRight => 76,
+    ArrowUp => 77,
+    NumLock => 78,
+    Numpad0 => 79,
+    Numpad1 => 80,
+    Numpad2 => 81,
+    Numpad3 => 82,
+    Numpad4 => 83,
+    Numpad5 => 84,
+    Numpad6 => 85,
+    Numpad7 => 86,
+    Numpad8 => 87,
+    Numpad9 => 88,
+    NumpadAdd => 89,
+    NumpadDecimal => 90,
+    NumpadDivide => 91,
+    NumpadEnter => 92,
+    NumpadMultiply => 93,
+    NumpadSubtract => 94,
+    Escape => 95,
+    F1 => 96,
+    F2 => 97,
+    F3 => 98,
+    F4 => 99,
+    F5 => 100,
+    F6 => 101,
+    F7 => 102,
+    F8 => 103,
+    F9 => 104,
+    F10 => 105,
+    F11 => 106,
+    F12 => 107,
+    F13 => 108,
+    F14 => 109,
+    F15 => 110,
+    F16 => 111,
+    F17 => 112,
+    F18 => 113,
+    F19 => 114,
+    F20 => 115,
+    F21 => 116,
+    F22 => 117,
+    F23 => 118,
+    F24 => 119,
+    PrintScreen => 120,
+    ScrollLock => 121,
+    Pause => 122,
+}
+
+/// Appends the encoding of `event` to `out`.
+///
+/// # Example
+/// ```
+/// use raw_input::{Event, Key, wire};
+///
+/// let event = Event::KeyDown { key: Key::KeyA, code: Some(65) };
+/// let mut buf = Vec::new();
+/// wire::encode(&event, &mut buf);
+/// assert_eq!(buf.len(), 4);
+///
+/// let mut input = buf.as_slice();
+/// assert_eq!(wire::decode(&mut input), Ok(Some(event)));
+/// assert!(input.is_empty());
+/// ```
+pub fn encode(event: &Event, out: &mut Vec<u8>) {
+    let mut payload = Vec::with_capacity(16);
+    let tag = match *event {
+        Event::MouseMove { delta } => {
+            encode_point(delta, &mut payload);
+            TAG_MOUSE_MOVE
+        }
+        Event::MouseMoveAbsolute { position } => {
+            encode_point(position, &mut payload);
+            TAG_MOUSE_MOVE_ABSOLUTE
+        }
+        Event::MouseWheel { delta, raw } => {
+            encode_point(delta, &mut payload);
+            if let Some(raw) = raw {
+                encode_point(raw, &mut payload);
+            }
+            TAG_MOUSE_WHEEL
+        }
+        Event::MouseDown { button } => {
+            payload.push(button_to_id(button));
+            TAG_MOUSE_DOWN
+        }
+        Event::MouseUp { button } => {
+            payload.push(button_to_id(button));
+            TAG_MOUSE_UP
+        }
+        Event::KeyDown { key, code } => {
+            encode_key(key, code, &mut payload);
+            TAG_KEY_DOWN
+        }
+        Event::KeyUp { key, code } => {
+            encode_key(key, code, &mut payload);
+            TAG_KEY_UP
+        }
+        Event::FocusChanged { pid } => {
+            write_varint(u64::from(pid), &mut payload);
+            TAG_FOCUS_CHANGED
+        }
+        Event::ModifiersChanged { modifiers } => {
+            payload.push(modifiers.bits());
+            TAG_MODIFIERS_CHANGED
+        }
+    };
+    out.push(tag);
+    write_varint(payload.len() as u64, out);
+    out.extend_from_slice(&payload);
+}
+
+/// Decodes one record from the front of `input`, advancing it past the consumed bytes.
+///
+/// Returns `Ok(None)` for records this version does not understand; they are skipped
+/// according to the compatibility rules, so decoding can simply continue.
+pub fn decode(input: &mut &[u8]) -> Result<Option<Event>, DecodeError> {
+    let tag = take_u8(input).ok_or(DecodeError::Truncated)?;
+    let len = read_varint(input).ok_or(DecodeError::Truncated)?;
+    let len = usize::try_from(len).map_err(|_| DecodeError::Malformed)?;
+    if len > input.len() {
+        return Err(DecodeError::Truncated);
+    }
+    let (mut payload, rest) = input.split_at(len);
+    *input = rest;
+
+    let payload = &mut payload;
+    let event = match tag {
+        TAG_MOUSE_MOVE => decode_point(payload)?.map(|delta| Event::MouseMove { delta }),
+        TAG_MOUSE_WHEEL => decode_wheel(payload)?,
+        TAG_MOUSE_MOVE_ABSOLUTE => {
+            decode_point(payload)?.map(|position| Event::MouseMoveAbsolute { position })
+        }
+        TAG_MOUSE_DOWN => decode_button(payload)?.map(|button| Event::MouseDown { button }),
+        TAG_MOUSE_UP => decode_button(payload)?.map(|button| Event::MouseUp { button }),
+        TAG_KEY_DOWN => {
+            let (key, code) = decode_key(payload)?;
+            Some(Event::KeyDown { key, code })
+        }
+        TAG_KEY_UP => {
+            let (key, code) = decode_key(payload)?;
+            Some(Event::KeyUp { key, code })
+        }
+        TAG_FOCUS_CHANGED => {
+            let pid = read_varint(payload).ok_or(DecodeError::Truncated)?;
+            let pid = u32::try_from(pid).map_err(|_| DecodeError::Malformed)?;
+            Some(Event::FocusChanged { pid })
+        }
+        TAG_MODIFIERS_CHANGED => {
+            let bits = take_u8(payload).ok_or(DecodeError::Truncated)?;
+            Some(Event::ModifiersChanged {
+                modifiers: Modifiers::from_bits_truncate(bits),
+            })
+        }
+        _ => None,
+    };
+    Ok(event)
+}
+
+fn encode_point(point: Point, out: &mut Vec<u8>) {
+    let is_int = |v: f64| v.fract() == 0.0 && v.abs() < MAX_INT_COORD;
+    let is_f32 = |v: f64| (v as f32) as f64 == v;
+
+    if is_int(point.x) && is_int(point.y) {
+        out.push(POINT_INT);
+        write_varint(zigzag(point.x as i64), out);
+        write_varint(zigzag(point.y as i64), out);
+    } else if is_f32(point.x) && is_f32(point.y) {
+        out.push(POINT_F32);
+        out.extend_from_slice(&(point.x as f32).to_le_bytes());
+        out.extend_from_slice(&(point.y as f32).to_le_bytes());
+    } else {
+        out.push(POINT_F64);
+        out.extend_from_slice(&point.x.to_le_bytes());
+        out.extend_from_slice(&point.y.to_le_bytes());
+    }
+}
+
+fn decode_point(input: &mut &[u8]) -> Result<Option<Point>, DecodeError> {
+    let coord = |input: &mut &[u8], mode: u8| -> Option<f64> {
+        match mode {
+            POINT_INT => read_varint(input).map(|v| unzigzag(v) as f64),
+            POINT_F32 => take(input).map(|b| f32::from_le_bytes(b) as f64),
+            _ => take(input).map(f64::from_le_bytes),
+        }
+    };
+
+    let mode = take_u8(input).ok_or(DecodeError::Malformed)?;
+    if !matches!(mode, POINT_INT | POINT_F32 | POINT_F64) {
+        return Ok(None);
+    }
+    let x = coord(input, mode).ok_or(DecodeError::Malformed)?;
+    let y = coord(input, mode).ok_or(DecodeError::Malformed)?;
+    Ok(Some(Point { x, y }))
+}
+
+fn decode_wheel(input: &mut &[u8]) -> Result<Option<Event>, DecodeError> {
+    let Some(delta) = decode_point(input)? else {
+        return Ok(None);
+    };
+    let raw = match input.is_empty() {
+        true => None,
+        false => match decode_point(input)? {
+            Some(raw) => Some(raw),
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(Event::MouseWheel { delta, raw }))
+}
+
+fn decode_button(input: &mut &[u8]) -> Result<Option<MouseButton>, DecodeError> {
+    let id = take_u8(input).ok_or(DecodeError::Malformed)?;
+    Ok(id_to_button(id))
+}
+
+/// Returns the stable id of a key, as used by the encoding.
+///
+/// Named keys have ids below `0x10000`; raw keys are `0x10000` plus their raw code.
+pub fn key_id(key: Key) -> u64 {
+    match key {
+        Key::Raw(RawKey(code)) => RAW_KEY_ID + code as u64,
+        _ => key_to_id(key).map_or(0, u64::from),
+    }
+}
+
+/// Returns the key for a stable id produced by [`key_id`], or [`Key::Unidentified`] if
+/// the id is unknown to this version of the crate.
+pub fn key_from_id(id: u64) -> Key {
+    let key = match id.checked_sub(RAW_KEY_ID) {
+        Some(code) => KeyCode::try_from(code)
+            .map(|code| Key::Raw(RawKey(code)))
+            .ok(),
+        None => id_to_key(id as u16),
+    };
+    key.unwrap_or(Key::Unidentified)
+}
+
+fn encode_key(key: Key, code: Option<KeyCode>, out: &mut Vec<u8>) {
+    write_varint(key_id(key), out);
+    if let Some(code) = code {
+        write_varint(code as u64, out);
+    }
+}
+
+fn decode_key(input: &mut &[u8]) -> Result<(Key, Option<KeyCode>), DecodeError> {
+    let key = key_from_id(read_varint(input).ok_or(DecodeError::Malformed)?);
+    let code = match input.is_empty() {
+        true => None,
+        false => {
+            let code = read_varint(input).ok_or(DecodeError::Malformed)?;
+            Some(KeyCode::try_from(code).map_err(|_| DecodeError::Malformed)?)
+        }
+    };
+    Ok((key, code))
+}
+
+/// Decodes one event in the version 1 encoding, used by old recordings.
+///
+/// Version 1 had no length prefixes, so unknown tags or ids cannot be skipped and make
+/// the whole input undecodable.
+#[cfg(any(feature = "serialize", test))]
+pub(crate) fn decode_v1(input: &mut &[u8]) -> Option<Event> {
+    let point = |input: &mut &[u8]| {
+        let x = f64::from_le_bytes(take(input)?);
+        let y = f64::from_le_bytes(take(input)?);
+        Some(Point { x, y })
+    };
+    let key = |input: &mut &[u8]| {
+        let key = id_to_key(u16::from_le_bytes(take(input)?))?;
+        let code = match take_u8(input)? {
+            0 => None,
+            1 => Some(KeyCode::from_le_bytes(take(input)?)),
+            _ => return None,
+        };
+        Some((key, code))
+    };
+
+    let event = match take_u8(input)? {
+        TAG_MOUSE_MOVE => Event::MouseMove {
+            delta: point(input)?,
+        },
+        TAG_MOUSE_WHEEL => Event::MouseWheel {
+            delta: point(input)?,
+            raw: None,
+        },
+        TAG_MOUSE_DOWN => Event::MouseDown {
+            button: id_to_button(take_u8(input)?)?,
+        },
+        TAG_MOUSE_UP => Event::MouseUp {
+            button: id_to_button(take_u8(input)?)?,
+        },
+        TAG_KEY_DOWN => {
+            let (key, code) = key(input)?;
+            Event::KeyDown { key, code }
+        }
+        TAG_KEY_UP => {
+            let (key, code) = key(input)?;
+            Event::KeyUp { key, code }
+        }
+        _ => return None,
+    };
+    Some(event)
+}
+
+pub(crate) fn button_to_id(button: MouseButton) -> u8 {
+    match button {
+        MouseButton::Left => 0,
+        MouseButton::Right => 1,
+        MouseButton::Middle => 2,
+        MouseButton::Back => 3,
+        MouseButton::Forward => 4,
+    }
+}
+
+pub(crate) fn id_to_button(id: u8) -> Option<MouseButton> {
+    match id {
+        0 => Some(MouseButton::Left),
+        1 => Some(MouseButton::Right),
+        2 => Some(MouseButton::Middle),
+        3 => Some(MouseButton::Back),
+        4 => Some(MouseButton::Forward),
+        _ => None,
+    }
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = take_u8(input)?;
+        value |= ((byte & 0x7f) as u64).checked_shl(shift)?;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Splits `N` bytes off the front of `input`.
+pub(crate) fn take<const N: usize>(input: &mut &[u8]) -> Option<[u8; N]> {
+    let (head, rest) = input.split_first_chunk::<N>()?;
+    *input = rest;
+    Some(*head)
+}
+
+fn take_u8(input: &mut &[u8]) -> Option<u8> {
+    take::<1>(input).map(|[b]| b)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    fn encoded(event: Event) -> Vec<u8> {
+        let mut buf = Vec::new();
+        encode(&event, &mut buf);
+        buf
+    }
+
+    fn roundtrip(event: Event) {
+        let buf = encoded(event);
+        let mut input = buf.as_slice();
+        assert_eq!(decode(&mut input), Ok(Some(event)));
+        assert!(
+            input.is_empty(),
+            "decoder should consume the whole encoding"
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_mouse_events() {
+        roundtrip(Event::MouseMove {
+            delta: Point { x: -3.0, y: 7.5 },
+        });
+        roundtrip(Event::MouseMove {
+            delta: Point { x: 0.1, y: 1e300 },
+        });
+        roundtrip(Event::MouseMoveAbsolute {
+            position: Point { x: -19.0, y: 5.5 },
+        });
+        roundtrip(Event::MouseWheel {
+            delta: Point { x: 0.0, y: -1.0 },
+            raw: None,
+        });
+        roundtrip(Event::MouseWheel {
+            delta: Point { x: 0.0, y: -0.25 },
+            raw: Some(Point { x: 0.0, y: -30.0 }),
+        });
+        roundtrip(Event::MouseDown {
+            button: MouseButton::Back,
+        });
+        roundtrip(Event::MouseUp {
+            button: MouseButton::Forward,
+        });
+    }
+
+    #[test]
+    fn test_roundtrip_key_events() {
+        roundtrip(Event::KeyDown {
+            key: Key::KeyA,
+            code: Some(65),
+        });
+        roundtrip(Event::KeyUp {
+            key: Key::Unidentified,
+            code: None,
+        });
+        roundtrip(Event::KeyUp {
+            key: Key::Pause,
+            code: Some(u32::MAX),
+        });
+        roundtrip(Event::KeyDown {
+            key: Key::Raw(RawKey(0xff)),
+            code: Some(0xff),
+        });
+        roundtrip(Event::KeyUp {
+            key: Key::Raw(RawKey(u32::MAX)),
+            code: None,
+        });
+    }
+
+    #[test]
+    fn test_key_ids_roundtrip() {
+        for id in 0..=u16::MAX {
+            if let Some(key) = id_to_key(id) {
+                assert_eq!(key_to_id(key), Some(id));
+            }
+        }
+        assert_eq!(key_to_id(Key::Unidentified), Some(0));
+    }
+
+    /// Every event variant, with every named key, every button and a raw key.
+    pub(crate) fn every_event() -> Vec<Event> {
+        let buttons = [
+            MouseButton::Left,
+            MouseButton::Right,
+            MouseButton::Middle,
+            MouseButton::Back,
+            MouseButton::Forward,
+        ];
+        let delta = Point { x: 1.0, y: -0.25 };
+
+        let mut events = vec![
+            Event::MouseMove { delta },
+            Event::MouseMoveAbsolute { position: delta },
+            Event::MouseWheel { delta, raw: None },
+            Event::MouseWheel {
+                delta,
+                raw: Some(Point { x: 120.0, y: -30.0 }),
+            },
+        ];
+        for button in buttons {
+            events.push(Event::MouseDown { button });
+            events.push(Event::MouseUp { button });
+        }
+        let keys = Key::ALL.iter().copied().chain([Key::Raw(RawKey(u32::MAX))]);
+        for (i, key) in keys.enumerate() {
+            let code = (i % 2 == 0).then_some(i as KeyCode);
+            events.push(Event::KeyDown { key, code });
+            events.push(Event::KeyUp { key, code });
+        }
+        events.push(Event::FocusChanged { pid: 4242 });
+        events.push(Event::ModifiersChanged {
+            modifiers: Modifiers::SHIFT | Modifiers::ALT,
+        });
+        events
+    }
+
+    #[test]
+    fn test_roundtrip_every_variant() {
+        for event in every_event() {
+            roundtrip(event);
+        }
+    }
+
+    #[cfg(feature = "serialize")]
+    #[test]
+    fn test_serde_roundtrip_every_variant() {
+        for event in every_event() {
+            let json = serde_json::to_vec(&event).unwrap();
+            assert_eq!(serde_json::from_slice::<Event>(&json).unwrap(), event);
+        }
+    }
+
+    #[test]
+    fn test_encoding_is_compact() {
+        let small_move = Event::MouseMove {
+            delta: Point { x: 3.0, y: -2.0 },
+        };
+        assert_eq!(encoded(small_move), [TAG_MOUSE_MOVE, 3, POINT_INT, 6, 3]);
+
+        let click = Event::MouseDown {
+            button: MouseButton::Left,
+        };
+        assert_eq!(encoded(click).len(), 3);
+
+        let key = Event::KeyUp {
+            key: Key::Escape,
+            code: None,
+        };
+        assert_eq!(encoded(key).len(), 3);
+    }
+
+    #[test]
+    fn test_unknown_records_are_skipped() {
+        let next = Event::KeyDown {
+            key: Key::KeyB,
+            code: None,
+        };
+        let mut buf = vec![200, 2, 0xaa, 0xbb];
+        buf.extend(encoded(next));
+
+        let mut input = buf.as_slice();
+        assert_eq!(decode(&mut input), Ok(None));
+        assert_eq!(decode(&mut input), Ok(Some(next)));
+        assert!(input.is_empty());
+
+        // Unknown button id and unknown point mode
+        let mut input: &[u8] = &[TAG_MOUSE_DOWN, 1, 99, TAG_MOUSE_MOVE, 1, 42];
+        assert_eq!(decode(&mut input), Ok(None));
+        assert_eq!(decode(&mut input), Ok(None));
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_forward_compatible_payloads() {
+        // A newer key id, with the platform code preserved
+        let mut input: &[u8] = &[TAG_KEY_DOWN, 3, 0xe7, 0x07, 42];
+        assert_eq!(
+            decode(&mut input),
+            Ok(Some(Event::KeyDown {
+                key: Key::Unidentified,
+                code: Some(42),
+            }))
+        );
+
+        // Trailing fields appended by a newer version are ignored
+        let mut buf = encoded(Event::MouseUp {
+            button: MouseButton::Right,
+        });
+        buf[1] = 3;
+        buf.extend([0x01, 0x02]);
+        let mut input = buf.as_slice();
+        assert_eq!(
+            decode(&mut input),
+            Ok(Some(Event::MouseUp {
+                button: MouseButton::Right,
+            }))
+        );
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let buf = encoded(Event::KeyDown {
+            key: Key::KeyA,
+            code: Some(65),
+        });
+        for len in 0..buf.len() {
+            let mut input = &buf[..len];
+            assert_eq!(decode(&mut input), Err(DecodeError::Truncated));
+        }
+
+        let mut input: &[u8] = &[TAG_MOUSE_MOVE, 2, POINT_F64, 0];
+        assert_eq!(decode(&mut input), Err(DecodeError::Malformed));
+    }
+
+    #[test]
+    fn test_decode_v1() {
+        let mut buf = vec![TAG_KEY_DOWN];
+        buf.extend_from_slice(&20u16.to_le_bytes());
+        buf.push(1);
+        buf.extend_from_slice(&65u32.to_le_bytes());
+        buf.push(TAG_MOUSE_WHEEL);
+        buf.extend_from_slice(&0.5f64.to_le_bytes());
+        buf.extend_from_slice(&(-1.0f64).to_le_bytes());
+
+        let mut input = buf.as_slice();
+        assert_eq!(
+            decode_v1(&mut input),
+            Some(Event::KeyDown {
+                key: Key::KeyA,
+                code: Some(65),
+            })
+        );
+        assert_eq!(
+            decode_v1(&mut input),
+            Some(Event::MouseWheel {
+                delta: Point { x: 0.5, y: -1.0 },
+                raw: None,
+            })
+        );
+        assert!(input.is_empty());
+    }
+}